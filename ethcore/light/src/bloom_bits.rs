@@ -0,0 +1,338 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bloom-bit index definitions and helper functions.
+//!
+//! A bloom-bit section rotates the log blooms of the blocks covered by a CHT:
+//! for every bit of the bloom it holds a vector with one bit per block, set when
+//! that block's bloom has the bit set. Each section is committed to by the root of
+//! a trie mapping bloom bit indices to those vectors, computed alongside the CHT root.
+//!
+//! To find the blocks of a section which may contain matching logs, a light client
+//! requests the vectors for the few bits set in the filter's bloom and intersects them,
+//! rather than fetching thousands of headers.
+
+use std::collections::VecDeque;
+
+use ethcore::ids::BlockId;
+use ethereum_types::{H256, Bloom};
+use hashdb::HashDB;
+use memorydb::MemoryDB;
+use bytes::Bytes;
+use trie::{self, TrieMut, TrieDBMut, Trie, TrieDB, Recorder};
+
+use cht;
+
+// encode a key.
+macro_rules! key {
+	($bit: expr) => { ::rlp::encode(&($bit as u64)) }
+}
+
+/// The number of blocks covered by each section. Sections line up with CHTs.
+pub const SIZE: u64 = cht::SIZE;
+
+/// The number of bits in a log bloom, and so the number of entries in each section.
+pub const BLOOM_BITS: u64 = 2048;
+
+/// The length in bytes of each bit vector.
+pub const VECTOR_LEN: usize = (SIZE / 8) as usize;
+
+/// The number of built sections kept by a `SectionCache`.
+const CACHED_SECTIONS: usize = 4;
+
+/// A bloom-bit index section. This is generic over any database it can query.
+/// See module docs for more details.
+#[derive(Debug, Clone)]
+pub struct BloomBits<DB: HashDB> {
+	db: DB,
+	root: H256, // the root of this section's trie.
+	number: u64,
+}
+
+impl<DB: HashDB> BloomBits<DB> {
+	/// Query the root of the section.
+	pub fn root(&self) -> H256 { self.root }
+
+	/// Query the number of the section.
+	pub fn number(&self) -> u64 { self.number }
+
+	/// Generate an inclusion proof for the vector of a specific bloom bit.
+	/// Nodes before level `from_level` will be omitted.
+	/// Returns an error on an incomplete trie, and `Ok(None)` on an unprovable request.
+	pub fn prove(&self, bit: u64, from_level: u32) -> trie::Result<Option<Vec<Bytes>>> {
+		if bit >= BLOOM_BITS { return Ok(None) }
+
+		let mut recorder = Recorder::with_depth(from_level);
+		let t = TrieDB::new(&self.db, &self.root)?;
+		t.get_with(&key!(bit), &mut recorder)?;
+
+		Ok(Some(recorder.drain().into_iter().map(|x| x.data).collect()))
+	}
+}
+
+/// A cache of the most recently used in-memory sections, so that serving
+/// the bits of a section doesn't require building it again for every request.
+///
+/// Sections are identified by the hash of their last block, so the section
+/// is built again after a reorganization.
+#[derive(Default)]
+pub struct SectionCache {
+	sections: VecDeque<(H256, BloomBits<MemoryDB>)>,
+}
+
+impl SectionCache {
+	/// Get the section ending with the block `last_hash`, building it with `build`
+	/// if it isn't cached. Returns `None` if the section can't be built.
+	pub fn get_or_build<F>(&mut self, last_hash: H256, build: F) -> Option<&BloomBits<MemoryDB>>
+		where F: FnOnce() -> Option<BloomBits<MemoryDB>>
+	{
+		let cached = self.sections.iter().position(|&(ref hash, _)| *hash == last_hash);
+		match cached {
+			Some(pos) => {
+				let entry = self.sections.remove(pos).expect("position of an existing entry; qed");
+				self.sections.push_front(entry);
+			}
+			None => {
+				let section = match build() {
+					Some(section) => section,
+					None => return None,
+				};
+
+				self.sections.truncate(CACHED_SECTIONS - 1);
+				self.sections.push_front((last_hash, section));
+			}
+		}
+
+		self.sections.front().map(|&(_, ref section)| section)
+	}
+}
+
+/// Block information necessary to build a bloom-bit section.
+pub struct BlockInfo {
+	/// The block's parent's hash.
+	pub parent_hash: H256,
+	/// The block's log bloom.
+	pub log_bloom: Bloom,
+}
+
+/// Build an in-memory bloom-bit section from a closure which provides necessary
+/// information about blocks. If the fetcher ever fails to provide the info, the
+/// section will not be generated.
+pub fn build<F>(section_num: u64, mut fetcher: F) -> Option<BloomBits<MemoryDB>>
+	where F: FnMut(BlockId) -> Option<BlockInfo>
+{
+	// start from the last block by number and work backwards, as the CHT does.
+	let mut id = BlockId::Number(last_number(section_num));
+	let mut blooms = Vec::with_capacity(SIZE as usize);
+
+	for _ in 0..SIZE {
+		let info = match fetcher(id) {
+			Some(info) => info,
+			None => return None,
+		};
+
+		id = BlockId::Hash(info.parent_hash);
+		blooms.push(info.log_bloom);
+	}
+	blooms.reverse();
+
+	let mut db = MemoryDB::new();
+	let mut root = H256::default();
+
+	{
+		let mut t = TrieDBMut::new(&mut db, &mut root);
+		for (bit, vector) in bit_vectors(&blooms).into_iter().enumerate() {
+			t.insert(&key!(bit), &vector)
+				.expect("fresh in-memory database is infallible; qed");
+		}
+	}
+
+	Some(BloomBits {
+		db: db,
+		root: root,
+		number: section_num,
+	})
+}
+
+/// Compute a section root from an iterator of log blooms. Fails if shorter than
+/// SIZE items. The items are assumed to proceed sequentially from the start of the section.
+/// Discards the trie's nodes.
+pub fn compute_root<I>(iterable: I) -> Option<H256>
+	where I: IntoIterator<Item=Bloom>
+{
+	let blooms: Vec<_> = iterable.into_iter().take(SIZE as usize).collect();
+	if blooms.len() != SIZE as usize { return None }
+
+	let v = bit_vectors(&blooms).into_iter()
+		.enumerate()
+		.map(|(bit, vector)| (key!(bit).into_vec(), vector))
+		.collect::<Vec<_>>();
+
+	Some(::triehash::trie_root(v))
+}
+
+/// Check a proof for a bloom-bit section.
+/// Given a set of trie nodes, a bloom bit to query, and a section root,
+/// verify the given trie branch and extract the bit vector.
+pub fn check_proof(proof: &[Bytes], bit: u64, root: H256) -> Option<Bytes> {
+	let mut db = MemoryDB::new();
+
+	for node in proof { db.insert(&node[..]); }
+	let res = match TrieDB::new(&db, &root) {
+		Err(_) => return None,
+		Ok(trie) => trie.get_with(&key!(bit), |val: &[u8]| val.to_vec()),
+	};
+
+	match res {
+		Ok(Some(ref vector)) if vector.len() == VECTOR_LEN => Some(vector.clone()),
+		_ => None,
+	}
+}
+
+/// Get the indices of the bits set in a bloom, in ascending order.
+/// These are the entries of a section needed to match against the bloom.
+pub fn bloom_bits(bloom: &Bloom) -> Vec<u64> {
+	(0..BLOOM_BITS).filter(|&bit| is_set(&bloom[..], bit as usize)).collect()
+}
+
+/// Given the vectors of all bits set in a bloom, get the numbers of the blocks
+/// in the section whose log blooms contain it. Vectors of the wrong length are
+/// treated as empty.
+pub fn matching_blocks(section_num: u64, vectors: &[Bytes]) -> Vec<u64> {
+	let start = start_number(section_num);
+
+	(0..SIZE)
+		.filter(|&i| vectors.iter().all(|v| v.len() == VECTOR_LEN && is_set(v, i as usize)))
+		.map(|i| start + i)
+		.collect()
+}
+
+/// Convert a block number to a section number.
+/// Returns `None` for `block_num` == 0, `Some` otherwise.
+pub fn block_to_section_number(block_num: u64) -> Option<u64> {
+	cht::block_to_cht_number(block_num)
+}
+
+/// Get the last block of a given section.
+pub fn last_number(section_num: u64) -> u64 {
+	start_number(section_num + 1) - 1
+}
+
+/// Get the starting block of a given section. This is the same as for the CHT
+/// with the same number.
+pub fn start_number(section_num: u64) -> u64 {
+	cht::start_number(section_num)
+}
+
+// rotate a section's blooms into one vector per bloom bit.
+fn bit_vectors(blooms: &[Bloom]) -> Vec<Bytes> {
+	let mut vectors = vec![vec![0u8; VECTOR_LEN]; BLOOM_BITS as usize];
+
+	for (i, bloom) in blooms.iter().enumerate() {
+		for bit in bloom_bits(bloom) {
+			vectors[bit as usize][i / 8] |= 0x80 >> (i % 8);
+		}
+	}
+
+	vectors
+}
+
+fn is_set(bytes: &[u8], bit: usize) -> bool {
+	bytes[bit / 8] & (0x80 >> (bit % 8)) != 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethcore::ids::BlockId;
+	use ethereum_types::{H256, U256, Bloom, BloomInput};
+
+	fn test_bloom(num: u64) -> Bloom {
+		let mut bloom = Bloom::default();
+		if num % 3 == 0 { bloom.accrue(BloomInput::Raw(b"three")) }
+		if num % 5 == 0 { bloom.accrue(BloomInput::Raw(b"five")) }
+		bloom
+	}
+
+	// block hashes are their numbers.
+	fn block_info(id: BlockId) -> Option<BlockInfo> {
+		let num = match id {
+			BlockId::Number(n) => n,
+			BlockId::Hash(h) => U256::from(h).low_u64(),
+			_ => return None,
+		};
+
+		Some(BlockInfo { parent_hash: H256::from(num - 1), log_bloom: test_bloom(num) })
+	}
+
+	#[test]
+	fn build_matches_compute_root() {
+		let section = build(1, block_info).unwrap();
+
+		let start = start_number(1);
+		let root = compute_root((start..start + SIZE).map(test_bloom)).unwrap();
+		assert_eq!(section.root(), root);
+		assert!(compute_root((start..start + SIZE - 1).map(test_bloom)).is_none());
+	}
+
+	#[test]
+	fn proofs_locate_matching_blocks() {
+		let section = build(0, block_info).unwrap();
+
+		let mut filter = Bloom::default();
+		filter.accrue(BloomInput::Raw(b"three"));
+		filter.accrue(BloomInput::Raw(b"five"));
+
+		let vectors: Vec<_> = bloom_bits(&filter).into_iter().map(|bit| {
+			let proof = section.prove(bit, 0).unwrap().unwrap();
+			check_proof(&proof, bit, section.root()).unwrap()
+		}).collect();
+
+		let matching = matching_blocks(0, &vectors);
+		let start = start_number(0);
+		for n in (start..start + SIZE).filter(|n| n % 15 == 0) {
+			assert!(matching.contains(&n));
+		}
+		for n in &matching {
+			assert!(test_bloom(*n).contains_bloom(&filter));
+		}
+	}
+
+	#[test]
+	fn sections_are_cached_by_last_hash() {
+		let mut cache = SectionCache::default();
+		let root = cache.get_or_build(H256::from(1), || build(0, block_info)).unwrap().root();
+
+		assert_eq!(cache.get_or_build(H256::from(1), || None).map(|s| s.root()), Some(root));
+		assert!(cache.get_or_build(H256::from(2), || None).is_none());
+
+		for hash in 2..(CACHED_SECTIONS as u64 + 2) {
+			assert!(cache.get_or_build(H256::from(hash), || build(0, block_info)).is_some());
+		}
+		assert!(cache.get_or_build(H256::from(2), || None).is_some());
+		assert!(cache.get_or_build(H256::from(1), || None).is_none());
+	}
+
+	#[test]
+	fn bad_proofs_rejected() {
+		let section = build(0, block_info).unwrap();
+		let proof = section.prove(7, 0).unwrap().unwrap();
+
+		assert!(check_proof(&proof, 7, section.root()).is_some());
+		assert!(check_proof(&proof, 7, H256::from(1)).is_none());
+		assert!(section.prove(BLOOM_BITS, 0).unwrap().is_none());
+	}
+}
@@ -28,6 +28,7 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use bloom_bits;
 use cht;
 
 use ethcore::block_status::BlockStatus;
@@ -151,6 +152,10 @@ fn cht_key(number: u64) -> String {
 	format!("{:08x}_canonical", number)
 }
 
+fn bloom_bits_key(number: u64) -> String {
+	format!("{:08x}_bloom_bits", number)
+}
+
 fn era_key(number: u64) -> String {
 	format!("candidates_{}", number)
 }
@@ -305,6 +310,11 @@ impl HeaderChain {
 					batch.put(col, cht_key(cht_num as u64).as_bytes(), &::rlp::encode(cht_root));
 				}
 
+				// and the roots of the bloom-bit sections, which can't be computed without the headers.
+				for (section_num, section_root) in hardcoded_sync.bloom_bits.iter().enumerate() {
+					batch.put(col, bloom_bits_key(section_num as u64).as_bytes(), &::rlp::encode(section_root));
+				}
+
 				let decoded_header = hardcoded_sync.header.decode()?;
 				let decoded_header_num = decoded_header.number();
 
@@ -497,6 +507,7 @@ impl HeaderChain {
 					.expect("fails only for number == 0; genesis never imported; qed");
 
 				let mut last_canonical_transition = None;
+				let mut log_blooms = Vec::with_capacity(cht::SIZE as usize);
 				let cht_root = {
 					let mut i = earliest_era;
 					let mut live_epoch_proofs = self.live_epoch_proofs.write();
@@ -510,6 +521,14 @@ impl HeaderChain {
 
 						i += 1;
 
+						// collect the canonical log bloom for the bloom-bit section
+						// before the header is pruned.
+						match self.db.get(self.col, &era_entry.canonical_hash) {
+							Ok(Some(header)) => log_blooms.push(encoded::Header::new(header.into_vec()).log_bloom()),
+							Ok(None) => warn!(target: "chain", "Canonical header {} missing from DB", era_entry.canonical_hash),
+							Err(e) => warn!(target: "chain", "Error reading from DB: {}", e),
+						}
+
 						// prune old blocks and epoch proofs.
 						for ancient in &era_entry.candidates {
 							let maybe_transition = live_epoch_proofs.remove(&ancient.hash);
@@ -546,6 +565,12 @@ impl HeaderChain {
 				debug!(target: "chain", "Produced CHT {} root: {:?}", cht_num, cht_root);
 				transaction.put(self.col, cht_key(cht_num).as_bytes(), &::rlp::encode(&cht_root));
 
+				// a section missing any bloom can't be proven against, so none is stored.
+				if let Some(bloom_bits_root) = bloom_bits::compute_root(log_blooms) {
+					debug!(target: "chain", "Produced bloom-bit section {} root: {:?}", cht_num, bloom_bits_root);
+					transaction.put(self.col, bloom_bits_key(cht_num).as_bytes(), &::rlp::encode(&bloom_bits_root));
+				}
+
 				// update the last canonical transition proof
 				if let Some((epoch_transition, header)) = last_canonical_transition {
 					let x = encode_canonical_transition(&header, &epoch_transition.proof);
@@ -569,6 +594,7 @@ impl HeaderChain {
 	/// Returns `None` if we are at the genesis block, or if an error happens .
 	pub fn read_hardcoded_sync(&self) -> Result<Option<SpecHardcodedSync>, Error> {
 		let mut chts = Vec::new();
+		let mut bloom_bits = Vec::new();
 		let mut cht_num = 0;
 
 		loop {
@@ -610,6 +636,7 @@ impl HeaderChain {
 						header,
 						total_difficulty,
 						chts,
+						bloom_bits,
 					}));
 				},
 				None => {
@@ -617,6 +644,13 @@ impl HeaderChain {
 				},
 			};
 
+			// only a gapless sequence of section roots can be hardcoded.
+			if bloom_bits.len() == cht_num {
+				if let Some(root) = self.bloom_bits_root(cht_num) {
+					bloom_bits.push(root);
+				}
+			}
+
 			chts.push(cht);
 			cht_num += 1;
 		}
@@ -753,6 +787,21 @@ impl HeaderChain {
 		}
 	}
 
+	/// Get the root of the nth bloom-bit section, if it's been computed.
+	///
+	/// Sections cover the same blocks as the CHT with the same number and
+	/// are computed along with it. Sections of CHTs obtained through
+	/// hardcoded sync are only known if the hardcoded sync includes them.
+	pub fn bloom_bits_root(&self, n: usize) -> Option<H256> {
+		match self.db.get(self.col, bloom_bits_key(n as u64).as_bytes()) {
+			Ok(db_fetch) => db_fetch.map(|bytes| ::rlp::decode(&bytes).expect("decoding value from db failed")),
+			Err(e) => {
+				warn!(target: "chain", "Error reading from database: {}", e);
+				None
+			}
+		}
+	}
+
 	/// Get the genesis hash.
 	pub fn genesis_hash(&self) -> H256 {
 		self.genesis_header.hash()
@@ -916,6 +965,8 @@ mod tests {
 		assert!(chain.block_header(BlockId::Number(9000)).is_some());
 		assert!(chain.cht_root(2).is_some());
 		assert!(chain.cht_root(3).is_none());
+		assert!(chain.bloom_bits_root(2).is_some());
+		assert!(chain.bloom_bits_root(3).is_none());
 	}
 
 	#[test]
@@ -1225,6 +1276,7 @@ mod tests {
 
 		let hardcoded_sync = chain.read_hardcoded_sync().expect("failed reading hardcoded sync").expect("failed unwrapping hardcoded sync");
 		assert_eq!(hardcoded_sync.chts.len(), 3);
+		assert_eq!(hardcoded_sync.bloom_bits.len(), 3);
 		assert_eq!(hardcoded_sync.total_difficulty, total_difficulty);
 		let decoded: Header = hardcoded_sync.header.decode().expect("decoding failed");
 		assert_eq!(decoded.number(), h_num);
@@ -127,6 +127,9 @@ pub trait LightChainClient: Send + Sync {
 	/// Get the `i`th CHT root.
 	fn cht_root(&self, i: usize) -> Option<H256>;
 
+	/// Get the root of the `i`th bloom-bit section.
+	fn bloom_bits_root(&self, i: usize) -> Option<H256>;
+
 	/// Get the EIP-86 transition block number.
 	fn eip86_transition(&self) -> BlockNumber;
 
@@ -288,6 +291,11 @@ impl<T: ChainDataFetcher> Client<T> {
 		self.chain.cht_root(i)
 	}
 
+	/// Get the root of the `i`th bloom-bit section.
+	pub fn bloom_bits_root(&self, i: usize) -> Option<H256> {
+		self.chain.bloom_bits_root(i)
+	}
+
 	/// Import a set of pre-verified headers from the queue.
 	pub fn import_verified(&self) {
 		const MAX: usize = 256;
@@ -586,6 +594,10 @@ impl<T: ChainDataFetcher> LightChainClient for Client<T> {
 		Client::cht_root(self, i)
 	}
 
+	fn bloom_bits_root(&self, i: usize) -> Option<H256> {
+		Client::bloom_bits_root(self, i)
+	}
+
 	fn eip86_transition(&self) -> BlockNumber {
 		self.engine().params().eip86_transition
 	}
@@ -625,4 +637,10 @@ impl<T: ChainDataFetcher> ::ethcore::client::EngineClient for Client<T> {
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header> {
 		Client::block_header(self, id)
 	}
+
+	fn engine_data(&self, _key: &[u8]) -> Option<Vec<u8>> {
+		None
+	}
+
+	fn write_engine_data(&self, _values: Vec<(Vec<u8>, Option<Vec<u8>>)>) { }
 }
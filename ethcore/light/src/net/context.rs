@@ -44,6 +44,12 @@ pub trait IoContext {
 
 	/// Persistent peer id
 	fn persistent_peer_id(&self, peer: PeerId) -> Option<NodeId>;
+
+	/// Get a peer's persistent reputation.
+	fn peer_reputation(&self, peer: PeerId) -> i32;
+
+	/// Adjust a peer's persistent reputation.
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32);
 }
 
 
@@ -77,6 +83,14 @@ impl<T> IoContext for T where T: ?Sized + NetworkContext {
 	fn persistent_peer_id(&self, peer: PeerId) -> Option<NodeId> {
 		self.session_info(peer).and_then(|info| info.id)
 	}
+
+	fn peer_reputation(&self, peer: PeerId) -> i32 {
+		NetworkContext::peer_reputation(self, peer)
+	}
+
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32) {
+		NetworkContext::adjust_peer_reputation(self, peer, delta);
+	}
 }
 
 /// Basic context for the protocol.
@@ -101,6 +115,12 @@ pub trait BasicContext {
 
 	/// Disable a peer.
 	fn disable_peer(&self, peer: PeerId);
+
+	/// Get a peer's persistent reputation.
+	fn peer_reputation(&self, peer: PeerId) -> i32;
+
+	/// Adjust a peer's persistent reputation.
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32);
 }
 
 /// Context for a protocol event which has a peer ID attached.
@@ -141,6 +161,14 @@ impl<'a> BasicContext for TickCtx<'a> {
 	fn disable_peer(&self, peer: PeerId) {
 		self.io.disable_peer(peer);
 	}
+
+	fn peer_reputation(&self, peer: PeerId) -> i32 {
+		self.io.peer_reputation(peer)
+	}
+
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32) {
+		self.io.adjust_peer_reputation(peer, delta);
+	}
 }
 
 /// Concrete implementation of `EventContext` over the light protocol struct and
@@ -174,6 +202,14 @@ impl<'a> BasicContext for Ctx<'a> {
 	fn disable_peer(&self, peer: PeerId) {
 		self.io.disable_peer(peer);
 	}
+
+	fn peer_reputation(&self, peer: PeerId) -> i32 {
+		self.io.peer_reputation(peer)
+	}
+
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32) {
+		self.io.adjust_peer_reputation(peer, delta);
+	}
 }
 
 impl<'a> EventContext for Ctx<'a> {
@@ -66,6 +66,8 @@ pub enum Error {
 	Overburdened,
 	/// No handler kept the peer.
 	RejectedByHandlers,
+	/// The limit of served light clients has been reached.
+	TooManyClients,
 }
 
 impl Error {
@@ -86,6 +88,7 @@ impl Error {
 			Error::BadProtocolVersion => Punishment::Disable,
 			Error::Overburdened => Punishment::None,
 			Error::RejectedByHandlers => Punishment::Disconnect,
+			Error::TooManyClients => Punishment::Disconnect,
 		}
 	}
 }
@@ -119,6 +122,7 @@ impl fmt::Display for Error {
 			Error::BadProtocolVersion => write!(f, "Bad protocol version in handshake"),
 			Error::Overburdened => write!(f, "Peer overburdened"),
 			Error::RejectedByHandlers => write!(f, "No handler kept this peer"),
+			Error::TooManyClients => write!(f, "Too many light clients"),
 		}
 	}
 }
@@ -63,6 +63,7 @@ fn hardcoded_serve_time(kind: Kind) -> Duration {
 		Kind::Code => 1_500_000,
 		Kind::Execution => 250, // per gas.
 		Kind::Signal => 500_000,
+		Kind::BloomBits => 2_500_000,
 	})
 }
 
@@ -21,8 +21,9 @@
 use transaction::UnverifiedTransaction;
 
 use io::TimerToken;
-use network::{HostInfo, NetworkProtocolHandler, NetworkContext, PeerId};
+use network::{HostInfo, NetworkProtocolHandler, NetworkContext, PeerId, NodeId};
 use rlp::{RlpStream, Rlp};
+use ethcore::ids::BlockId;
 use ethereum_types::{H256, U256};
 use kvdb::DBValue;
 use parking_lot::{Mutex, RwLock};
@@ -34,6 +35,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::ops::{BitOr, BitAnd, Not};
 
+use bloom_bits;
 use provider::Provider;
 use request::{Request, NetworkRequests as Requests, Response};
 
@@ -81,13 +83,17 @@ const UPDATE_INTERVAL: Duration = Duration::from_millis(5000);
 /// Packet count for PIP.
 const PACKET_COUNT_V1: u8 = 9;
 
+/// Packet count for PIP v2, which only adds the bloom bits request kind.
+const PACKET_COUNT_V2: u8 = 9;
+
 /// Supported protocol versions.
 pub const PROTOCOL_VERSIONS: &'static [(u8, u8)] = &[
 	(1, PACKET_COUNT_V1),
+	(2, PACKET_COUNT_V2),
 ];
 
 /// Max protocol version.
-pub const MAX_PROTOCOL_VERSION: u8 = 1;
+pub const MAX_PROTOCOL_VERSION: u8 = 2;
 
 
 // packet ID definitions.
@@ -131,6 +137,7 @@ mod timeout {
 	pub const HEADER_PROOF: u64 = 100;
 	pub const TRANSACTION_PROOF: u64 = 1000; // per gas?
 	pub const EPOCH_SIGNAL: u64 = 200;
+	pub const BLOOM_BITS: u64 = 250;
 }
 
 /// A request id.
@@ -153,6 +160,7 @@ impl fmt::Display for ReqId {
 struct PendingPeer {
 	sent_head: H256,
 	last_update: Instant,
+	priority: bool,
 }
 
 /// Relevant data to each peer. Not accessible publicly, only `pub` due to
@@ -170,6 +178,7 @@ pub struct Peer {
 	skip_update: bool,
 	local_flow: Arc<FlowParams>,
 	awaiting_acknowledge: Option<(Instant, Arc<FlowParams>)>,
+	priority: bool, // served for free and not counted against the client limit.
 }
 
 /// Whether or not a peer was kept by a handler
@@ -264,6 +273,15 @@ pub struct Config {
 	pub max_stored_seconds: u64,
 	/// How much of the total load capacity each peer should be allowed to take.
 	pub load_share: f64,
+	/// How many credits each peer recharges per second.
+	/// Request costs are measured against `request_credits::DEFAULT_RECHARGE_RATE`.
+	pub recharge_rate: u64,
+	/// The maximum number of light clients to serve at once, not counting
+	/// priority peers. `None` for no limit beyond that of the network.
+	pub max_client_peers: Option<usize>,
+	/// Node IDs of peers to serve without charging for requests, even when
+	/// the client limit has been reached.
+	pub priority_peers: HashSet<NodeId>,
 }
 
 impl Default for Config {
@@ -274,6 +292,9 @@ impl Default for Config {
 		Config {
 			max_stored_seconds: MAX_ACCUMULATED,
 			load_share: LOAD_SHARE,
+			recharge_rate: request_credits::DEFAULT_RECHARGE_RATE,
+			max_client_peers: None,
+			priority_peers: HashSet::new(),
 		}
 	}
 }
@@ -364,6 +385,7 @@ pub struct LightProtocol {
 	req_id: AtomicUsize,
 	sample_store: Box<SampleStore>,
 	load_distribution: LoadDistribution,
+	bloom_bits: Mutex<bloom_bits::SectionCache>,
 }
 
 impl LightProtocol {
@@ -378,6 +400,7 @@ impl LightProtocol {
 			|kind| load_distribution.expected_time(kind),
 			params.config.load_share,
 			Duration::from_secs(params.config.max_stored_seconds),
+			params.config.recharge_rate,
 		);
 
 		LightProtocol {
@@ -393,6 +416,7 @@ impl LightProtocol {
 			req_id: AtomicUsize::new(0),
 			sample_store: sample_store,
 			load_distribution: load_distribution,
+			bloom_bits: Mutex::new(Default::default()),
 		}
 	}
 
@@ -712,12 +736,14 @@ impl LightProtocol {
 		};
 
 		let capabilities = self.capabilities.read().clone();
-		let local_flow = self.flow_params.read();
-		let status_packet = status::write_handshake(&status, &capabilities, Some(&**local_flow));
+		let priority = self.is_priority(*peer, io);
+		let local_flow = self.local_flow(priority, proto_version);
+		let status_packet = status::write_handshake(&status, &capabilities, Some(&local_flow));
 
 		self.pending_peers.write().insert(*peer, PendingPeer {
 			sent_head: chain_info.best_block_hash,
 			last_update: Instant::now(),
+			priority: priority,
 		});
 
 		trace!(target: "pip", "Sending status to peer {}", peer);
@@ -768,6 +794,51 @@ impl LightProtocol {
 		}
 	}
 
+	// whether the peer is one we serve with priority.
+	fn is_priority(&self, peer: PeerId, io: &IoContext) -> bool {
+		!self.config.priority_peers.is_empty() && io.persistent_peer_id(peer)
+			.map_or(false, |id| self.config.priority_peers.contains(&id))
+	}
+
+	// prove the vector of a bloom bit, building its section only if it isn't cached.
+	fn bloom_bits_proof(&self, req: ::request::CompleteBloomBitsRequest) -> Option<::request::BloomBitsResponse> {
+		let last_hash = match self.provider.block_header(BlockId::Number(bloom_bits::last_number(req.section))) {
+			Some(header) => header.hash(),
+			None => return None, // incomplete section.
+		};
+
+		let mut cache = self.bloom_bits.lock();
+		let section = match cache.get_or_build(last_hash, || self.provider.bloom_bits_section(req.section)) {
+			Some(section) => section,
+			None => return None,
+		};
+
+		match section.prove(req.bit, 0) {
+			Ok(Some(proof)) => {
+				let bits = bloom_bits::check_proof(&proof, req.bit, section.root())
+					.expect("proof generated from the same trie; qed");
+
+				Some(::request::BloomBitsResponse {
+					proof: proof,
+					bits: bits,
+				})
+			}
+			Ok(None) => None,
+			Err(e) => {
+				debug!(target: "pip", "Error looking up bit in in-memory bloom-bit section: {}", e);
+				None
+			}
+		}
+	}
+
+	// the flow params a peer of the given protocol version is served with.
+	fn local_flow(&self, priority: bool, proto_version: u8) -> FlowParams {
+		match priority {
+			true => FlowParams::free().for_protocol_version(proto_version),
+			false => self.flow_params.read().for_protocol_version(proto_version),
+		}
+	}
+
 	fn begin_new_cost_period(&self, io: &IoContext) {
 		self.load_distribution.end_period(&*self.sample_store);
 
@@ -775,25 +846,31 @@ impl LightProtocol {
 			|kind| self.load_distribution.expected_time(kind),
 			self.config.load_share,
 			Duration::from_secs(self.config.max_stored_seconds),
+			self.config.recharge_rate,
 		));
 		*self.flow_params.write() = new_params.clone();
 
 		let peers = self.peers.read();
 		let now = Instant::now();
 
-		let packet_body = {
-			let mut stream = RlpStream::new_list(3);
-			stream.append(new_params.limit())
-				.append(new_params.recharge_rate())
-				.append(new_params.cost_table());
-			stream.out()
-		};
-
 		for (peer_id, peer_info) in peers.iter() {
 			let mut peer_info = peer_info.lock();
 
-			io.send(*peer_id, packet::UPDATE_CREDITS, packet_body.clone());
-			peer_info.awaiting_acknowledge = Some((now.clone(), new_params.clone()));
+			// priority peers are always served for free.
+			if peer_info.priority { continue }
+
+			// peers may only be given costs for the request kinds they know about.
+			let peer_params = Arc::new(new_params.for_protocol_version(peer_info.status.protocol_version as u8));
+			let packet_body = {
+				let mut stream = RlpStream::new_list(3);
+				stream.append(peer_params.limit())
+					.append(peer_params.recharge_rate())
+					.append(peer_params.cost_table());
+				stream.out()
+			};
+
+			io.send(*peer_id, packet::UPDATE_CREDITS, packet_body);
+			peer_info.awaiting_acknowledge = Some((now.clone(), peer_params));
 		}
 	}
 }
@@ -823,8 +900,21 @@ impl LightProtocol {
 			return Err(Error::BadProtocolVersion);
 		}
 
+		// peers which don't serve headers are light clients we'd be serving.
+		if !capabilities.serve_headers && !pending.priority {
+			if let Some(max_clients) = self.config.max_client_peers {
+				let clients = self.peers.read().values()
+					.filter(|p| { let p = p.lock(); !p.capabilities.serve_headers && !p.priority })
+					.count();
+
+				if clients >= max_clients {
+					return Err(Error::TooManyClients);
+				}
+			}
+		}
+
 		let remote_flow = flow_params.map(|params| (params.create_credits(), params));
-		let local_flow = self.flow_params.read().clone();
+		let local_flow = Arc::new(self.local_flow(pending.priority, status.protocol_version as u8));
 
 		self.peers.write().insert(*peer, Mutex::new(Peer {
 			local_credits: local_flow.create_credits(),
@@ -839,6 +929,7 @@ impl LightProtocol {
 			skip_update: false,
 			local_flow: local_flow,
 			awaiting_acknowledge: None,
+			priority: pending.priority,
 		}));
 
 		let any_kept = self.handlers.iter().map(
@@ -958,6 +1049,7 @@ impl LightProtocol {
 				CompleteRequest::Code(req) => self.provider.contract_code(req).map(Response::Code),
 				CompleteRequest::Execution(req) => self.provider.transaction_proof(req).map(Response::Execution),
 				CompleteRequest::Signal(req) => self.provider.epoch_signal(req).map(Response::Signal),
+				CompleteRequest::BloomBits(req) => self.bloom_bits_proof(req).map(Response::BloomBits),
 			}
 		});
 
@@ -33,6 +33,9 @@ use rlp::{Rlp, RlpStream, Decodable, Encodable, DecoderError};
 use ethereum_types::U256;
 use std::time::{Duration, Instant};
 
+/// Default number of credits peers recharge per second.
+pub const DEFAULT_RECHARGE_RATE: u64 = 100_000_000;
+
 /// Credits value.
 ///
 /// Produced and recharged using `FlowParams`.
@@ -93,6 +96,7 @@ pub struct CostTable {
 	header_proof: Option<U256>,
 	transaction_proof: Option<U256>, // cost per gas.
 	epoch_signal: Option<U256>,
+	bloom_bits: Option<U256>,
 }
 
 impl CostTable {
@@ -111,6 +115,7 @@ impl CostTable {
 			incr_if_set(&self.header_proof);
 			incr_if_set(&self.transaction_proof);
 			incr_if_set(&self.epoch_signal);
+			incr_if_set(&self.bloom_bits);
 		}
 
 		num_set
@@ -132,6 +137,7 @@ impl Default for CostTable {
 			header_proof: Some(15000.into()),
 			transaction_proof: Some(2.into()),
 			epoch_signal: Some(10000.into()),
+			bloom_bits: Some(25000.into()),
 		}
 	}
 }
@@ -158,6 +164,7 @@ impl Encodable for CostTable {
 		append_cost(s, &self.header_proof, request::Kind::HeaderProof);
 		append_cost(s, &self.transaction_proof, request::Kind::Execution);
 		append_cost(s, &self.epoch_signal, request::Kind::Signal);
+		append_cost(s, &self.bloom_bits, request::Kind::BloomBits);
 	}
 }
 
@@ -175,6 +182,7 @@ impl Decodable for CostTable {
 		let mut header_proof = None;
 		let mut transaction_proof = None;
 		let mut epoch_signal = None;
+		let mut bloom_bits = None;
 
 		for cost_list in rlp.iter().skip(1) {
 			let cost = cost_list.val_at(1)?;
@@ -189,6 +197,7 @@ impl Decodable for CostTable {
 				request::Kind::HeaderProof => header_proof = Some(cost),
 				request::Kind::Execution => transaction_proof = Some(cost),
 				request::Kind::Signal => epoch_signal = Some(cost),
+				request::Kind::BloomBits => bloom_bits = Some(cost),
 			}
 		}
 
@@ -204,6 +213,7 @@ impl Decodable for CostTable {
 			header_proof: header_proof,
 			transaction_proof: transaction_proof,
 			epoch_signal: epoch_signal,
+			bloom_bits: bloom_bits,
 		};
 
 		if table.costs_set() == 0 {
@@ -235,17 +245,19 @@ impl FlowParams {
 
 	/// Create new flow parameters from ,
 	/// proportion of total capacity which should be given to a peer,
-	/// and stored capacity a peer can accumulate.
+	/// stored capacity a peer can accumulate, and credits recharged per second.
+	///
+	/// Costs are measured against the default recharge rate, so a peer recharging
+	/// faster than that may use more than its share of the total capacity.
 	pub fn from_request_times<F: Fn(::request::Kind) -> Duration>(
 		request_time: F,
 		load_share: f64,
-		max_stored: Duration
+		max_stored: Duration,
+		recharge: u64,
 	) -> Self {
 		use request::Kind;
 
 		let load_share = load_share.abs();
-
-		let recharge: u64 = 100_000_000;
 		let max = {
 			let sec = max_stored.as_secs().saturating_mul(recharge);
 			let nanos = (max_stored.subsec_nanos() as u64).saturating_mul(recharge) / 1_000_000_000;
@@ -264,8 +276,8 @@ impl FlowParams {
 			let serve_per_second = second_duration * load_share;
 			let serve_per_second = serve_per_second.max(1.0 / 10_000.0);
 
-			// as a percentage of the recharge per second.
-			Some(U256::from((recharge as f64 / serve_per_second) as u64))
+			// as a percentage of the default recharge per second.
+			Some(U256::from((DEFAULT_RECHARGE_RATE as f64 / serve_per_second) as u64))
 		};
 
 		let costs = CostTable {
@@ -280,6 +292,7 @@ impl FlowParams {
 			header_proof: cost_for_kind(Kind::HeaderProof),
 			transaction_proof: cost_for_kind(Kind::Execution),
 			epoch_signal: cost_for_kind(Kind::Signal),
+			bloom_bits: cost_for_kind(Kind::BloomBits),
 		};
 
 		FlowParams {
@@ -306,11 +319,23 @@ impl FlowParams {
 				code: free_cost.clone(),
 				header_proof: free_cost.clone(),
 				transaction_proof: free_cost.clone(),
-				epoch_signal: free_cost,
+				epoch_signal: free_cost.clone(),
+				bloom_bits: free_cost,
 			}
 		}
 	}
 
+	/// Restrict the parameters to the request kinds known to the given
+	/// protocol version. Peers fail to decode cost tables with unknown kinds.
+	pub fn for_protocol_version(&self, version: u8) -> Self {
+		let mut params = self.clone();
+		if version < 2 {
+			params.costs.bloom_bits = None;
+		}
+
+		params
+	}
+
 	/// Get a reference to the credit limit.
 	pub fn limit(&self) -> &U256 { &self.limit }
 
@@ -337,6 +362,7 @@ impl FlowParams {
 			Request::Code(_) => self.costs.code,
 			Request::Execution(ref req) => self.costs.transaction_proof.map(|c| c * req.gas),
 			Request::Signal(_) => self.costs.epoch_signal,
+			Request::BloomBits(_) => self.costs.bloom_bits,
 		}
 	}
 
@@ -412,6 +438,22 @@ mod tests {
 		assert_eq!(costs, new_costs);
 	}
 
+	#[test]
+	fn v1_cost_table_omits_bloom_bits() {
+		let request = Request::BloomBits(::request::IncompleteBloomBitsRequest {
+			section: 0.into(),
+			bit: 0,
+		});
+
+		let params = FlowParams::default();
+		assert!(params.for_protocol_version(2).compute_cost(&request).is_some());
+
+		let v1_params = params.for_protocol_version(1);
+		assert!(v1_params.compute_cost(&request).is_none());
+		let costs: CostTable = ::rlp::decode(&*::rlp::encode(v1_params.cost_table())).unwrap();
+		assert_eq!(&costs, v1_params.cost_table());
+	}
+
 	#[test]
 	fn credits_mechanism() {
 		use std::thread;
@@ -436,21 +478,45 @@ mod tests {
 			|_| Duration::new(0, 10_000),
 			0.05,
 			Duration::from_secs(60),
+			DEFAULT_RECHARGE_RATE,
 		);
 
 		let flow_params2 = FlowParams::from_request_times(
 			|_| Duration::new(0, 10_000),
 			0.1,
 			Duration::from_secs(60),
+			DEFAULT_RECHARGE_RATE,
 		);
 
 		let flow_params3 = FlowParams::from_request_times(
 			|_| Duration::new(0, 5_000),
 			0.05,
 			Duration::from_secs(60),
+			DEFAULT_RECHARGE_RATE,
 		);
 
 		assert_eq!(flow_params2.costs, flow_params3.costs);
 		assert_eq!(flow_params.costs.headers.unwrap(), flow_params2.costs.headers.unwrap() * 2u32);
 	}
+
+	#[test]
+	fn scale_by_recharge_rate() {
+		let flow_params = FlowParams::from_request_times(
+			|_| Duration::new(0, 10_000),
+			0.05,
+			Duration::from_secs(60),
+			DEFAULT_RECHARGE_RATE,
+		);
+
+		let flow_params2 = FlowParams::from_request_times(
+			|_| Duration::new(0, 10_000),
+			0.05,
+			Duration::from_secs(60),
+			DEFAULT_RECHARGE_RATE * 2,
+		);
+
+		assert_eq!(*flow_params2.recharge_rate(), *flow_params.recharge_rate() * 2u32);
+		assert_eq!(*flow_params2.limit(), *flow_params.limit() * 2u32);
+		assert_eq!(flow_params2.costs, flow_params.costs);
+	}
 }
@@ -89,6 +89,12 @@ impl IoContext for Expect {
 	fn persistent_peer_id(&self, _peer: PeerId) -> Option<NodeId> {
 		None
 	}
+
+	fn peer_reputation(&self, _peer: PeerId) -> i32 {
+		0
+	}
+
+	fn adjust_peer_reputation(&self, _peer: PeerId, _delta: i32) {}
 }
 
 // can't implement directly for Arc due to cross-crate orphan rules.
@@ -167,6 +173,10 @@ impl Provider for TestProvider {
 		None
 	}
 
+	fn bloom_bits_section(&self, _section: u64) -> Option<::bloom_bits::BloomBits<::memorydb::MemoryDB>> {
+		None
+	}
+
 	fn epoch_signal(&self, _req: request::CompleteSignalRequest) -> Option<request::SignalResponse> {
 		Some(request::SignalResponse {
 			signal: vec![1, 2, 3, 4],
@@ -210,7 +220,7 @@ fn setup(capabilities: Capabilities) -> (Arc<TestProviderInner>, LightProtocol)
 
 fn status(chain_info: BlockChainInfo) -> Status {
 	Status {
-		protocol_version: 1,
+		protocol_version: super::MAX_PROTOCOL_VERSION as u32,
 		network_id: 2,
 		head_td: chain_info.total_difficulty,
 		head_hash: chain_info.best_block_hash,
@@ -278,6 +288,34 @@ fn credit_overflow() {
 	proto.handle_packet(&Expect::Punish(1), &1, packet::REQUEST, &request);
 }
 
+#[test]
+fn client_limit() {
+	let capabilities = capabilities();
+
+	let (provider, mut proto) = setup(capabilities.clone());
+	proto.config.max_client_peers = Some(1);
+
+	let status = status(provider.client.chain_info());
+	let client_capabilities = Capabilities {
+		serve_headers: false,
+		serve_chain_since: None,
+		serve_state_since: None,
+		tx_relay: false,
+	};
+
+	for peer in 1..4 {
+		let packet_body = write_handshake(&status, &capabilities, &proto);
+		proto.on_connect(&peer, &Expect::Send(peer, packet::STATUS, packet_body));
+	}
+
+	// servers aren't counted against the limit.
+	let client_status = write_handshake(&status, &client_capabilities, &proto);
+	let server_status = write_handshake(&status, &capabilities, &proto);
+	proto.handle_packet(&Expect::Nothing, &1, packet::STATUS, &client_status);
+	proto.handle_packet(&Expect::Nothing, &2, packet::STATUS, &server_status);
+	proto.handle_packet(&Expect::Punish(3), &3, packet::STATUS, &client_status);
+}
+
 // test the basic request types -- these just make sure that requests are parsed
 // and sent to the provider correctly as well as testing response formatting.
 
@@ -678,6 +716,7 @@ fn id_guard() {
 		skip_update: false,
 		local_flow: flow_params,
 		awaiting_acknowledge: None,
+		priority: false,
 	}));
 
 	// first, malformed responses.
@@ -29,7 +29,7 @@ use futures::{Poll, Future};
 use futures::sync::oneshot::{self, Receiver, Canceled};
 use network::PeerId;
 use parking_lot::{RwLock, Mutex};
-use rand;
+use rand::{self, Rng};
 
 use net::{
 	self, Handler, PeerStatus, Status, Capabilities,
@@ -49,6 +49,12 @@ pub mod request;
 /// The result of execution
 pub type ExecutionResult = Result<Executed, ExecutionError>;
 
+// reputation adjustments for peers serving requests.
+const USEFUL_RESPONSE_REPUTATION: i32 = 1;
+const BAD_RESPONSE_REPUTATION: i32 = -50;
+// weight of the peer with the worst reputation when choosing peers at random.
+const MIN_PEER_WEIGHT: i64 = 100;
+
 // relevant peer info.
 struct Peer {
 	status: Status,
@@ -79,6 +85,7 @@ struct Pending {
 	requests: basic_request::Batch<CheckedRequest>,
 	net_requests: basic_request::Batch<NetworkRequest>,
 	required_capabilities: Capabilities,
+	required_protocol_version: u32,
 	responses: Vec<Response>,
 	sender: oneshot::Sender<Vec<Response>>,
 }
@@ -177,8 +184,10 @@ impl Pending {
 
 		// update pending fields.
 		let capabilities = guess_capabilities(&self.requests[num_answered..]);
+		let protocol_version = required_protocol_version(&self.requests[num_answered..]);
 		self.net_requests = builder.build();
 		self.required_capabilities = capabilities;
+		self.required_protocol_version = protocol_version;
 	}
 }
 
@@ -207,6 +216,8 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 			CheckedRequest::TransactionIndex(_, _) => {} // hashes yield no info.
 			CheckedRequest::Signal(_, _) =>
 				caps.serve_headers = true,
+			CheckedRequest::BloomBits(_, _) =>
+				caps.serve_headers = true,
 			CheckedRequest::Body(ref req, _) => if let Ok(ref hdr) = req.0.as_ref() {
 				update_since(&mut caps.serve_chain_since, hdr.number());
 			},
@@ -222,12 +233,41 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 			CheckedRequest::Execution(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
 				update_since(&mut caps.serve_state_since, hdr.number());
 			},
+			CheckedRequest::ExecutionProof(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
+				update_since(&mut caps.serve_state_since, hdr.number());
+			},
 		}
 	}
 
 	caps
 }
 
+// helper to find the minimal protocol version of peers which can serve a given batch
+// of network requests. Bloom-bit sections are only served since PIP v2.
+fn required_protocol_version(requests: &[CheckedRequest]) -> u32 {
+	let needs_bloom_bits = requests.iter().any(|request| match *request {
+		CheckedRequest::BloomBits(_, _) => true,
+		_ => false,
+	});
+
+	if needs_bloom_bits { 2 } else { 1 }
+}
+
+// orders peers randomly, the chance of a peer to come first is proportional to its weight:
+// its reputation above the worst one plus `MIN_PEER_WEIGHT`.
+fn order_by_reputation<R: Rng, T>(rng: &mut R, peers: Vec<(T, i32)>) -> Vec<T> {
+	let worst = peers.iter().map(|&(_, reputation)| reputation).min().unwrap_or(0);
+	let mut keyed: Vec<(f64, T)> = peers.into_iter()
+		.map(|(peer, reputation)| {
+			let weight = (i64::from(reputation) - i64::from(worst) + MIN_PEER_WEIGHT) as f64;
+			(rng.gen::<f64>().powf(1.0 / weight), peer)
+		})
+		.collect();
+
+	keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(cmp::Ordering::Equal));
+	keyed.into_iter().map(|(_, peer)| peer).collect()
+}
+
 /// A future extracting the concrete output type of the generic adapter
 /// from a vector of responses.
 pub struct OnResponses<T: request::RequestAdapter> {
@@ -319,11 +359,13 @@ impl OnDemand {
 		let requests = builder.build();
 		let net_requests = requests.clone().map_requests(|req| req.into_net_request());
 		let capabilities = guess_capabilities(requests.requests());
+		let protocol_version = required_protocol_version(requests.requests());
 
 		self.submit_pending(ctx, Pending {
 			requests: requests,
 			net_requests: net_requests,
 			required_capabilities: capabilities,
+			required_protocol_version: protocol_version,
 			responses: responses,
 			sender: sender,
 		});
@@ -362,16 +404,21 @@ impl OnDemand {
 		// iterate over all pending requests, and check them for hang-up.
 		// then, try and find a peer who can serve it.
 		let peers = self.peers.read();
+
+		// peers are tried in random order, peers with better reputation are likely to come first.
+		let ordered_peers = order_by_reputation(
+			&mut rand::thread_rng(),
+			peers.iter().map(|(id, peer)| ((id, peer), ctx.peer_reputation(*id))).collect(),
+		);
+
 		*pending = ::std::mem::replace(&mut *pending, Vec::new()).into_iter()
 			.filter(|pending| !pending.sender.is_canceled())
 			.filter_map(|pending| {
-				// the peer we dispatch to is chosen randomly
-				let num_peers = peers.len();
-				let rng = rand::random::<usize>() % cmp::max(num_peers, 1);
-				for (peer_id, peer) in peers.iter().chain(peers.iter()).skip(rng).take(num_peers) {
+				for &(peer_id, peer) in &ordered_peers {
 					// TODO: see which requests can be answered by the cache?
 
-					if !peer.can_fulfill(&pending.required_capabilities) {
+					if !peer.can_fulfill(&pending.required_capabilities) ||
+						peer.status.protocol_version < pending.required_protocol_version {
 						continue
 					}
 
@@ -463,16 +510,23 @@ impl Handler for OnDemand {
 		//   1. ensure verification data filled.
 		//   2. pending.requests.supply_response
 		//   3. if extracted on-demand response, keep it for later.
+		let mut bad_response = false;
 		for response in responses {
 			if let Err(e) = pending.supply_response(&*self.cache, response) {
 				let peer = ctx.peer();
 				debug!(target: "on_demand", "Peer {} gave bad response: {:?}", peer, e);
+				ctx.adjust_peer_reputation(peer, BAD_RESPONSE_REPUTATION);
 				ctx.disable_peer(peer);
+				bad_response = true;
 
 				break;
 			}
 		}
 
+		if !bad_response && !responses.is_empty() {
+			ctx.adjust_peer_reputation(ctx.peer(), USEFUL_RESPONSE_REPUTATION);
+		}
+
 		pending.fill_unanswered();
 		self.submit_pending(ctx.as_basic(), pending);
 	}
@@ -60,8 +60,12 @@ pub enum Request {
 	Code(Code),
 	/// A request for proof of execution.
 	Execution(TransactionProof),
+	/// A request for the state items proving execution.
+	ExecutionProof(ExecutionProof),
 	/// A request for epoch change signal.
 	Signal(Signal),
+	/// A request for a bloom bit vector.
+	BloomBits(BloomBits),
 }
 
 /// A request argument.
@@ -143,7 +147,9 @@ impl_single!(Body, Body, encoded::Block);
 impl_single!(Account, Account, Option<BasicAccount>);
 impl_single!(Code, Code, Bytes);
 impl_single!(Execution, TransactionProof, super::ExecutionResult);
+impl_single!(ExecutionProof, ExecutionProof, Vec<DBValue>);
 impl_single!(Signal, Signal, Vec<u8>);
+impl_single!(BloomBits, BloomBits, Bytes);
 
 macro_rules! impl_args {
 	() => {
@@ -253,7 +259,9 @@ pub enum CheckedRequest {
 	Account(Account, net_request::IncompleteAccountRequest),
 	Code(Code, net_request::IncompleteCodeRequest),
 	Execution(TransactionProof, net_request::IncompleteExecutionRequest),
-	Signal(Signal, net_request::IncompleteSignalRequest)
+	ExecutionProof(ExecutionProof, net_request::IncompleteExecutionRequest),
+	Signal(Signal, net_request::IncompleteSignalRequest),
+	BloomBits(BloomBits, net_request::IncompleteBloomBitsRequest),
 }
 
 impl From<Request> for CheckedRequest {
@@ -318,12 +326,31 @@ impl From<Request> for CheckedRequest {
 				};
 				CheckedRequest::Execution(req, net_req)
 			}
+			Request::ExecutionProof(req) => {
+				let net_req = net_request::IncompleteExecutionRequest {
+					block_hash: req.header.field(),
+					from: req.tx.sender(),
+					gas: req.tx.gas,
+					gas_price: req.tx.gas_price,
+					action: req.tx.action.clone(),
+					value: req.tx.value,
+					data: req.tx.data.clone(),
+				};
+				CheckedRequest::ExecutionProof(req, net_req)
+			}
 			Request::Signal(req) => {
 				let net_req = net_request::IncompleteSignalRequest {
 					block_hash: req.hash.into(),
 				};
 				CheckedRequest::Signal(req, net_req)
 			}
+			Request::BloomBits(req) => {
+				let net_req = net_request::IncompleteBloomBitsRequest {
+					section: req.section.into(),
+					bit: req.bit,
+				};
+				CheckedRequest::BloomBits(req, net_req)
+			}
 		}
 	}
 }
@@ -342,7 +369,9 @@ impl CheckedRequest {
 			CheckedRequest::Account(_, req) => NetRequest::Account(req),
 			CheckedRequest::Code(_, req) => NetRequest::Code(req),
 			CheckedRequest::Execution(_, req) => NetRequest::Execution(req),
+			CheckedRequest::ExecutionProof(_, req) => NetRequest::Execution(req),
 			CheckedRequest::Signal(_, req) => NetRequest::Signal(req),
+			CheckedRequest::BloomBits(_, req) => NetRequest::BloomBits(req),
 		}
 	}
 
@@ -357,6 +386,7 @@ impl CheckedRequest {
 			CheckedRequest::Account(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Code(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Execution(ref x, _) => x.header.needs_header(),
+			CheckedRequest::ExecutionProof(ref x, _) => x.header.needs_header(),
 			_ => None,
 		}
 	}
@@ -371,6 +401,7 @@ impl CheckedRequest {
 			CheckedRequest::Account(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Code(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Execution(ref mut x, _) => x.header = HeaderRef::Stored(header),
+			CheckedRequest::ExecutionProof(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			_ => {},
 		}
 	}
@@ -465,7 +496,9 @@ macro_rules! match_me {
 			CheckedRequest::Account($check, $req) => $e,
 			CheckedRequest::Code($check, $req) => $e,
 			CheckedRequest::Execution($check, $req) => $e,
+			CheckedRequest::ExecutionProof($check, $req) => $e,
 			CheckedRequest::Signal($check, $req) => $e,
+			CheckedRequest::BloomBits($check, $req) => $e,
 		}
 	}
 }
@@ -494,7 +527,9 @@ impl IncompleteRequest for CheckedRequest {
 			CheckedRequest::Account(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Code(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Execution(_, ref req) => req.check_outputs(f),
+			CheckedRequest::ExecutionProof(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Signal(_, ref req) => req.check_outputs(f),
+			CheckedRequest::BloomBits(_, ref req) => req.check_outputs(f),
 		}
 	}
 
@@ -516,7 +551,9 @@ impl IncompleteRequest for CheckedRequest {
 			CheckedRequest::Account(_, req) => req.complete().map(CompleteRequest::Account),
 			CheckedRequest::Code(_, req) => req.complete().map(CompleteRequest::Code),
 			CheckedRequest::Execution(_, req) => req.complete().map(CompleteRequest::Execution),
+			CheckedRequest::ExecutionProof(_, req) => req.complete().map(CompleteRequest::Execution),
 			CheckedRequest::Signal(_, req) => req.complete().map(CompleteRequest::Signal),
+			CheckedRequest::BloomBits(_, req) => req.complete().map(CompleteRequest::BloomBits),
 		}
 	}
 
@@ -571,9 +608,15 @@ impl net_request::CheckedRequest for CheckedRequest {
 			CheckedRequest::Execution(ref prover, _) =>
 				expect!((&NetResponse::Execution(ref res), _) =>
 					prover.check_response(cache, &res.items).map(Response::Execution)),
+			CheckedRequest::ExecutionProof(ref prover, _) =>
+				expect!((&NetResponse::Execution(ref res), _) =>
+					prover.check_response(cache, &res.items).map(Response::ExecutionProof)),
 			CheckedRequest::Signal(ref prover, _) =>
 				expect!((&NetResponse::Signal(ref res), _) =>
 					prover.check_response(cache, &res.signal).map(Response::Signal)),
+			CheckedRequest::BloomBits(ref prover, _) =>
+				expect!((&NetResponse::BloomBits(ref res), _) =>
+					prover.check_response(cache, &res.proof, &res.bits).map(Response::BloomBits)),
 		}
 	 }
 }
@@ -599,8 +642,12 @@ pub enum Response {
 	Code(Vec<u8>),
 	/// Response to a request for proved execution.
 	Execution(super::ExecutionResult),
+	/// Response to a request for the state items proving execution.
+	ExecutionProof(Vec<DBValue>),
 	/// Response to a request for epoch change signal.
 	Signal(Vec<u8>),
+	/// Response to a request for a bloom bit vector.
+	BloomBits(Bytes),
 }
 
 impl net_request::ResponseLike for Response {
@@ -908,6 +955,40 @@ impl TransactionProof {
 	}
 }
 
+/// Request for the state items proving execution of a transaction, so it can
+/// be re-executed locally: with tracing, or after other transactions.
+#[derive(Clone)]
+pub struct ExecutionProof {
+	/// The transaction to request proof of.
+	pub tx: SignedTransaction,
+	/// Block header.
+	pub header: HeaderRef,
+	/// Transaction environment info.
+	pub env_info: EnvInfo,
+	/// Consensus engine.
+	pub engine: Arc<EthEngine>,
+}
+
+impl ExecutionProof {
+	/// Check that the proof is enough to execute the transaction, returning its state items.
+	pub fn check_response(&self, _: &Mutex<::cache::Cache>, state_items: &[DBValue]) -> Result<Vec<DBValue>, Error> {
+		let root = self.header.as_ref()?.state_root();
+
+		let proved_execution = state::check_proof(
+			state_items,
+			root,
+			&self.tx,
+			self.engine.machine(),
+			&self.env_info,
+		);
+
+		match proved_execution {
+			ProvedExecution::BadProof => Err(Error::BadProof),
+			_ => Ok(state_items.to_vec()),
+		}
+	}
+}
+
 /// Request for epoch signal.
 /// Provide engine and state-dependent proof checker.
 #[derive(Clone)]
@@ -929,6 +1010,27 @@ impl Signal {
 	}
 }
 
+/// Request for the vector of a bloom bit from a bloom-bit section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomBits {
+	/// The number of the section.
+	pub section: u64,
+	/// The bloom bit to fetch the vector of.
+	pub bit: u64,
+	/// The expected root of the section, computed alongside the CHT.
+	pub root: H256,
+}
+
+impl BloomBits {
+	/// Check a response with a section proof, get the bit vector back.
+	pub fn check_response(&self, _: &Mutex<::cache::Cache>, proof: &[Bytes], bits: &[u8]) -> Result<Bytes, Error> {
+		match ::bloom_bits::check_proof(proof, self.bit, self.root) {
+			Some(ref vector) if &vector[..] == bits => Ok(vector.clone()),
+			_ => Err(Error::BadProof),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -982,6 +1084,34 @@ mod tests {
 		assert!(req.check_response(&cache, &proof[..]).is_ok());
 	}
 
+	#[test]
+	fn check_bloom_bits() {
+		use ::bloom_bits;
+
+		let test_client = TestBlockChainClient::new();
+		test_client.add_blocks(4200, EachBlockWith::Nothing);
+
+		let section = {
+			let fetcher = |id| {
+				let hdr = test_client.block_header(id).unwrap();
+				Some(bloom_bits::BlockInfo {
+					parent_hash: hdr.parent_hash(),
+					log_bloom: hdr.log_bloom(),
+				})
+			};
+
+			bloom_bits::build(1, fetcher).unwrap()
+		};
+
+		let proof = section.prove(42, 0).unwrap().unwrap();
+		let bits = vec![0; bloom_bits::VECTOR_LEN];
+		let req = BloomBits { section: 1, bit: 42, root: section.root() };
+
+		let cache = Mutex::new(make_cache());
+		assert_eq!(req.check_response(&cache, &proof[..], &bits).unwrap(), bits);
+		assert!(req.check_response(&cache, &proof[..], &[1; bloom_bits::VECTOR_LEN]).is_err());
+	}
+
 	#[test]
 	fn check_header_by_hash() {
 		let mut header = Header::new();
@@ -28,7 +28,7 @@ use ::request::{self as basic_request, Response};
 
 use std::sync::Arc;
 
-use super::{request, OnDemand, Peer, HeaderRef};
+use super::{request, order_by_reputation, OnDemand, Peer, HeaderRef};
 
 // useful contexts to give the service.
 enum Context {
@@ -78,6 +78,10 @@ impl BasicContext for Context {
 			_ => panic!("Unexpectedly punished peer."),
 		}
 	}
+
+	fn peer_reputation(&self, _: PeerId) -> i32 { 0 }
+
+	fn adjust_peer_reputation(&self, _: PeerId, _: i32) {}
 }
 
 // test harness.
@@ -195,6 +199,39 @@ fn no_capabilities() {
 	assert_eq!(harness.service.pending.read().len(), 1);
 }
 
+#[test]
+fn bloom_bits_need_pip_v2() {
+	let harness = Harness::create();
+
+	let peer_ids = (10101, 12345);
+	let req_id = ReqId(14426);
+
+	harness.inject_peer(peer_ids.0, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let _recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![request::BloomBits { section: 0, bit: 42, root: H256::default() }.into()]
+	).unwrap();
+
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	harness.service.dispatch_pending(&Context::NoOp);
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	let mut status = dummy_status();
+	status.protocol_version = 2;
+	harness.inject_peer(peer_ids.1, Peer {
+		status: status,
+		capabilities: dummy_capabilities(),
+	});
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_ids.1, req_id));
+	assert_eq!(harness.service.pending.read().len(), 0);
+}
+
 #[test]
 fn reassign() {
 	let harness = Harness::create();
@@ -495,3 +532,17 @@ fn fill_from_cache() {
 
 	assert!(recv.wait().is_ok());
 }
+
+#[test]
+fn peers_ordered_randomly_by_reputation() {
+	use rand::{SeedableRng, XorShiftRng};
+
+	let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+	let first = |rng: &mut XorShiftRng| order_by_reputation(rng, vec![(0, -50), (1, 1000)])[0];
+
+	let reputable_first = (0..1000).filter(|_| first(&mut rng) == 1).count();
+
+	// weights are 100 and 1150, so the reputable peer is first about 92% of the time.
+	assert!(reputable_first > 850);
+	assert!(reputable_first < 1000);
+}
@@ -24,9 +24,11 @@ use ethcore::client::{BlockChainClient, ProvingBlockChainClient, ChainInfo, Bloc
 use ethcore::ids::BlockId;
 use ethcore::encoded;
 use ethereum_types::{H256, U256};
+use memorydb::MemoryDB;
 use parking_lot::RwLock;
 use transaction::PendingTransaction;
 
+use bloom_bits;
 use cht::{self, BlockInfo};
 use client::{LightChainClient, AsLightClient};
 use transaction_queue::TransactionQueue;
@@ -136,6 +138,10 @@ pub trait Provider: Send + Sync {
 
 	/// Provide epoch signal data at given block hash. This should be just the
 	fn epoch_signal(&self, req: request::CompleteSignalRequest) -> Option<request::SignalResponse>;
+
+	/// Build the bloom-bit section with the given number, to prove its bit vectors from.
+	/// Returns `None` if the section isn't complete.
+	fn bloom_bits_section(&self, section: u64) -> Option<bloom_bits::BloomBits<MemoryDB>>;
 }
 
 // Implementation of a light client data provider for a client.
@@ -301,6 +307,15 @@ impl<T: ProvingBlockChainClient + ?Sized> Provider for T {
 			signal: signal,
 		})
 	}
+
+	fn bloom_bits_section(&self, section: u64) -> Option<bloom_bits::BloomBits<MemoryDB>> {
+		let block_info = |id| self.block_header(id).map(|hdr| bloom_bits::BlockInfo {
+			parent_hash: hdr.parent_hash(),
+			log_bloom: hdr.log_bloom(),
+		});
+
+		bloom_bits::build(section, block_info)
+	}
 }
 
 /// The light client "provider" implementation. This wraps a `LightClient` and
@@ -376,6 +391,10 @@ impl<L: AsLightClient + Send + Sync> Provider for LightProvider<L> {
 		None
 	}
 
+	fn bloom_bits_section(&self, _section: u64) -> Option<bloom_bits::BloomBits<MemoryDB>> {
+		None
+	}
+
 	fn ready_transactions(&self, max_len: usize) -> Vec<PendingTransaction> {
 		let chain_info = self.chain_info();
 		let mut transactions = self.txqueue.read()
@@ -413,4 +432,19 @@ mod tests {
 
 		assert!(client.header_proof(req.clone()).is_some());
 	}
+
+	#[test]
+	fn bloom_bits_section() {
+		let client = TestBlockChainClient::new();
+		client.add_blocks(2000, EachBlockWith::Nothing);
+
+		assert!(client.bloom_bits_section(0).is_none());
+
+		client.add_blocks(48, EachBlockWith::Nothing);
+
+		let section = client.bloom_bits_section(0).unwrap();
+		assert_eq!(section.number(), 0);
+		assert!(section.prove(100, 0).unwrap().is_some());
+		assert!(section.prove(2048, 0).unwrap().is_none());
+	}
 }
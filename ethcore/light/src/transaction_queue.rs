@@ -80,6 +80,25 @@ impl<'a> From<&'a PendingTransaction> for TransactionInfo {
 	}
 }
 
+// whether the condition can be checked without access to the state.
+fn is_supported(condition: &Option<Condition>) -> bool {
+	match *condition {
+		None | Some(Condition::Number(_)) | Some(Condition::Timestamp(_)) => true,
+		Some(Condition::Confirmations { .. }) | Some(Condition::MinBalance { .. }) | Some(Condition::Call { .. }) => false,
+	}
+}
+
+// whether the condition of a transaction is met at given block number and timestamp.
+// conditions depending on the state are rejected on import, so they are never met.
+fn is_ready(condition: &Option<Condition>, best_block_number: u64, best_block_timestamp: u64) -> bool {
+	match *condition {
+		None => true,
+		Some(Condition::Number(blk_num)) => blk_num <= best_block_number,
+		Some(Condition::Timestamp(time)) => time <= best_block_timestamp,
+		Some(Condition::Confirmations { .. }) | Some(Condition::MinBalance { .. }) | Some(Condition::Call { .. }) => false,
+	}
+}
+
 // transactions associated with a specific account.
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct AccountTransactions {
@@ -99,6 +118,14 @@ impl AccountTransactions {
 			.unwrap_or_else(|| *self.cur_nonce.value())
 	}
 
+	// the queued transaction with the given nonce, whether current or future.
+	fn by_nonce(&self, nonce: &U256) -> Option<&TransactionInfo> {
+		match self.current.binary_search_by(|x| x.nonce.cmp(nonce)) {
+			Ok(idx) => Some(&self.current[idx]),
+			Err(_) => self.future.get(nonce),
+		}
+	}
+
 	// attempt to move transactions from the future queue into the current queue.
 	fn adjust_future(&mut self) -> Vec<H256> {
 		let mut promoted = Vec::new();
@@ -154,6 +181,9 @@ impl fmt::Debug for TransactionQueue {
 
 impl TransactionQueue {
 	/// Import a pending transaction to be queued.
+	///
+	/// Transactions with conditions depending on the state (which the light client can't check)
+	/// are rejected.
 	pub fn import(&mut self, tx: PendingTransaction) -> Result<ImportDestination, transaction::Error>  {
 		let sender = tx.sender();
 		let hash = tx.hash();
@@ -161,6 +191,7 @@ impl TransactionQueue {
 		let tx_info = TransactionInfo::from(&tx);
 
 		if self.by_hash.contains_key(&hash) { return Err(transaction::Error::AlreadyImported) }
+		if !is_supported(&tx.condition) { return Err(transaction::Error::UnsupportedCondition) }
 
 		let (res, promoted) = match self.by_account.entry(sender) {
 			Entry::Vacant(entry) => {
@@ -253,6 +284,38 @@ impl TransactionQueue {
 		self.by_account.get(address).map(AccountTransactions::next_nonce)
 	}
 
+	/// Get the queued transactions of a sender which would execute in sequence on
+	/// top of a state where its nonce is `state_nonce`, in order of ascending nonce.
+	///
+	/// Transactions before `state_nonce` are assumed to be included already, and
+	/// those after the first nonce gap can't execute until it is filled.
+	pub fn pending_from(&self, address: &Address, state_nonce: U256) -> Vec<PendingTransaction> {
+		let acct_txs = match self.by_account.get(address) {
+			Some(acct_txs) => acct_txs,
+			None => return Vec::new(),
+		};
+
+		let mut pending = Vec::new();
+		let mut nonce = state_nonce;
+		while let Some(info) = acct_txs.by_nonce(&nonce) {
+			match self.by_hash.get(&info.hash) {
+				Some(tx) => pending.push(tx.clone()),
+				None => break,
+			}
+
+			nonce = nonce + 1.into();
+		}
+
+		pending
+	}
+
+	/// Get the next nonce for a given address, given its nonce in the latest known state.
+	/// This is the first nonce from `state_nonce` onwards without a queued transaction,
+	/// so a gap before queued future transactions is filled first.
+	pub fn next_nonce_from(&self, address: &Address, state_nonce: U256) -> U256 {
+		state_nonce + self.pending_from(address, state_nonce).len().into()
+	}
+
 	/// Get all transactions ready to be propagated.
 	/// `best_block_number` and `best_block_timestamp` are used to filter out conditionally
 	/// propagated transactions.
@@ -261,11 +324,8 @@ impl TransactionQueue {
 	pub fn ready_transactions(&self, best_block_number: u64, best_block_timestamp: u64) -> Vec<PendingTransaction> {
 		self.by_account.values()
 			.flat_map(|acct_txs| {
-				acct_txs.current.iter().take_while(|tx| match tx.condition {
-					None => true,
-					Some(Condition::Number(blk_num)) => blk_num <= best_block_number,
-					Some(Condition::Timestamp(time)) => time <= best_block_timestamp,
-				}).map(|info| info.hash)
+				acct_txs.current.iter().take_while(|tx| is_ready(&tx.condition, best_block_number, best_block_timestamp))
+					.map(|info| info.hash)
 			})
 			.filter_map(|hash| match self.by_hash.get(&hash) {
 				Some(tx) => Some(tx.clone()),
@@ -286,11 +346,8 @@ impl TransactionQueue {
 	pub fn future_transactions(&self, best_block_number: u64, best_block_timestamp: u64) -> Vec<PendingTransaction> {
 		self.by_account.values()
 			.flat_map(|acct_txs| {
-				acct_txs.current.iter().skip_while(|tx| match tx.condition {
-					None => true,
-					Some(Condition::Number(blk_num)) => blk_num <= best_block_number,
-					Some(Condition::Timestamp(time)) => time <= best_block_timestamp,
-				}).chain(acct_txs.future.values()).map(|info| info.hash)
+				acct_txs.current.iter().skip_while(|tx| is_ready(&tx.condition, best_block_number, best_block_timestamp))
+					.chain(acct_txs.future.values()).map(|info| info.hash)
 			})
 			.filter_map(|hash| match self.by_hash.get(&hash) {
 				Some(tx) => Some(tx.clone()),
@@ -376,7 +433,7 @@ impl TransactionQueue {
 mod tests {
 	use super::TransactionQueue;
 	use ethereum_types::Address;
-	use transaction::{Transaction, PendingTransaction, Condition};
+	use transaction::{self, Transaction, PendingTransaction, Condition};
 
 	#[test]
 	fn queued_senders() {
@@ -490,6 +547,19 @@ mod tests {
 		assert_eq!(txq.ready_transactions(100, 1234).len(), 5);
 	}
 
+	#[test]
+	fn rejects_state_conditions() {
+		let mut txq = TransactionQueue::default();
+		let sender = Address::default();
+
+		let tx = Transaction::default().fake_sign(sender);
+		let res = txq.import(PendingTransaction::new(tx, Some(Condition::MinBalance { address: sender, value: 0.into() })));
+
+		assert!(match res { Err(transaction::Error::UnsupportedCondition) => true, _ => false });
+		assert_eq!(txq.ready_transactions(100, 1234).len(), 0);
+		assert_eq!(txq.future_transactions(100, 1234).len(), 0);
+	}
+
 	#[test]
 	fn cull_from_future() {
 		let sender = Address::default();
@@ -510,6 +580,35 @@ mod tests {
 		assert_eq!(txq.next_nonce(&sender).unwrap(), 10.into());
 	}
 
+	#[test]
+	fn pending_from_state_nonce() {
+		let sender = Address::default();
+		let mut txq = TransactionQueue::default();
+
+		for i in (3..5).chain(6..8) {
+			let mut tx = Transaction::default();
+			tx.nonce = i.into();
+
+			let tx = tx.fake_sign(sender);
+
+			txq.import(tx.into()).unwrap();
+		}
+
+		// state nonce behind the queue: nothing can execute until the gap is filled.
+		assert!(txq.pending_from(&sender, 2.into()).is_empty());
+		assert_eq!(txq.next_nonce_from(&sender, 2.into()), 2.into());
+
+		// the gap at 5 is filled next, rather than queueing after the futures.
+		let pending = txq.pending_from(&sender, 3.into());
+		assert_eq!(pending.iter().map(|tx| tx.nonce.low_u64()).collect::<Vec<_>>(), vec![3, 4]);
+		assert_eq!(txq.next_nonce_from(&sender, 3.into()), 5.into());
+
+		// transactions already included are skipped.
+		assert_eq!(txq.pending_from(&sender, 4.into()).len(), 1);
+		assert_eq!(txq.next_nonce_from(&sender, 6.into()), 8.into());
+		assert_eq!(txq.next_nonce_from(&Address::from(1), 9.into()), 9.into());
+	}
+
 	#[test]
 	fn import_old() {
 		let sender = Address::default();
@@ -72,6 +72,11 @@ pub use self::epoch_signal::{
 	Incomplete as IncompleteSignalRequest,
 	Response as SignalResponse,
 };
+pub use self::bloom_bits::{
+	Complete as CompleteBloomBitsRequest,
+	Incomplete as IncompleteBloomBitsRequest,
+	Response as BloomBitsResponse,
+};
 
 pub use self::batch::{Batch, Builder};
 
@@ -267,6 +272,8 @@ pub enum Request {
 	Execution(IncompleteExecutionRequest),
 	/// A request for an epoch signal.
 	Signal(IncompleteSignalRequest),
+	/// A request for a bloom bit vector (from a bloom-bit section)
+	BloomBits(IncompleteBloomBitsRequest),
 }
 
 /// All request types, in an answerable state.
@@ -292,6 +299,8 @@ pub enum CompleteRequest {
 	Execution(CompleteExecutionRequest),
 	/// A request for an epoch signal.
 	Signal(CompleteSignalRequest),
+	/// A request for a bloom bit vector (from a bloom-bit section)
+	BloomBits(CompleteBloomBitsRequest),
 }
 
 impl CompleteRequest {
@@ -308,6 +317,7 @@ impl CompleteRequest {
 			CompleteRequest::Code(_) => Kind::Code,
 			CompleteRequest::Execution(_) => Kind::Execution,
 			CompleteRequest::Signal(_) => Kind::Signal,
+			CompleteRequest::BloomBits(_) => Kind::BloomBits,
 		}
 	}
 }
@@ -326,6 +336,7 @@ impl Request {
 			Request::Code(_) => Kind::Code,
 			Request::Execution(_) => Kind::Execution,
 			Request::Signal(_) => Kind::Signal,
+			Request::BloomBits(_) => Kind::BloomBits,
 		}
 	}
 }
@@ -343,6 +354,7 @@ impl Decodable for Request {
 			Kind::Code => Ok(Request::Code(rlp.val_at(1)?)),
 			Kind::Execution => Ok(Request::Execution(rlp.val_at(1)?)),
 			Kind::Signal => Ok(Request::Signal(rlp.val_at(1)?)),
+			Kind::BloomBits => Ok(Request::BloomBits(rlp.val_at(1)?)),
 		}
 	}
 }
@@ -365,6 +377,7 @@ impl Encodable for Request {
 			Request::Code(ref req) => s.append(req),
 			Request::Execution(ref req) => s.append(req),
 			Request::Signal(ref req) => s.append(req),
+			Request::BloomBits(ref req) => s.append(req),
 		};
 	}
 }
@@ -387,6 +400,7 @@ impl IncompleteRequest for Request {
 			Request::Code(ref req) => req.check_outputs(f),
 			Request::Execution(ref req) => req.check_outputs(f),
 			Request::Signal(ref req) => req.check_outputs(f),
+			Request::BloomBits(ref req) => req.check_outputs(f),
 		}
 	}
 
@@ -402,6 +416,7 @@ impl IncompleteRequest for Request {
 			Request::Code(ref req) => req.note_outputs(f),
 			Request::Execution(ref req) => req.note_outputs(f),
 			Request::Signal(ref req) => req.note_outputs(f),
+			Request::BloomBits(ref req) => req.note_outputs(f),
 		}
 	}
 
@@ -417,6 +432,7 @@ impl IncompleteRequest for Request {
 			Request::Code(ref mut req) => req.fill(oracle),
 			Request::Execution(ref mut req) => req.fill(oracle),
 			Request::Signal(ref mut req) => req.fill(oracle),
+			Request::BloomBits(ref mut req) => req.fill(oracle),
 		}
 	}
 
@@ -432,6 +448,7 @@ impl IncompleteRequest for Request {
 			Request::Code(req) => req.complete().map(CompleteRequest::Code),
 			Request::Execution(req) => req.complete().map(CompleteRequest::Execution),
 			Request::Signal(req) => req.complete().map(CompleteRequest::Signal),
+			Request::BloomBits(req) => req.complete().map(CompleteRequest::BloomBits),
 		}
 	}
 
@@ -447,6 +464,7 @@ impl IncompleteRequest for Request {
 			Request::Code(ref mut req) => req.adjust_refs(mapping),
 			Request::Execution(ref mut req) => req.adjust_refs(mapping),
 			Request::Signal(ref mut req) => req.adjust_refs(mapping),
+			Request::BloomBits(ref mut req) => req.adjust_refs(mapping),
 		}
 	}
 }
@@ -490,6 +508,8 @@ pub enum Kind {
 	Execution = 8,
 	/// A request for epoch transition signal.
 	Signal = 9,
+	/// A request for a bloom bit vector + merkle proof.
+	BloomBits = 10,
 }
 
 impl Decodable for Kind {
@@ -505,6 +525,7 @@ impl Decodable for Kind {
 			7 => Ok(Kind::Code),
 			8 => Ok(Kind::Execution),
 			9 => Ok(Kind::Signal),
+			10 => Ok(Kind::BloomBits),
 			_ => Err(DecoderError::Custom("Unknown PIP request ID.")),
 		}
 	}
@@ -539,6 +560,8 @@ pub enum Response {
 	Execution(ExecutionResponse),
 	/// A response for epoch change signal.
 	Signal(SignalResponse),
+	/// A response for a bloom bit vector.
+	BloomBits(BloomBitsResponse),
 }
 
 impl ResponseLike for Response {
@@ -555,6 +578,7 @@ impl ResponseLike for Response {
 			Response::Code(ref res) => res.fill_outputs(f),
 			Response::Execution(ref res) => res.fill_outputs(f),
 			Response::Signal(ref res) => res.fill_outputs(f),
+			Response::BloomBits(ref res) => res.fill_outputs(f),
 		}
 	}
 }
@@ -573,6 +597,7 @@ impl Response {
 			Response::Code(_) => Kind::Code,
 			Response::Execution(_) => Kind::Execution,
 			Response::Signal(_) => Kind::Signal,
+			Response::BloomBits(_) => Kind::BloomBits,
 		}
 	}
 }
@@ -590,6 +615,7 @@ impl Decodable for Response {
 			Kind::Code => Ok(Response::Code(rlp.val_at(1)?)),
 			Kind::Execution => Ok(Response::Execution(rlp.val_at(1)?)),
 			Kind::Signal => Ok(Response::Signal(rlp.val_at(1)?)),
+			Kind::BloomBits => Ok(Response::BloomBits(rlp.val_at(1)?)),
 		}
 	}
 }
@@ -612,6 +638,7 @@ impl Encodable for Response {
 			Response::Code(ref res) => s.append(res),
 			Response::Execution(ref res) => s.append(res),
 			Response::Signal(ref res) => s.append(res),
+			Response::BloomBits(ref res) => s.append(res),
 		};
 	}
 }
@@ -1633,6 +1660,98 @@ pub mod epoch_signal {
 	}
 }
 
+/// A request for a bloom bit vector from a bloom-bit section.
+pub mod bloom_bits {
+	use super::{Field, NoSuchOutput, OutputKind, Output};
+	use rlp::{Encodable, Decodable, DecoderError, RlpStream, Rlp};
+	use bytes::Bytes;
+
+	/// Potentially incomplete bloom bits request.
+	#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+	pub struct Incomplete {
+		/// Number of the section.
+		pub section: Field<u64>,
+		/// Index of the bloom bit.
+		pub bit: u64,
+	}
+
+	impl super::IncompleteRequest for Incomplete {
+		type Complete = Complete;
+		type Response = Response;
+
+		fn check_outputs<F>(&self, mut f: F) -> Result<(), NoSuchOutput>
+			where F: FnMut(usize, usize, OutputKind) -> Result<(), NoSuchOutput>
+		{
+			match self.section {
+				Field::Scalar(_) => Ok(()),
+				Field::BackReference(req, idx) => f(req, idx, OutputKind::Number),
+			}
+		}
+
+		fn note_outputs<F>(&self, _: F) where F: FnMut(usize, OutputKind) {}
+
+		fn fill<F>(&mut self, oracle: F) where F: Fn(usize, usize) -> Result<Output, NoSuchOutput> {
+			if let Field::BackReference(req, idx) = self.section {
+				self.section = match oracle(req, idx) {
+					Ok(Output::Number(section)) => Field::Scalar(section.into()),
+					_ => Field::BackReference(req, idx),
+				}
+			}
+		}
+
+		fn complete(self) -> Result<Self::Complete, NoSuchOutput> {
+			Ok(Complete {
+				section: self.section.into_scalar()?,
+				bit: self.bit,
+			})
+		}
+
+		fn adjust_refs<F>(&mut self, mapping: F) where F: FnMut(usize) -> usize {
+			self.section.adjust_req(mapping)
+		}
+	}
+
+	/// A complete bloom bits request.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct Complete {
+		/// The number of the section to get a bit vector from.
+		pub section: u64,
+		/// The bloom bit whose vector is requested.
+		pub bit: u64,
+	}
+
+	/// The output of a request for a bloom bit vector.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct Response {
+		/// Inclusion proof of the bit vector in the section's trie.
+		pub proof: Vec<Bytes>,
+		/// The proved bit vector, with one bit per block of the section.
+		pub bits: Bytes,
+	}
+
+	impl super::ResponseLike for Response {
+		/// Fill reusable outputs by providing them to the function.
+		fn fill_outputs<F>(&self, _: F) where F: FnMut(usize, Output) {}
+	}
+
+	impl Decodable for Response {
+		fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+			Ok(Response {
+				proof: rlp.list_at(0)?,
+				bits: rlp.val_at(1)?,
+			})
+		}
+	}
+
+	impl Encodable for Response {
+		fn rlp_append(&self, s: &mut RlpStream) {
+			s.begin_list(2)
+				.append_list::<Vec<u8>,_>(&self.proof[..])
+				.append(&self.bits);
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1920,6 +2039,7 @@ mod tests {
 			Response::Storage(StorageResponse { proof: vec![], value: H256::default() }),
 			Response::Code(CodeResponse { code: vec![1, 2, 3, 4, 5] }),
 			Response::Execution(ExecutionResponse { items: vec![] }),
+			Response::BloomBits(BloomBitsResponse { proof: vec![], bits: vec![0; 256] }),
 		];
 
 		let raw = ::rlp::encode_list(&reqs);
@@ -1943,4 +2063,24 @@ mod tests {
 		check_roundtrip(res);
 		check_roundtrip(full_res);
 	}
+
+	#[test]
+	fn bloom_bits_roundtrip() {
+		let req = IncompleteBloomBitsRequest {
+			section: Field::BackReference(1, 234),
+			bit: 1337,
+		};
+
+		let full_req = Request::BloomBits(req.clone());
+		let res = BloomBitsResponse {
+			proof: vec![vec![1, 2, 3], vec![4, 5, 6]],
+			bits: vec![0x80, 0, 0, 1],
+		};
+		let full_res = Response::BloomBits(res.clone());
+
+		check_roundtrip(req);
+		check_roundtrip(full_req);
+		check_roundtrip(res);
+		check_roundtrip(full_res);
+	}
 }
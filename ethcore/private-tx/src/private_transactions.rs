@@ -61,6 +61,7 @@ impl Default for VerificationStore {
 					max_count: MAX_QUEUE_LEN,
 					max_per_sender: MAX_QUEUE_LEN / 10,
 					max_mem_usage: 8 * 1024 * 1024,
+					..Default::default()
 				},
 				pool::verifier::Options {
 					// TODO [ToDr] This should probably be based on some real values?
@@ -69,6 +70,7 @@ impl Default for VerificationStore {
 					tx_gas_limit: U256::max_value(),
 				},
 				pool::PrioritizationStrategy::GasPriceOnly,
+				pool::ReplacementPolicy::default(),
 			)
 		}
 	}
@@ -114,7 +116,9 @@ impl VerificationStore {
 
 	/// Returns transactions ready for verification
 	/// Returns only one transaction per sender because several cannot be verified in a row without verification from other peers
-	pub fn ready_transactions<C: pool::client::NonceClient>(&self, client: C) -> Vec<Arc<pool::VerifiedTransaction>> {
+	pub fn ready_transactions<C>(&self, client: C) -> Vec<Arc<pool::VerifiedTransaction>> where
+		C: pool::client::NonceClient + pool::client::ConditionClient + Clone,
+	{
 		// We never store PendingTransactions and we don't use internal cache,
 		// so we don't need to provide real block number of timestamp here
 		let block_number = 0;
@@ -338,6 +338,87 @@ impl<'x> OpenBlock<'x> {
 		Ok(self.block.receipts.last().expect("receipt just pushed; qed"))
 	}
 
+	/// Execute a transaction on top of the block without including it.
+	///
+	/// Returns gas used by the transaction and the increase of block author's balance
+	/// (transaction fee and any direct payments to the author).
+	/// The changes are reverted using a state checkpoint, which is not possible
+	/// if the state is committed after every transaction (before EIP-98 or EIP-658).
+	pub fn simulate_transaction(&mut self, t: &SignedTransaction) -> Result<(U256, U256), Error> {
+		if !self.can_revert_transactions() {
+			return Err("Simulation requires no intermediate state commits".into());
+		}
+
+		let author = *self.block.header.author();
+		let balance = self.block.state.balance(&author)?;
+		let gas_used = self.block.receipts.last().map_or_else(U256::zero, |r| r.gas_used);
+		let transactions_len = self.block.transactions.len();
+
+		self.block.state.checkpoint();
+		let cumulative_gas_used = self.push_transaction(t.clone(), None).map(|receipt| receipt.gas_used);
+		let new_balance = self.block.state.balance(&author);
+		self.revert_transactions(transactions_len);
+
+		Ok((cumulative_gas_used? - gas_used, new_balance?.saturating_sub(balance)))
+	}
+
+	/// Returns true if the state is not committed after every transaction,
+	/// so that pushed transactions can be reverted to a state checkpoint.
+	fn can_revert_transactions(&self) -> bool {
+		let params = self.engine.params();
+		let number = self.block.header.number();
+		number >= params.eip658_transition || (number >= params.eip98_transition && number >= params.validate_receipts_transition)
+	}
+
+	/// Push a group of transactions onto the block atomically.
+	///
+	/// Transactions are executed in the given order. If any of them can't be included
+	/// or its execution fails (receipt status is `0`) the block is reverted to the state
+	/// from before the first one was pushed.
+	///
+	/// Requires receipt status codes (EIP-658) to detect failed executions.
+	pub fn push_transactions_atomically(&mut self, transactions: Vec<SignedTransaction>) -> Result<(), Error> {
+		if self.block.header.number() < self.engine.params().eip658_transition {
+			return Err("Atomic push requires receipt status codes (EIP-658)".into());
+		}
+
+		let transactions_len = self.block.transactions.len();
+		self.block.state.checkpoint();
+
+		for t in transactions {
+			let hash = t.hash();
+			let result = match self.push_transaction(t, None) {
+				Ok(receipt) => match receipt.outcome {
+					TransactionOutcome::StatusCode(0) => Err(format!("Execution of transaction {:?} failed", hash).into()),
+					_ => Ok(()),
+				},
+				Err(e) => Err(e),
+			};
+
+			if let Err(e) = result {
+				self.revert_transactions(transactions_len);
+				return Err(e);
+			}
+		}
+
+		self.block.state.discard_checkpoint();
+		Ok(())
+	}
+
+	/// Reverts the state to the last checkpoint and removes transactions pushed after it.
+	///
+	/// NOTE: Only valid if `can_revert_transactions` is true.
+	fn revert_transactions(&mut self, transactions_len: usize) {
+		self.block.state.revert_to_checkpoint();
+		for t in self.block.transactions.drain(transactions_len..) {
+			self.block.transactions_set.remove(&t.hash());
+		}
+		self.block.receipts.truncate(transactions_len);
+		if let Tracing::Enabled(ref mut traces) = self.block.traces {
+			traces.truncate(transactions_len);
+		}
+	}
+
 	/// Push transactions onto the block.
 	#[cfg(not(feature = "slow-blocks"))]
 	fn push_transactions(&mut self, transactions: Vec<SignedTransaction>) -> Result<(), Error> {
@@ -529,6 +610,23 @@ impl LockedBlock {
 		Ok(SealedBlock { block: s.block, uncle_bytes: s.uncle_bytes })
 	}
 
+	/// Provide a seal generated by this node in order to turn this into a `SealedBlock`,
+	/// letting the engine finalize the header once the seal is applied.
+	///
+	/// NOTE: This does not check the validity of `seal` with the engine.
+	pub fn seal_internally(self, engine: &EthEngine, seal: Vec<Bytes>) -> Result<SealedBlock, Error> {
+		let expected_seal_fields = engine.seal_fields(self.header());
+		let mut s = self;
+		if seal.len() != expected_seal_fields {
+			return Err(BlockError::InvalidSealArity(
+				Mismatch { expected: expected_seal_fields, found: seal.len() }).into());
+		}
+		s.block.header.set_seal(seal);
+		engine.on_seal_block(&mut s.block.header)?;
+		s.block.header.compute_hash();
+		Ok(SealedBlock { block: s.block, uncle_bytes: s.uncle_bytes })
+	}
+
 	/// Provide a valid seal in order to turn this into a `SealedBlock`.
 	/// This does check the validity of `seal` with the engine.
 	/// Returns the `ClosedBlock` back again if the seal is no good.
@@ -741,6 +839,107 @@ mod tests {
 		let _ = b.seal(&*spec.engine, vec![]);
 	}
 
+	#[test]
+	fn should_revert_block_when_atomic_push_fails() {
+		use spec::*;
+		use ethkey::{Generator, Random};
+		use transaction::{Action, Transaction};
+
+		let spec = Spec::new_test_constructor();
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(&*spec.engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+
+		let keypair = Random.generate().unwrap();
+		let tx = |nonce: u64| Transaction {
+			action: Action::Call(Address::zero()),
+			gas: 100_000.into(),
+			nonce: nonce.into(),
+			..Default::default()
+		}.sign(keypair.secret(), None);
+
+		// second transaction has a nonce gap
+		assert!(b.push_transactions_atomically(vec![tx(0), tx(2)]).is_err());
+		assert!(b.transactions().is_empty());
+		assert_eq!(b.state().nonce(&keypair.address()).unwrap(), 0.into());
+
+		b.push_transactions_atomically(vec![tx(0), tx(1)]).unwrap();
+		assert_eq!(b.transactions().len(), 2);
+		assert_eq!(b.state().nonce(&keypair.address()).unwrap(), 2.into());
+	}
+
+	#[test]
+	fn should_revert_block_when_atomically_pushed_transaction_fails() {
+		use spec::*;
+		use ethkey::{Generator, Random};
+		use transaction::{Action, Transaction};
+
+		let spec = Spec::new_test_constructor();
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(&*spec.engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+
+		let keypair = Random.generate().unwrap();
+		let call = Transaction {
+			action: Action::Call(Address::zero()),
+			gas: 100_000.into(),
+			nonce: 0.into(),
+			..Default::default()
+		}.sign(keypair.secret(), None);
+		// init code consisting of a single invalid instruction.
+		let failing = Transaction {
+			action: Action::Create,
+			gas: 100_000.into(),
+			nonce: 1.into(),
+			data: vec![0xfe],
+			..Default::default()
+		}.sign(keypair.secret(), None);
+
+		assert!(b.push_transactions_atomically(vec![call.clone(), failing]).is_err());
+		assert!(b.transactions().is_empty());
+		assert!(b.receipts().is_empty());
+		assert_eq!(b.state().nonce(&keypair.address()).unwrap(), 0.into());
+
+		// the reverted transaction can be pushed again
+		b.push_transaction(call, None).unwrap();
+		assert_eq!(b.transactions().len(), 1);
+	}
+
+	#[test]
+	fn should_not_include_simulated_transaction() {
+		use spec::*;
+		use ethkey::{Generator, Random};
+		use transaction::{Action, Transaction};
+
+		let spec = Spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(&*spec.engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+
+		let keypair = Random.generate().unwrap();
+		let tx = |nonce: u64| Transaction {
+			action: Action::Call(Address::zero()),
+			gas: 100_000.into(),
+			nonce: nonce.into(),
+			..Default::default()
+		}.sign(keypair.secret(), None);
+
+		let (gas_used, reward) = b.simulate_transaction(&tx(0)).unwrap();
+		assert!(gas_used > 0.into());
+		assert_eq!(reward, 0.into());
+		assert!(b.simulate_transaction(&tx(1)).is_err());
+		assert!(b.transactions().is_empty());
+		assert!(b.receipts().is_empty());
+		assert_eq!(b.state().nonce(&keypair.address()).unwrap(), 0.into());
+
+		// simulated transaction can be pushed afterwards
+		b.push_transaction(tx(0), None).unwrap();
+		assert_eq!(b.receipts()[0].gas_used, gas_used);
+	}
+
 	#[test]
 	fn enact_block() {
 		use spec::*;
@@ -828,6 +828,34 @@ impl Client {
 		}
 	}
 
+	// sign and import a call to `address` from the engine signer, at the given gas price.
+	// returns the hash of the imported transaction.
+	fn transact_contract_with_gas_price(&self, address: Address, data: Bytes, gas_price: U256) -> Result<H256, transaction::Error> {
+	        trace!(target: "iolite_exec_trace", "[transact_contract] at {path}", path="ethcore/src/client/client.rs:line 1936");
+		let authoring_params = self.importer.miner.authoring_params();
+		let transaction = Transaction {
+			nonce: self.latest_nonce(&authoring_params.author),
+			action: Action::Call(address),
+			gas: self.importer.miner.sensible_gas_limit(),
+			gas_price: gas_price,
+			value: U256::zero(),
+			data: data,
+			//TODO: <IOLITE> probably later we will need to pass some metadata and
+			// metadataLimit here also
+			metadata: Bytes::new(),
+			metadataLimit: U256::zero(),
+			isOld: false,
+			..Default::default()
+		};
+		let chain_id = self.engine.signing_chain_id(&self.latest_env_info());
+		let signature = self.engine.sign(transaction.hash(chain_id))
+			.map_err(|e| transaction::Error::InvalidSignature(e.to_string()))?;
+		let signed = SignedTransaction::new(transaction.with_signature(signature, chain_id))?;
+		let hash = signed.hash();
+		self.importer.miner.import_own_transaction(self, signed.into())?;
+		Ok(hash)
+	}
+
 	/// The env info as of the best block.
 	pub fn latest_env_info(&self) -> EnvInfo {
 		self.env_info(BlockId::Latest).expect("Best block header always stored; qed")
@@ -1156,6 +1184,17 @@ impl Client {
 		}
 	}
 
+	fn execute_contract_call(&self, block_id: BlockId, address: Address, data: Bytes) -> Result<Executed, String> {
+		let state_pruned = || CallError::StatePruned.to_string();
+		let state = &mut self.state_at(block_id).ok_or_else(&state_pruned)?;
+		let header = self.block_header_decoded(block_id).ok_or_else(&state_pruned)?;
+
+		let transaction = self.contract_call_tx(block_id, address, data);
+
+		self.call(&transaction, Default::default(), state, &header)
+			.map_err(|e| format!("{:?}", e))
+	}
+
 	// transaction for calling contracts from services like engine.
 	// from the null sender, with 50M gas.
 	fn contract_call_tx(&self, block_id: BlockId, address: Address, data: Bytes) -> SignedTransaction {
@@ -1340,16 +1379,17 @@ impl RegistryInfo for Client {
 
 impl CallContract for Client {
 	fn call_contract(&self, block_id: BlockId, address: Address, data: Bytes) -> Result<Bytes, String> {
-		let state_pruned = || CallError::StatePruned.to_string();
-		let state = &mut self.state_at(block_id).ok_or_else(&state_pruned)?;
-		let header = self.block_header_decoded(block_id).ok_or_else(&state_pruned)?;
-
-		let transaction = self.contract_call_tx(block_id, address, data);
-
-		self.call(&transaction, Default::default(), state, &header)
-			.map_err(|e| format!("{:?}", e))
+		self.execute_contract_call(block_id, address, data)
 			.map(|executed| executed.output)
 	}
+
+	fn call_contract_checked(&self, block_id: BlockId, address: Address, data: Bytes) -> Result<Bytes, String> {
+		let executed = self.execute_contract_call(block_id, address, data)?;
+		match executed.exception {
+			Some(exception) => Err(format!("{:?}", exception)),
+			None => Ok(executed.output),
+		}
+	}
 }
 
 impl ImportBlock for Client {
@@ -1776,6 +1816,60 @@ impl BlockChainClient for Client {
 		self.chain.read().block_receipts(hash).map(|receipts| ::rlp::encode(&receipts).into_vec())
 	}
 
+	fn has_state_node(&self, hash: &H256, account: Option<&H256>) -> bool {
+		self.state_db.read().contains_state_node(hash, account)
+	}
+
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, accounts: Vec<H256>) -> Result<(), BlockImportError> {
+		trace_time!("import_state_nodes");
+		let _import_lock = self.importer.import_lock.lock();
+		let nodes = nodes.into_iter().map(|(account, data)| (account, DBValue::from_vec(data))).collect();
+		let mut batch = DBTransaction::new();
+		self.state_db.write().inject_state_nodes(&mut batch, nodes, &accounts).map_err(EthcoreError::from)?;
+		self.db.read().write_buffered(batch);
+		Ok(())
+	}
+
+	fn verify_header_chain(&self, headers: &[Header]) -> Result<(), BlockImportError> {
+		verification::verify_header_chain(headers, &*self.engine)?;
+		Ok(())
+	}
+
+	fn import_state_sync_pivot(&self, block_bytes: Bytes, receipts_bytes: Bytes, parent_total_difficulty: U256) -> Result<H256, BlockImportError> {
+		let header: Header = ::rlp::Rlp::new(&block_bytes).val_at(0)?;
+		let hash = header.hash();
+		let receipts = ::rlp::decode_list(&receipts_bytes);
+
+		let _import_lock = self.importer.import_lock.lock();
+		if self.chain.read().is_known(&hash) {
+			bail!(BlockImportErrorKind::Import(ImportErrorKind::AlreadyInChain));
+		}
+		if !self.state_db.read().contains_state_node(header.state_root(), None) {
+			bail!(BlockImportErrorKind::Other(format!("State of the pivot block {} is not available", hash)));
+		}
+
+		let mut state_db = self.state_db.write();
+		let mut chain = self.chain.write();
+		let mut tracedb = self.tracedb.write();
+		let db = self.db.read();
+		{
+			let mut batch = DBTransaction::new();
+			chain.insert_unordered_block(&mut batch, &block_bytes, receipts, Some(parent_total_difficulty), true, false);
+			db.write_buffered(batch);
+			chain.commit();
+		}
+		db.flush().expect("DB flush failed.");
+
+		// Reload the chain and the journal from the database so that the first and ancient
+		// blocks are recalculated around the pivot.
+		let cache_size = state_db.cache_size();
+		*state_db = StateDB::new(journaldb::new(db.clone(), self.pruning, ::db::COL_STATE), cache_size);
+		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
+		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
+		self.importer.miner.clear();
+		Ok(hash)
+	}
+
 	fn queue_info(&self) -> BlockQueueInfo {
 		self.importer.block_queue.queue_info()
 	}
@@ -1916,6 +2010,10 @@ impl BlockChainClient for Client {
 		self.importer.miner.ready_transactions(self, max_len, ::miner::PendingOrdering::Priority)
 	}
 
+	fn pooled_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.importer.miner.transaction(hash)
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> {
 		self.engine.signing_chain_id(&self.latest_env_info())
 	}
@@ -1942,27 +2040,12 @@ impl BlockChainClient for Client {
 	}
 
 	fn transact_contract(&self, address: Address, data: Bytes) -> Result<(), transaction::Error> {
-	        trace!(target: "iolite_exec_trace", "[transact_contract] at {path}", path="ethcore/src/client/client.rs:line 1936");
-		let authoring_params = self.importer.miner.authoring_params();
-		let transaction = Transaction {
-			nonce: self.latest_nonce(&authoring_params.author),
-			action: Action::Call(address),
-			gas: self.importer.miner.sensible_gas_limit(),
-			gas_price: self.importer.miner.sensible_gas_price(),
-			value: U256::zero(),
-			data: data,
-			//TODO: <IOLITE> probably later we will need to pass some metadata and
-			// metadataLimit here also
-			metadata: Bytes::new(),
-			metadataLimit: U256::zero(),
-			isOld: false,
-			..Default::default()
-		};
-		let chain_id = self.engine.signing_chain_id(&self.latest_env_info());
-		let signature = self.engine.sign(transaction.hash(chain_id))
-			.map_err(|e| transaction::Error::InvalidSignature(e.to_string()))?;
-		let signed = SignedTransaction::new(transaction.with_signature(signature, chain_id))?;
-		self.importer.miner.import_own_transaction(self, signed.into())
+		let gas_price = self.importer.miner.sensible_gas_price();
+		self.transact_contract_with_gas_price(address, data, gas_price).map(|_| ())
+	}
+
+	fn transact_service_contract(&self, address: Address, data: Bytes) -> Result<H256, transaction::Error> {
+		self.transact_contract_with_gas_price(address, data, U256::zero())
 	}
 
 	fn registrar_address(&self) -> Option<Address> {
@@ -2241,6 +2324,23 @@ impl super::traits::EngineClient for Client {
 	fn block_header(&self, id: BlockId) -> Option<::encoded::Header> {
 		BlockChainClient::block_header(self, id)
 	}
+
+	fn engine_data(&self, key: &[u8]) -> Option<Bytes> {
+		self.db.read().get(::db::COL_NODE_INFO, key)
+			.expect("Low level database error. Some issue with disk?")
+			.map(|value| value.into_vec())
+	}
+
+	fn write_engine_data(&self, values: Vec<(Vec<u8>, Option<Bytes>)>) {
+		let mut batch = DBTransaction::new();
+		for (key, value) in values {
+			match value {
+				Some(value) => batch.put_vec(::db::COL_NODE_INFO, &key, value),
+				None => batch.delete(::db::COL_NODE_INFO, &key),
+			}
+		}
+		self.db.read().write(batch).expect("Low level database error. Some issue with disk?");
+	}
 }
 
 impl ProvingBlockChainClient for Client {
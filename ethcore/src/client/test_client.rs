@@ -37,7 +37,7 @@ use blockchain::{TreeRoute, BlockReceipts};
 use client::{
 	Nonce, Balance, ChainInfo, BlockInfo, ReopenBlock, CallContract, TransactionInfo, RegistryInfo,
 	PrepareOpenBlock, BlockChainClient, BlockChainInfo, BlockStatus, BlockId,
-	TransactionId, UncleId, TraceId, TraceFilter, LastHashes, CallAnalytics, BlockImportError,
+	TransactionId, UncleId, TraceId, TraceFilter, LastHashes, CallAnalytics, BlockImportError, BlockImportErrorKind,
 	ProvingBlockChainClient, ScheduleInfo, ImportSealedBlock, BroadcastProposalBlock, ImportBlock, StateOrBlock,
 	Call, StateClient, EngineInfo, AccountData, BlockChain, BlockProducer, SealedBlockImporter, IoClient
 };
@@ -114,6 +114,12 @@ pub struct TestBlockChainClient {
 	pub history: RwLock<Option<u64>>,
 	/// Is disabled
 	pub disabled: AtomicBool,
+	/// State trie nodes and code imported by state sync, keyed by the owning account hash and node hash.
+	pub state_nodes: RwLock<HashMap<(Option<H256>, H256), Bytes>>,
+	/// Block, receipts and parent total difficulty of the imported state sync pivot.
+	pub state_sync_pivot: RwLock<Option<(Bytes, Bytes, U256)>>,
+	/// Data persisted by the engine.
+	pub engine_data: RwLock<HashMap<Bytes, Bytes>>,
 }
 
 /// Used for generating test client blocks.
@@ -180,6 +186,9 @@ impl TestBlockChainClient {
 			traces: RwLock::new(None),
 			history: RwLock::new(None),
 			disabled: AtomicBool::new(false),
+			state_nodes: RwLock::new(HashMap::new()),
+			state_sync_pivot: RwLock::new(None),
+			engine_data: RwLock::new(HashMap::new()),
 		};
 
 		// insert genesis hash.
@@ -507,6 +516,7 @@ impl BlockInfo for TestBlockChainClient {
 
 impl CallContract for TestBlockChainClient {
 	fn call_contract(&self, _id: BlockId, _address: Address, _data: Bytes) -> Result<Bytes, String> { Ok(vec![]) }
+	fn call_contract_checked(&self, _id: BlockId, _address: Address, _data: Bytes) -> Result<Bytes, String> { Ok(vec![]) }
 }
 
 impl TransactionInfo for TestBlockChainClient {
@@ -783,6 +793,32 @@ impl BlockChainClient for TestBlockChainClient {
 		None
 	}
 
+	fn has_state_node(&self, hash: &H256, account: Option<&H256>) -> bool {
+		self.state_nodes.read().contains_key(&(account.cloned(), *hash))
+	}
+
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, _accounts: Vec<H256>) -> Result<(), BlockImportError> {
+		let mut state_nodes = self.state_nodes.write();
+		for (account, data) in nodes {
+			state_nodes.insert((account, keccak(&data)), data);
+		}
+		Ok(())
+	}
+
+	fn verify_header_chain(&self, headers: &[Header]) -> Result<(), BlockImportError> {
+		::verification::verify_header_chain(headers, &*self.spec.engine)?;
+		Ok(())
+	}
+
+	fn import_state_sync_pivot(&self, block_bytes: Bytes, receipts_bytes: Bytes, parent_total_difficulty: U256) -> Result<H256, BlockImportError> {
+		let header = view!(BlockView, &block_bytes).header();
+		if !self.has_state_node(header.state_root(), None) {
+			return Err(BlockImportErrorKind::Other("Missing pivot state".into()).into());
+		}
+		*self.state_sync_pivot.write() = Some((block_bytes, receipts_bytes, parent_total_difficulty));
+		Ok(header.hash())
+	}
+
 	fn queue_info(&self) -> QueueInfo {
 		QueueInfo {
 			verified_queue_size: self.queue_size.load(AtomicOrder::Relaxed),
@@ -821,6 +857,10 @@ impl BlockChainClient for TestBlockChainClient {
 		self.miner.ready_transactions(self, max_len, miner::PendingOrdering::Priority)
 	}
 
+	fn pooled_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.miner.transaction(hash)
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> { None }
 
 	fn mode(&self) -> Mode { Mode::Active }
@@ -842,6 +882,11 @@ impl BlockChainClient for TestBlockChainClient {
 	}
 
 	fn transact_contract(&self, address: Address, data: Bytes) -> Result<(), transaction::Error> {
+		// test contract transactions are already free.
+		self.transact_service_contract(address, data).map(|_| ())
+	}
+
+	fn transact_service_contract(&self, address: Address, data: Bytes) -> Result<H256, transaction::Error> {
 		let transaction = Transaction {
 			nonce: self.latest_nonce(&self.miner.authoring_params().author),
 			action: Action::Call(address),
@@ -859,7 +904,9 @@ impl BlockChainClient for TestBlockChainClient {
 		let chain_id = Some(self.spec.chain_id());
 		let sig = self.spec.engine.sign(transaction.hash(chain_id)).unwrap();
 		let signed = SignedTransaction::new(transaction.with_signature(sig, chain_id)).unwrap();
-		self.miner.import_own_transaction(self, signed.into())
+		let hash = signed.hash();
+		self.miner.import_own_transaction(self, signed.into())?;
+		Ok(hash)
 	}
 
 	fn registrar_address(&self) -> Option<Address> { None }
@@ -928,4 +975,18 @@ impl super::traits::EngineClient for TestBlockChainClient {
 	fn block_header(&self, id: BlockId) -> Option<::encoded::Header> {
 		BlockChainClient::block_header(self, id)
 	}
+
+	fn engine_data(&self, key: &[u8]) -> Option<Bytes> {
+		self.engine_data.read().get(key).cloned()
+	}
+
+	fn write_engine_data(&self, values: Vec<(Vec<u8>, Option<Bytes>)>) {
+		let mut engine_data = self.engine_data.write();
+		for (key, value) in values {
+			match value {
+				Some(value) => { engine_data.insert(key, value); },
+				None => { engine_data.remove(&key); },
+			}
+		}
+	}
 }
@@ -174,6 +174,9 @@ pub trait ImportBlock {
 pub trait CallContract {
 	/// Like `call`, but with various defaults. Designed to be used for calling contracts.
 	fn call_contract(&self, id: BlockId, address: Address, data: Bytes) -> Result<Bytes, String>;
+
+	/// Like `call_contract`, but fails if the execution threw an exception (e.g. reverted or ran out of gas).
+	fn call_contract_checked(&self, id: BlockId, address: Address, data: Bytes) -> Result<Bytes, String>;
 }
 
 /// Provides `call` and `call_many` methods
@@ -287,6 +290,21 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// Get raw block receipts data by block header hash.
 	fn block_receipts(&self, hash: &H256) -> Option<Bytes>;
 
+	/// Check if a state trie node or code is already in the state database.
+	/// `account` is the address hash of the account that owns a storage node or code.
+	fn has_state_node(&self, hash: &H256, account: Option<&H256>) -> bool;
+
+	/// Write state trie nodes and code downloaded by state sync to the state database.
+	/// `accounts` lists the address hashes of all the accounts contained in the nodes.
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, accounts: Vec<H256>) -> Result<(), BlockImportError>;
+
+	/// Verify a chain of headers leading to a state sync pivot, ordered from the oldest one.
+	fn verify_header_chain(&self, headers: &[Header]) -> Result<(), BlockImportError>;
+
+	/// Import the state sync pivot block along with its receipts and make it the best block.
+	/// The state of the block must be fully present in the state database.
+	fn import_state_sync_pivot(&self, block_bytes: Bytes, receipts_bytes: Bytes, parent_total_difficulty: U256) -> Result<H256, BlockImportError>;
+
 	/// Get block queue information.
 	fn queue_info(&self) -> BlockQueueInfo;
 
@@ -323,6 +341,9 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// List all transactions that are allowed into the next block.
 	fn ready_transactions(&self, max_len: usize) -> Vec<Arc<VerifiedTransaction>>;
 
+	/// Get a transaction from the local pool by its hash.
+	fn pooled_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>>;
+
 	/// Sorted list of transaction gas prices from at least last sample_size blocks.
 	fn gas_price_corpus(&self, sample_size: usize) -> ::stats::Corpus<U256> {
 		let mut h = self.chain_info().best_block_hash;
@@ -375,6 +396,11 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// Schedule state-altering transaction to be executed on the next pending block.
 	fn transact_contract(&self, address: Address, data: Bytes) -> Result<(), transaction::Error>;
 
+	/// Schedule state-altering service transaction (zero gas price) to be executed on the next
+	/// pending block. Only mined by other nodes if the engine signer is certified.
+	/// Returns the hash of the imported transaction.
+	fn transact_service_contract(&self, address: Address, data: Bytes) -> Result<H256, transaction::Error>;
+
 	/// Get the address of the registry itself.
 	fn registrar_address(&self) -> Option<Address>;
 
@@ -448,6 +474,13 @@ pub trait EngineClient: Sync + Send + ChainInfo {
 
 	/// Get raw block header data by block id.
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header>;
+
+	/// Read data persisted by the engine with `write_engine_data`.
+	fn engine_data(&self, key: &[u8]) -> Option<Bytes>;
+
+	/// Persist engine data so that it survives restarts, all the given entries are written
+	/// atomically. A `None` value removes the entry.
+	fn write_engine_data(&self, values: Vec<(Vec<u8>, Option<Bytes>)>);
 }
 
 /// Extended client interface for providing proofs of the state.
@@ -17,6 +17,7 @@
 //! A blockchain engine that supports a non-instant BFT proof-of-authority.
 
 use std::collections::{BTreeMap, HashSet};
+use std::collections::btree_map::Entry;
 use std::fmt;
 use std::iter::FromIterator;
 use std::ops::Deref;
@@ -26,7 +27,7 @@ use std::time::{UNIX_EPOCH, SystemTime, Duration};
 
 use account_provider::AccountProvider;
 use block::*;
-use client::EngineClient;
+use client::{EngineClient, ChainInfo};
 use engines::{Engine, Seal, EngineError, ConstructedVerifier};
 use engines::block_reward;
 use engines::block_reward::{BlockRewardContract, RewardKind};
@@ -40,6 +41,7 @@ use super::signer::EngineSigner;
 use super::validator_set::{ValidatorSet, SimpleList, new_validator_set};
 
 use self::finality::RollingFinality;
+use self::randomness::{RandomnessContract, RandomnessPhase};
 
 use ethkey::{self, Signature};
 use io::{IoContext, IoHandler, TimerToken, IoService};
@@ -50,15 +52,17 @@ use parking_lot::{Mutex, RwLock};
 use unexpected::{Mismatch, OutOfBounds};
 
 mod finality;
+mod randomness;
 
 /// `AuthorityRound` params.
 pub struct AuthorityRoundParams {
 	/// Time to wait before next block or authority switching,
-	/// in seconds.
+	/// in seconds, keyed by the timestamp from which it applies.
+	/// Must contain an entry for timestamp 0.
 	///
 	/// Deliberately typed as u16 as too high of a value leads
 	/// to slow block issuance.
-	pub step_duration: u16,
+	pub step_durations: BTreeMap<u64, u16>,
 	/// Starting step,
 	pub start_step: Option<u64>,
 	/// Valid validators.
@@ -83,19 +87,31 @@ pub struct AuthorityRoundParams {
 	pub empty_steps_transition: u64,
 	/// Number of accepted empty steps.
 	pub maximum_empty_steps: usize,
+	/// Randomness contract validators commit and reveal secrets to.
+	pub randomness_contract_address: Option<Address>,
 }
 
 const U16_MAX: usize = ::std::u16::MAX as usize;
 
 impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 	fn from(p: ethjson::spec::AuthorityRoundParams) -> Self {
-		let mut step_duration_usize: usize = p.step_duration.into();
-		if step_duration_usize > U16_MAX {
-			step_duration_usize = U16_MAX;
-			warn!(target: "engine", "step_duration is too high ({}), setting it to {}", step_duration_usize, U16_MAX);
-		}
+		let map_step_duration = |u: ethjson::uint::Uint| {
+			let step_duration_usize: usize = u.into();
+			if step_duration_usize > U16_MAX {
+				warn!(target: "engine", "step_duration is too high ({}), setting it to {}", step_duration_usize, U16_MAX);
+				U16_MAX as u16
+			} else {
+				step_duration_usize as u16
+			}
+		};
+		let step_durations = match p.step_duration {
+			ethjson::spec::StepDuration::Single(u) => ::std::iter::once((0, map_step_duration(u))).collect(),
+			ethjson::spec::StepDuration::Transitions(transitions) => transitions.into_iter()
+				.map(|(timestamp, u)| (timestamp.into(), map_step_duration(u)))
+				.collect(),
+		};
 		AuthorityRoundParams {
-			step_duration: step_duration_usize as u16,
+			step_durations: step_durations,
 			validators: new_validator_set(p.validators),
 			start_step: p.start_step.map(Into::into),
 			validate_score_transition: p.validate_score_transition.map_or(0, Into::into),
@@ -108,25 +124,91 @@ impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 			maximum_uncle_count: p.maximum_uncle_count.map_or(0, Into::into),
 			empty_steps_transition: p.empty_steps_transition.map_or(u64::max_value(), |n| ::std::cmp::max(n.into(), 1)),
 			maximum_empty_steps: p.maximum_empty_steps.map_or(0, Into::into),
+			randomness_contract_address: p.randomness_contract_address.map(Into::into),
 		}
 	}
 }
 
+// Step duration in effect from a given step onwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StepDurationInfo {
+	transition_step: u64,
+	transition_timestamp: u64,
+	step_duration: u64,
+}
+
+// Computes the steps at which the step duration changes. A new duration takes effect from the
+// first step starting at or after its timestamp, so that step numbers stay continuous.
+fn step_duration_infos(step_durations: &BTreeMap<u64, u16>) -> Vec<StepDurationInfo> {
+	let mut durations: Vec<StepDurationInfo> = Vec::with_capacity(step_durations.len());
+	for (&timestamp, &duration) in step_durations {
+		let info = match durations.last() {
+			None => StepDurationInfo {
+				transition_step: 0,
+				transition_timestamp: 0,
+				step_duration: duration as u64,
+			},
+			Some(prev) => {
+				let elapsed = timestamp.saturating_sub(prev.transition_timestamp);
+				let steps = (elapsed + prev.step_duration - 1) / prev.step_duration;
+				StepDurationInfo {
+					transition_step: prev.transition_step + steps,
+					transition_timestamp: prev.transition_timestamp + steps * prev.step_duration,
+					step_duration: duration as u64,
+				}
+			},
+		};
+		durations.push(info);
+	}
+	durations
+}
+
 // Helper for managing the step.
 #[derive(Debug)]
 struct Step {
 	calibrate: bool, // whether calibration is enabled.
 	inner: AtomicUsize,
-	duration: u16,
+	durations: Vec<StepDurationInfo>, // ordered by transition step, the first one at step 0.
 }
 
 impl Step {
 	fn load(&self) -> usize { self.inner.load(AtomicOrdering::SeqCst) }
+
+	// step duration in effect at the given step.
+	fn duration_at(&self, step: u64) -> StepDurationInfo {
+		*self.durations.iter()
+			.take_while(|info| info.transition_step <= step)
+			.last()
+			.expect("durations always start at step 0; qed")
+	}
+
+	// unix timestamp at which the given step starts, `None` on overflow.
+	fn step_start(&self, step: u64) -> Option<u64> {
+		let info = self.duration_at(step);
+		(step - info.transition_step)
+			.checked_mul(info.step_duration)
+			.and_then(|secs| secs.checked_add(info.transition_timestamp))
+	}
+
+	// first step from which the step duration differs from the initial one, if it ever does.
+	fn first_transition_step(&self) -> Option<u64> {
+		self.durations.get(1).map(|info| info.transition_step)
+	}
+
+	// step in progress at the given unix timestamp.
+	fn step_at_time(&self, timestamp: u64) -> u64 {
+		let info = *self.durations.iter()
+			.take_while(|info| info.transition_timestamp <= timestamp)
+			.last()
+			.expect("durations always start at timestamp 0; qed");
+		info.transition_step.saturating_add((timestamp - info.transition_timestamp) / info.step_duration)
+	}
+
 	fn duration_remaining(&self) -> Duration {
 		let now = unix_now();
 		let expected_seconds = (self.load() as u64)
 			.checked_add(1)
-			.and_then(|ctr| ctr.checked_mul(self.duration as u64))
+			.and_then(|next_step| self.step_start(next_step))
 			.map(Duration::from_secs);
 
 		match expected_seconds {
@@ -155,7 +237,7 @@ impl Step {
 
 	fn calibrate(&self) {
 		if self.calibrate {
-			let new_step = unix_now().as_secs() / (self.duration as u64);
+			let new_step = self.step_at_time(unix_now().as_secs());
 			self.inner.store(new_step as usize, AtomicOrdering::SeqCst);
 		}
 	}
@@ -177,11 +259,10 @@ impl Step {
 			Err(None)
 		// wait a bit for blocks in near future
 		} else if given > current {
-			let d = self.duration as u64;
 			Err(Some(OutOfBounds {
 				min: None,
-				max: Some(d * current as u64),
-				found: d * given as u64,
+				max: self.step_start(current as u64),
+				found: self.step_start(given as u64).unwrap_or(u64::max_value()),
 			}))
 		} else {
 			Ok(())
@@ -398,6 +479,9 @@ pub struct AuthorityRound {
 	validate_score_transition: u64,
 	validate_step_transition: u64,
 	empty_steps: Mutex<Vec<EmptyStep>>,
+	// headers seen recently, by step and author, to detect equivocations,
+	// and whether an equivocation was already reported for that step and author.
+	received_step_headers: RwLock<BTreeMap<(u64, Address), (Header, bool)>>,
 	epoch_manager: Mutex<EpochManager>,
 	immediate_transitions: bool,
 	block_reward: U256,
@@ -407,6 +491,9 @@ pub struct AuthorityRound {
 	maximum_uncle_count: usize,
 	empty_steps_transition: u64,
 	maximum_empty_steps: usize,
+	randomness_contract: Option<RandomnessContract>,
+	// last randomness phase we sent a transaction for and its hash, so that it is not resent while pending.
+	randomness_phase_sent: Mutex<Option<(RandomnessPhase, H256)>>,
 	machine: EthereumMachine,
 }
 
@@ -582,6 +669,13 @@ fn verify_external(header: &Header, validators: &ValidatorSet, empty_steps_trans
 	}
 }
 
+// proof of equivocation: both headers signed by the same author for the same step.
+fn equivocation_proof(first: &Header, second: &Header) -> Vec<u8> {
+	let mut stream = ::rlp::RlpStream::new_list(2);
+	stream.append(first).append(second);
+	stream.out()
+}
+
 fn combine_proofs(signal_number: BlockNumber, set_proof: &[u8], finality_proof: &[u8]) -> Vec<u8> {
 	let mut stream = ::rlp::RlpStream::new_list(3);
 	stream.append(&signal_number).append(&set_proof).append(&finality_proof);
@@ -627,21 +721,26 @@ impl<'a, A: ?Sized, B> Deref for CowLike<'a, A, B> where B: AsRef<A> {
 impl AuthorityRound {
 	/// Create a new instance of AuthorityRound engine.
 	pub fn new(our_params: AuthorityRoundParams, machine: EthereumMachine) -> Result<Arc<Self>, Error> {
-		if our_params.step_duration == 0 {
+		if our_params.step_durations.values().any(|duration| *duration == 0) {
 			error!(target: "engine", "Authority Round step duration can't be zero, aborting");
 			panic!("authority_round: step duration can't be zero")
 		}
+		if !our_params.step_durations.contains_key(&0) {
+			error!(target: "engine", "Authority Round step duration for timestamp 0 is missing, aborting");
+			panic!("authority_round: step duration for timestamp 0 is missing")
+		}
 		let should_timeout = our_params.start_step.is_none();
-		let initial_step = our_params.start_step.unwrap_or_else(|| (unix_now().as_secs() / (our_params.step_duration as u64))) as usize;
+		let step = Step {
+			inner: AtomicUsize::new(our_params.start_step.unwrap_or(0) as usize),
+			calibrate: our_params.start_step.is_none(),
+			durations: step_duration_infos(&our_params.step_durations),
+		};
+		step.calibrate();
 		let engine = Arc::new(
 			AuthorityRound {
 				transition_service: IoService::<()>::start()?,
 				step: Arc::new(PermissionedStep {
-					inner: Step {
-						inner: AtomicUsize::new(initial_step),
-						calibrate: our_params.start_step.is_none(),
-						duration: our_params.step_duration,
-					},
+					inner: step,
 					can_propose: AtomicBool::new(true),
 				}),
 				client: Arc::new(RwLock::new(None)),
@@ -650,6 +749,7 @@ impl AuthorityRound {
 				validate_score_transition: our_params.validate_score_transition,
 				validate_step_transition: our_params.validate_step_transition,
 				empty_steps: Mutex::new(Vec::new()),
+				received_step_headers: RwLock::new(BTreeMap::new()),
 				epoch_manager: Mutex::new(EpochManager::blank()),
 				immediate_transitions: our_params.immediate_transitions,
 				block_reward: our_params.block_reward,
@@ -659,6 +759,8 @@ impl AuthorityRound {
 				maximum_uncle_count: our_params.maximum_uncle_count,
 				empty_steps_transition: our_params.empty_steps_transition,
 				maximum_empty_steps: our_params.maximum_empty_steps,
+				randomness_contract: our_params.randomness_contract_address.map(RandomnessContract::new),
+				randomness_phase_sent: Mutex::new(None),
 				machine: machine,
 			});
 
@@ -667,6 +769,7 @@ impl AuthorityRound {
 			let handler = TransitionHandler {
 				step: engine.step.clone(),
 				client: engine.client.clone(),
+				engine: Arc::downgrade(&engine),
 			};
 			engine.transition_service.register_handler(Arc::new(handler))?;
 		}
@@ -697,6 +800,41 @@ impl AuthorityRound {
 		})
 	}
 
+	// remember the header's author signed it for the given step, and report the author as malicious
+	// if a different header was already seen for that step. Each equivocation is reported once.
+	fn report_equivocation(&self, header: &Header, step: u64, validators: &ValidatorSet, set_number: u64) {
+		let key = (step, *header.author());
+		let proof = {
+			let mut received = self.received_step_headers.write();
+
+			// forget headers older than two full rounds of steps.
+			let oldest_step = step.saturating_sub(2 * validators.count(header.parent_hash()) as u64);
+			let recent = received.split_off(&(oldest_step, Address::zero()));
+			*received = recent;
+
+			match received.entry(key) {
+				Entry::Vacant(entry) => {
+					entry.insert((header.clone(), false));
+					None
+				},
+				Entry::Occupied(mut entry) => {
+					let &mut (ref previous, ref mut reported) = entry.get_mut();
+					if *reported || previous.hash() == header.hash() {
+						None
+					} else {
+						*reported = true;
+						Some(equivocation_proof(previous, header))
+					}
+				},
+			}
+		};
+
+		if let Some(proof) = proof {
+			trace!(target: "engine", "Validator {} signed multiple blocks for step {}.", header.author(), step);
+			self.validators.report_malicious(header.author(), set_number, header.number(), proof);
+		}
+	}
+
 	fn empty_steps(&self, from_step: U256, to_step: U256, parent_hash: H256) -> Vec<EmptyStep> {
 		self.empty_steps.lock().iter().filter(|e| {
 			U256::from(e.step) > from_step &&
@@ -743,6 +881,62 @@ impl AuthorityRound {
 		}
 	}
 
+	// Take part in the randomness contract's commit-reveal scheme, if one is configured and we are
+	// a validator. Commits and reveals are sent as service transactions signed by the engine signer.
+	fn run_randomness_phase(&self) {
+		let contract = match self.randomness_contract {
+			Some(ref contract) => contract,
+			None => return,
+		};
+		let signer = self.signer.read();
+		let our_address = match signer.address() {
+			Some(address) => address,
+			None => return,
+		};
+		let client = match self.client.read().as_ref().and_then(Weak::upgrade) {
+			Some(client) => client,
+			None => return,
+		};
+		let full_client = match client.as_full_client() {
+			Some(full_client) => full_client,
+			None => return,
+		};
+
+		let best_hash = client.chain_info().best_block_hash;
+		if !self.validators.contains(&best_hash, &our_address) {
+			trace!(target: "engine", "Not a validator at block {}, skipping randomness phase", best_hash);
+			return;
+		}
+
+		let caller = |data| full_client.call_contract(::client::BlockId::Latest, contract.address(), data);
+		let phase = match contract.phase(our_address, &caller) {
+			Ok(phase) => phase,
+			Err(e) => {
+				warn!(target: "engine", "Failed to read randomness contract phase: {}", e);
+				return;
+			},
+		};
+
+		// the transaction for this phase is resent only if it was dropped from the queue.
+		let mut phase_sent = self.randomness_phase_sent.lock();
+		match *phase_sent {
+			Some((sent, ref hash)) if sent == phase && full_client.pooled_transaction(hash).is_some() => return,
+			_ => {},
+		}
+
+		match contract.advance(phase, &*signer, &caller) {
+			Ok(Some(data)) => match full_client.transact_service_contract(contract.address(), data) {
+				Ok(hash) => {
+					debug!(target: "engine", "Sent randomness transaction {} for {:?}", hash, phase);
+					*phase_sent = Some((phase, hash));
+				},
+				Err(e) => warn!(target: "engine", "Failed to send randomness transaction: {}", e),
+			},
+			Ok(None) => {},
+			Err(e) => warn!(target: "engine", "Failed to advance randomness phase {:?}: {}", phase, e),
+		}
+	}
+
 	fn report_skipped(&self, header: &Header, current_step: usize, parent_step: usize, validators: &ValidatorSet, set_number: u64) {
 		// we're building on top of the genesis block so don't report any skipped steps
 		if header.number() == 1 {
@@ -773,6 +967,7 @@ fn unix_now() -> Duration {
 struct TransitionHandler {
 	step: Arc<PermissionedStep>,
 	client: Arc<RwLock<Option<Weak<EngineClient>>>>,
+	engine: Weak<AuthorityRound>,
 }
 
 const ENGINE_TIMEOUT_TOKEN: TimerToken = 23;
@@ -792,6 +987,9 @@ impl IoHandler<()> for TransitionHandler {
 			while self.step.inner.duration_remaining().as_millis() == 0 {
 				self.step.inner.increment();
 				self.step.can_propose.store(true, AtomicOrdering::SeqCst);
+				if let Some(engine) = self.engine.upgrade() {
+					engine.run_randomness_phase();
+				}
 				if let Some(ref weak) = *self.client.read() {
 					if let Some(c) = weak.upgrade() {
 						c.update_sealing();
@@ -820,6 +1018,7 @@ impl Engine<EthereumMachine> for AuthorityRound {
 	fn step(&self) {
 		self.step.inner.increment();
 		self.step.can_propose.store(true, AtomicOrdering::SeqCst);
+		self.run_randomness_phase();
 		if let Some(ref weak) = *self.client.read() {
 			if let Some(c) = weak.upgrade() {
 				c.update_sealing();
@@ -1140,12 +1339,21 @@ impl Engine<EthereumMachine> for AuthorityRound {
 
 		let (validators, set_number) = self.epoch_set(header)?;
 
+		// Steps must be monotonic from `validate_step_transition` on, and once the step duration
+		// has changed, since the step of a block is no longer proportional to its time.
+		let validate_step = header.number() >= self.validate_step_transition
+			|| self.step.inner.first_transition_step().map_or(false, |transition| step as u64 >= transition);
+
 		// Ensure header is from the step after parent.
-		if step == parent_step
-			|| (header.number() >= self.validate_step_transition && step <= parent_step) {
+		if step == parent_step || (validate_step && step <= parent_step) {
 			trace!(target: "engine", "Multiple blocks proposed for step {}.", parent_step);
 
-			self.validators.report_malicious(header.author(), set_number, header.number(), Default::default());
+			let proof = if step == parent_step && header.author() == parent.author() {
+				equivocation_proof(parent, header)
+			} else {
+				Default::default()
+			};
+			self.validators.report_malicious(header.author(), set_number, header.number(), proof);
 			Err(EngineError::DoubleVote(header.author().clone()))?;
 		}
 
@@ -1200,6 +1408,9 @@ impl Engine<EthereumMachine> for AuthorityRound {
 				// we can drop all accumulated empty step messages that are older than this header's step
 				let header_step = header_step(header, self.empty_steps_transition)?;
 				self.clear_empty_steps(header_step.into());
+
+				// the signature is valid, so a different header for the same step is proof of equivocation.
+				self.report_equivocation(header, header_step, &*validators, set_number);
 			},
 			_ => {},
 		}
@@ -1580,7 +1791,7 @@ mod tests {
 	fn reports_skipped() {
 		let last_benign = Arc::new(AtomicUsize::new(0));
 		let params = AuthorityRoundParams {
-			step_duration: 1,
+			step_durations: [(0, 1)].to_vec().into_iter().collect(),
 			start_step: Some(1),
 			validators: Box::new(TestSet::new(Default::default(), last_benign.clone())),
 			validate_score_transition: 0,
@@ -1590,6 +1801,7 @@ mod tests {
 			maximum_uncle_count: 0,
 			empty_steps_transition: u64::max_value(),
 			maximum_empty_steps: 0,
+			randomness_contract_address: None,
 			block_reward: Default::default(),
 			block_reward_contract_transition: 0,
 			block_reward_contract: Default::default(),
@@ -1626,11 +1838,94 @@ mod tests {
 		assert_eq!(last_benign.load(AtomicOrdering::SeqCst), 2);
 	}
 
+	#[test]
+	fn reports_equivocation() {
+		let last_malicious = Arc::new(AtomicUsize::new(0));
+		let params = AuthorityRoundParams {
+			step_durations: [(0, 1)].to_vec().into_iter().collect(),
+			start_step: Some(1),
+			validators: Box::new(TestSet::new(last_malicious.clone(), Default::default())),
+			validate_score_transition: 0,
+			validate_step_transition: 0,
+			immediate_transitions: true,
+			maximum_uncle_count_transition: 0,
+			maximum_uncle_count: 0,
+			empty_steps_transition: u64::max_value(),
+			maximum_empty_steps: 0,
+			randomness_contract_address: None,
+			block_reward: Default::default(),
+			block_reward_contract_transition: 0,
+			block_reward_contract: Default::default(),
+		};
+
+		let aura = {
+			let mut c_params = ::spec::CommonParams::default();
+			c_params.gas_limit_bound_divisor = 5.into();
+			let machine = ::machine::EthereumMachine::regular(c_params, Default::default());
+			AuthorityRound::new(params, machine).unwrap()
+		};
+
+		let tap = AccountProvider::transient_provider();
+		let addr = tap.insert_account(keccak("1").into(), "1").unwrap();
+		let seal = |header: &mut Header, step: usize| {
+			let signature = tap.sign(addr, Some("1".into()), header.bare_hash()).unwrap();
+			header.set_seal(vec![encode(&step).into_vec(), encode(&(&*signature as &[u8])).into_vec()]);
+		};
+
+		let mut header: Header = Header::default();
+		header.set_number(2);
+		header.set_author(addr);
+		let mut sibling = header.clone();
+		sibling.set_timestamp(1);
+		let mut next = header.clone();
+		next.set_number(3);
+
+		seal(&mut header, 2);
+		seal(&mut sibling, 2);
+		seal(&mut next, 3);
+
+		// Seeing the same header twice is not an equivocation.
+		assert!(aura.verify_block_external(&header).is_ok());
+		assert!(aura.verify_block_external(&header).is_ok());
+		assert_eq!(last_malicious.load(AtomicOrdering::SeqCst), 0);
+
+		// Neither is signing a header for another step.
+		assert!(aura.verify_block_external(&next).is_ok());
+		assert_eq!(last_malicious.load(AtomicOrdering::SeqCst), 0);
+
+		// Report a different header signed for the same step.
+		assert!(aura.verify_block_external(&sibling).is_ok());
+		assert_eq!(last_malicious.load(AtomicOrdering::SeqCst), 2);
+
+		// The same equivocation is reported only once.
+		last_malicious.store(0, AtomicOrdering::SeqCst);
+		assert!(aura.verify_block_external(&sibling).is_ok());
+		assert!(aura.verify_block_external(&header).is_ok());
+		assert_eq!(last_malicious.load(AtomicOrdering::SeqCst), 0);
+	}
+
+	#[test]
+	fn equivocation_proof_contains_both_headers() {
+		use rlp::Rlp;
+		use super::equivocation_proof;
+
+		let mut first: Header = Header::default();
+		first.set_number(2);
+		let mut second = first.clone();
+		second.set_timestamp(1);
+
+		let proof = equivocation_proof(&first, &second);
+		let rlp = Rlp::new(&proof);
+		assert_eq!(rlp.item_count().unwrap(), 2);
+		assert_eq!(rlp.val_at::<Header>(0).unwrap(), first);
+		assert_eq!(rlp.val_at::<Header>(1).unwrap(), second);
+	}
+
 	#[test]
 	fn test_uncles_transition() {
 		let last_benign = Arc::new(AtomicUsize::new(0));
 		let params = AuthorityRoundParams {
-			step_duration: 1,
+			step_durations: [(0, 1)].to_vec().into_iter().collect(),
 			start_step: Some(1),
 			validators: Box::new(TestSet::new(Default::default(), last_benign.clone())),
 			validate_score_transition: 0,
@@ -1640,6 +1935,7 @@ mod tests {
 			maximum_uncle_count: 0,
 			empty_steps_transition: u64::max_value(),
 			maximum_empty_steps: 0,
+			randomness_contract_address: None,
 			block_reward: Default::default(),
 			block_reward_contract_transition: 0,
 			block_reward_contract: Default::default(),
@@ -1660,11 +1956,11 @@ mod tests {
     #[test]
     #[should_panic(expected="counter is too high")]
     fn test_counter_increment_too_high() {
-        use super::Step;
+        use super::{Step, StepDurationInfo};
         let step = Step {
             calibrate: false,
             inner: AtomicUsize::new(::std::usize::MAX),
-            duration: 1,
+            durations: vec![StepDurationInfo { transition_step: 0, transition_timestamp: 0, step_duration: 1 }],
         };
         step.increment();
 	}
@@ -1672,11 +1968,11 @@ mod tests {
 	#[test]
 	#[should_panic(expected="counter is too high")]
 	fn test_counter_duration_remaining_too_high() {
-		use super::Step;
+		use super::{Step, StepDurationInfo};
 		let step = Step {
 			calibrate: false,
 			inner: AtomicUsize::new(::std::usize::MAX),
-			duration: 1,
+			durations: vec![StepDurationInfo { transition_step: 0, transition_timestamp: 0, step_duration: 1 }],
 		};
 		step.duration_remaining();
 	}
@@ -1686,7 +1982,7 @@ mod tests {
 	fn test_step_duration_zero() {
 		let last_benign = Arc::new(AtomicUsize::new(0));
 		let params = AuthorityRoundParams {
-			step_duration: 0,
+			step_durations: [(0, 0)].to_vec().into_iter().collect(),
 			start_step: Some(1),
 			validators: Box::new(TestSet::new(Default::default(), last_benign.clone())),
 			validate_score_transition: 0,
@@ -1696,6 +1992,33 @@ mod tests {
 			maximum_uncle_count: 0,
 			empty_steps_transition: u64::max_value(),
 			maximum_empty_steps: 0,
+			randomness_contract_address: None,
+			block_reward: Default::default(),
+			block_reward_contract_transition: 0,
+			block_reward_contract: Default::default(),
+		};
+
+		let mut c_params = ::spec::CommonParams::default();
+		c_params.gas_limit_bound_divisor = 5.into();
+		let machine = ::machine::EthereumMachine::regular(c_params, Default::default());
+		AuthorityRound::new(params, machine).unwrap();
+	}
+
+	#[test]
+	#[should_panic(expected="authority_round: step duration for timestamp 0 is missing")]
+	fn test_step_duration_missing_genesis_entry() {
+		let params = AuthorityRoundParams {
+			step_durations: [(10, 2)].to_vec().into_iter().collect(),
+			start_step: Some(1),
+			validators: Box::new(TestSet::new(Default::default(), Default::default())),
+			validate_score_transition: 0,
+			validate_step_transition: 0,
+			immediate_transitions: true,
+			maximum_uncle_count_transition: 0,
+			maximum_uncle_count: 0,
+			empty_steps_transition: u64::max_value(),
+			maximum_empty_steps: 0,
+			randomness_contract_address: None,
 			block_reward: Default::default(),
 			block_reward_contract_transition: 0,
 			block_reward_contract: Default::default(),
@@ -1707,6 +2030,75 @@ mod tests {
 		AuthorityRound::new(params, machine).unwrap();
 	}
 
+	#[test]
+	fn step_duration_transitions() {
+		use super::{Step, StepDurationInfo, step_duration_infos};
+
+		// switch from 5 to 2 second steps at the first step boundary after timestamp 1001.
+		let durations = step_duration_infos(&[(0, 5), (1001, 2)].to_vec().into_iter().collect());
+		assert_eq!(durations, vec![
+			StepDurationInfo { transition_step: 0, transition_timestamp: 0, step_duration: 5 },
+			StepDurationInfo { transition_step: 201, transition_timestamp: 1005, step_duration: 2 },
+		]);
+
+		let step = Step {
+			calibrate: false,
+			inner: AtomicUsize::new(0),
+			durations: durations,
+		};
+
+		assert_eq!(step.step_start(200), Some(1000));
+		assert_eq!(step.step_start(201), Some(1005));
+		assert_eq!(step.step_start(202), Some(1007));
+		assert_eq!(step.step_at_time(1004), 200);
+		assert_eq!(step.step_at_time(1005), 201);
+		assert_eq!(step.step_at_time(1008), 202);
+	}
+
+	#[test]
+	fn validates_steps_after_step_duration_transition() {
+		let params = AuthorityRoundParams {
+			// steps of 2 seconds from step 10 on.
+			step_durations: [(0, 1), (10, 2)].to_vec().into_iter().collect(),
+			start_step: Some(1),
+			validators: Box::new(TestSet::new(Default::default(), Default::default())),
+			validate_score_transition: 0,
+			validate_step_transition: u64::max_value(),
+			immediate_transitions: true,
+			maximum_uncle_count_transition: 0,
+			maximum_uncle_count: 0,
+			empty_steps_transition: u64::max_value(),
+			maximum_empty_steps: 0,
+			randomness_contract_address: None,
+			block_reward: Default::default(),
+			block_reward_contract_transition: 0,
+			block_reward_contract: Default::default(),
+		};
+
+		let aura = {
+			let mut c_params = ::spec::CommonParams::default();
+			c_params.gas_limit_bound_divisor = 5.into();
+			let machine = ::machine::EthereumMachine::regular(c_params, Default::default());
+			AuthorityRound::new(params, machine).unwrap()
+		};
+
+		let mut parent_header: Header = Header::default();
+		parent_header.set_gas_limit("222222".parse::<U256>().unwrap());
+		let mut header: Header = Header::default();
+		header.set_number(2);
+		header.set_gas_limit("222222".parse::<U256>().unwrap());
+
+		// steps are not validated before the transition.
+		parent_header.set_seal(vec![encode(&5usize).into_vec()]);
+		header.set_seal(vec![encode(&4usize).into_vec()]);
+		assert!(aura.verify_block_family(&header, &parent_header).is_ok());
+
+		// but are once the step duration has changed.
+		parent_header.set_seal(vec![encode(&12usize).into_vec()]);
+		header.set_seal(vec![encode(&11usize).into_vec()]);
+		assert!(aura.verify_block_family(&header, &parent_header).is_err());
+	}
+
 	fn setup_empty_steps() -> (Spec, Arc<AccountProvider>, Vec<Address>) {
 		let spec = Spec::new_test_round_empty_steps();
 		let tap = Arc::new(AccountProvider::transient_provider());
@@ -0,0 +1,221 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! On-chain randomness generation for authority round.
+//!
+//! Validators take part in a commit-reveal scheme run by a randomness contract: in the commit
+//! phase of a collection round each validator commits to the hash of a fresh secret, and in the
+//! reveal phase it reveals that secret. The contract combines the revealed secrets into a random
+//! seed which other contracts can read.
+//!
+//! The secret itself is committed alongside its hash, encrypted to the validator's own public
+//! key, so that it can be recovered through the account provider when it has to be revealed.
+
+use bytes::Bytes;
+use ethcore_crypto::{ecies, DEFAULT_MAC};
+use ethereum_types::{H256, U256, Address};
+use hash::keccak;
+
+use super::super::signer::EngineSigner;
+
+use_contract!(aura_random, "AuthorityRoundRandom", "res/contracts/authority_round_random.json");
+
+/// What the local validator has to do in the current collection round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandomnessPhase {
+	/// Nothing to do until the next phase.
+	Waiting,
+	/// Commit phase of the given round, our secret hash was not committed yet.
+	BeforeCommit(U256),
+	/// Reveal phase of the given round, our secret was committed but not revealed yet.
+	Reveal(U256),
+}
+
+/// Randomness contract deployed at a given address.
+pub struct RandomnessContract {
+	address: Address,
+	provider: aura_random::AuthorityRoundRandom,
+}
+
+impl RandomnessContract {
+	/// Create a new randomness contract handle.
+	pub fn new(address: Address) -> Self {
+		RandomnessContract {
+			address: address,
+			provider: aura_random::AuthorityRoundRandom::default(),
+		}
+	}
+
+	/// Address of the contract.
+	pub fn address(&self) -> Address {
+		self.address
+	}
+
+	/// Determine the phase `our_address` is in, reading the contract state through `caller`.
+	pub fn phase(&self, our_address: Address, caller: &Fn(Bytes) -> Result<Bytes, String>) -> Result<RandomnessPhase, String> {
+		let functions = self.provider.functions();
+		let call = |data| caller(data);
+
+		let round = functions.current_collect_round().call(&call).map_err(|e| e.to_string())?;
+		let committed = functions.is_committed().call(round, our_address, &call).map_err(|e| e.to_string())?;
+
+		if functions.is_commit_phase().call(&call).map_err(|e| e.to_string())? {
+			return Ok(if committed { RandomnessPhase::Waiting } else { RandomnessPhase::BeforeCommit(round) });
+		}
+
+		if committed && functions.is_reveal_phase().call(&call).map_err(|e| e.to_string())? {
+			let revealed = functions.sent_reveal().call(round, our_address, &call).map_err(|e| e.to_string())?;
+			if !revealed {
+				return Ok(RandomnessPhase::Reveal(round));
+			}
+		}
+
+		Ok(RandomnessPhase::Waiting)
+	}
+
+	/// Build the call data taking `signer` to the next phase, if there is anything to do.
+	pub fn advance(&self, phase: RandomnessPhase, signer: &EngineSigner, caller: &Fn(Bytes) -> Result<Bytes, String>) -> Result<Option<Bytes>, String> {
+		let functions = self.provider.functions();
+		let call = |data| caller(data);
+
+		match phase {
+			RandomnessPhase::Waiting => Ok(None),
+			RandomnessPhase::BeforeCommit(_) => {
+				let public = signer.public().ok_or_else(|| "Engine signer public key is unavailable".to_owned())?;
+				let secret = H256::random();
+				let cipher = ecies::encrypt(&public, &DEFAULT_MAC, &secret[..])
+					.map_err(|e| format!("Failed to encrypt secret: {}", e))?;
+				Ok(Some(functions.commit_hash().input(keccak(&secret[..]), cipher)))
+			},
+			RandomnessPhase::Reveal(round) => {
+				let our_address = signer.address().ok_or_else(|| "Engine signer is not set".to_owned())?;
+				let committed = functions.get_commit().call(round, our_address, &call).map_err(|e| e.to_string())?;
+				let cipher = functions.get_cipher().call(round, our_address, &call).map_err(|e| e.to_string())?;
+				let plain = signer.decrypt(&DEFAULT_MAC, &cipher)
+					.map_err(|e| format!("Failed to decrypt committed secret: {}", e))?;
+				if plain.len() != 32 {
+					return Err(format!("Committed secret has invalid length {}", plain.len()));
+				}
+				let secret = H256::from_slice(&plain);
+				if keccak(&secret[..]) != committed {
+					return Err("Committed hash does not match the decrypted secret".into());
+				}
+				Ok(Some(functions.reveal_secret().input(secret)))
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use ethabi::{self, Token};
+	use ethcore_crypto::{ecies, DEFAULT_MAC};
+	use ethereum_types::{H256, U256, Address};
+	use hash::keccak;
+	use account_provider::AccountProvider;
+	use bytes::Bytes;
+	use engines::signer::EngineSigner;
+	use super::{aura_random, RandomnessContract, RandomnessPhase};
+
+	struct ContractState {
+		round: U256,
+		commit_phase: bool,
+		committed: bool,
+		revealed: bool,
+		commit: H256,
+		cipher: Bytes,
+	}
+
+	// answers the contract's view functions from `state`.
+	fn call(state: &ContractState, data: Bytes) -> Result<Bytes, String> {
+		let contract = aura_random::AuthorityRoundRandom::default();
+		let functions = contract.functions();
+		let token = if data == functions.current_collect_round().input() {
+			Token::Uint(state.round)
+		} else if data == functions.is_commit_phase().input() {
+			Token::Bool(state.commit_phase)
+		} else if data == functions.is_reveal_phase().input() {
+			Token::Bool(!state.commit_phase)
+		} else if data[..4] == functions.is_committed().input(state.round, Address::default())[..4] {
+			Token::Bool(state.committed)
+		} else if data[..4] == functions.sent_reveal().input(state.round, Address::default())[..4] {
+			Token::Bool(state.revealed)
+		} else if data[..4] == functions.get_commit().input(state.round, Address::default())[..4] {
+			Token::FixedBytes(state.commit.to_vec())
+		} else if data[..4] == functions.get_cipher().input(state.round, Address::default())[..4] {
+			Token::Bytes(state.cipher.clone())
+		} else {
+			return Err("unknown function".into());
+		};
+		Ok(ethabi::encode(&[token]))
+	}
+
+	fn state() -> ContractState {
+		ContractState {
+			round: 3.into(),
+			commit_phase: true,
+			committed: false,
+			revealed: false,
+			commit: H256::default(),
+			cipher: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn determines_phase() {
+		let contract = RandomnessContract::new(Address::default());
+		let mut state = state();
+		let our_address = Address::from(5);
+
+		assert_eq!(contract.phase(our_address, &|data| call(&state, data)).unwrap(), RandomnessPhase::BeforeCommit(3.into()));
+		state.committed = true;
+		assert_eq!(contract.phase(our_address, &|data| call(&state, data)).unwrap(), RandomnessPhase::Waiting);
+		state.commit_phase = false;
+		assert_eq!(contract.phase(our_address, &|data| call(&state, data)).unwrap(), RandomnessPhase::Reveal(3.into()));
+		state.revealed = true;
+		assert_eq!(contract.phase(our_address, &|data| call(&state, data)).unwrap(), RandomnessPhase::Waiting);
+		state.committed = false;
+		state.revealed = false;
+		assert_eq!(contract.phase(our_address, &|data| call(&state, data)).unwrap(), RandomnessPhase::Waiting);
+	}
+
+	#[test]
+	fn commits_and_reveals_secret() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let our_address = tap.insert_account(keccak("1").into(), "").unwrap();
+		let mut signer = EngineSigner::default();
+		signer.set(tap.clone(), our_address, "".into());
+
+		let contract = RandomnessContract::new(Address::default());
+		let functions = aura_random::AuthorityRoundRandom::default();
+		let mut state = state();
+
+		assert_eq!(contract.advance(RandomnessPhase::Waiting, &signer, &|data| call(&state, data)), Ok(None));
+		let commit = contract.advance(RandomnessPhase::BeforeCommit(3.into()), &signer, &|data| call(&state, data)).unwrap().unwrap();
+		assert_eq!(commit[..4], functions.functions().commit_hash().input(H256::default(), Vec::new())[..4]);
+
+		let secret = H256::random();
+		let public = tap.account_public(our_address, "").unwrap();
+		state.commit = keccak(&secret[..]);
+		state.cipher = ecies::encrypt(&public, &DEFAULT_MAC, &secret[..]).unwrap();
+		let reveal = contract.advance(RandomnessPhase::Reveal(3.into()), &signer, &|data| call(&state, data)).unwrap();
+		assert_eq!(reveal, Some(functions.functions().reveal_secret().input(secret)));
+
+		state.commit = H256::default();
+		assert!(contract.advance(RandomnessPhase::Reveal(3.into()), &signer, &|data| call(&state, data)).is_err());
+	}
+}
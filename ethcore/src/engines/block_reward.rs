@@ -46,10 +46,10 @@ impl From<RewardKind> for u16 {
 }
 
 /// A client for the block reward contract.
+#[derive(PartialEq, Debug, Clone)]
 pub struct BlockRewardContract {
 	/// Address of the contract.
 	address: Address,
-	block_reward_contract: block_reward_contract::BlockReward,
 }
 
 impl BlockRewardContract {
@@ -57,7 +57,6 @@ impl BlockRewardContract {
 	pub fn new(address: Address) -> BlockRewardContract {
 		BlockRewardContract {
 			address,
-			block_reward_contract: block_reward_contract::BlockReward::default(),
 		}
 	}
 
@@ -70,7 +69,8 @@ impl BlockRewardContract {
 		benefactors: &[(Address, RewardKind)],
 		caller: &mut SystemCall,
 	) -> Result<Vec<(Address, U256)>, Error> {
-		let reward = self.block_reward_contract.functions().reward();
+		let block_reward_contract = block_reward_contract::BlockReward::default();
+		let reward = block_reward_contract.functions().reward();
 
 		let input = reward.input(
 			benefactors.iter().map(|&(address, _)| H160::from(address)),
@@ -0,0 +1,232 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique signer set and voting state at a given block.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use ethereum_types::{H64, Address};
+use rlp::Rlp;
+
+use engines::EngineError;
+use error::{Error, BlockError};
+use header::{Header, BlockNumber};
+use unexpected::Mismatch;
+
+use super::{NONCE_AUTH_VOTE, NONCE_DROP_VOTE};
+use super::util::recover_creator;
+
+/// A vote on the signer set, encoded in the header nonce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoteType {
+	/// Vote to add the beneficiary to the signers.
+	Add,
+	/// Vote to remove the beneficiary from the signers.
+	Remove,
+}
+
+impl VoteType {
+	/// Decode a vote from a header nonce.
+	pub fn from_nonce(nonce: H64) -> Result<Self, Error> {
+		if nonce == NONCE_AUTH_VOTE.into() {
+			Ok(VoteType::Add)
+		} else if nonce == NONCE_DROP_VOTE.into() {
+			Ok(VoteType::Remove)
+		} else {
+			Err(EngineError::CliqueInvalidNonce(nonce).into())
+		}
+	}
+
+	/// Header nonce casting this vote.
+	pub fn as_nonce(&self) -> H64 {
+		match *self {
+			VoteType::Add => NONCE_AUTH_VOTE.into(),
+			VoteType::Remove => NONCE_DROP_VOTE.into(),
+		}
+	}
+}
+
+/// Nonce of a Clique header, stored as its second seal field.
+pub fn header_nonce(header: &Header) -> Result<H64, Error> {
+	match header.seal().get(1) {
+		Some(nonce) => Ok(Rlp::new(nonce).as_val()?),
+		None => Err(BlockError::InvalidSealArity(Mismatch { expected: 2, found: header.seal().len() }).into()),
+	}
+}
+
+// a vote cast by a signer and not yet enacted.
+#[derive(Debug, Clone, PartialEq)]
+struct Vote {
+	signer: Address,
+	beneficiary: Address,
+	kind: VoteType,
+}
+
+/// Signer set, recent signers and pending votes after a given block.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CliqueBlockState {
+	signers: BTreeSet<Address>,
+	// signers of the most recent blocks, who may not sign again yet.
+	recent_signers: BTreeMap<BlockNumber, Address>,
+	// votes cast since the last checkpoint, in order.
+	votes: Vec<Vote>,
+	// number of votes for each beneficiary.
+	tally: HashMap<Address, (VoteType, usize)>,
+}
+
+impl CliqueBlockState {
+	/// State at a checkpoint with the given signers.
+	pub fn new(signers: BTreeSet<Address>) -> Self {
+		CliqueBlockState {
+			signers: signers,
+			..Default::default()
+		}
+	}
+
+	/// Authorized signers, in ascending order.
+	pub fn signers(&self) -> &BTreeSet<Address> {
+		&self.signers
+	}
+
+	/// Whether `signer` is in turn to sign block `number`.
+	pub fn is_inturn(&self, number: BlockNumber, signer: &Address) -> bool {
+		if self.signers.is_empty() {
+			return false;
+		}
+		let index = (number % self.signers.len() as u64) as usize;
+		self.signers.iter().nth(index) == Some(signer)
+	}
+
+	/// How many signers `signer` is away from the in-turn signer of block `number`.
+	pub fn turn_distance(&self, number: BlockNumber, signer: &Address) -> Option<usize> {
+		let len = self.signers.len();
+		let position = self.signers.iter().position(|s| s == signer)?;
+		let inturn = (number % len as u64) as usize;
+		Some((position + len - inturn) % len)
+	}
+
+	/// Whether `signer` signed one of the blocks preventing it from signing block `number`.
+	pub fn is_recently_signed(&self, number: BlockNumber, signer: &Address) -> bool {
+		let limit = self.signer_limit();
+		self.recent_signers.iter()
+			.any(|(&signed, s)| s == signer && (number < limit || signed > number - limit))
+	}
+
+	/// Whether a vote of the given kind for `beneficiary` would change the signer set.
+	pub fn is_valid_vote(&self, beneficiary: &Address, kind: VoteType) -> bool {
+		let is_signer = self.signers.contains(beneficiary);
+		match kind {
+			VoteType::Add => !is_signer,
+			VoteType::Remove => is_signer,
+		}
+	}
+
+	/// Apply the next header on top of this state, returning its signer.
+	pub fn apply(&mut self, header: &Header, epoch_length: u64) -> Result<Address, Error> {
+		let number = header.number();
+		if number % epoch_length == 0 {
+			self.votes.clear();
+			self.tally.clear();
+		}
+
+		// the oldest recent signer may sign again.
+		self.forget_oldest_signer(number);
+
+		let signer = recover_creator(header)?;
+		if !self.signers.contains(&signer) {
+			return Err(EngineError::NotAuthorized(signer).into());
+		}
+		if self.recent_signers.values().any(|s| *s == signer) {
+			return Err(EngineError::CliqueTooRecentlySigned(signer).into());
+		}
+		self.recent_signers.insert(number, signer);
+
+		// a new vote replaces the signer's previous vote for the same beneficiary.
+		let beneficiary = *header.author();
+		if let Some(position) = self.votes.iter().position(|v| v.signer == signer && v.beneficiary == beneficiary) {
+			let vote = self.votes.remove(position);
+			self.uncast(&vote);
+		}
+
+		let kind = VoteType::from_nonce(header_nonce(header)?)?;
+		if self.is_valid_vote(&beneficiary, kind) {
+			let vote = Vote { signer: signer, beneficiary: beneficiary, kind: kind };
+			self.cast(&vote);
+			self.votes.push(vote);
+		}
+
+		let passed = match self.tally.get(&beneficiary) {
+			Some(&(kind, count)) if count > self.signers.len() / 2 => Some(kind),
+			_ => None,
+		};
+
+		if let Some(kind) = passed {
+			match kind {
+				VoteType::Add => {
+					self.signers.insert(beneficiary);
+				},
+				VoteType::Remove => {
+					self.signers.remove(&beneficiary);
+					// the signer limit shrank, release one more recent signer.
+					self.forget_oldest_signer(number);
+
+					// votes cast by the removed signer no longer count.
+					let votes = ::std::mem::replace(&mut self.votes, Vec::new());
+					let (removed, kept): (Vec<_>, Vec<_>) = votes.into_iter().partition(|v| v.signer == beneficiary);
+					self.votes = kept;
+					for vote in &removed {
+						self.uncast(vote);
+					}
+				},
+			}
+
+			self.votes.retain(|v| v.beneficiary != beneficiary);
+			self.tally.remove(&beneficiary);
+		}
+
+		Ok(signer)
+	}
+
+	// a signer may sign once every `signer_limit` blocks.
+	fn signer_limit(&self) -> u64 {
+		self.signers.len() as u64 / 2 + 1
+	}
+
+	fn forget_oldest_signer(&mut self, number: BlockNumber) {
+		let limit = self.signer_limit();
+		if number >= limit {
+			self.recent_signers.remove(&(number - limit));
+		}
+	}
+
+	fn cast(&mut self, vote: &Vote) {
+		let tally = self.tally.entry(vote.beneficiary).or_insert((vote.kind, 0));
+		tally.1 += 1;
+	}
+
+	fn uncast(&mut self, vote: &Vote) {
+		let remove = match self.tally.get_mut(&vote.beneficiary) {
+			Some(ref mut tally) if tally.0 == vote.kind => {
+				tally.1 -= 1;
+				tally.1 == 0
+			},
+			_ => false,
+		};
+		if remove {
+			self.tally.remove(&vote.beneficiary);
+		}
+	}
+}
@@ -0,0 +1,583 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique proof-of-authority consensus engine, as specified in EIP-225.
+//!
+//! Authorized signers take turns sealing blocks, signing over the header and storing the
+//! signature at the end of the extra data. Signers are added and removed by majority vote:
+//! a signer votes by setting the block beneficiary to the candidate and the nonce to
+//! `0xffffffffffffffff` (add) or `0x0000000000000000` (remove). Every `epoch` blocks votes
+//! are reset and the checkpoint block lists the current signers in its extra data.
+//!
+//! Extra data holds 32 bytes of vanity, the signer list on checkpoints and a 65 byte
+//! signature, so chain specs must raise `maximumExtraDataSize` accordingly.
+
+mod block_state;
+mod util;
+
+use std::cmp;
+use std::collections::HashMap;
+use std::sync::{Weak, Arc};
+use std::time::{Duration, UNIX_EPOCH};
+
+use ethereum_types::{H256, H520, U256, Address};
+use ethkey::Signature;
+use hash::KECCAK_EMPTY_LIST_RLP;
+use io::{IoContext, IoHandler, IoService, TimerToken};
+use lru_cache::LruCache;
+use parking_lot::{Mutex, RwLock};
+use rand::{self, Rng};
+use rlp::{encode, Rlp};
+use unexpected::Mismatch;
+
+use account_provider::AccountProvider;
+use block::ExecutedBlock;
+use client::{BlockId, EngineClient};
+use engines::{Engine, EngineError, Seal};
+use error::{BlockError, Error};
+use ethjson;
+use header::{BlockNumber, Header};
+use machine::EthereumMachine;
+use super::signer::EngineSigner;
+
+use self::block_state::{CliqueBlockState, header_nonce};
+use self::util::{extract_signers, recover_creator, seal_hash};
+
+pub use self::block_state::VoteType;
+
+// Extra data bytes reserved for signer vanity.
+const VANITY_LENGTH: usize = 32;
+// Extra data bytes reserved for the signature.
+const SIGNATURE_LENGTH: usize = 65;
+// Size of an address in the checkpoint signer list.
+const ADDRESS_LENGTH: usize = 20;
+// Nonce of a vote to add a signer.
+const NONCE_AUTH_VOTE: [u8; 8] = [0xff; 8];
+// Nonce of a vote to remove a signer.
+const NONCE_DROP_VOTE: [u8; 8] = [0; 8];
+// Difficulty of blocks sealed by the in-turn signer.
+const DIFF_INTURN: u64 = 2;
+// Difficulty of blocks sealed out of turn.
+const DIFF_NOTURN: u64 = 1;
+// Additional delay for each signer an out-of-turn signer is away from the in-turn one,
+// so that out-of-turn signers do not all seal at the same time.
+const OUT_OF_TURN_DELAY_MS: u64 = 500;
+// Number of block states kept in memory.
+const STATE_CACHE_NUM: usize = 128;
+
+/// `Clique` params.
+#[derive(Debug, PartialEq)]
+pub struct CliqueParams {
+	/// Minimum time between blocks, in seconds.
+	pub period: u64,
+	/// Number of blocks after which votes are reset and a checkpoint is made.
+	pub epoch: u64,
+}
+
+impl From<ethjson::spec::CliqueParams> for CliqueParams {
+	fn from(p: ethjson::spec::CliqueParams) -> Self {
+		CliqueParams {
+			period: p.period.map_or(15, Into::into),
+			epoch: p.epoch.map_or(30000, |e| cmp::max(e.into(), 1)),
+		}
+	}
+}
+
+/// Engine using `Clique` proof-of-authority consensus.
+pub struct Clique {
+	period: u64,
+	epoch_length: u64,
+	machine: EthereumMachine,
+	client: Arc<RwLock<Option<Weak<EngineClient>>>>,
+	signer: RwLock<EngineSigner>,
+	block_state_by_hash: Mutex<LruCache<H256, CliqueBlockState>>,
+	proposals: RwLock<HashMap<Address, VoteType>>,
+	sealing_service: IoService<()>,
+}
+
+impl Clique {
+	/// Create a new instance of Clique engine.
+	pub fn new(our_params: CliqueParams, machine: EthereumMachine) -> Result<Arc<Self>, Error> {
+		let engine = Arc::new(Clique {
+			period: our_params.period,
+			epoch_length: our_params.epoch,
+			machine: machine,
+			client: Arc::new(RwLock::new(None)),
+			signer: Default::default(),
+			block_state_by_hash: Mutex::new(LruCache::new(STATE_CACHE_NUM)),
+			proposals: RwLock::new(HashMap::new()),
+			sealing_service: IoService::<()>::start()?,
+		});
+
+		// with a zero period blocks are only sealed when there are transactions.
+		if engine.period > 0 {
+			let handler = SealingHandler { client: engine.client.clone() };
+			engine.sealing_service.register_handler(Arc::new(handler))?;
+		}
+		Ok(engine)
+	}
+
+	// state after the given block, walking back to the last known state or checkpoint.
+	fn state(&self, header: &Header) -> Result<CliqueBlockState, Error> {
+		if let Some(state) = self.block_state_by_hash.lock().get_mut(&header.hash()) {
+			return Ok(state.clone());
+		}
+
+		let client = self.client.read().as_ref()
+			.and_then(Weak::upgrade);
+
+		let mut pending = Vec::new();
+		let mut current = header.clone();
+		let mut state = loop {
+			if let Some(state) = self.block_state_by_hash.lock().get_mut(&current.hash()) {
+				break state.clone();
+			}
+			if current.number() % self.epoch_length == 0 {
+				let state = CliqueBlockState::new(extract_signers(&current)?);
+				self.block_state_by_hash.lock().insert(current.hash(), state.clone());
+				break state;
+			}
+
+			let parent_hash = *current.parent_hash();
+			let parent = match client {
+				Some(ref client) => client.block_header(BlockId::Hash(parent_hash))
+					.ok_or_else(|| BlockError::UnknownParent(parent_hash))?
+					.decode()?,
+				None => return Err(EngineError::RequiresClient.into()),
+			};
+			pending.push(current);
+			current = parent;
+		};
+
+		for header in pending.iter().rev() {
+			state.apply(header, self.epoch_length)?;
+			self.block_state_by_hash.lock().insert(header.hash(), state.clone());
+		}
+		Ok(state)
+	}
+
+	fn is_checkpoint(&self, number: BlockNumber) -> bool {
+		number % self.epoch_length == 0
+	}
+}
+
+fn unix_now() -> Duration {
+	UNIX_EPOCH.elapsed().expect("Valid time has to be set in your system.")
+}
+
+fn as_millis(duration: Duration) -> u64 {
+	duration.as_secs() * 1_000 + (duration.subsec_nanos() / 1_000_000) as u64
+}
+
+// Triggers resealing every second, so that blocks get sealed once their timestamp is reached.
+struct SealingHandler {
+	client: Arc<RwLock<Option<Weak<EngineClient>>>>,
+}
+
+const SEALING_TIMER_TOKEN: TimerToken = 24;
+
+impl IoHandler<()> for SealingHandler {
+	fn initialize(&self, io: &IoContext<()>) {
+		io.register_timer(SEALING_TIMER_TOKEN, Duration::from_secs(1))
+			.unwrap_or_else(|e| warn!(target: "engine", "Failed to start Clique sealing timer: {}.", e))
+	}
+
+	fn timeout(&self, _io: &IoContext<()>, timer: TimerToken) {
+		if timer == SEALING_TIMER_TOKEN {
+			if let Some(ref weak) = *self.client.read() {
+				if let Some(c) = weak.upgrade() {
+					c.update_sealing();
+				}
+			}
+		}
+	}
+}
+
+impl Engine<EthereumMachine> for Clique {
+	fn name(&self) -> &str { "Clique" }
+
+	fn machine(&self) -> &EthereumMachine { &self.machine }
+
+	// Two fields - the mix hash (always zero) and the nonce carrying the vote.
+	fn seal_fields(&self, _header: &Header) -> usize { 2 }
+
+	fn seals_internally(&self) -> Option<bool> {
+		Some(self.signer.read().is_some())
+	}
+
+	fn populate_from_parent(&self, header: &mut Header, parent: &Header) {
+		let state = match self.state(parent) {
+			Ok(state) => state,
+			Err(e) => {
+				trace!(target: "engine", "populate_from_parent: no state for parent {}: {}", parent.hash(), e);
+				return;
+			},
+		};
+
+		let number = header.number();
+		let is_checkpoint = self.is_checkpoint(number);
+		let inturn = self.signer.read().address().map_or(false, |address| state.is_inturn(number, &address));
+		let difficulty: U256 = if inturn { DIFF_INTURN.into() } else { DIFF_NOTURN.into() };
+		header.set_difficulty(difficulty);
+
+		// cast one of our proposals that would still change the signer set.
+		let (beneficiary, vote) = if is_checkpoint {
+			(Address::default(), VoteType::Remove)
+		} else {
+			let proposals: Vec<_> = self.proposals.read().iter()
+				.filter(|&(address, kind)| state.is_valid_vote(address, *kind))
+				.map(|(address, kind)| (*address, *kind))
+				.collect();
+			rand::thread_rng().choose(&proposals).cloned().unwrap_or((Address::default(), VoteType::Remove))
+		};
+		header.set_author(beneficiary);
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&vote.as_nonce()).into_vec()]);
+
+		let mut extra_data = header.extra_data().clone();
+		extra_data.resize(VANITY_LENGTH, 0);
+		if is_checkpoint {
+			for signer in state.signers() {
+				extra_data.extend_from_slice(&signer[..]);
+			}
+		}
+		extra_data.extend_from_slice(&[0u8; SIGNATURE_LENGTH]);
+		header.set_extra_data(extra_data);
+	}
+
+	fn open_block_header_timestamp(&self, parent_timestamp: u64) -> u64 {
+		cmp::max(unix_now().as_secs(), parent_timestamp + self.period)
+	}
+
+	fn is_timestamp_valid(&self, header_timestamp: u64, parent_timestamp: u64) -> bool {
+		header_timestamp >= parent_timestamp + self.period
+	}
+
+	/// Attempt to seal the block internally.
+	fn generate_seal(&self, block: &ExecutedBlock, parent: &Header) -> Seal {
+		let header = block.header();
+		let number = header.number();
+		if number == 0 {
+			return Seal::None;
+		}
+
+		let address = match self.signer.read().address() {
+			Some(address) => address,
+			None => return Seal::None,
+		};
+		let state = match self.state(parent) {
+			Ok(state) => state,
+			Err(e) => {
+				trace!(target: "engine", "generate_seal: no state for parent {}: {}", parent.hash(), e);
+				return Seal::None;
+			},
+		};
+
+		let distance = match state.turn_distance(number, &address) {
+			Some(distance) => distance,
+			None => {
+				trace!(target: "engine", "generate_seal: {} is not an authorized signer", address);
+				return Seal::None;
+			},
+		};
+		if state.is_recently_signed(number, &address) {
+			trace!(target: "engine", "generate_seal: {} signed recently, waiting for others", address);
+			return Seal::None;
+		}
+
+		let expected_difficulty = if distance == 0 { DIFF_INTURN } else { DIFF_NOTURN };
+		if *header.difficulty() != expected_difficulty.into() {
+			trace!(target: "engine", "generate_seal: block was prepared for a different signer");
+			return Seal::None;
+		}
+
+		let seal_at = header.timestamp() * 1_000 + distance as u64 * OUT_OF_TURN_DELAY_MS;
+		if as_millis(unix_now()) < seal_at {
+			return Seal::None;
+		}
+
+		Seal::Regular(header.seal().to_vec())
+	}
+
+	fn on_seal_block(&self, header: &mut Header) -> Result<(), Error> {
+		let signature = self.sign(seal_hash(header)?)?;
+		let mut extra_data = header.extra_data().clone();
+		let len = extra_data.len();
+		extra_data[len - SIGNATURE_LENGTH..].copy_from_slice(&H520::from(signature)[..]);
+		header.set_extra_data(extra_data);
+		Ok(())
+	}
+
+	fn verify_local_seal(&self, _header: &Header) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn verify_block_basic(&self, header: &Header) -> Result<(), Error> {
+		if header.number() == 0 {
+			return Ok(());
+		}
+
+		let is_checkpoint = self.is_checkpoint(header.number());
+		if is_checkpoint && !header.author().is_zero() {
+			return Err(EngineError::CliqueWrongAuthorCheckpoint(Mismatch { expected: Address::default(), found: *header.author() }).into());
+		}
+
+		let vote = VoteType::from_nonce(header_nonce(header)?)?;
+		if is_checkpoint && vote != VoteType::Remove {
+			return Err(EngineError::CliqueInvalidNonce(vote.as_nonce()).into());
+		}
+
+		let signers = extract_signers(header)?;
+		if !is_checkpoint && !signers.is_empty() {
+			return Err(EngineError::CliqueCheckpointNumberOfSigners(signers.len() * ADDRESS_LENGTH).into());
+		}
+
+		let mix_hash: H256 = Rlp::new(&header.seal()[0]).as_val()?;
+		if !mix_hash.is_zero() {
+			return Err(BlockError::InvalidSeal.into());
+		}
+
+		if *header.uncles_hash() != KECCAK_EMPTY_LIST_RLP {
+			return Err(BlockError::InvalidUnclesHash(Mismatch { expected: KECCAK_EMPTY_LIST_RLP, found: *header.uncles_hash() }).into());
+		}
+
+		let difficulty = *header.difficulty();
+		if difficulty != DIFF_INTURN.into() && difficulty != DIFF_NOTURN.into() {
+			return Err(BlockError::InvalidDifficulty(Mismatch { expected: DIFF_NOTURN.into(), found: difficulty }).into());
+		}
+
+		Ok(())
+	}
+
+	fn verify_block_family(&self, header: &Header, parent: &Header) -> Result<(), Error> {
+		let parent_state = self.state(parent)?;
+
+		if self.is_checkpoint(header.number()) && extract_signers(header)? != *parent_state.signers() {
+			return Err(EngineError::CliqueCheckpointSignersMismatch.into());
+		}
+
+		let mut state = parent_state.clone();
+		let signer = state.apply(header, self.epoch_length)?;
+
+		let expected: U256 = if parent_state.is_inturn(header.number(), &signer) { DIFF_INTURN.into() } else { DIFF_NOTURN.into() };
+		if *header.difficulty() != expected {
+			return Err(BlockError::InvalidDifficulty(Mismatch { expected: expected, found: *header.difficulty() }).into());
+		}
+
+		self.block_state_by_hash.lock().insert(header.hash(), state);
+		Ok(())
+	}
+
+	fn verify_block_external(&self, header: &Header) -> Result<(), Error> {
+		if header.number() == 0 {
+			return Ok(());
+		}
+		recover_creator(header).map(|_| ())
+	}
+
+	fn register_client(&self, client: Weak<EngineClient>) {
+		*self.client.write() = Some(client);
+	}
+
+	fn set_signer(&self, ap: Arc<AccountProvider>, address: Address, password: String) {
+		self.signer.write().set(ap, address, password);
+	}
+
+	// proposals are cast until the vote passes or they are discarded.
+	fn propose_signer(&self, address: Address, add: bool) -> bool {
+		let kind = if add { VoteType::Add } else { VoteType::Remove };
+		self.proposals.write().insert(address, kind);
+		true
+	}
+
+	fn discard_signer(&self, address: &Address) -> bool {
+		self.proposals.write().remove(address);
+		true
+	}
+
+	fn sign(&self, hash: H256) -> Result<Signature, Error> {
+		Ok(self.signer.read().sign(hash)?)
+	}
+
+	fn stop(&self) {
+		self.sealing_service.stop()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use ethereum_types::{H256, H520, Address};
+	use ethkey::{self, KeyPair, Secret};
+	use hash::keccak;
+	use rlp::encode;
+	use account_provider::AccountProvider;
+	use block::*;
+	use engines::Seal;
+	use header::Header;
+	use spec::Spec;
+	use tempdir::TempDir;
+	use test_helpers::get_temp_state_db;
+	use super::{VoteType, VANITY_LENGTH, SIGNATURE_LENGTH};
+	use super::block_state::CliqueBlockState;
+	use super::util::{recover_creator, seal_hash};
+
+	fn new_test_clique() -> Spec {
+		let bytes: &[u8] = include_bytes!("../../../res/clique.json");
+		let tempdir = TempDir::new("").unwrap();
+		Spec::load(&tempdir.path(), bytes).expect("invalid chain spec")
+	}
+
+	fn address(secret: &str) -> Address {
+		KeyPair::from_secret(keccak(secret).into()).unwrap().address()
+	}
+
+	// header at `number` signed by the account with secret `keccak(secret)`.
+	fn signed_header(number: u64, secret: &str, beneficiary: Address, vote: VoteType) -> Header {
+		let mut header = Header::default();
+		header.set_number(number);
+		header.set_author(beneficiary);
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&vote.as_nonce()).into_vec()]);
+		header.set_extra_data(vec![0u8; VANITY_LENGTH + SIGNATURE_LENGTH]);
+
+		let secret: Secret = keccak(secret).into();
+		let signature = ethkey::sign(&secret, &seal_hash(&header).unwrap()).unwrap();
+		let mut extra_data = header.extra_data().clone();
+		extra_data[VANITY_LENGTH..].copy_from_slice(&H520::from(signature)[..]);
+		header.set_extra_data(extra_data);
+		header
+	}
+
+	fn two_signers() -> CliqueBlockState {
+		CliqueBlockState::new(vec![address("0"), address("1")].into_iter().collect())
+	}
+
+	#[test]
+	fn recovers_signer() {
+		let header = signed_header(1, "0", Address::default(), VoteType::Remove);
+		assert_eq!(recover_creator(&header).unwrap(), address("0"));
+	}
+
+	#[test]
+	fn turns_follow_signer_order() {
+		let state = two_signers();
+		assert!(state.is_inturn(2, &address("0")));
+		assert!(state.is_inturn(1, &address("1")));
+		assert!(!state.is_inturn(1, &address("0")));
+		assert_eq!(state.turn_distance(1, &address("0")), Some(1));
+		assert_eq!(state.turn_distance(1, &address("2")), None);
+	}
+
+	#[test]
+	fn rejects_unauthorized_and_recent_signers() {
+		let mut state = two_signers();
+		assert!(state.apply(&signed_header(1, "2", Address::default(), VoteType::Remove), 30000).is_err());
+
+		assert_eq!(state.apply(&signed_header(1, "0", Address::default(), VoteType::Remove), 30000).unwrap(), address("0"));
+		assert!(state.is_recently_signed(2, &address("0")));
+		assert!(state.clone().apply(&signed_header(2, "0", Address::default(), VoteType::Remove), 30000).is_err());
+
+		state.apply(&signed_header(2, "1", Address::default(), VoteType::Remove), 30000).unwrap();
+		assert!(!state.is_recently_signed(3, &address("0")));
+		state.apply(&signed_header(3, "0", Address::default(), VoteType::Remove), 30000).unwrap();
+	}
+
+	#[test]
+	fn majority_votes_change_signers() {
+		let mut state = two_signers();
+		let candidate = address("2");
+
+		state.apply(&signed_header(1, "0", candidate, VoteType::Add), 30000).unwrap();
+		assert!(!state.signers().contains(&candidate));
+		state.apply(&signed_header(2, "1", candidate, VoteType::Add), 30000).unwrap();
+		assert!(state.signers().contains(&candidate));
+		assert!(!state.is_valid_vote(&candidate, VoteType::Add));
+
+		state.apply(&signed_header(3, "2", address("1"), VoteType::Remove), 30000).unwrap();
+		state.apply(&signed_header(4, "0", address("1"), VoteType::Remove), 30000).unwrap();
+		assert_eq!(state.signers().len(), 2);
+		assert!(!state.signers().contains(&address("1")));
+	}
+
+	#[test]
+	fn checkpoint_resets_votes() {
+		let mut state = two_signers();
+		let candidate = address("2");
+
+		state.apply(&signed_header(1, "0", candidate, VoteType::Add), 2).unwrap();
+		state.apply(&signed_header(2, "1", Address::default(), VoteType::Remove), 2).unwrap();
+		state.apply(&signed_header(3, "0", Address::default(), VoteType::Remove), 2).unwrap();
+		state.apply(&signed_header(4, "1", candidate, VoteType::Add), 2).unwrap();
+		assert!(!state.signers().contains(&candidate));
+	}
+
+	#[test]
+	fn can_generate_seal() {
+		let tap = AccountProvider::transient_provider();
+		let addr = tap.insert_account(keccak("1").into(), "").unwrap();
+
+		let spec = new_test_clique();
+		let engine = &*spec.engine;
+		engine.set_signer(Arc::new(tap), addr, "".into());
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, addr, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close_and_lock();
+		let seal = match engine.generate_seal(b.block(), &genesis_header) {
+			Seal::Regular(seal) => seal,
+			other => panic!("unexpected seal {:?}", other),
+		};
+
+		let sealed = b.seal_internally(engine, seal).unwrap();
+		let header = sealed.header();
+		assert_eq!(*header.difficulty(), 2.into());
+		assert_eq!(recover_creator(header).unwrap(), addr);
+		assert!(engine.verify_block_basic(header).is_ok());
+		assert!(engine.verify_block_family(header, &genesis_header).is_ok());
+	}
+
+	#[test]
+	fn casts_proposed_votes() {
+		let spec = new_test_clique();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let open_block = || {
+			let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+			let last_hashes = Arc::new(vec![genesis_header.hash()]);
+			OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, Address::default(), (3141562.into(), 31415620.into()), vec![], false).unwrap()
+		};
+
+		assert!(engine.propose_signer(address("5"), true));
+		let b = open_block();
+		assert_eq!(*b.header().author(), address("5"));
+		assert_eq!(b.header().seal()[1], encode(&VoteType::Add.as_nonce()).into_vec());
+
+		assert!(engine.discard_signer(&address("5")));
+		let b = open_block();
+		assert_eq!(*b.header().author(), Address::default());
+	}
+
+	#[test]
+	fn seals_internally() {
+		let tap = AccountProvider::transient_provider();
+		let signer = tap.insert_account(keccak("0").into(), "").unwrap();
+
+		let engine = new_test_clique().engine;
+		assert!(!engine.seals_internally().unwrap());
+		engine.set_signer(Arc::new(tap), signer, "".into());
+		assert!(engine.seals_internally().unwrap());
+	}
+}
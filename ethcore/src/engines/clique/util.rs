@@ -0,0 +1,71 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique header signing and signer recovery.
+
+use std::collections::BTreeSet;
+
+use ethereum_types::{H256, H520, Address};
+use ethkey::{public_to_address, recover};
+
+use engines::EngineError;
+use error::Error;
+use header::Header;
+
+use super::{ADDRESS_LENGTH, SIGNATURE_LENGTH, VANITY_LENGTH};
+
+// checks that the extra data can hold both the vanity and the signature.
+fn check_extra_data(header: &Header) -> Result<(), Error> {
+	let len = header.extra_data().len();
+	if len < VANITY_LENGTH {
+		return Err(EngineError::CliqueMissingVanity.into());
+	}
+	if len < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(EngineError::CliqueMissingSignature.into());
+	}
+	Ok(())
+}
+
+/// Hash signed by the Clique signer: the hash of the full header with the signature
+/// stripped from the end of the extra data.
+pub fn seal_hash(header: &Header) -> Result<H256, Error> {
+	check_extra_data(header)?;
+	let mut unsigned = header.clone();
+	let len = header.extra_data().len();
+	unsigned.set_extra_data(header.extra_data()[..len - SIGNATURE_LENGTH].to_vec());
+	Ok(unsigned.hash())
+}
+
+/// Recover the signer of a Clique header from the signature in its extra data.
+pub fn recover_creator(header: &Header) -> Result<Address, Error> {
+	let hash = seal_hash(header)?;
+	let len = header.extra_data().len();
+	let signature = H520::from_slice(&header.extra_data()[len - SIGNATURE_LENGTH..]);
+	let public = recover(&signature.into(), &hash)?;
+	Ok(public_to_address(&public))
+}
+
+/// Signer list stored in the extra data of a checkpoint header, between the vanity
+/// and the signature.
+pub fn extract_signers(header: &Header) -> Result<BTreeSet<Address>, Error> {
+	check_extra_data(header)?;
+	let len = header.extra_data().len();
+	let signers = &header.extra_data()[VANITY_LENGTH..len - SIGNATURE_LENGTH];
+	if signers.len() % ADDRESS_LENGTH != 0 {
+		return Err(EngineError::CliqueCheckpointNumberOfSigners(signers.len()).into());
+	}
+	Ok(signers.chunks(ADDRESS_LENGTH).map(Address::from_slice).collect())
+}
@@ -19,6 +19,7 @@
 mod authority_round;
 mod basic_authority;
 mod block_reward;
+mod clique;
 mod instant_seal;
 mod null_engine;
 mod signer;
@@ -31,6 +32,7 @@ pub mod epoch;
 
 pub use self::authority_round::AuthorityRound;
 pub use self::basic_authority::BasicAuthority;
+pub use self::clique::Clique;
 pub use self::epoch::{EpochVerifier, Transition as EpochTransition};
 pub use self::instant_seal::InstantSeal;
 pub use self::null_engine::NullEngine;
@@ -51,9 +53,10 @@ use snapshot::SnapshotComponents;
 use spec::CommonParams;
 use transaction::{self, UnverifiedTransaction, SignedTransaction};
 
+use ethash::EthashManager;
 use ethkey::Signature;
 use parity_machine::{Machine, LocalizedMachine as Localized};
-use ethereum_types::{H256, U256, Address};
+use ethereum_types::{H64, H256, U256, Address};
 use unexpected::{Mismatch, OutOfBounds};
 use bytes::Bytes;
 
@@ -82,6 +85,20 @@ pub enum EngineError {
 	MalformedMessage(String),
 	/// Requires client ref, but none registered.
 	RequiresClient,
+	/// Clique extra data is too short to contain the signer vanity.
+	CliqueMissingVanity,
+	/// Clique extra data is too short to contain the signature.
+	CliqueMissingSignature,
+	/// Clique extra data contains an invalid signer list of the given length.
+	CliqueCheckpointNumberOfSigners(usize),
+	/// Clique checkpoint signer list does not match the current signers.
+	CliqueCheckpointSignersMismatch,
+	/// Clique checkpoint has a non-zero beneficiary.
+	CliqueWrongAuthorCheckpoint(Mismatch<Address>),
+	/// Clique header nonce is not a valid vote.
+	CliqueInvalidNonce(H64),
+	/// Clique signer signed one of the recent blocks.
+	CliqueTooRecentlySigned(Address),
 }
 
 impl fmt::Display for EngineError {
@@ -97,6 +114,13 @@ impl fmt::Display for EngineError {
 			FailedSystemCall(ref msg) => format!("Failed to make system call: {}", msg),
 			MalformedMessage(ref msg) => format!("Received malformed consensus message: {}", msg),
 			RequiresClient => format!("Call requires client but none registered"),
+			CliqueMissingVanity => format!("Extra data is missing vanity data"),
+			CliqueMissingSignature => format!("Extra data is missing signature"),
+			CliqueCheckpointNumberOfSigners(len) => format!("Extra data contains an invalid signer list of {} bytes", len),
+			CliqueCheckpointSignersMismatch => format!("Checkpoint signer list does not match the current signers"),
+			CliqueWrongAuthorCheckpoint(ref mis) => format!("Checkpoint has an unexpected beneficiary: {}", mis),
+			CliqueInvalidNonce(ref nonce) => format!("Header nonce {} is not a valid vote", nonce),
+			CliqueTooRecentlySigned(ref address) => format!("Signer {} has signed too recently", address),
 		};
 
 		f.write_fmt(format_args!("Engine error ({})", msg))
@@ -233,6 +257,10 @@ pub trait Engine<M: Machine>: Sync + Send {
 	/// light clients do not generate seals.
 	fn generate_seal(&self, _block: &M::LiveBlock, _parent: &M::Header) -> Seal { Seal::None }
 
+	/// Finalize the header of a block sealed by this node, after the seal has been applied.
+	/// Used by engines which sign over header fields other than the seal.
+	fn on_seal_block(&self, _header: &mut M::Header) -> Result<(), M::Error> { Ok(()) }
+
 	/// Verify a locally-generated seal of a header.
 	///
 	/// If this engine seals internally,
@@ -313,6 +341,16 @@ pub trait Engine<M: Machine>: Sync + Send {
 	/// Register an account which signs consensus messages.
 	fn set_signer(&self, _account_provider: Arc<AccountProvider>, _address: Address, _password: String) {}
 
+	/// Propose a vote on adding (`add`) or removing `address` from the signers, cast in
+	/// blocks sealed by this node. Returns `false` if the engine doesn't vote on signers.
+	fn propose_signer(&self, _address: Address, _add: bool) -> bool { false }
+
+	/// Discard the proposal on `address`. Returns `false` if the engine doesn't vote on signers.
+	fn discard_signer(&self, _address: &Address) -> bool { false }
+
+	/// Proof-of-work manager of the engine, if any. Shared with external share verification.
+	fn pow_manager(&self) -> Option<Arc<EthashManager>> { None }
+
 	/// Sign using the EngineSigner, to be used for consensus tx signing.
 	fn sign(&self, _hash: H256) -> Result<Signature, M::Error> { unimplemented!() }
 
@@ -21,9 +21,11 @@
 /// Next the `View` proceeds through `Prevote` and `Precommit` `Step`s.
 /// Block is issued when there is enough `Precommit` votes collected on a particular block at the end of a `View`.
 /// Once enough votes have been gathered the proposer issues that block in the `Commit` step.
+/// Signed messages and the lock are written ahead to the client database and restored on restart.
 
 mod message;
 mod params;
+mod wal;
 
 use std::sync::{Weak, Arc};
 use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
@@ -41,6 +43,7 @@ use ethkey::{self, Message, Signature};
 use account_provider::AccountProvider;
 use block::*;
 use engines::{Engine, Seal, EngineError, ConstructedVerifier};
+use engines::block_reward::{self, BlockRewardContract, RewardKind};
 use io::IoService;
 use super::signer::EngineSigner;
 use super::validator_set::{ValidatorSet, SimpleList};
@@ -48,6 +51,7 @@ use super::transition::TransitionHandler;
 use super::vote_collector::VoteCollector;
 use self::message::*;
 use self::params::TendermintParams;
+use self::wal::LockState;
 use machine::{AuxiliaryData, EthereumMachine};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -99,6 +103,10 @@ pub struct Tendermint {
 	validators: Box<ValidatorSet>,
 	/// Reward per block, in base units.
 	block_reward: U256,
+	/// Block at which the block reward contract should start being used.
+	block_reward_contract_transition: u64,
+	/// Block reward contract, overrides the static block reward.
+	block_reward_contract: Option<BlockRewardContract>,
 	/// ethereum machine descriptor
 	machine: EthereumMachine,
 }
@@ -183,6 +191,8 @@ impl Tendermint {
 				last_proposed: Default::default(),
 				validators: our_params.validators,
 				block_reward: our_params.block_reward,
+				block_reward_contract_transition: our_params.block_reward_contract_transition,
+				block_reward_contract: our_params.block_reward_contract,
 				machine: machine,
 			});
 
@@ -216,15 +226,75 @@ impl Tendermint {
 		}
 	}
 
+	/// Log the message before it is acted upon.
+	fn write_ahead(&self, message: &ConsensusMessage) {
+		if let Some(ref weak) = *self.client.read() {
+			if let Some(c) = weak.upgrade() {
+				wal::append(&*c, message);
+			}
+		}
+	}
+
+	/// Persist the current step and lock.
+	fn persist_lock(&self) {
+		let lock = LockState {
+			height: self.height.load(AtomicOrdering::SeqCst),
+			view: self.view.load(AtomicOrdering::SeqCst),
+			// Commit is transient, the next step is Propose.
+			step: match *self.step.read() {
+				Step::Commit => Step::Propose,
+				step => step,
+			},
+			lock_change: self.lock_change.read().clone(),
+			last_lock: self.last_lock.load(AtomicOrdering::SeqCst),
+		};
+		if let Some(ref weak) = *self.client.read() {
+			if let Some(c) = weak.upgrade() {
+				wal::write_lock_state(&*c, &lock);
+			}
+		}
+	}
+
+	/// Restore the lock and logged messages for the given height.
+	fn restore(&self, client: &EngineClient, height: Height) {
+		match wal::lock_state(client) {
+			Some(ref lock) if lock.height == height => {
+				debug!(target: "engine", "Restoring lock at height {} view {} step {:?}.", lock.height, lock.view, lock.step);
+				self.view.store(lock.view, AtomicOrdering::SeqCst);
+				*self.step.write() = lock.step;
+				*self.lock_change.write() = lock.lock_change.clone();
+				self.last_lock.store(lock.last_lock, AtomicOrdering::SeqCst);
+			},
+			_ => {},
+		}
+		wal::compact(client, height);
+		for message in wal::messages(client, height) {
+			match message.verify() {
+				Ok(sender) => {
+					trace!(target: "engine", "Restoring {:?} from {}.", message, sender);
+					self.votes.vote(message, sender);
+				},
+				Err(e) => warn!(target: "engine", "Skipping logged message {:?}: {}", message, e),
+			}
+		}
+	}
+
 	fn generate_message(&self, block_hash: Option<BlockHash>) -> Option<Bytes> {
 		let h = self.height.load(AtomicOrdering::SeqCst);
 		let r = self.view.load(AtomicOrdering::SeqCst);
 		let s = *self.step.read();
-		let vote_info = message_info_rlp(&VoteStep::new(h, r, s), block_hash);
+		let vote_step = VoteStep::new(h, r, s);
+		if let Some(previous) = self.signer.read().address().and_then(|a| self.votes.voted(&vote_step, &a)) {
+			// Never sign a conflicting vote, e.g. after a restart.
+			debug!(target: "engine", "Already voted {:?}, repeating it.", previous);
+			return Some(::rlp::encode(&previous).into_vec());
+		}
+		let vote_info = message_info_rlp(&vote_step, block_hash);
 		match (self.signer.read().address(), self.sign(keccak(&vote_info)).map(Into::into)) {
 			(Some(validator), Ok(signature)) => {
 				let message_rlp = message_full_rlp(&signature, &vote_info);
 				let message = ConsensusMessage::new(signature, h, r, s, block_hash);
+				self.write_ahead(&message);
 				self.votes.vote(message.clone(), validator);
 				debug!(target: "engine", "Generated {:?} as {}.", message, validator);
 				self.handle_valid_message(&message);
@@ -263,6 +333,13 @@ impl Tendermint {
 		self.view.store(0, AtomicOrdering::SeqCst);
 		*self.lock_change.write() = None;
 		*self.proposal.write() = None;
+		if new_height % wal::COMPACTION_PERIOD == 0 {
+			if let Some(ref weak) = *self.client.read() {
+				if let Some(c) = weak.upgrade() {
+					wal::compact(&*c, new_height);
+				}
+			}
+		}
 	}
 
 	/// Use via step_service to transition steps.
@@ -271,6 +348,7 @@ impl Tendermint {
 			warn!(target: "engine", "Could not proceed to step {}.", io_err)
 		}
 		*self.step.write() = step;
+		self.persist_lock();
 		match step {
 			Step::Propose => {
 				self.update_sealing()
@@ -292,6 +370,7 @@ impl Tendermint {
 					},
 					_ => None,
 				};
+				self.persist_lock();
 				self.generate_and_broadcast_message(block_hash);
 			},
 			Step::Commit => {
@@ -391,6 +470,10 @@ impl Tendermint {
 		if lock_change {
 			trace!(target: "engine", "handle_valid_message: Lock change.");
 			*self.lock_change.write() = Some(message.clone());
+			// in the prevote step the lock is persisted by the transition to precommit below.
+			if !(self.is_height(message) && *self.step.read() == Step::Prevote) {
+				self.persist_lock();
+			}
 		}
 		// Check if it can affect the step transition.
 		if self.is_height(message) {
@@ -480,11 +563,18 @@ impl Engine<EthereumMachine> for Tendermint {
 		let height = header.number() as Height;
 		let view = self.view.load(AtomicOrdering::SeqCst);
 		let bh = Some(header.bare_hash());
-		let vote_info = message_info_rlp(&VoteStep::new(height, view, Step::Propose), bh.clone());
+		let vote_step = VoteStep::new(height, view, Step::Propose);
+		if self.votes.voted(&vote_step, author).is_some() {
+			// Proposed in this view before a restart.
+			return Seal::None;
+		}
+		let vote_info = message_info_rlp(&vote_step, bh.clone());
 		if let Ok(signature) = self.sign(keccak(&vote_info)).map(Into::into) {
 			// Insert Propose vote.
 			debug!(target: "engine", "Submitting proposal {} at height {} view {}.", header.bare_hash(), height, view);
-			self.votes.vote(ConsensusMessage::new(signature, height, view, Step::Propose, bh), *author);
+			let proposal = ConsensusMessage::new(signature, height, view, Step::Propose, bh);
+			self.write_ahead(&proposal);
+			self.votes.vote(proposal, *author);
 			// Remember the owned block.
 			*self.last_proposed.write() = header.bare_hash();
 			// Remember proposal for later seal submission.
@@ -524,6 +614,7 @@ impl Engine<EthereumMachine> for Tendermint {
 				return Err(EngineError::DoubleVote(sender));
 			}
 			trace!(target: "engine", "Handling a valid {:?} from {}.", message, sender);
+			self.write_ahead(&message);
 			self.handle_valid_message(&message);
 		}
 		Ok(())
@@ -552,10 +643,25 @@ impl Engine<EthereumMachine> for Tendermint {
 
 	/// Apply the block reward on finalisation of the block.
 	fn on_close_block(&self, block: &mut ExecutedBlock) -> Result<(), Error>{
-		use parity_machine::WithBalances;
 		let author = *block.header().author();
-		self.machine.add_balance(block, &author, &self.block_reward)?;
-		self.machine.note_rewards(block, &[(author, self.block_reward)], &[])
+		let rewards = match self.block_reward_contract {
+			Some(ref c) if block.header().number() >= self.block_reward_contract_transition => {
+				let mut call = |to, data| {
+					let result = self.machine.execute_as_system(
+						block,
+						to,
+						U256::max_value(),
+						Some(data),
+					);
+					result.map_err(|e| format!("{}", e))
+				};
+
+				c.reward(&[(author, RewardKind::Author)], &mut call)?
+			},
+			_ => vec![(author, self.block_reward)],
+		};
+
+		block_reward::apply_block_rewards(&rewards, block, &self.machine)
 	}
 
 	fn verify_local_seal(&self, _header: &Header) -> Result<(), Error> {
@@ -678,7 +784,12 @@ impl Engine<EthereumMachine> for Tendermint {
 		{
 			self.signer.write().set(ap, address, password);
 		}
-		self.to_step(Step::Propose);
+		// Resume a step restored from the write-ahead log.
+		let step = match *self.step.read() {
+			Step::Commit => Step::Propose,
+			step => step,
+		};
+		self.to_step(step);
 	}
 
 	fn sign(&self, hash: H256) -> Result<Signature, Error> {
@@ -756,7 +867,9 @@ impl Engine<EthereumMachine> for Tendermint {
 
 	fn register_client(&self, client: Weak<EngineClient>) {
 		if let Some(c) = client.upgrade() {
-			self.height.store(c.chain_info().best_block_number as usize + 1, AtomicOrdering::SeqCst);
+			let height = c.chain_info().best_block_number as usize + 1;
+			self.height.store(height, AtomicOrdering::SeqCst);
+			self.restore(&*c, height);
 		}
 		*self.client.write() = Some(client.clone());
 		self.validators.register_client(client);
@@ -772,7 +885,7 @@ mod tests {
 	use block::*;
 	use error::{Error, ErrorKind, BlockError};
 	use header::Header;
-	use client::ChainInfo;
+	use client::{Client, ChainInfo};
 	use miner::MinerService;
 	use test_helpers::{
 		TestNotify, get_temp_state_db, generate_dummy_client,
@@ -835,6 +948,48 @@ mod tests {
 		addr
 	}
 
+	fn signed_message(tap: &Arc<AccountProvider>, signer: Address, height: usize, view: usize, step: Step, block_hash: Option<H256>) -> Bytes {
+		let mi = message_info_rlp(&VoteStep::new(height, view, step), block_hash);
+		message_full_rlp(&H520::from(tap.sign(signer, None, keccak(&mi)).unwrap()), &mi)
+	}
+
+	/// Client with "1" proposing at height 1 and having prevoted for the returned proposal.
+	fn client_after_own_prevote(tap: &Arc<AccountProvider>, v1: Address) -> (Arc<Client>, Arc<TestNotify>, Option<H256>) {
+		use ethkey::{Generator, Random};
+		use transaction::{Transaction, Action};
+
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_tendermint, Some(tap.clone()));
+		let notify = Arc::new(TestNotify::default());
+		client.add_notify(notify.clone());
+		client.engine().register_client(Arc::downgrade(&client) as _);
+		client.miner().set_author(v1, Some("1".into())).unwrap();
+
+		let keypair = Random.generate().unwrap();
+		let transaction = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: "3331600055".from_hex().unwrap(),
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero(),
+		}.sign(keypair.secret(), None);
+		client.miner().import_own_transaction(client.as_ref(), transaction.into()).unwrap();
+
+		let proposal = Some(client.miner().pending_block(0).unwrap().header.bare_hash());
+		// Propose timeout.
+		client.engine().step();
+		assert!(notify.messages.read().contains(&signed_message(tap, v1, 1, 0, Step::Prevote, proposal)));
+		(client, notify, proposal)
+	}
+
+	/// Start a fresh engine on top of the client, as after a node restart.
+	fn restart(client: &Arc<Client>) -> Arc<EthEngine> {
+		client.engine().stop();
+		let engine = Spec::new_test_tendermint().engine;
+		engine.register_client(Arc::downgrade(client) as _);
+		engine
+	}
+
 	#[test]
 	fn has_valid_metadata() {
 		let engine = Spec::new_test_tendermint().engine;
@@ -1054,6 +1209,71 @@ mod tests {
 		assert_eq!(client.chain_info().best_block_number, 1);
 	}
 
+	#[test]
+	fn does_not_change_prevote_after_restart() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let v0 = insert_and_unlock(&tap, "0");
+		let v1 = insert_and_unlock(&tap, "1");
+		let (client, notify, proposal) = client_after_own_prevote(&tap, v1);
+		let own_prevote = signed_message(&tap, v1, 1, 0, Step::Prevote, proposal);
+
+		// Restart before precommitting, the new engine has not seen the proposal.
+		let engine = restart(&client);
+		notify.messages.write().clear();
+		engine.set_signer(tap.clone(), v1, "1".into());
+
+		// The logged prevote is repeated instead of a nil one.
+		assert!(notify.messages.read().contains(&own_prevote));
+		assert!(!notify.messages.read().contains(&signed_message(&tap, v1, 1, 0, Step::Prevote, None)));
+
+		// Logged prevote still counts towards the lock.
+		vote(engine.as_ref(), |mh| tap.sign(v0, None, mh).map(H520::from), 1, 0, Step::Prevote, proposal);
+		assert!(notify.messages.read().contains(&signed_message(&tap, v1, 1, 0, Step::Precommit, proposal)));
+		engine.stop();
+	}
+
+	#[test]
+	fn keeps_lock_after_restart() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let v0 = insert_and_unlock(&tap, "0");
+		let v1 = insert_and_unlock(&tap, "1");
+		let (client, notify, proposal) = client_after_own_prevote(&tap, v1);
+
+		// Lock on the proposal and precommit it.
+		vote(client.engine(), |mh| tap.sign(v0, None, mh).map(H520::from), 1, 0, Step::Prevote, proposal);
+		let own_precommit = signed_message(&tap, v1, 1, 0, Step::Precommit, proposal);
+		assert!(notify.messages.read().contains(&own_precommit));
+
+		let engine = restart(&client);
+		notify.messages.write().clear();
+		engine.set_signer(tap.clone(), v1, "1".into());
+		assert!(notify.messages.read().contains(&own_precommit));
+
+		// Round fails, move to the next view.
+		vote(engine.as_ref(), |mh| tap.sign(v0, None, mh).map(H520::from), 1, 0, Step::Precommit, None);
+		engine.step();
+		engine.step();
+
+		// Without a new proposal the restored lock is prevoted.
+		assert!(notify.messages.read().contains(&signed_message(&tap, v1, 1, 1, Step::Prevote, proposal)));
+		engine.stop();
+	}
+
+	#[test]
+	fn block_reward_contract() {
+		let spec = Spec::new_test_tendermint_block_reward_contract();
+		let author: Address = "0000000000000000000000000000000000000033".into();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let genesis_header = spec.genesis_header();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(spec.engine.as_ref(), Default::default(), false, db, &genesis_header, last_hashes, author, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+
+		// the contract rewards (1000 + kind) for each benefactor
+		let b = b.close_and_lock();
+		assert_eq!(b.block().state().balance(&author).unwrap(), U256::from(1000));
+		spec.engine.stop();
+	}
+
 	#[test]
 	fn epoch_verifier_verify_light() {
 		use ethkey::Error as EthkeyError;
@@ -21,6 +21,7 @@ use std::time::Duration;
 use ethereum_types::U256;
 use super::super::validator_set::{ValidatorSet, new_validator_set};
 use super::super::transition::Timeouts;
+use super::super::block_reward::BlockRewardContract;
 use super::Step;
 
 /// `Tendermint` params.
@@ -31,6 +32,11 @@ pub struct TendermintParams {
 	pub timeouts: TendermintTimeouts,
 	/// Reward per block in base units.
 	pub block_reward: U256,
+	/// Block at which the block reward contract should start being used.
+	pub block_reward_contract_transition: u64,
+	/// Block reward contract (setting the block reward contract
+	/// overrides the static block reward definition).
+	pub block_reward_contract: Option<BlockRewardContract>,
 }
 
 /// Base timeout of each step in ms.
@@ -85,6 +91,8 @@ impl From<ethjson::spec::TendermintParams> for TendermintParams {
 				commit: p.timeout_commit.map_or(dt.commit, to_duration),
 			},
 			block_reward: p.block_reward.map_or(U256::default(), Into::into),
+			block_reward_contract_transition: p.block_reward_contract_transition.map_or(0, Into::into),
+			block_reward_contract: p.block_reward_contract_address.map(|a| BlockRewardContract::new(a.into())),
 		}
 	}
 }
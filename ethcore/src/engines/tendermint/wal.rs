@@ -0,0 +1,239 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tendermint write-ahead log.
+//!
+//! Signed consensus messages are logged before they are acted upon or broadcast and the lock is
+//! persisted on every step transition, both through the client database. A restarted validator
+//! restores them before participating, so it never signs a vote conflicting with one it already
+//! sent.
+//!
+//! Each message is appended under its own key, in a single database write with the updated log
+//! index. Messages of past heights are dropped only when the log is compacted.
+
+use client::EngineClient;
+use rlp::{Rlp, RlpStream, Encodable, Decodable, DecoderError};
+use super::{Height, View, Step};
+use super::message::ConsensusMessage;
+
+const INDEX_KEY: &'static [u8] = &*b"tendermint_wal_index";
+const MESSAGE_KEY_PREFIX: &'static [u8] = &*b"tendermint_wal_message_";
+const LOCK_KEY: &'static [u8] = &*b"tendermint_wal_lock";
+/// Number of heights between compactions of the log.
+pub const COMPACTION_PERIOD: Height = 16;
+
+/// Consensus position and lock of the local node.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LockState {
+	/// Blockchain height.
+	pub height: Height,
+	/// Consensus view.
+	pub view: View,
+	/// Consensus step.
+	pub step: Step,
+	/// Message for the last PoLC.
+	pub lock_change: Option<ConsensusMessage>,
+	/// Last lock view.
+	pub last_lock: View,
+}
+
+/// (height, view, step, [lock_change], last_lock)
+impl Encodable for LockState {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		let lock_change: Vec<ConsensusMessage> = self.lock_change.iter().cloned().collect();
+		s.begin_list(5)
+			.append(&self.height)
+			.append(&self.view)
+			.append(&self.step)
+			.append_list(&lock_change)
+			.append(&self.last_lock);
+	}
+}
+
+impl Decodable for LockState {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		let lock_change: Vec<ConsensusMessage> = rlp.list_at(3)?;
+		Ok(LockState {
+			height: rlp.val_at(0)?,
+			view: rlp.val_at(1)?,
+			step: rlp.val_at(2)?,
+			lock_change: lock_change.into_iter().next(),
+			last_lock: rlp.val_at(4)?,
+		})
+	}
+}
+
+/// Read the persisted lock state.
+pub fn lock_state(client: &EngineClient) -> Option<LockState> {
+	client.engine_data(LOCK_KEY).and_then(|bytes| match ::rlp::decode(&bytes) {
+		Ok(lock) => Some(lock),
+		Err(e) => {
+			warn!(target: "engine", "Ignoring malformed Tendermint lock state: {:?}", e);
+			None
+		},
+	})
+}
+
+/// Persist the lock state.
+pub fn write_lock_state(client: &EngineClient, lock: &LockState) {
+	client.write_engine_data(vec![(LOCK_KEY.to_vec(), Some(::rlp::encode(lock).into_vec()))]);
+}
+
+/// Positions of the oldest retained and of the next logged message.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+struct Index {
+	first: u64,
+	next: u64,
+}
+
+fn index(client: &EngineClient) -> Index {
+	client.engine_data(INDEX_KEY).and_then(|bytes| {
+		let rlp = Rlp::new(&bytes);
+		match (rlp.val_at(0), rlp.val_at(1)) {
+			(Ok(first), Ok(next)) => Some(Index { first, next }),
+			_ => {
+				warn!(target: "engine", "Ignoring malformed Tendermint write-ahead log index.");
+				None
+			},
+		}
+	}).unwrap_or_default()
+}
+
+// index entry to write along with the logged messages.
+fn index_entry(index: Index) -> (Vec<u8>, Option<Vec<u8>>) {
+	let value = match index.first == index.next {
+		true => None,
+		false => {
+			let mut s = RlpStream::new_list(2);
+			s.append(&index.first).append(&index.next);
+			Some(s.out())
+		},
+	};
+	(INDEX_KEY.to_vec(), value)
+}
+
+fn message_key(position: u64) -> Vec<u8> {
+	let mut key = MESSAGE_KEY_PREFIX.to_vec();
+	key.extend_from_slice(&::rlp::encode(&position));
+	key
+}
+
+/// Logged messages with their positions, compacted entries are skipped.
+fn entries(client: &EngineClient, index: Index) -> Vec<(u64, ConsensusMessage)> {
+	(index.first..index.next)
+		.filter_map(|position| client.engine_data(&message_key(position)).map(|bytes| (position, bytes)))
+		.filter_map(|(position, bytes)| match ::rlp::decode(&bytes) {
+			Ok(message) => Some((position, message)),
+			Err(e) => {
+				warn!(target: "engine", "Ignoring malformed Tendermint write-ahead log entry: {:?}", e);
+				None
+			},
+		})
+		.collect()
+}
+
+/// Logged messages at or above the given height.
+pub fn messages(client: &EngineClient, height: Height) -> Vec<ConsensusMessage> {
+	entries(client, index(client)).into_iter()
+		.map(|(_, message)| message)
+		.filter(|message| message.vote_step.height >= height)
+		.collect()
+}
+
+/// Log a message, must be called before the message is acted upon.
+pub fn append(client: &EngineClient, message: &ConsensusMessage) {
+	let mut index = index(client);
+	let entry = (message_key(index.next), Some(::rlp::encode(message).into_vec()));
+	index.next += 1;
+	client.write_engine_data(vec![entry, index_entry(index)]);
+}
+
+/// Drop messages below the given height.
+pub fn compact(client: &EngineClient, height: Height) {
+	let mut index = index(client);
+	let mut retained = None;
+	let mut values = Vec::new();
+	for (position, message) in entries(client, index) {
+		if message.vote_step.height < height {
+			values.push((message_key(position), None));
+		} else if retained.is_none() {
+			retained = Some(position);
+		}
+	}
+	index.first = retained.unwrap_or(index.next);
+	values.push(index_entry(index));
+	client.write_engine_data(values);
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{H256, H520};
+	use client::TestBlockChainClient;
+	use super::super::Step;
+	use super::super::message::ConsensusMessage;
+	use super::*;
+
+	fn message(height: Height, step: Step) -> ConsensusMessage {
+		ConsensusMessage::new(H520::default(), height, 0, step, Some(H256::from(height as u64)))
+	}
+
+	#[test]
+	fn persists_lock_state() {
+		let client = TestBlockChainClient::new();
+		assert_eq!(lock_state(&client), None);
+
+		let mut lock = LockState {
+			height: 3,
+			view: 2,
+			step: Step::Precommit,
+			lock_change: Some(message(3, Step::Prevote)),
+			last_lock: 1,
+		};
+		write_lock_state(&client, &lock);
+		assert_eq!(lock_state(&client), Some(lock.clone()));
+
+		lock.lock_change = None;
+		write_lock_state(&client, &lock);
+		assert_eq!(lock_state(&client), Some(lock));
+	}
+
+	#[test]
+	fn logs_and_compacts_messages() {
+		let client = TestBlockChainClient::new();
+		assert!(messages(&client, 0).is_empty());
+
+		append(&client, &message(1, Step::Prevote));
+		append(&client, &message(3, Step::Prevote));
+		append(&client, &message(2, Step::Precommit));
+		assert_eq!(messages(&client, 0), vec![message(1, Step::Prevote), message(3, Step::Prevote), message(2, Step::Precommit)]);
+		assert_eq!(messages(&client, 2), vec![message(3, Step::Prevote), message(2, Step::Precommit)]);
+		// Index and one entry per message.
+		assert_eq!(client.engine_data.read().len(), 4);
+
+		compact(&client, 2);
+		assert_eq!(messages(&client, 0), vec![message(3, Step::Prevote), message(2, Step::Precommit)]);
+		assert_eq!(client.engine_data.read().len(), 3);
+
+		// Appending after a compaction keeps the order.
+		append(&client, &message(3, Step::Precommit));
+		compact(&client, 3);
+		assert_eq!(messages(&client, 0), vec![message(3, Step::Prevote), message(3, Step::Precommit)]);
+
+		compact(&client, 4);
+		assert!(messages(&client, 0).is_empty());
+		assert_eq!(client.engine_data.read().len(), 0);
+	}
+}
@@ -53,14 +53,27 @@ impl ValidatorContract {
 
 impl ValidatorContract {
 	fn transact(&self, data: Bytes) -> Result<(), String> {
+		self.transact_with(data, false)
+	}
+
+	// reports of misbehaviour backed by proof are sent as service transactions.
+	fn transact_service(&self, data: Bytes) -> Result<(), String> {
+		self.transact_with(data, true)
+	}
+
+	fn transact_with(&self, data: Bytes, service: bool) -> Result<(), String> {
 		let client = self.client.read().as_ref()
 			.and_then(Weak::upgrade)
 			.ok_or_else(|| "No client!")?;
 
 		match client.as_full_client() {
 			Some(c) => {
-				c.transact_contract(self.contract_address, data)
-					.map_err(|e| format!("Transaction import error: {}", e))?;
+				let res = if service {
+					c.transact_service_contract(self.contract_address, data).map(|_| ())
+				} else {
+					c.transact_contract(self.contract_address, data)
+				};
+				res.map_err(|e| format!("Transaction import error: {}", e))?;
 				Ok(())
 			},
 			None => Err("No full client!".into()),
@@ -112,7 +125,7 @@ impl ValidatorSet for ValidatorContract {
 
 	fn report_malicious(&self, address: &Address, _set_block: BlockNumber, block: BlockNumber, proof: Bytes) {
 		let data = self.provider.functions().report_malicious().input(*address, block, proof);
-		match self.transact(data) {
+		match self.transact_service(data) {
 			Ok(_) => warn!(target: "engine", "Reported malicious validator {}", address),
 			Err(s) => warn!(target: "engine", "Validator {} could not be reported {}", address, s),
 		}
@@ -193,6 +193,11 @@ impl <M: Message + Default + Encodable + Debug> VoteCollector<M> {
 			.fold(Vec::new(), |mut acc, mut messages| { acc.append(&mut messages); acc })
 	}
 
+	/// Message sent by the address in the given round, if any.
+	pub fn voted(&self, round: &M::Round, address: &Address) -> Option<M> {
+		self.votes.read().get(round).and_then(|c| c.voted.get(address).cloned())
+	}
+
 	/// Retrieve address from which the message was sent from cache.
 	pub fn get(&self, message: &M) -> Option<Address> {
 		let guard = self.votes.read();
@@ -26,6 +26,7 @@ use block::*;
 use error::{BlockError, Error};
 use header::{Header, BlockNumber};
 use engines::{self, Engine};
+use engines::block_reward::{BlockRewardContract, RewardKind};
 use ethjson;
 use rlp::Rlp;
 use machine::EthereumMachine;
@@ -113,6 +114,11 @@ pub struct EthashParams {
 	pub mcip3_dev_contract: Address,
 	/// Block reward in base units.
 	pub block_reward: U256,
+	/// Block at which the block reward contract should start being used.
+	pub block_reward_contract_transition: u64,
+	/// Block reward contract (setting the block reward contract
+	/// overrides all other block reward parameters).
+	pub block_reward_contract: Option<BlockRewardContract>,
 	/// EIP-649 transition block.
 	pub eip649_transition: u64,
 	/// EIP-649 bomb delay.
@@ -148,6 +154,8 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			mcip3_dev_reward: p.mcip3_dev_reward.map_or(U256::from(0), Into::into),
 			mcip3_dev_contract: p.mcip3_dev_contract.map_or_else(Address::new, Into::into),
 			block_reward: p.block_reward.map_or_else(Default::default, Into::into),
+			block_reward_contract_transition: p.block_reward_contract_transition.map_or(0, Into::into),
+			block_reward_contract: p.block_reward_contract_address.map(|a| BlockRewardContract::new(a.into())),
 			eip649_transition: p.eip649_transition.map_or(u64::max_value(), Into::into),
 			eip649_delay: p.eip649_delay.map_or(DEFAULT_EIP649_DELAY, Into::into),
 			eip649_reward: p.eip649_reward.map(Into::into),
@@ -161,7 +169,7 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 /// mainnet chains in the Olympic, Frontier and Homestead eras.
 pub struct Ethash {
 	ethash_params: EthashParams,
-	pow: EthashManager,
+	pow: Arc<EthashManager>,
 	machine: EthereumMachine,
 }
 
@@ -176,7 +184,7 @@ impl Ethash {
 		Arc::new(Ethash {
 			ethash_params,
 			machine,
-			pow: EthashManager::new(cache_dir.as_ref(), optimize_for.into()),
+			pow: Arc::new(EthashManager::new(cache_dir.as_ref(), optimize_for.into())),
 		})
 	}
 }
@@ -216,6 +224,10 @@ impl Engine<EthereumMachine> for Arc<Ethash> {
 
 	fn maximum_uncle_count(&self, _block: BlockNumber) -> usize { 2 }
 
+	fn pow_manager(&self) -> Option<Arc<EthashManager>> {
+		Some(self.pow.clone())
+	}
+
 	fn populate_from_parent(&self, header: &mut Header, parent: &Header) {
 		let difficulty = self.calculate_difficulty(header, parent);
 		header.set_difficulty(difficulty);
@@ -238,6 +250,39 @@ impl Engine<EthereumMachine> for Arc<Ethash> {
 		let author = *LiveBlock::header(&*block).author();
 		let number = LiveBlock::header(&*block).number();
 
+		// Delegate all rewards to the block reward contract once it is active.
+		if let Some(ref c) = self.ethash_params.block_reward_contract {
+			if number >= self.ethash_params.block_reward_contract_transition {
+				let uncle_authors: Vec<Address> = LiveBlock::uncles(&*block).iter().map(|u| *u.author()).collect();
+				let mut benefactors = vec![(author, RewardKind::Author)];
+				benefactors.extend(uncle_authors.iter().map(|a| (*a, RewardKind::Uncle)));
+
+				let rewards = {
+					let mut call = |to, data| {
+						let result = self.machine.execute_as_system(
+							block,
+							to,
+							U256::max_value(),
+							Some(data),
+						);
+						result.map_err(|e| format!("{}", e))
+					};
+
+					c.reward(&benefactors, &mut call)?
+				};
+
+				// rewards of uncle authors are traced as uncle rewards.
+				let (uncle_rewards, rewards): (Vec<_>, Vec<_>) = rewards.into_iter()
+					.partition(|&(address, _)| address != author && uncle_authors.contains(&address));
+
+				for &(ref a, ref reward) in rewards.iter().chain(uncle_rewards.iter()) {
+					self.machine.add_balance(block, a, reward)?;
+				}
+
+				return self.machine.note_rewards(block, &rewards, &uncle_rewards);
+			}
+		}
+
 		// Applies EIP-649 reward.
 		let reward = if number >= self.ethash_params.eip649_transition {
 			self.ethash_params.eip649_reward.unwrap_or(self.ethash_params.block_reward)
@@ -499,7 +544,9 @@ mod tests {
 	use header::Header;
 	use spec::Spec;
 	use engines::Engine;
-	use super::super::{new_morden, new_mcip3_test, new_homestead_test_machine};
+	use super::super::{new_morden, new_mcip3_test, new_block_reward_contract_test, new_homestead_test_machine};
+	use trace::{FlatTrace, RewardType, Tracing};
+	use trace::trace::Action;
 	use super::{Ethash, EthashParams, ecip1017_eras_block_reward};
 	use rlp;
 	use tempdir::TempDir;
@@ -518,6 +565,8 @@ mod tests {
 			homestead_transition: 1150000,
 			duration_limit: 13,
 			block_reward: 0.into(),
+			block_reward_contract_transition: 0,
+			block_reward_contract: None,
 			difficulty_hardfork_transition: u64::max_value(),
 			difficulty_hardfork_bound_divisor: U256::from(0),
 			bomb_defuse_transition: u64::max_value(),
@@ -606,6 +655,38 @@ mod tests {
 		assert_eq!(b.state().balance(&uncle_author).unwrap(), "3cb71f51fc558000".into());
 	}
 
+	#[test]
+	fn on_close_block_with_reward_contract() {
+		let spec = new_block_reward_contract_test();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let author: Address = "0000000000000000000000000000000000000033".into();
+		let mut b = OpenBlock::new(engine, Default::default(), true, db, &genesis_header, last_hashes, author, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let mut uncle = Header::new();
+		let uncle_author: Address = "ef2d6d194084c2de36e0dabfce45d046b37d1106".into();
+		uncle.set_author(uncle_author);
+		b.push_uncle(uncle).unwrap();
+
+		// the contract rewards (1000 + kind) for each benefactor, replacing the static rewards
+		let b = b.close();
+		assert_eq!(b.state().balance(&author).unwrap(), U256::from(1000));
+		assert_eq!(b.state().balance(&uncle_author).unwrap(), U256::from(1000 + 1));
+
+		let rewards: Vec<(Address, RewardType)> = match *b.traces() {
+			Tracing::Enabled(ref traces) => traces.iter()
+				.flat_map(|traces| Into::<Vec<FlatTrace>>::into(traces.clone()))
+				.filter_map(|trace| match trace.action {
+					Action::Reward(reward) => Some((reward.author, reward.reward_type)),
+					_ => None,
+				})
+				.collect(),
+			Tracing::Disabled => panic!("tracing is enabled"),
+		};
+		assert_eq!(rewards, vec![(author, RewardType::Block), (uncle_author, RewardType::Uncle)]);
+	}
+
 	#[test]
 	fn has_valid_mcip3_era_block_rewards() {
 		let spec = new_mcip3_test();
@@ -133,6 +133,9 @@ pub fn new_constantinople_test() -> Spec { load(None, include_bytes!("../../res/
 /// Create a new Musicoin-MCIP3-era spec.
 pub fn new_mcip3_test() -> Spec { load(None, include_bytes!("../../res/ethereum/mcip3_test.json")) }
 
+/// Create a new Ethash spec rewarding blocks through a block reward contract.
+pub fn new_block_reward_contract_test() -> Spec { load(None, include_bytes!("../../res/ethereum/block_reward_contract_test.json")) }
+
 // For tests
 
 /// Create a new Foundation Frontier-era chain spec as though it never changes to Homestead.
@@ -68,6 +68,7 @@ extern crate ethash;
 extern crate ethcore_bloom_journal as bloom_journal;
 extern crate ethcore_io as io;
 extern crate ethcore_bytes as bytes;
+extern crate ethcore_crypto;
 extern crate ethcore_logger;
 extern crate ethcore_miner;
 extern crate ethcore_stratum;
@@ -0,0 +1,265 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Strategies of filling pending block with transactions from the queue.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ethcore_miner::pool::VerifiedTransaction;
+use ethereum_types::{H256, U256, Address};
+
+use block::OpenBlock;
+use transaction::SignedTransaction;
+
+/// Strategy of filling pending block with transactions.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BlockBuilder {
+	/// Push transactions in the order of their priority in the queue.
+	Priority,
+	/// Simulate transactions against the state of the block and push the ones
+	/// rewarding block author the most for the gas they use first.
+	/// Transactions that can't be included yet (e.g. because of a nonce gap)
+	/// are retried after other transactions change the state.
+	MaximizeFees {
+		/// Maximal time spent on simulating and pushing transactions.
+		/// Transactions not ordered in time are pushed in the order of their priority.
+		time_budget: Duration,
+	},
+}
+
+impl Default for BlockBuilder {
+	fn default() -> Self {
+		BlockBuilder::Priority
+	}
+}
+
+/// Reason why a pending transaction was not included in the block.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExclusionReason {
+	/// Transaction gas exceeds gas left in the block.
+	GasLimit {
+		/// Gas left in the block.
+		gas_left: U256,
+		/// Gas of the transaction.
+		gas: U256,
+	},
+	/// Transaction gas exceeds block gas limit.
+	AboveGasLimit,
+	/// Transaction nonce does not follow nonce of sender's preceding transactions.
+	InvalidNonce {
+		/// Nonce expected by the state.
+		expected: U256,
+		/// Nonce of the transaction.
+		got: U256,
+	},
+	/// Sender is not allowed to send transactions.
+	NotAllowed,
+	/// The block got full before the transaction was considered.
+	BlockFull,
+	/// Transaction failed to verify or execute.
+	Invalid(String),
+}
+
+impl fmt::Display for ExclusionReason {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ExclusionReason::GasLimit { ref gas_left, ref gas } =>
+				write!(f, "Transaction gas ({}) exceeds gas left in the block ({})", gas, gas_left),
+			ExclusionReason::AboveGasLimit => write!(f, "Transaction gas exceeds block gas limit"),
+			ExclusionReason::InvalidNonce { ref expected, ref got } =>
+				write!(f, "Invalid transaction nonce: expected {}, got {}", expected, got),
+			ExclusionReason::NotAllowed => write!(f, "Sender is not allowed to send transactions"),
+			ExclusionReason::BlockFull => write!(f, "Block got full before the transaction was considered"),
+			ExclusionReason::Invalid(ref err) => write!(f, "Invalid transaction: {}", err),
+		}
+	}
+}
+
+/// Pending transaction that was not included in the most recently prepared block.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Exclusion {
+	/// Transaction hash.
+	pub hash: H256,
+	/// Transaction sender.
+	pub sender: Address,
+	/// Why the transaction was not included.
+	pub reason: ExclusionReason,
+}
+
+/// Reward of block author for including a transaction.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Score {
+	/// Reward per unit of gas used by the transaction.
+	reward_per_gas: U256,
+	/// Total reward.
+	reward: U256,
+}
+
+impl Score {
+	fn new(gas_used: U256, reward: U256) -> Self {
+		Score {
+			reward_per_gas: if gas_used.is_zero() { reward } else { reward / gas_used },
+			reward,
+		}
+	}
+}
+
+/// Pushes pending transactions to the block in the order maximizing reward of block author.
+///
+/// Transactions are processed in rounds. In every round the remaining transactions are simulated
+/// on top of the current state of the block (reverting to a state checkpoint after each of them)
+/// and the successful ones are pushed by descending reward per gas used. Failed transactions are
+/// retried in the next round as long as the previous one included anything and the time budget allows.
+/// The budget covers both simulating and pushing the transactions.
+///
+/// Only transactions accepted by `verify` are considered.
+/// Returns number of pushed transactions and the transactions left out in their original order,
+/// so that pushing them in the order of their priority reports why they were excluded.
+pub fn push_by_fees<F>(
+	block: &mut OpenBlock,
+	pending: Vec<Arc<VerifiedTransaction>>,
+	time_budget: Duration,
+	verify: F,
+) -> (usize, Vec<Arc<VerifiedTransaction>>) where
+	F: Fn(&SignedTransaction) -> bool,
+{
+	let deadline = Instant::now() + time_budget;
+	let (mut remaining, mut left_out): (Vec<_>, Vec<_>) = pending.into_iter()
+		.enumerate()
+		.partition(|&(_, ref tx)| verify(tx.signed()));
+	let mut pushed = 0;
+	let mut rounds = 0;
+
+	while !remaining.is_empty() && Instant::now() < deadline {
+		rounds += 1;
+		let mut candidates = Vec::new();
+		let mut skipped = Vec::new();
+
+		for (index, tx) in remaining {
+			if Instant::now() >= deadline {
+				skipped.push((index, tx));
+				continue;
+			}
+
+			match block.simulate_transaction(tx.signed()) {
+				Ok((gas_used, reward)) => candidates.push((Score::new(gas_used, reward), index, tx)),
+				Err(_) => skipped.push((index, tx)),
+			}
+		}
+
+		// Stable sort keeps the original order of equally rewarding transactions.
+		candidates.sort_by(|a, b| b.0.cmp(&a.0));
+
+		let mut included = false;
+		for (_, index, tx) in candidates {
+			// State might have been changed by transactions pushed before.
+			if Instant::now() < deadline && block.push_transaction(tx.signed().clone(), None).is_ok() {
+				pushed += 1;
+				included = true;
+			} else {
+				skipped.push((index, tx));
+			}
+		}
+
+		remaining = skipped;
+
+		if !included {
+			break;
+		}
+	}
+
+	debug!(target: "miner", "Pushed {} transactions ordered by fees in {} rounds, {} left out.", pushed, rounds, remaining.len() + left_out.len());
+	left_out.extend(remaining);
+	left_out.sort_by_key(|&(index, _)| index);
+	(pushed, left_out.into_iter().map(|(_, tx)| tx).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::time::Duration;
+	use ethcore_miner::pool::VerifiedTransaction;
+	use ethereum_types::{U256, Address};
+	use ethkey::{Generator, Random};
+	use block::{OpenBlock, IsBlock};
+	use spec::Spec;
+	use test_helpers::get_temp_state_db;
+	use transaction::{Action, Transaction};
+	use super::{push_by_fees, Score};
+
+	fn nonces(transactions: &[Arc<VerifiedTransaction>]) -> Vec<u64> {
+		transactions.iter().map(|tx| tx.signed().nonce.as_u64()).collect()
+	}
+
+	fn block_nonces(block: &OpenBlock) -> Vec<u64> {
+		block.transactions().iter().map(|tx| tx.nonce.as_u64()).collect()
+	}
+
+	#[test]
+	fn should_prefer_higher_reward_per_gas() {
+		assert!(Score::new(21_000.into(), 42_000.into()) > Score::new(42_000.into(), 42_000.into()));
+		assert!(Score::new(21_000.into(), 42_000.into()) > Score::new(21_000.into(), 21_000.into()));
+		assert!(Score::new(21_000.into(), 42_001.into()) > Score::new(21_000.into(), 42_000.into()));
+		assert_eq!(Score::new(0.into(), 5.into()).reward_per_gas, U256::from(5));
+	}
+
+	#[test]
+	fn should_retry_transactions_after_nonce_gap_is_filled() {
+		let spec = Spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let new_block = || {
+			let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+			let last_hashes = Arc::new(vec![genesis_header.hash()]);
+			OpenBlock::new(&*spec.engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap()
+		};
+
+		let keypair = Random.generate().unwrap();
+		let tx = |nonce: u64| {
+			let signed = Transaction {
+				action: Action::Call(Address::zero()),
+				gas: 100_000.into(),
+				nonce: nonce.into(),
+				..Default::default()
+			}.sign(keypair.secret(), None);
+			Arc::new(VerifiedTransaction::from_pending_block_transaction(signed))
+		};
+
+		// when
+		let mut block = new_block();
+		let (pushed, left_out) = push_by_fees(&mut block, vec![tx(2), tx(1), tx(5), tx(0)], Duration::from_secs(10), |_| true);
+
+		// then
+		assert_eq!(pushed, 3);
+		assert_eq!(block_nonces(&block), vec![0, 1, 2]);
+		assert_eq!(nonces(&left_out), vec![5]);
+
+		// transactions failing verification are left out
+		let mut block = new_block();
+		let (pushed, left_out) = push_by_fees(&mut block, vec![tx(2), tx(1), tx(0)], Duration::from_secs(10), |tx| tx.nonce != 1.into());
+		assert_eq!(pushed, 1);
+		assert_eq!(block_nonces(&block), vec![0]);
+		assert_eq!(nonces(&left_out), vec![2, 1]);
+
+		// nothing is pushed without time budget
+		let mut block = new_block();
+		let (pushed, left_out) = push_by_fees(&mut block, vec![tx(1), tx(0)], Duration::from_secs(0), |_| true);
+		assert_eq!(pushed, 0);
+		assert!(block.transactions().is_empty());
+		assert_eq!(nonces(&left_out), vec![1, 0]);
+	}
+}
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp;
-use std::time::{Instant, Duration};
+use std::time::{Instant, Duration, SystemTime};
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::sync::Arc;
 
@@ -23,8 +23,8 @@ use ansi_term::Colour;
 use bytes::Bytes;
 use engines::{EthEngine, Seal};
 use error::{Error, ErrorKind, ExecutionError};
-use ethcore_miner::gas_pricer::GasPricer;
-use ethcore_miner::pool::{self, TransactionQueue, VerifiedTransaction, QueueStatus, PrioritizationStrategy};
+use ethcore_miner::gas_pricer::{GasPricer, GasPriceOracleOptions};
+use ethcore_miner::pool::{self, TransactionQueue, VerifiedTransaction, QueueStatus, PrioritizationStrategy, ReplacementPolicy};
 use ethcore_miner::work_notify::NotifyWork;
 use ethereum_types::{H256, U256, Address};
 use io::IoChannel;
@@ -42,12 +42,13 @@ use using_queue::{UsingQueue, GetAction};
 use account_provider::{AccountProvider, SignError as AccountError};
 use block::{ClosedBlock, IsBlock, Block, SealedBlock};
 use client::{
-	BlockChain, ChainInfo, CallContract, BlockProducer, SealedBlockImporter, Nonce
+	BlockChain, ChainInfo, CallContract, BlockProducer, SealedBlockImporter, Nonce, AccountData, BlockInfo,
 };
 use client::{BlockId, ClientIoMessage};
 use executive::contract_address;
 use header::{Header, BlockNumber};
 use miner;
+use miner::block_builder::{self, BlockBuilder, Exclusion, ExclusionReason};
 use miner::pool_client::{PoolClient, CachedNonceClient, NonceCache};
 use receipt::{Receipt, RichReceipt};
 use spec::Spec;
@@ -122,10 +123,14 @@ pub struct MinerOptions {
 	/// NOTE: Such block will contain all pending transactions but
 	/// will be invalid if mined.
 	pub infinite_pending_block: bool,
+	/// Strategy of filling pending block with transactions from the queue.
+	pub block_builder: BlockBuilder,
 
 
 	/// Strategy to use for prioritizing transactions in the queue.
 	pub tx_queue_strategy: PrioritizationStrategy,
+	/// Rules for replacing transactions with the same sender and nonce in the queue.
+	pub tx_queue_replacement: ReplacementPolicy,
 	/// Simple senders penalization.
 	pub tx_queue_penalization: Penalization,
 	/// Do we want to mark transactions recieved locally (e.g. RPC) as local if we don't have the sending account?
@@ -151,7 +156,9 @@ impl Default for MinerOptions {
 			work_queue_size: 20,
 			enable_resubmission: true,
 			infinite_pending_block: false,
+			block_builder: BlockBuilder::Priority,
 			tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
+			tx_queue_replacement: ReplacementPolicy::default(),
 			tx_queue_penalization: Penalization::Disabled,
 			tx_queue_no_unfamiliar_locals: false,
 			refuse_service_transactions: false,
@@ -159,6 +166,7 @@ impl Default for MinerOptions {
 				max_count: 8_192,
 				max_per_sender: 81,
 				max_mem_usage: 4 * 1024 * 1024,
+				..Default::default()
 			},
 			pool_verification_options: pool::verifier::Options {
 				minimal_gas_price: DEFAULT_MINIMAL_GAS_PRICE.into(),
@@ -211,6 +219,7 @@ pub struct Miner {
 	engine: Arc<EthEngine>,
 	accounts: Option<Arc<AccountProvider>>,
 	io_channel: RwLock<Option<IoChannel<ClientIoMessage>>>,
+	exclusions: RwLock<Vec<Exclusion>>,
 }
 
 impl Miner {
@@ -234,7 +243,8 @@ impl Miner {
 	) -> Self {
 		let limits = options.pool_limits.clone();
 		let verifier_options = options.pool_verification_options.clone();
-		let tx_queue_strategy = options.tx_queue_strategy;
+		let tx_queue_strategy = options.tx_queue_strategy.clone();
+		let tx_queue_replacement = options.tx_queue_replacement;
 		let nonce_cache_size = cmp::max(4096, limits.max_count / 4);
 
 		Miner {
@@ -251,10 +261,11 @@ impl Miner {
 			gas_pricer: Mutex::new(gas_pricer),
 			nonce_cache: NonceCache::new(nonce_cache_size),
 			options,
-			transaction_queue: Arc::new(TransactionQueue::new(limits, verifier_options, tx_queue_strategy)),
+			transaction_queue: Arc::new(TransactionQueue::new(limits, verifier_options, tx_queue_strategy, tx_queue_replacement)),
 			accounts,
 			engine: spec.engine.clone(),
 			io_channel: RwLock::new(None),
+			exclusions: RwLock::new(Vec::new()),
 		}
 	}
 
@@ -284,6 +295,24 @@ impl Miner {
 		self.sealing.lock().queue.reset();
 	}
 
+	/// Imports transactions restored from the pool journal.
+	///
+	/// Unlike regular imports the transactions keep their original insertion time.
+	pub fn import_journaled_transactions<C: miner::BlockChainClient>(
+		&self,
+		chain: &C,
+		transactions: Vec<(PendingTransaction, pool::Priority, SystemTime)>,
+	) -> Vec<Result<(), transaction::Error>> {
+		trace!(target: "external_tx", "Importing {} journaled transactions", transactions.len());
+		let client = self.pool_client(chain);
+		self.transaction_queue.import(
+			client,
+			transactions.into_iter()
+				.map(|(transaction, priority, inserted_at)| pool::verifier::Transaction::Journaled { transaction, priority, inserted_at })
+				.collect(),
+		)
+	}
+
 	/// Updates transaction queue verification limits.
 	///
 	/// Limits consist of current block gas limit and minimal gas price.
@@ -329,7 +358,7 @@ impl Miner {
 
 	/// Prepares new block for sealing including top transactions from queue.
 	fn prepare_block<C>(&self, chain: &C) -> (ClosedBlock, Option<H256>) where
-		C: BlockChain + CallContract + BlockProducer + Nonce + Sync,
+		C: BlockChain + CallContract + BlockProducer + AccountData + Sync,
 	{
 		trace_time!("prepare_block");
 		let chain_info = chain.chain_info();
@@ -374,6 +403,7 @@ impl Miner {
 		let mut invalid_transactions = HashSet::new();
 		let mut not_allowed_transactions = HashSet::new();
 		let mut senders_to_penalize = HashSet::new();
+		let mut exclusions = Vec::new();
 		let block_number = open_block.block().header().number();
 
 		let mut tx_count = 0usize;
@@ -410,9 +440,72 @@ impl Miner {
 		};
 
 		let block_start = Instant::now();
+
+		// Bundles go first, each of them is pushed as a whole or not at all.
+		let bundles = self.transaction_queue.pending_bundles(block_number);
+		debug!(target: "miner", "Attempting to push {} bundles.", bundles.len());
+
+		for bundle in bundles {
+			let bundle_hash = *bundle.hash();
+
+			let included = {
+				let block_transactions = open_block.transactions().iter().map(|tx| tx.hash()).collect::<HashSet<_>>();
+				bundle.transactions().iter().filter(|tx| block_transactions.contains(&tx.signed().hash())).count()
+			};
+
+			// The bundle is already included in the reopened block.
+			if included == bundle.transactions().len() {
+				continue;
+			}
+
+			// Some of the transactions were included separately, so the bundle can't be applied as a whole.
+			if included > 0 {
+				debug!(target: "miner", "[{:?}] Dropping bundle partially included in the block.", bundle_hash);
+				self.transaction_queue.remove_bundle(&bundle_hash);
+				continue;
+			}
+
+			let transactions = bundle.signed();
+			let verified = transactions.iter()
+				.map(|tx| client.verify_signed(tx))
+				.collect::<Result<Vec<_>, _>>();
+			let result = verified
+				.map_err(|e| e.into())
+				.and_then(|_| open_block.push_transactions_atomically(transactions));
+
+			match result {
+				Err(Error(ErrorKind::Execution(ExecutionError::BlockGasLimitReached { gas_limit, .. }), _)) => {
+					debug!(target: "miner", "[{:?}] Skipping bundle because of gas limit.", bundle_hash);
+
+					// Bundle will never fit into a block.
+					if bundle.gas() > gas_limit {
+						self.transaction_queue.remove_bundle(&bundle_hash);
+					}
+				},
+				Err(e) => {
+					debug!(target: "miner", "[{:?}] Dropping bundle that failed to execute: {:?}", bundle_hash, e);
+					self.transaction_queue.remove_bundle(&bundle_hash);
+				},
+				Ok(()) => tx_count += bundle.transactions().len(),
+			}
+		}
+
+		// Transactions not pushed by the fee-maximising builder are pushed in the order of their priority.
+		let pending = match self.options.block_builder {
+			BlockBuilder::Priority => pending,
+			BlockBuilder::MaximizeFees { time_budget } => {
+				let (pushed, left_out) = block_builder::push_by_fees(&mut open_block, pending, time_budget, |tx| {
+					client.verify_signed(tx).is_ok()
+				});
+				tx_count += pushed;
+				left_out
+			},
+		};
+
 		debug!(target: "miner", "Attempting to push {} transactions.", pending.len());
 
-		for tx in pending {
+		let mut pending = pending.into_iter();
+		for tx in &mut pending {
 			let start = Instant::now();
 
 			let transaction = tx.signed().clone();
@@ -444,13 +537,16 @@ impl Miner {
 					debug!(target: "miner", "Skipping adding transaction to block because of gas limit: {:?} (limit: {:?}, used: {:?}, gas: {:?})", hash, gas_limit, gas_used, gas);
 
 					// Penalize transaction if it's above current gas limit
+					let gas_left = gas_limit - gas_used;
 					if gas > gas_limit {
 						debug!(target: "txqueue", "[{:?}] Transaction above block gas limit.", hash);
 						invalid_transactions.insert(hash);
+						exclusions.push(Exclusion { hash, sender, reason: ExclusionReason::AboveGasLimit });
+					} else {
+						exclusions.push(Exclusion { hash, sender, reason: ExclusionReason::GasLimit { gas_left, gas } });
 					}
 
 					// Exit early if gas left is smaller then min_tx_gas
-					let gas_left = gas_limit - gas_used;
 					if gas_left < min_tx_gas {
 						debug!(target: "miner", "Remaining gas is lower than minimal gas for a transaction. Block is full.");
 						break;
@@ -467,11 +563,13 @@ impl Miner {
 				// If there is errornous state of transaction queue it will be fixed when next block is imported.
 				Err(Error(ErrorKind::Execution(ExecutionError::InvalidNonce { expected, got }), _)) => {
 					debug!(target: "miner", "Skipping adding transaction to block because of invalid nonce: {:?} (expected: {:?}, got: {:?})", hash, expected, got);
+					exclusions.push(Exclusion { hash, sender, reason: ExclusionReason::InvalidNonce { expected, got } });
 				},
 				// already have transaction - ignore
 				Err(Error(ErrorKind::Transaction(transaction::Error::AlreadyImported), _)) => {},
 				Err(Error(ErrorKind::Transaction(transaction::Error::NotAllowed), _)) => {
 					not_allowed_transactions.insert(hash);
+					exclusions.push(Exclusion { hash, sender, reason: ExclusionReason::NotAllowed });
 					debug!(target: "miner", "Skipping non-allowed transaction for sender {:?}", hash);
 				},
 				Err(e) => {
@@ -480,11 +578,19 @@ impl Miner {
 						target: "miner", "Error adding transaction to block: number={}. transaction_hash={:?}, Error: {:?}", block_number, hash, e
 					);
 					invalid_transactions.insert(hash);
+					exclusions.push(Exclusion { hash, sender, reason: ExclusionReason::Invalid(format!("{}", e)) });
 				},
 				// imported ok
 				_ => tx_count += 1,
 			}
 		}
+		// Transactions left after the block got full.
+		exclusions.extend(pending.map(|tx| Exclusion {
+			hash: tx.signed().hash(),
+			sender: tx.signed().sender(),
+			reason: ExclusionReason::BlockFull,
+		}));
+
 		let elapsed = block_start.elapsed();
 		debug!(target: "miner", "Pushed {} transactions in {} ms", tx_count, took_ms(&elapsed));
 
@@ -495,6 +601,7 @@ impl Miner {
 			self.transaction_queue.remove(not_allowed_transactions.iter(), false);
 			self.transaction_queue.penalize(senders_to_penalize.iter());
 		}
+		*self.exclusions.write() = exclusions;
 
 		(block, original_work_hash)
 	}
@@ -615,7 +722,7 @@ impl Miner {
 
 				block
 					.lock()
-					.seal(&*self.engine, seal)
+					.seal_internally(&*self.engine, seal)
 					.map(|sealed| {
 						chain.import_sealed_block(sealed).is_ok()
 					})
@@ -679,7 +786,7 @@ impl Miner {
 
 	/// Returns true if we had to prepare new pending block.
 	fn prepare_pending_block<C>(&self, client: &C) -> bool where
-		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + Nonce + Sync,
+		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + AccountData + Sync,
 	{
 		trace!(target: "miner", "prepare_pending_block: entering");
 		let prepare_new = {
@@ -783,6 +890,10 @@ impl miner::MinerService for Miner {
 		self.transaction_queue.current_worst_gas_price() * 110u32 / 100.into()
 	}
 
+	fn gas_price_oracle(&self) -> Option<GasPriceOracleOptions> {
+		self.gas_pricer.lock().oracle_options().cloned()
+	}
+
 	fn sensible_gas_limit(&self) -> U256 {
 		self.params.read().gas_range_target.0 / 5.into()
 	}
@@ -867,10 +978,44 @@ impl miner::MinerService for Miner {
 		}
 	}
 
+	fn import_bundle<C: miner::BlockChainClient>(
+		&self,
+		chain: &C,
+		transactions: Vec<SignedTransaction>,
+		target_block: Option<BlockNumber>,
+	) -> Result<H256, transaction::Error> {
+		trace!(target: "own_tx", "Importing bundle of {} transactions (target block: {:?})", transactions.len(), target_block);
+
+		let client = self.pool_client(chain);
+		let imported = self.transaction_queue.import_bundle(
+			client,
+			transactions.into_iter().map(|tx| pool::verifier::Transaction::Local(tx.into())).collect(),
+			target_block,
+		);
+
+		// --------------------------------------------------------------------------
+		// | NOTE Code below requires sealing locks.                                |
+		// | Make sure to release the locks before calling that method.             |
+		// --------------------------------------------------------------------------
+		if imported.is_ok() && self.options.reseal_on_own_tx && self.sealing.lock().reseal_allowed() {
+			self.prepare_and_update_sealing(chain);
+		}
+
+		imported
+	}
+
 	fn local_transactions(&self) -> BTreeMap<H256, pool::local_transactions::Status> {
 		self.transaction_queue.local_transactions()
 	}
 
+	fn queue_evictions(&self) -> Vec<pool::evictions::Eviction> {
+		self.transaction_queue.evictions()
+	}
+
+	fn block_exclusions(&self) -> Vec<Exclusion> {
+		self.exclusions.read().clone()
+	}
+
 	fn queued_transactions(&self) -> Vec<Arc<VerifiedTransaction>> {
 		self.transaction_queue.all_transactions()
 	}
@@ -909,27 +1054,11 @@ impl miner::MinerService for Miner {
 	fn ready_transactions<C>(&self, chain: &C, max_len: usize, ordering: miner::PendingOrdering)
 		-> Vec<Arc<VerifiedTransaction>>
 	where
-		C: ChainInfo + Nonce + Sync,
+		C: ChainInfo + AccountData + BlockInfo + CallContract + Sync,
 	{
 		let chain_info = chain.chain_info();
 
-		let from_queue = || {
-			// We propagate transactions over the nonce cap.
-			// The mechanism is only to limit number of transactions in pending block
-			// those transactions are valid and will just be ready to be included in next block.
-			let nonce_cap = None;
-
-			self.transaction_queue.pending(
-				CachedNonceClient::new(chain, &self.nonce_cache),
-				pool::PendingSettings {
-					block_number: chain_info.best_block_number,
-					current_timestamp: chain_info.best_block_timestamp,
-					nonce_cap,
-					max_len,
-					ordering,
-				},
-			)
-		};
+		let from_queue = || self.queue_ready_transactions(chain, max_len, ordering);
 
 		let from_pending = || {
 			self.map_existing_pending_block(|sealing| {
@@ -955,6 +1084,30 @@ impl miner::MinerService for Miner {
 		}
 	}
 
+	fn queue_ready_transactions<C>(&self, chain: &C, max_len: usize, ordering: miner::PendingOrdering)
+		-> Vec<Arc<VerifiedTransaction>>
+	where
+		C: ChainInfo + AccountData + BlockInfo + CallContract + Sync,
+	{
+		let chain_info = chain.chain_info();
+
+		// We propagate transactions over the nonce cap.
+		// The mechanism is only to limit number of transactions in pending block
+		// those transactions are valid and will just be ready to be included in next block.
+		let nonce_cap = None;
+
+		self.transaction_queue.pending(
+			CachedNonceClient::new(chain, &self.nonce_cache),
+			pool::PendingSettings {
+				block_number: chain_info.best_block_number,
+				current_timestamp: chain_info.best_block_timestamp,
+				nonce_cap,
+				max_len,
+				ordering,
+			},
+		)
+	}
+
 	fn next_nonce<C>(&self, chain: &C, address: &Address) -> U256 where
 		C: Nonce + Sync,
 	{
@@ -1022,7 +1175,7 @@ impl miner::MinerService for Miner {
 	/// Update sealing if required.
 	/// Prepare the block and work if the Engine does not seal internally.
 	fn update_sealing<C>(&self, chain: &C) where
-		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + Nonce + Sync,
+		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + AccountData + Sync,
 	{
 		trace!(target: "miner", "update_sealing");
 
@@ -1072,7 +1225,7 @@ impl miner::MinerService for Miner {
 	}
 
 	fn work_package<C>(&self, chain: &C) -> Option<(H256, BlockNumber, u64, U256)> where
-		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + Nonce + Sync,
+		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + AccountData + Sync,
 	{
 		if self.engine.seals_internally().is_some() {
 			return None;
@@ -1155,6 +1308,11 @@ impl miner::MinerService for Miner {
 				});
 		}
 
+		if has_new_best_block {
+			// Drop bundles that can't be included anymore before we prepare new work.
+			self.transaction_queue.cull_bundles(client.clone(), chain.chain_info().best_block_number);
+		}
+
 		if has_new_best_block || (imported.len() > 0 && self.options.reseal_on_uncle) {
 			// Reset `next_allowed_reseal` in case a block is imported.
 			// Even if min_period is high, we will always attempt to create
@@ -1281,8 +1439,10 @@ mod tests {
 				work_queue_size: 5,
 				enable_resubmission: true,
 				infinite_pending_block: false,
+				block_builder: BlockBuilder::Priority,
 				tx_queue_penalization: Penalization::Disabled,
 				tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
+				tx_queue_replacement: ReplacementPolicy::default(),
 				tx_queue_no_unfamiliar_locals: false,
 				refuse_service_transactions: false,
 				pool_limits: Default::default(),
@@ -1335,6 +1495,34 @@ mod tests {
 		assert!(!miner.prepare_pending_block(&client));
 	}
 
+	#[test]
+	fn should_report_transactions_excluded_from_pending_block() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let keypair = Random.generate().unwrap();
+		let transaction = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: "3331600055".from_hex().unwrap(),
+			gas: U256::from(1) << 100,
+			gas_price: U256::zero(),
+			nonce: U256::zero(),
+		}.sign(keypair.secret(), Some(TEST_CHAIN_ID));
+		let hash = transaction.hash();
+
+		// when
+		let res = miner.import_own_transaction(&client, PendingTransaction::new(transaction, None));
+
+		// then
+		assert_eq!(res.unwrap(), ());
+		assert_eq!(miner.block_exclusions(), vec![Exclusion {
+			hash,
+			sender: keypair.address(),
+			reason: ExclusionReason::AboveGasLimit,
+		}]);
+	}
+
 	#[test]
 	fn should_not_use_pending_block_if_best_block_is_higher() {
 		// given
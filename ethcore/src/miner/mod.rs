@@ -19,12 +19,14 @@
 //! Miner module
 //! Keeps track of transactions and currently sealed pending block.
 
+mod block_builder;
 mod miner;
 mod service_transaction_checker;
 
 pub mod pool_client;
 pub mod stratum;
 
+pub use self::block_builder::{BlockBuilder, Exclusion, ExclusionReason};
 pub use self::miner::{Miner, MinerOptions, Penalization, PendingSet, AuthoringParams};
 pub use ethcore_miner::pool::PendingOrdering;
 
@@ -33,13 +35,15 @@ use std::collections::{BTreeSet, BTreeMap};
 
 use bytes::Bytes;
 use ethereum_types::{H256, U256, Address};
+use ethcore_miner::gas_pricer::GasPriceOracleOptions;
 use ethcore_miner::pool::{VerifiedTransaction, QueueStatus, local_transactions};
+use ethcore_miner::pool::evictions::Eviction;
 
 use block::{Block, SealedBlock};
 use client::{
 	CallContract, RegistryInfo, ScheduleInfo,
 	BlockChain, BlockProducer, SealedBlockImporter, ChainInfo,
-	AccountData, Nonce,
+	AccountData, BlockInfo, Nonce,
 };
 use error::Error;
 use header::{BlockNumber, Header};
@@ -76,11 +80,11 @@ pub trait MinerService : Send + Sync {
 	///
 	/// Returns `None` if engine seals internally.
 	fn work_package<C>(&self, chain: &C) -> Option<(H256, BlockNumber, u64, U256)>
-		where C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + Nonce + Sync;
+		where C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + AccountData + Sync;
 
 	/// Update current pending block
 	fn update_sealing<C>(&self, chain: &C)
-		where C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + Nonce + Sync;
+		where C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + AccountData + Sync;
 
 
 	// Notifications
@@ -146,6 +150,15 @@ pub trait MinerService : Send + Sync {
 		-> Result<(), transaction::Error>
 		where C: BlockChainClient;
 
+	/// Imports an atomic bundle of own (node owner) transactions.
+	///
+	/// Transactions of the bundle are included in a block all together and in the given order, or not at all.
+	/// When `target_block` is given the bundle is only considered for the block with that number.
+	/// Returns hash of the imported bundle.
+	fn import_bundle<C>(&self, chain: &C, transactions: Vec<SignedTransaction>, target_block: Option<BlockNumber>)
+		-> Result<H256, transaction::Error>
+		where C: BlockChainClient;
+
 	/// Removes transaction from the pool.
 	///
 	/// Attempts to "cancel" a transaction. If it was not propagated yet (or not accepted by other peers)
@@ -177,7 +190,11 @@ pub trait MinerService : Send + Sync {
 	/// If you don't need a full set of transactions, you can add `max_len` and create only a limited set of
 	/// transactions.
 	fn ready_transactions<C>(&self, chain: &C, max_len: usize, ordering: PendingOrdering) -> Vec<Arc<VerifiedTransaction>>
-		where C: ChainInfo + Nonce + Sync;
+		where C: ChainInfo + AccountData + BlockInfo + CallContract + Sync;
+
+	/// Get a list of all ready transactions in the pool, regardless of the pending block.
+	fn queue_ready_transactions<C>(&self, chain: &C, max_len: usize, ordering: PendingOrdering) -> Vec<Arc<VerifiedTransaction>>
+		where C: ChainInfo + AccountData + BlockInfo + CallContract + Sync;
 
 	/// Get a list of all transactions in the pool (some of them might not be ready for inclusion yet).
 	fn queued_transactions(&self) -> Vec<Arc<VerifiedTransaction>>;
@@ -185,6 +202,13 @@ pub trait MinerService : Send + Sync {
 	/// Get a list of local transactions with statuses.
 	fn local_transactions(&self) -> BTreeMap<H256, local_transactions::Status>;
 
+	/// Get a list of transactions recently evicted from the pool together with limits that caused it.
+	fn queue_evictions(&self) -> Vec<Eviction>;
+
+	/// Get a list of pending transactions that were not included in the most recently prepared block
+	/// together with the reasons why.
+	fn block_exclusions(&self) -> Vec<Exclusion>;
+
 	/// Get current queue status.
 	///
 	/// Status includes verification thresholds and current pool utilization and limits.
@@ -195,6 +219,9 @@ pub trait MinerService : Send + Sync {
 	/// Suggested gas price.
 	fn sensible_gas_price(&self) -> U256;
 
+	/// Options of the gas price oracle, if gas prices should be suggested from recent blocks.
+	fn gas_price_oracle(&self) -> Option<GasPriceOracleOptions>;
+
 	/// Suggested gas limit.
 	fn sensible_gas_limit(&self) -> U256;
 }
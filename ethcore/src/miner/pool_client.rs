@@ -24,7 +24,7 @@ use std::{
 
 use ethereum_types::{H256, U256, Address};
 use ethcore_miner::pool;
-use ethcore_miner::pool::client::NonceClient;
+use ethcore_miner::pool::client::{NonceClient, ConditionClient};
 use transaction::{
 	self,
 	UnverifiedTransaction,
@@ -33,7 +33,7 @@ use transaction::{
 use parking_lot::RwLock;
 
 use account_provider::AccountProvider;
-use client::{TransactionId, BlockInfo, CallContract, Nonce};
+use client::{TransactionId, BlockId, BlockInfo, CallContract, Nonce, Balance};
 use engines::EthEngine;
 use header::Header;
 use miner;
@@ -184,6 +184,22 @@ impl<'a, C: 'a> NonceClient for PoolClient<'a, C> where
 	}
 }
 
+impl<'a, C: 'a> ConditionClient for PoolClient<'a, C> where
+	C: BlockInfo + Balance + CallContract + Sync,
+{
+	fn transaction_block_number(&self, hash: &H256) -> Option<u64> {
+		self.cached_nonces.transaction_block_number(hash)
+	}
+
+	fn account_balance(&self, address: &Address) -> U256 {
+		self.cached_nonces.account_balance(address)
+	}
+
+	fn call_contract(&self, address: &Address, data: &[u8]) -> Result<Vec<u8>, String> {
+		self.cached_nonces.call_contract(address, data)
+	}
+}
+
 pub(crate) struct CachedNonceClient<'a, C: 'a> {
 	client: &'a C,
 	cache: &'a NonceCache,
@@ -246,3 +262,21 @@ impl<'a, C: 'a> NonceClient for CachedNonceClient<'a, C> where
 	  nonce
   }
 }
+
+impl<'a, C: 'a> ConditionClient for CachedNonceClient<'a, C> where
+	C: BlockInfo + Balance + CallContract + Sync,
+{
+	fn transaction_block_number(&self, hash: &H256) -> Option<u64> {
+		self.client.transaction_block(TransactionId::Hash(*hash))
+			.and_then(|block_hash| self.client.block_header(BlockId::Hash(block_hash)))
+			.map(|header| header.number())
+	}
+
+	fn account_balance(&self, address: &Address) -> U256 {
+		self.client.latest_balance(address)
+	}
+
+	fn call_contract(&self, address: &Address, data: &[u8]) -> Result<Vec<u8>, String> {
+		self.client.call_contract_checked(BlockId::Latest, *address, data.to_vec())
+	}
+}
@@ -16,17 +16,19 @@
 
 //! Client-side stratum job dispatcher and mining notifier handler
 
+use std::collections::HashSet;
 use std::sync::{Arc, Weak};
 use std::net::{SocketAddr, AddrParseError};
-use std::fmt;
+use std::path::Path;
+use std::{cmp, fmt};
 
-use client::{Client, ImportSealedBlock};
+use client::{Client, EngineInfo, ImportSealedBlock};
 use ethereum_types::{H64, H256, clean_0x, U256};
 use ethereum::ethash::Ethash;
-use ethash::SeedHashCompute;
+use ethash::{EthashManager, OptimizeFor, SeedHashCompute};
 use ethcore_miner::work_notify::NotifyWork;
 use ethcore_stratum::{
-	JobDispatcher, PushWorkHandler,
+	JobDispatcher, PushWorkHandler, Share,
 	Stratum as StratumService, Error as StratumServiceError,
 };
 use miner::{Miner, MinerService};
@@ -44,6 +46,16 @@ pub struct Options {
 	pub port: u16,
 	/// Secret for peers
 	pub secret: Option<H256>,
+	/// Difficulty of shares expected from workers (network difficulty if none)
+	pub share_difficulty: Option<U256>,
+}
+
+pub use ethcore_stratum::WorkerStats;
+
+/// Provides statistics of workers connected to the stratum server.
+pub trait StratumWorkers: Send + Sync {
+	/// Statistics of authorized workers.
+	fn workers(&self) -> Vec<WorkerStats>;
 }
 
 struct SubmitPayload {
@@ -107,6 +119,9 @@ impl fmt::Display for PayloadError {
 /// Job dispatcher for stratum service
 pub struct StratumJobDispatcher {
 	seed_compute: Mutex<SeedHashCompute>,
+	pow: Arc<EthashManager>,
+	// nonces of the shares submitted for the current work
+	shares: Mutex<(H256, HashSet<H64>)>,
 	client: Weak<Client>,
 	miner: Weak<Miner>,
 }
@@ -124,6 +139,12 @@ impl JobDispatcher for StratumJobDispatcher {
 		}))
 	}
 
+	fn job_for_difficulty(&self, difficulty: &U256) -> Option<String> {
+		self.with_core(|client, miner| miner.work_package(&*client).map(|(pow_hash, number, _timestamp, network_difficulty)| {
+			self.payload(pow_hash, share_target(Some(difficulty), network_difficulty), number)
+		}))
+	}
+
 	fn submit(&self, payload: Vec<String>) -> Result<(), StratumServiceError> {
 		let payload = SubmitPayload::from_args(payload).map_err(|e|
 			StratumServiceError::Dispatch(e.to_string())
@@ -137,32 +158,105 @@ impl JobDispatcher for StratumJobDispatcher {
 			payload.mix_hash,
 		);
 
-		self.with_core_result(|client, miner| {
-			let seal = vec![encode(&payload.mix_hash).into_vec(), encode(&payload.nonce).into_vec()];
+		let result = self.seal(&payload);
+		if let Err(ref e) = result {
+			warn!(target: "stratum", "submit_seal error: {:?}", e);
+		}
+		result
+	}
+
+	fn submit_share(&self, payload: Vec<String>, difficulty: Option<&U256>) -> Share {
+		let payload = match SubmitPayload::from_args(payload) {
+			Ok(payload) => payload,
+			Err(e) => {
+				trace!(target: "stratum", "submit_share: invalid payload ({})", e);
+				return Share::Invalid;
+			},
+		};
+
+		let (pow_hash, number, network_difficulty) = match self.with_core(|client, miner| miner.work_package(&*client)) {
+			Some((pow_hash, number, _timestamp, difficulty)) => (pow_hash, number, difficulty),
+			None => return Share::Stale,
+		};
+		let target = share_target(difficulty, network_difficulty);
+
+		if payload.pow_hash != pow_hash {
+			// previous work might still be in the sealing queue
+			return match self.seal(&payload) {
+				Ok(()) => Share::Block(target),
+				Err(_) => Share::Stale,
+			};
+		}
+
+		{
+			let mut shares = self.shares.lock();
+			if shares.0 != pow_hash {
+				*shares = (pow_hash, HashSet::new());
+			}
+			if !shares.1.insert(payload.nonce) {
+				trace!(target: "stratum", "submit_share: duplicate share (nonce={})", payload.nonce);
+				return Share::Invalid;
+			}
+		}
 
-			let import = miner.submit_seal(payload.pow_hash, seal)
-				.and_then(|block| client.import_sealed_block(block));
-			match import {
-				Ok(_) => Ok(()),
+		match self.share_difficulty(&payload, number) {
+			Some(share) if share >= network_difficulty => match self.seal(&payload) {
+				Ok(()) => Share::Block(target),
 				Err(e) => {
 					warn!(target: "stratum", "submit_seal error: {:?}", e);
-					Err(StratumServiceError::Dispatch(e.to_string()))
-				}
-			}
-		})
+					Share::Invalid
+				},
+			},
+			Some(share) if share >= target => Share::Accepted(target),
+			_ => Share::Invalid,
+		}
 	}
 }
 
+/// Share difficulty capped at the network one, so that no block is withheld by workers.
+fn share_target(difficulty: Option<&U256>, network_difficulty: U256) -> U256 {
+	difficulty.map_or(network_difficulty, |difficulty| cmp::min(*difficulty, network_difficulty))
+}
+
 impl StratumJobDispatcher {
-	/// New stratum job dispatcher given the miner and client
-	fn new(miner: Weak<Miner>, client: Weak<Client>) -> StratumJobDispatcher {
+	/// New stratum job dispatcher given the miner, client and directory for ethash caches.
+	/// Ethash caches of the client's engine are reused if it has any.
+	fn new(miner: Weak<Miner>, client: Weak<Client>, cache_dir: &Path) -> StratumJobDispatcher {
+		let pow = client.upgrade()
+			.and_then(|client| client.engine().pow_manager())
+			.unwrap_or_else(|| Arc::new(EthashManager::new(cache_dir, OptimizeFor::default())));
+
 		StratumJobDispatcher {
 			seed_compute: Mutex::new(SeedHashCompute::new()),
+			pow: pow,
+			shares: Mutex::new((H256::zero(), HashSet::new())),
 			client: client,
 			miner: miner,
 		}
 	}
 
+	/// Difficulty of the share for block with given number, `None` if its mix hash is invalid.
+	fn share_difficulty(&self, payload: &SubmitPayload, number: u64) -> Option<U256> {
+		let result = self.pow.compute_light(number, &payload.pow_hash.0, payload.nonce.low_u64());
+		if H256(result.mix_hash) != payload.mix_hash {
+			return None;
+		}
+
+		Some(Ethash::boundary_to_difficulty(&H256(result.value)))
+	}
+
+	/// Submits seal of the work and imports resulting block.
+	fn seal(&self, payload: &SubmitPayload) -> Result<(), StratumServiceError> {
+		self.with_core_result(|client, miner| {
+			let seal = vec![encode(&payload.mix_hash).into_vec(), encode(&payload.nonce).into_vec()];
+
+			miner.submit_seal(payload.pow_hash, seal)
+				.and_then(|block| client.import_sealed_block(block))
+				.map(|_| ())
+				.map_err(|e| StratumServiceError::Dispatch(e.to_string()))
+		})
+	}
+
 	/// Serializes payload for stratum service
 	fn payload(&self, pow_hash: H256, difficulty: U256, number: u64) -> String {
 		// TODO: move this to engine
@@ -188,6 +282,7 @@ impl StratumJobDispatcher {
 }
 
 /// Wrapper for dedicated stratum service
+#[derive(Clone)]
 pub struct Stratum {
 	dispatcher: Arc<StratumJobDispatcher>,
 	service: Arc<StratumService>,
@@ -228,12 +323,13 @@ impl Stratum {
 	pub fn start(options: &Options, miner: Weak<Miner>, client: Weak<Client>) -> Result<Stratum, Error> {
 		use std::net::IpAddr;
 
-		let dispatcher = Arc::new(StratumJobDispatcher::new(miner, client));
+		let dispatcher = Arc::new(StratumJobDispatcher::new(miner, client, Path::new(&options.io_path)));
 
 		let stratum_svc = StratumService::start(
 			&SocketAddr::new(options.listen_addr.parse::<IpAddr>()?, options.port),
 			dispatcher.clone(),
 			options.secret.clone(),
+			options.share_difficulty.clone(),
 		)?;
 
 		Ok(Stratum {
@@ -243,9 +339,15 @@ impl Stratum {
 	}
 
 	/// Start STRATUM job dispatcher and register it in the miner
-	pub fn register(cfg: &Options, miner: Arc<Miner>, client: Weak<Client>) -> Result<(), Error> {
+	pub fn register(cfg: &Options, miner: Arc<Miner>, client: Weak<Client>) -> Result<Stratum, Error> {
 		let stratum = Stratum::start(cfg, Arc::downgrade(&miner.clone()), client)?;
-		miner.add_work_listener(Box::new(stratum) as Box<NotifyWork>);
-		Ok(())
+		miner.add_work_listener(Box::new(stratum.clone()) as Box<NotifyWork>);
+		Ok(stratum)
+	}
+}
+
+impl StratumWorkers for Stratum {
+	fn workers(&self) -> Vec<WorkerStats> {
+		self.service.workers()
 	}
 }
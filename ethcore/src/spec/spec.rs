@@ -33,7 +33,7 @@ use vm::{EnvInfo, CallType, ActionValue, ActionParams, ParamsType};
 
 use builtin::Builtin;
 use encoded;
-use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, DEFAULT_BLOCKHASH_CONTRACT};
+use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, Clique, DEFAULT_BLOCKHASH_CONTRACT};
 use error::Error;
 use executive::Executive;
 use factory::Factories;
@@ -373,9 +373,36 @@ pub struct SpecHardcodedSync {
 	/// List of hardcoded CHTs, in order. If `hardcoded_sync` is set, the CHTs should include the
 	/// header of `hardcoded_sync`.
 	pub chts: Vec<H256>,
+	/// List of hardcoded bloom-bit section roots, in order. These may cover fewer
+	/// sections than `chts`.
+	pub bloom_bits: Vec<H256>,
 }
 
 impl SpecHardcodedSync {
+	/// Loads hardcoded sync parameters from their JSON representation, e.g. a light client
+	/// checkpoint supplied by the operator. The header may optionally be `0x`-prefixed.
+	pub fn from_json(sync: ethjson::spec::HardcodedSync) -> Result<Self, String> {
+		let header = {
+			let hex = sync.header.trim();
+			let hex = if hex.starts_with("0x") { &hex[2..] } else { hex };
+			hex.from_hex().map_err(|e| format!("Invalid header hex: {}", e))?
+		};
+
+		Ok(SpecHardcodedSync {
+			header: encoded::Header::new(header),
+			total_difficulty: sync.total_difficulty.into(),
+			chts: sync.chts.into_iter().map(Into::into).collect(),
+			bloom_bits: sync.bloom_bits.into_iter().map(Into::into).collect(),
+		})
+	}
+
+	/// Loads hardcoded sync parameters from a JSON reader.
+	pub fn load<R>(reader: R) -> Result<Self, String> where R: Read {
+		ethjson::spec::HardcodedSync::load(reader)
+			.map_err(|e| format!("{}", e))
+			.and_then(SpecHardcodedSync::from_json)
+	}
+
 	/// Turns this specifications back into JSON. Useful for pretty printing.
 	pub fn to_json(self) -> ethjson::spec::HardcodedSync {
 		self.into()
@@ -389,6 +416,7 @@ impl Clone for SpecHardcodedSync {
 			header: self.header.clone(),
 			total_difficulty: self.total_difficulty.clone(),
 			chts: self.chts.clone(),
+			bloom_bits: self.bloom_bits.clone(),
 		}
 	}
 }
@@ -399,6 +427,7 @@ impl From<SpecHardcodedSync> for ethjson::spec::HardcodedSync {
 			header: sync.header.into_inner().to_hex(),
 			total_difficulty: ethjson::uint::Uint(sync.total_difficulty),
 			chts: sync.chts.into_iter().map(Into::into).collect(),
+			bloom_bits: sync.bloom_bits.into_iter().map(Into::into).collect(),
 		}
 	}
 }
@@ -421,22 +450,7 @@ fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Er
 	let GenericSeal(seal_rlp) = g.seal.into();
 	let params = CommonParams::from(s.params);
 
-	let hardcoded_sync = if let Some(ref hs) = s.hardcoded_sync {
-		if let Ok(header) = hs.header.from_hex() {
-			Some(SpecHardcodedSync {
-				header: encoded::Header::new(header),
-				total_difficulty: hs.total_difficulty.into(),
-				chts: s.hardcoded_sync
-					.as_ref()
-					.map(|s| s.chts.iter().map(|c| c.clone().into()).collect())
-					.unwrap_or(Vec::new()),
-			})
-		} else {
-			None
-		}
-	} else {
-		None
-	};
+	let hardcoded_sync = s.hardcoded_sync.and_then(|hs| SpecHardcodedSync::from_json(hs).ok());
 
 	let mut s = Spec {
 		name: s.name.clone().into(),
@@ -527,6 +541,8 @@ impl Spec {
 				.expect("Failed to start AuthorityRound consensus engine."),
 			ethjson::spec::Engine::Tendermint(tendermint) => Tendermint::new(tendermint.params.into(), machine)
 				.expect("Failed to start the Tendermint consensus engine."),
+			ethjson::spec::Engine::Clique(clique) => Clique::new(clique.params.into(), machine)
+				.expect("Failed to start the Clique consensus engine."),
 		}
 	}
 
@@ -873,6 +889,12 @@ impl Spec {
 		load_bundled!("tendermint")
 	}
 
+	/// Create a new Spec with Tendermint consensus which rewards blocks through the test block
+	/// reward contract at address 0x42.
+	pub fn new_test_tendermint_block_reward_contract() -> Self {
+		load_bundled!("tendermint_block_reward_contract")
+	}
+
 	/// TestList.sol used in both specs: https://github.com/paritytech/contracts/pull/30/files
 	/// Accounts with secrets keccak("0") and keccak("1") are initially the validators.
 	/// Create a new Spec with BasicAuthority which uses a contract at address 5 to determine
@@ -38,6 +38,7 @@ use pod_state::{self, PodState};
 use types::basic_account::BasicAccount;
 use executed::{Executed, ExecutionError};
 use types::state_diff::StateDiff;
+use types::call_analytics::CallAnalytics;
 use transaction::SignedTransaction;
 use state_db::StateDB;
 use factory::VmFactory;
@@ -230,6 +231,102 @@ pub fn check_proof(
 	}
 }
 
+/// Check the given proof of execution, producing the traces and state diff
+/// requested by `analytics`.
+///
+/// Transactions in `prior` are executed first on the proved state, as when
+/// replaying a transaction which isn't the first in its block. The proof must
+/// then cover their execution too. Nonce checks are skipped, and only `transaction`
+/// is executed virtually: prior transactions must be affordable with the proved balance.
+pub fn check_proof_with_analytics(
+	proof: &[DBValue],
+	root: H256,
+	prior: &[SignedTransaction],
+	transaction: &SignedTransaction,
+	machine: &Machine,
+	env_info: &EnvInfo,
+	analytics: CallAnalytics,
+) -> ProvedExecution {
+	fn call<T, V>(
+		state: &mut State<self::backend::ProofCheck>,
+		env_info: &EnvInfo,
+		machine: &Machine,
+		transaction: &SignedTransaction,
+		options: TransactOptions<T, V>,
+		virt: bool,
+	) -> Result<Executed, ExecutionError> where
+		T: trace::Tracer<Output=FlatTrace>,
+		V: trace::VMTracer<Output=VMTrace>,
+	{
+		let options = options
+			.dont_check_nonce()
+			.save_output_from_contract();
+
+		state.execute(env_info, machine, transaction, options, virt)
+	}
+
+	fn into_proved(err: ExecutionError) -> ProvedExecution {
+		match err {
+			ExecutionError::Internal(_) => ProvedExecution::BadProof,
+			e => ProvedExecution::Failed(e),
+		}
+	}
+
+	// build the proved state and execute the prior transactions on it.
+	let prepare_state = |env_info: &mut EnvInfo| -> Result<State<self::backend::ProofCheck>, ProvedExecution> {
+		let mut factories = Factories::default();
+		factories.accountdb = ::account_db::Factory::Plain;
+
+		let backend = self::backend::ProofCheck::new(proof);
+		let mut state = State::from_existing(backend, root, machine.account_start_nonce(env_info.number), factories)
+			.map_err(|_| ProvedExecution::BadProof)?;
+
+		for t in prior {
+			let executed = call(&mut state, env_info, machine, t, TransactOptions::with_no_tracing(), false)
+				.map_err(into_proved)?;
+			env_info.gas_used = env_info.gas_used + executed.gas_used;
+		}
+
+		Ok(state)
+	};
+
+	let mut env_info = env_info.clone();
+	let mut state = match prepare_state(&mut env_info) {
+		Ok(state) => state,
+		Err(proved) => return proved,
+	};
+
+	// the diff is computed against an untouched copy of the state.
+	let original_state = match analytics.state_diffing {
+		true => match prepare_state(&mut env_info.clone()) {
+			Ok(state) => Some(state),
+			Err(proved) => return proved,
+		},
+		false => None,
+	};
+
+	let res = match (analytics.transaction_tracing, analytics.vm_tracing) {
+		(true, true) => call(&mut state, &env_info, machine, transaction, TransactOptions::with_tracing_and_vm_tracing(), true),
+		(true, false) => call(&mut state, &env_info, machine, transaction, TransactOptions::with_tracing(), true),
+		(false, true) => call(&mut state, &env_info, machine, transaction, TransactOptions::with_vm_tracing(), true),
+		(false, false) => call(&mut state, &env_info, machine, transaction, TransactOptions::with_no_tracing(), true),
+	};
+
+	let mut executed = match res {
+		Ok(executed) => executed,
+		Err(e) => return into_proved(e),
+	};
+
+	if let Some(original) = original_state {
+		match state.diff_from(original) {
+			Ok(diff) => executed.state_diff = Some(diff),
+			Err(_) => return ProvedExecution::BadProof,
+		}
+	}
+
+	ProvedExecution::Complete(executed)
+}
+
 /// Prove a transaction on the given state.
 /// Returns `None` when the transacion could not be proved,
 /// and a proof otherwise.
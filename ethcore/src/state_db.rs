@@ -21,9 +21,10 @@ use std::sync::Arc;
 use lru_cache::LruCache;
 use memory_cache::MemoryLruCache;
 use journaldb::JournalDB;
-use kvdb::{KeyValueDB, DBTransaction};
+use kvdb::{KeyValueDB, DBTransaction, DBValue};
 use ethereum_types::{H256, Address};
 use hashdb::HashDB;
+use account_db::{AccountDB, AccountDBMut};
 use state::{self, Account};
 use header::BlockNumber;
 use hash::keccak;
@@ -309,6 +310,38 @@ impl StateDB {
 		}
 	}
 
+	/// Check if a state trie node or a code blob is present in the database.
+	/// Storage trie nodes and code are looked up in the database of the account
+	/// with the given address hash.
+	pub fn contains_state_node(&self, hash: &H256, account: Option<&H256>) -> bool {
+		match account {
+			Some(account) => AccountDB::from_hash(self.db.as_hashdb(), *account).contains(hash),
+			None => self.db.as_hashdb().contains(hash),
+		}
+	}
+
+	/// Write state trie nodes and code obtained by state sync to the database
+	/// transaction, bypassing the journal. Nodes that belong to an account storage
+	/// (and code) are paired with the account address hash. `accounts` are the
+	/// address hashes of all the downloaded accounts and are noted in the account bloom.
+	pub fn inject_state_nodes(&mut self, batch: &mut DBTransaction, nodes: Vec<(Option<H256>, DBValue)>, accounts: &[H256]) -> Result<u32, UtilError> {
+		{
+			let mut bloom = self.account_bloom.lock();
+			for hash in accounts {
+				bloom.set(&**hash);
+			}
+			Self::commit_bloom(batch, bloom.drain_journal())?;
+		}
+		for (account, value) in nodes {
+			let hash = keccak(&value);
+			match account {
+				Some(account) => AccountDBMut::from_hash(self.db.as_hashdb_mut(), account).emplace(hash, value),
+				None => self.db.as_hashdb_mut().emplace(hash, value),
+			}
+		}
+		self.db.inject(batch)
+	}
+
 	/// Conversion method to interpret self as `HashDB` reference
 	pub fn as_hashdb(&self) -> &HashDB {
 		self.db.as_hashdb()
@@ -391,3 +391,58 @@ fn transaction_proof() {
 	assert_eq!(state.balance(&Address::default()).unwrap(), 5.into());
 	assert_eq!(state.balance(&address).unwrap(), 95.into());
 }
+
+#[test]
+fn traced_transaction_proof() {
+	use ::client::{ProvingBlockChainClient, CallAnalytics};
+
+	let client = generate_dummy_client(0);
+	let address = Address::random();
+	let test_spec = Spec::new_test();
+	for _ in 0..20 {
+		let mut b = client.prepare_open_block(Address::default(), (3141562.into(), 31415620.into()), vec![]);
+		b.block_mut().state_mut().add_balance(&address, &5.into(), CleanupMode::NoEmpty).unwrap();
+		b.block_mut().state_mut().commit().unwrap();
+		let b = b.close_and_lock().seal(&*test_spec.engine, vec![]).unwrap();
+		client.import_sealed_block(b).unwrap();
+	}
+
+	let transaction = |value: u64| Transaction {
+		nonce: 0.into(),
+		gas_price: 0.into(),
+		gas: 21000.into(),
+		action: Action::Call(Address::default()),
+		value: value.into(),
+		..Default::default()
+	}.fake_sign(address);
+
+	let (first, second) = (transaction(5), transaction(10));
+	let mut proof = client.prove_transaction(first.clone(), BlockId::Latest).unwrap().1;
+	proof.extend(client.prove_transaction(second.clone(), BlockId::Latest).unwrap().1);
+
+	let root = *client.best_block_header().state_root();
+	let analytics = CallAnalytics {
+		transaction_tracing: true,
+		vm_tracing: false,
+		state_diffing: true,
+	};
+
+	let proved = state::check_proof_with_analytics(
+		&proof,
+		root,
+		&[first],
+		&second,
+		test_spec.engine.machine(),
+		&client.latest_env_info(),
+		analytics,
+	);
+
+	match proved {
+		state::ProvedExecution::Complete(executed) => {
+			assert_eq!(executed.trace.len(), 1);
+			let diff = executed.state_diff.unwrap();
+			assert!(diff.raw.contains_key(&Address::default()));
+		}
+		_ => panic!("both transactions are covered by the proof"),
+	}
+}
@@ -251,6 +251,25 @@ pub fn verify_block_final(expected: &Header, got: &Header) -> Result<(), Error>
 	Ok(())
 }
 
+/// Verify a chain of headers downloaded without their ancestors, ordered from the oldest one.
+/// Checks the seals and that every header is a valid child of the previous one, but not the
+/// engine family rules, which may need the chain state before the headers.
+pub fn verify_header_chain(headers: &[Header], engine: &EthEngine) -> Result<(), Error> {
+	for header in headers {
+		verify_header_params(header, engine, true)?;
+		engine.verify_block_basic(header)?;
+		engine.verify_block_unordered(header)?;
+	}
+	for pair in headers.windows(2) {
+		let (parent, header) = (&pair[0], &pair[1]);
+		if header.parent_hash() != &parent.hash() {
+			return Err(From::from(BlockError::UnknownParent(header.parent_hash().clone())));
+		}
+		verify_parent(header, parent, engine)?;
+	}
+	Ok(())
+}
+
 /// Check basic header parameters.
 pub fn verify_header_params(header: &Header, engine: &EthEngine, is_full: bool) -> Result<(), Error> {
 	let expected_seal_fields = engine.seal_fields(header);
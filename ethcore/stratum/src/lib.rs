@@ -30,10 +30,12 @@ extern crate parking_lot;
 #[cfg(test)] extern crate ethcore_logger;
 
 mod traits;
+mod workers;
 
 pub use traits::{
-	JobDispatcher, PushWorkHandler, Error, ServiceConfiguration,
+	JobDispatcher, PushWorkHandler, Error, ServiceConfiguration, Share,
 };
+pub use workers::WorkerStats;
 
 use jsonrpc_tcp_server::{
 	Server as JsonRpcServer, ServerBuilder as JsonRpcServerBuilder,
@@ -46,8 +48,9 @@ use std::sync::Arc;
 use std::net::SocketAddr;
 use std::collections::{HashSet, HashMap};
 use hash::keccak;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256};
 use parking_lot::RwLock;
+use workers::Worker;
 
 type RpcResult = Result<jsonrpc_core::Value, jsonrpc_core::Error>;
 
@@ -74,6 +77,7 @@ impl Stratum {
 		addr: &SocketAddr,
 		dispatcher: Arc<JobDispatcher>,
 		secret: Option<H256>,
+		share_difficulty: Option<U256>,
 	) -> Result<Arc<Stratum>, Error> {
 
 		let implementation = Arc::new(StratumImpl {
@@ -82,6 +86,7 @@ impl Stratum {
 			dispatcher,
 			workers: Arc::new(RwLock::default()),
 			secret,
+			share_difficulty,
 			notify_counter: RwLock::new(NOTIFY_COUNTER_INITIAL),
 		});
 
@@ -89,6 +94,7 @@ impl Stratum {
 		delegate.add_method_with_meta("mining.subscribe", StratumImpl::subscribe);
 		delegate.add_method_with_meta("mining.authorize", StratumImpl::authorize);
 		delegate.add_method_with_meta("mining.submit", StratumImpl::submit);
+		delegate.add_method_with_meta("mining.suggest_difficulty", StratumImpl::suggest_difficulty);
 		let mut handler = MetaIoHandler::<SocketMetadata>::with_compatibility(Compatibility::Both);
 		handler.extend_with(delegate);
 
@@ -105,6 +111,13 @@ impl Stratum {
 
 		Ok(stratum)
 	}
+
+	/// Statistics of authorized workers.
+	pub fn workers(&self) -> Vec<WorkerStats> {
+		self.implementation.workers.read().iter()
+			.map(|(addr, worker)| worker.stats(*addr))
+			.collect()
+	}
 }
 
 impl PushWorkHandler for Stratum {
@@ -131,10 +144,12 @@ struct StratumImpl {
 	job_que: RwLock<HashSet<SocketAddr>>,
 	/// Payload manager
 	dispatcher: Arc<JobDispatcher>,
-	/// Authorized workers (socket - worker_id)
-	workers: Arc<RwLock<HashMap<SocketAddr, String>>>,
+	/// Authorized workers (socket - worker)
+	workers: Arc<RwLock<HashMap<SocketAddr, Worker>>>,
 	/// Secret if any
 	secret: Option<H256>,
+	/// Initial (and minimal) share difficulty of workers, network difficulty if none
+	share_difficulty: Option<U256>,
 	/// Dispatch notify couinter
 	notify_counter: RwLock<u32>,
 }
@@ -170,7 +185,7 @@ impl StratumImpl {
 				}
 			}
 			trace!(target: "stratum", "New worker #{} registered", worker_id);
-			self.workers.write().insert(meta.addr().clone(), worker_id);
+			self.workers.write().insert(meta.addr().clone(), Worker::new(worker_id, self.share_difficulty));
 			to_value(true)
 		}).map(|v| v.expect("Only true/false is returned and it's always serializable; qed"))
 	}
@@ -180,21 +195,30 @@ impl StratumImpl {
 		Ok(match params {
 			Params::Array(vals) => {
 				// first two elements are service messages (worker_id & job_id)
-				match self.dispatcher.submit(vals.iter().skip(2)
+				let payload = vals.iter().skip(2)
 					.filter_map(|val| match *val {
 						Value::String(ref s) => Some(s.to_owned()),
 						_ => None
 					})
-					.collect::<Vec<String>>()) {
-						Ok(()) => {
-							self.update_peers(&meta.tcp_dispatcher.expect("tcp_dispatcher is always initialized; qed"));
-							to_value(true)
-						},
-						Err(submit_err) => {
-							warn!("Error while submitting share: {:?}", submit_err);
-							to_value(false)
-						}
-					}
+					.collect::<Vec<String>>();
+				let difficulty = self.workers.read().get(meta.addr()).and_then(|worker| worker.difficulty());
+				let share = self.dispatcher.submit_share(payload, difficulty.as_ref());
+
+				if let Some(worker) = self.workers.write().get_mut(meta.addr()) {
+					worker.record(&share);
+				}
+
+				match share {
+					Share::Block(_) => {
+						self.update_peers(&meta.tcp_dispatcher.expect("tcp_dispatcher is always initialized; qed"));
+						to_value(true)
+					},
+					Share::Accepted(_) => to_value(true),
+					Share::Stale | Share::Invalid => {
+						trace!(target: "stratum", "Share from {} rejected: {:?}", meta.addr(), share);
+						to_value(false)
+					},
+				}
 			},
 			_ => {
 				trace!(target: "stratum", "Invalid submit work format {:?}", params);
@@ -203,6 +227,36 @@ impl StratumImpl {
 		}.expect("Only true/false is returned and it's always serializable; qed"))
 	}
 
+	/// rpc method `mining.suggest_difficulty`
+	fn suggest_difficulty(&self, params: Params, meta: SocketMetadata) -> RpcResult {
+		params.parse::<(u64,)>().map(|(difficulty,)| {
+			let difficulty = U256::from(difficulty);
+			let accepted = match self.share_difficulty {
+				// workers may only raise their share difficulty
+				Some(min_difficulty) if difficulty >= min_difficulty => match self.workers.write().get_mut(meta.addr()) {
+					Some(worker) => {
+						worker.set_difficulty(Some(difficulty));
+						true
+					},
+					None => false,
+				},
+				_ => false,
+			};
+
+			if accepted {
+				trace!(target: "stratum", "Share difficulty of worker {} set to {}", meta.addr(), difficulty);
+				// push a job with the new target right away so that following shares meet it
+				if let (Some(job), Some(tcp_dispatcher)) = (self.dispatcher.job_for_difficulty(&difficulty), meta.tcp_dispatcher.as_ref()) {
+					if let Err(e) = tcp_dispatcher.push_message(meta.addr(), notify_message(self.next_request_id(), &job)) {
+						warn!(target: "stratum", "Failed to push work to {}: {:?}", meta.addr(), e);
+					}
+				}
+			}
+
+			to_value(accepted)
+		}).map(|v| v.expect("Only true/false is returned and it's always serializable; qed"))
+	}
+
 	/// Helper method
 	fn update_peers(&self, tcp_dispatcher: &Dispatcher) {
 		if let Some(job) = self.dispatcher.job() {
@@ -215,22 +269,28 @@ impl StratumImpl {
 	fn push_work_all(&self, payload: String, tcp_dispatcher: &Dispatcher) -> Result<(), Error> {
 		let hup_peers = {
 			let workers = self.workers.read();
-			let next_request_id = {
-				let mut counter = self.notify_counter.write();
-				if *counter == ::std::u32::MAX { *counter = NOTIFY_COUNTER_INITIAL; }
-				else { *counter = *counter + 1 }
-				*counter
-			};
+			let next_request_id = self.next_request_id();
 
 			let mut hup_peers = HashSet::with_capacity(0); // most of the cases won't be needed, hence avoid allocation
-			let workers_msg = format!("{{ \"id\": {}, \"method\": \"mining.notify\", \"params\": {} }}", next_request_id, payload);
+			let workers_msg = notify_message(next_request_id, &payload);
+			// workers with own share difficulty need a job with their target
+			let mut difficulty_msgs = HashMap::new();
 			trace!(target: "stratum", "pushing work for {} workers (payload: '{}')", workers.len(), &workers_msg);
-			for (ref addr, _) in workers.iter() {
+			for (addr, worker) in workers.iter() {
+				let worker_msg = match worker.difficulty() {
+					Some(difficulty) => difficulty_msgs.entry(difficulty)
+						.or_insert_with(|| self.dispatcher.job_for_difficulty(&difficulty).map(|job| notify_message(next_request_id, &job)))
+						.as_ref()
+						.unwrap_or(&workers_msg)
+						.clone(),
+					None => workers_msg.clone(),
+				};
+
 				trace!(target: "stratum", "pusing work to {}", addr);
-				match tcp_dispatcher.push_message(addr, workers_msg.clone()) {
+				match tcp_dispatcher.push_message(addr, worker_msg) {
 					Err(PushMessageError::NoSuchPeer) => {
 						trace!(target: "stratum", "Worker no longer connected: {}", &addr);
-						hup_peers.insert(*addr.clone());
+						hup_peers.insert(*addr);
 					},
 					Err(e) => {
 						warn!(target: "stratum", "Unexpected transport error: {:?}", e);
@@ -249,6 +309,13 @@ impl StratumImpl {
 		Ok(())
 	}
 
+	fn next_request_id(&self) -> u32 {
+		let mut counter = self.notify_counter.write();
+		if *counter == ::std::u32::MAX { *counter = NOTIFY_COUNTER_INITIAL; }
+		else { *counter = *counter + 1 }
+		*counter
+	}
+
 	fn push_work(&self, payloads: Vec<String>, tcp_dispatcher: &Dispatcher) -> Result<(), Error> {
 		if !payloads.len() > 0 {
 			return Err(Error::NoWork);
@@ -273,6 +340,10 @@ impl StratumImpl {
 	}
 }
 
+fn notify_message(request_id: u32, payload: &str) -> String {
+	format!("{{ \"id\": {}, \"method\": \"mining.notify\", \"params\": {} }}", request_id, payload)
+}
+
 #[derive(Clone)]
 pub struct SocketMetadata {
 	addr: SocketAddr,
@@ -329,7 +400,9 @@ mod tests {
 	use tokio_core::reactor::{Core, Timeout};
 	use tokio_core::net::TcpStream;
 	use tokio_io::io;
+	use jsonrpc_core::{Params, Value};
 	use jsonrpc_core::futures::{Future, future};
+	use ethereum_types::U256;
 
 	use ethcore_logger::init_log;
 
@@ -365,7 +438,7 @@ mod tests {
 
 	#[test]
 	fn can_be_started() {
-		let stratum = Stratum::start(&"127.0.0.1:19980".parse().unwrap(), Arc::new(VoidManager), None);
+		let stratum = Stratum::start(&"127.0.0.1:19980".parse().unwrap(), Arc::new(VoidManager), None, None);
 		assert!(stratum.is_ok());
 	}
 
@@ -374,7 +447,7 @@ mod tests {
 		init_log();
 
 		let addr = "127.0.0.1:19985".parse().unwrap();
-		let stratum = Stratum::start(&addr, Arc::new(VoidManager), None).unwrap();
+		let stratum = Stratum::start(&addr, Arc::new(VoidManager), None, None).unwrap();
 		let request = r#"{"jsonrpc": "2.0", "method": "mining.subscribe", "params": [], "id": 1}"#;
 		dummy_request(&addr, request);
 		assert_eq!(1, stratum.implementation.subscribers.read().len());
@@ -419,7 +492,7 @@ mod tests {
 	#[test]
 	fn receives_initial_paylaod() {
 		let addr = "127.0.0.1:19975".parse().unwrap();
-		let _stratum = Stratum::start(&addr, DummyManager::new(), None).expect("There should be no error starting stratum");
+		let _stratum = Stratum::start(&addr, DummyManager::new(), None, None).expect("There should be no error starting stratum");
 		let request = r#"{"jsonrpc": "2.0", "method": "mining.subscribe", "params": [], "id": 2}"#;
 
 		let response = String::from_utf8(dummy_request(&addr, request)).unwrap();
@@ -433,7 +506,8 @@ mod tests {
 		let stratum = Stratum::start(
 			&addr,
 			Arc::new(DummyManager::build().of_initial(r#"["dummy autorize payload"]"#)),
-			None
+			None,
+			None,
 		).expect("There should be no error starting stratum");
 
 		let request = r#"{"jsonrpc": "2.0", "method": "mining.authorize", "params": ["miner1", ""], "id": 1}"#;
@@ -451,7 +525,8 @@ mod tests {
 		let stratum = Stratum::start(
 			&addr,
 			Arc::new(DummyManager::build().of_initial(r#"["dummy autorize payload"]"#)),
-			None
+			None,
+			None,
 		).expect("There should be no error starting stratum");
 
 		let mut auth_request =
@@ -500,4 +575,61 @@ mod tests {
 			"{ \"id\": 17, \"method\": \"mining.notify\", \"params\": { \"00040008\", \"100500\" } }\n",
 			response);
 	}
+
+	struct ShareManager;
+
+	impl JobDispatcher for ShareManager {
+		fn job_for_difficulty(&self, difficulty: &U256) -> Option<String> {
+			Some(format!(r#"["{}"]"#, difficulty))
+		}
+
+		fn submit(&self, _payload: Vec<String>) -> Result<(), Error> {
+			Ok(())
+		}
+
+		fn submit_share(&self, payload: Vec<String>, difficulty: Option<&U256>) -> Share {
+			match payload.get(0).map(|s| s.as_str()) {
+				Some("stale") => Share::Stale,
+				Some("invalid") => Share::Invalid,
+				_ => Share::Accepted(difficulty.cloned().unwrap_or_default()),
+			}
+		}
+	}
+
+	#[test]
+	fn tracks_worker_shares() {
+		let addr = "127.0.0.1:19960".parse().unwrap();
+		let stratum = Stratum::start(&addr, Arc::new(ShareManager), None, Some(1000.into()))
+			.expect("There should be no error starting stratum");
+		let stratum_impl = &stratum.implementation;
+		let meta = SocketMetadata {
+			addr: "127.0.0.1:30000".parse().unwrap(),
+			tcp_dispatcher: None,
+		};
+		let submit = |share: &str| stratum_impl.submit(
+			Params::Array(vec!["miner1".into(), "0x1".into(), share.into()]),
+			meta.clone(),
+		).unwrap();
+
+		stratum_impl.authorize(Params::Array(vec!["miner1".into(), "".into()]), meta.clone()).unwrap();
+		assert_eq!(submit("good"), Value::Bool(true));
+		assert_eq!(submit("stale"), Value::Bool(false));
+		assert_eq!(submit("invalid"), Value::Bool(false));
+
+		// share difficulty can't be lowered below the configured one
+		let suggest = |difficulty: u64| stratum_impl.suggest_difficulty(Params::Array(vec![difficulty.into()]), meta.clone()).unwrap();
+		assert_eq!(suggest(500), Value::Bool(false));
+		assert_eq!(suggest(2000), Value::Bool(true));
+		assert_eq!(submit("good"), Value::Bool(true));
+
+		let workers = stratum.workers();
+		assert_eq!(workers.len(), 1);
+		assert_eq!(workers[0].id, "miner1");
+		assert_eq!(workers[0].difficulty, Some(2000.into()));
+		assert_eq!(workers[0].accepted_shares, 2);
+		assert_eq!(workers[0].blocks, 0);
+		assert_eq!(workers[0].stale_shares, 1);
+		assert_eq!(workers[0].invalid_shares, 1);
+		assert!(workers[0].hashrate > U256::zero());
+	}
 }
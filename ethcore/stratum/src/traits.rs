@@ -16,7 +16,7 @@
 
 use std;
 use std::error::Error as StdError;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256};
 use jsonrpc_tcp_server::PushMessageError;
 
 #[derive(Debug, Clone)]
@@ -40,6 +40,19 @@ impl From<PushMessageError> for Error {
 	}
 }
 
+/// Outcome of a share submitted by a worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Share {
+	/// Share of given difficulty that also sealed a block.
+	Block(U256),
+	/// Share of given difficulty that met the worker target only.
+	Accepted(U256),
+	/// Share submitted for outdated work.
+	Stale,
+	/// Malformed share or share not meeting the target.
+	Invalid,
+}
+
 /// Interface that can provide pow/blockchain-specific responses for the clients
 pub trait JobDispatcher: Send + Sync {
 	// json for initial client handshake
@@ -48,8 +61,21 @@ pub trait JobDispatcher: Send + Sync {
 	fn difficulty(&self) -> Option<String> { None }
 	// json for job update given worker_id (payload manager should split job!)
 	fn job(&self) -> Option<String> { None }
+	// json for job update with target of given share difficulty (`None` to use the one from `job`)
+	fn job_for_difficulty(&self, _difficulty: &U256) -> Option<String> { None }
 	// miner job result
 	fn submit(&self, payload: Vec<String>) -> Result<(), Error>;
+	// miner share checked against given share difficulty (`None` for network difficulty)
+	fn submit_share(&self, payload: Vec<String>, _difficulty: Option<&U256>) -> Share {
+		match self.submit(payload) {
+			// difficulty of the share is not known here
+			Ok(()) => Share::Block(U256::zero()),
+			Err(e) => {
+				warn!(target: "stratum", "Error while submitting share: {:?}", e);
+				Share::Invalid
+			},
+		}
+	}
 }
 
 /// Interface that can handle requests to push job for workers
@@ -0,0 +1,197 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-worker share accounting and hashrate estimation.
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant, SystemTime};
+
+use ethereum_types::U256;
+use traits::Share;
+
+/// Period of time over which worker hashrate is estimated.
+const HASHRATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Statistics of a single worker connected to the stratum server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerStats {
+	/// Worker id given during authorization.
+	pub id: String,
+	/// Address of the worker connection.
+	pub addr: SocketAddr,
+	/// Share difficulty of the worker (`None` if shares need to meet network difficulty).
+	pub difficulty: Option<U256>,
+	/// Number of accepted shares (including the ones that sealed a block).
+	pub accepted_shares: u64,
+	/// Number of shares that sealed a block.
+	pub blocks: u64,
+	/// Number of shares submitted for outdated work.
+	pub stale_shares: u64,
+	/// Number of malformed shares or shares not meeting the target.
+	pub invalid_shares: u64,
+	/// Hashrate estimated from difficulty of recently accepted shares.
+	pub hashrate: U256,
+	/// Time of the last submitted share.
+	pub last_share: Option<SystemTime>,
+}
+
+/// Authorized worker.
+#[derive(Debug)]
+pub struct Worker {
+	id: String,
+	difficulty: Option<U256>,
+	authorized_at: Instant,
+	accepted_shares: u64,
+	blocks: u64,
+	stale_shares: u64,
+	invalid_shares: u64,
+	recent_shares: VecDeque<(Instant, U256)>,
+	last_share: Option<SystemTime>,
+}
+
+impl Worker {
+	/// Create new worker with given share difficulty.
+	pub fn new(id: String, difficulty: Option<U256>) -> Self {
+		Worker {
+			id,
+			difficulty,
+			authorized_at: Instant::now(),
+			accepted_shares: 0,
+			blocks: 0,
+			stale_shares: 0,
+			invalid_shares: 0,
+			recent_shares: VecDeque::new(),
+			last_share: None,
+		}
+	}
+
+	/// Share difficulty of the worker.
+	pub fn difficulty(&self) -> Option<U256> {
+		self.difficulty
+	}
+
+	/// Change share difficulty of the worker.
+	pub fn set_difficulty(&mut self, difficulty: Option<U256>) {
+		self.difficulty = difficulty;
+	}
+
+	/// Account share submitted by the worker.
+	pub fn record(&mut self, share: &Share) {
+		self.record_at(share, Instant::now());
+	}
+
+	fn record_at(&mut self, share: &Share, now: Instant) {
+		self.last_share = Some(SystemTime::now());
+		match *share {
+			Share::Block(difficulty) => {
+				self.blocks += 1;
+				self.accept(difficulty, now);
+			},
+			Share::Accepted(difficulty) => self.accept(difficulty, now),
+			Share::Stale => self.stale_shares += 1,
+			Share::Invalid => self.invalid_shares += 1,
+		}
+	}
+
+	fn accept(&mut self, difficulty: U256, now: Instant) {
+		self.accepted_shares += 1;
+		self.recent_shares.push_back((now, difficulty));
+		self.prune(now);
+	}
+
+	fn prune(&mut self, now: Instant) {
+		while self.recent_shares.front().map_or(false, |&(time, _)| now.duration_since(time) > HASHRATE_WINDOW) {
+			self.recent_shares.pop_front();
+		}
+	}
+
+	/// Hashrate estimated from shares accepted within the last `HASHRATE_WINDOW`.
+	///
+	/// Every accepted share of difficulty `d` is expected to take `d` hashes to find.
+	fn hashrate_at(&self, now: Instant) -> U256 {
+		let elapsed = ::std::cmp::min(now.duration_since(self.authorized_at), HASHRATE_WINDOW).as_secs();
+		let work = self.recent_shares.iter()
+			.filter(|&&(time, _)| now.duration_since(time) <= HASHRATE_WINDOW)
+			.fold(U256::zero(), |acc, &(_, difficulty)| acc.saturating_add(difficulty));
+
+		work / U256::from(::std::cmp::max(elapsed, 1))
+	}
+
+	/// Current statistics of the worker.
+	pub fn stats(&self, addr: SocketAddr) -> WorkerStats {
+		WorkerStats {
+			id: self.id.clone(),
+			addr,
+			difficulty: self.difficulty,
+			accepted_shares: self.accepted_shares,
+			blocks: self.blocks,
+			stale_shares: self.stale_shares,
+			invalid_shares: self.invalid_shares,
+			hashrate: self.hashrate_at(Instant::now()),
+			last_share: self.last_share,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+	use ethereum_types::U256;
+	use traits::Share;
+	use super::{Worker, HASHRATE_WINDOW};
+
+	#[test]
+	fn should_count_shares() {
+		let mut worker = Worker::new("miner1".into(), Some(1000.into()));
+
+		worker.record(&Share::Accepted(1000.into()));
+		worker.record(&Share::Block(1000.into()));
+		worker.record(&Share::Stale);
+		worker.record(&Share::Invalid);
+		worker.record(&Share::Invalid);
+
+		let stats = worker.stats("127.0.0.1:1234".parse().unwrap());
+		assert_eq!(stats.accepted_shares, 2);
+		assert_eq!(stats.blocks, 1);
+		assert_eq!(stats.stale_shares, 1);
+		assert_eq!(stats.invalid_shares, 2);
+		assert!(stats.last_share.is_some());
+	}
+
+	#[test]
+	fn should_estimate_hashrate_from_recent_shares() {
+		let mut worker = Worker::new("miner1".into(), Some(1000.into()));
+		let start = worker.authorized_at;
+
+		worker.record_at(&Share::Accepted(1000.into()), start + Duration::from_secs(5));
+		worker.record_at(&Share::Accepted(1000.into()), start + Duration::from_secs(10));
+		assert_eq!(worker.hashrate_at(start + Duration::from_secs(10)), U256::from(200));
+
+		// shares older than the window are not taken into account
+		let later = start + HASHRATE_WINDOW + Duration::from_secs(6);
+		worker.record_at(&Share::Accepted(6000.into()), later);
+		assert_eq!(worker.hashrate_at(later), U256::from(7000 / HASHRATE_WINDOW.as_secs()));
+		assert_eq!(worker.recent_shares.len(), 2);
+	}
+
+	#[test]
+	fn should_not_divide_by_zero() {
+		let worker = Worker::new("miner1".into(), None);
+		let now = Instant::now();
+		assert_eq!(worker.hashrate_at(::std::cmp::max(now, worker.authorized_at)), U256::zero());
+	}
+}
@@ -34,11 +34,11 @@ use std::net::{SocketAddr, AddrParseError};
 use std::str::FromStr;
 use parking_lot::RwLock;
 use chain::{ETH_PROTOCOL_VERSION_63, ETH_PROTOCOL_VERSION_62,
-	PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3};
+	PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4};
 use light::client::AsLightClient;
 use light::Provider;
 use light::net::{self as light_net, LightProtocol, Params as LightParams, Capabilities, Handler as LightHandler, EventContext};
-use network::IpFilter;
+use network::{IpFilter, NodeReputation};
 use private_tx::PrivateTxHandler;
 use transaction::UnverifiedTransaction;
 
@@ -103,6 +103,8 @@ pub struct SyncConfig {
 	pub warp_sync: WarpSync,
 	/// Enable light client server.
 	pub serve_light: bool,
+	/// Enable state sync by downloading the state trie of a recent block.
+	pub state_sync: bool,
 }
 
 impl Default for SyncConfig {
@@ -116,6 +118,30 @@ impl Default for SyncConfig {
 			fork_block: None,
 			warp_sync: WarpSync::Disabled,
 			serve_light: false,
+			state_sync: false,
+		}
+	}
+}
+
+/// Light client serving configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightServeConfig {
+	/// The maximum number of light clients to serve at once, not counting
+	/// priority clients. Each is given an equal share of serving capacity.
+	pub max_peers: Option<usize>,
+	/// How many request credits each light client recharges per second.
+	pub recharge_rate: u64,
+	/// Node IDs of light clients to serve without charging request credits,
+	/// even when the limit of served clients has been reached.
+	pub priority_peers: Vec<H512>,
+}
+
+impl Default for LightServeConfig {
+	fn default() -> Self {
+		LightServeConfig {
+			max_peers: None,
+			recharge_rate: light_net::request_credits::DEFAULT_RECHARGE_RATE,
+			priority_peers: Vec::new(),
 		}
 	}
 }
@@ -234,6 +260,8 @@ pub struct Params {
 	pub provider: Arc<::light::Provider>,
 	/// Network layer configuration.
 	pub network_config: NetworkConfiguration,
+	/// Light client serving configuration.
+	pub light_serve_config: LightServeConfig,
 	/// Other protocols to attach.
 	pub attached_protos: Vec<AttachedProtocol>,
 }
@@ -281,8 +309,17 @@ impl EthSync {
 					sample_store: sample_store,
 				};
 
-				let max_peers = ::std::cmp::min(params.network_config.max_peers, 1);
-				light_params.config.load_share = MAX_LIGHTSERV_LOAD / max_peers as f64;
+				let light_serve_config = params.light_serve_config;
+				light_params.config.load_share = match light_serve_config.max_peers {
+					Some(max_peers) => MAX_LIGHTSERV_LOAD / ::std::cmp::max(max_peers, 1) as f64,
+					None => {
+						let max_peers = ::std::cmp::max(params.network_config.max_peers, 1);
+						MAX_LIGHTSERV_LOAD / max_peers as f64
+					}
+				};
+				light_params.config.max_client_peers = light_serve_config.max_peers;
+				light_params.config.recharge_rate = light_serve_config.recharge_rate;
+				light_params.config.priority_peers = light_serve_config.priority_peers.into_iter().collect();
 
 				let mut light_proto = LightProtocol::new(params.provider, light_params);
 				light_proto.add_handler(Arc::new(TxRelay(params.chain.clone())));
@@ -469,7 +506,7 @@ impl ChainNotify for EthSync {
 		self.network.register_protocol(self.eth_handler.clone(), self.subprotocol_name, &[ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63])
 			.unwrap_or_else(|e| warn!("Error registering ethereum protocol: {:?}", e));
 		// register the warp sync subprotocol
-		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3])
+		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4])
 			.unwrap_or_else(|e| warn!("Error registering snapshot sync protocol: {:?}", e));
 
 		// register the light protocol.
@@ -533,6 +570,8 @@ pub trait ManageNetwork : Send + Sync {
 	fn network_config(&self) -> NetworkConfiguration;
 	/// Get network context for protocol.
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext));
+	/// Get reputations of all known nodes, best first.
+	fn node_reputations(&self) -> Vec<NodeReputation>;
 }
 
 
@@ -577,6 +616,10 @@ impl ManageNetwork for EthSync {
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
+
+	fn node_reputations(&self) -> Vec<NodeReputation> {
+		self.network.node_reputations()
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -854,6 +897,10 @@ impl ManageNetwork for LightSync {
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
+
+	fn node_reputations(&self) -> Vec<NodeReputation> {
+		self.network.node_reputations()
+	}
 }
 
 impl LightSyncProvider for LightSync {
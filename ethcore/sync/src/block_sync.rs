@@ -22,6 +22,7 @@ use std::collections::{HashSet, VecDeque};
 use std::cmp;
 use heapsize::HeapSizeOf;
 use ethereum_types::H256;
+use network::PeerId;
 use rlp::Rlp;
 use ethcore::views::BlockView;
 use ethcore::header::{BlockNumber, Header as BlockHeader};
@@ -35,6 +36,7 @@ const MAX_HEADERS_TO_REQUEST: usize = 128;
 const MAX_BODIES_TO_REQUEST: usize = 32;
 const MAX_RECEPITS_TO_REQUEST: usize = 128;
 const SUBCHAIN_SIZE: u64 = 256;
+const SKELETON_SIZE: u64 = 384;
 const MAX_ROUND_PARENTS: usize = 16;
 const MAX_PARALLEL_SUBCHAIN_DOWNLOAD: usize = 5;
 
@@ -110,6 +112,12 @@ pub struct BlockDownloader {
 	retract_step: u64,
 	/// Whether reorg should be limited.
 	limit_reorg: bool,
+	/// Whether subchain heads are requested as a skeleton from a single peer.
+	skeleton: bool,
+	/// Peer the skeleton is currently requested from.
+	skeleton_peer: Option<PeerId>,
+	/// Peer which failed to serve the last skeleton request and is not asked again on retry.
+	failed_skeleton_peer: Option<PeerId>,
 }
 
 impl BlockDownloader {
@@ -130,6 +138,9 @@ impl BlockDownloader {
 			target_hash: None,
 			retract_step: 1,
 			limit_reorg: true,
+			skeleton: false,
+			skeleton_peer: None,
+			failed_skeleton_peer: None,
 		}
 	}
 
@@ -149,13 +160,26 @@ impl BlockDownloader {
 			target_hash: None,
 			retract_step: 1,
 			limit_reorg: false,
+			skeleton: false,
+			skeleton_peer: None,
+			failed_skeleton_peer: None,
 		}
 	}
 
+	/// Create a new instance of sync for ancient blocks. Subchain heads are requested
+	/// as a single skeleton from one peer, while the gaps, bodies and receipts are
+	/// filled in parallel by all the other peers.
+	pub fn with_skeleton(start_hash: &H256, start_number: BlockNumber) -> Self {
+		let mut downloader = BlockDownloader::with_unlimited_reorg(true, start_hash, start_number);
+		downloader.skeleton = true;
+		downloader
+	}
+
 	/// Reset sync. Clear all local downloaded data.
 	pub fn reset(&mut self) {
 		self.blocks.clear();
 		self.state = State::Idle;
+		self.skeleton_peer = None;
 	}
 
 	/// Mark a block as known in the chain
@@ -186,6 +210,10 @@ impl BlockDownloader {
 
 	/// Unmark header as being downloaded.
 	pub fn clear_header_download(&mut self, hash: &H256) {
+		// The skeleton peer is considered failed until its response turns out to be useful.
+		if self.state == State::ChainHead && hash == &self.last_imported_hash && self.skeleton_peer.is_some() {
+			self.failed_skeleton_peer = self.skeleton_peer.take();
+		}
 		self.blocks.clear_header_download(hash)
 	}
 
@@ -284,6 +312,7 @@ impl BlockDownloader {
 		match self.state {
 			State::ChainHead => {
 				if !headers.is_empty() {
+					self.failed_skeleton_peer = None;
 					// TODO: validate heads better. E.g. check that there is enough distance between blocks.
 					trace!(target: "sync", "Received {} subchain heads, proceeding to download", headers.len());
 					self.blocks.reset_to(hashes);
@@ -415,16 +444,29 @@ impl BlockDownloader {
 	}
 
 	/// Find some headers or blocks to download for a peer.
-	pub fn request_blocks(&mut self, io: &mut SyncIo, num_active_peers: usize) -> Option<BlockRequest> {
+	pub fn request_blocks(&mut self, peer_id: PeerId, io: &mut SyncIo, num_active_peers: usize) -> Option<BlockRequest> {
 		match self.state {
 			State::Idle => {
 				self.start_sync_round(io);
 				if self.state == State::ChainHead {
-					return self.request_blocks(io, num_active_peers);
+					return self.request_blocks(peer_id, io, num_active_peers);
 				}
 			},
 			State::ChainHead => {
-				if num_active_peers < MAX_PARALLEL_SUBCHAIN_DOWNLOAD {
+				if self.skeleton {
+					// The skeleton is requested from a single peer, the caller is expected to
+					// offer the fastest peers first. After a timeout or an empty response
+					// it is requested again from a different peer.
+					if self.skeleton_peer.is_none() && self.failed_skeleton_peer != Some(peer_id) {
+						trace!(target: "sync", "Requesting headers skeleton from {} from peer {}", self.last_imported_block, peer_id);
+						self.skeleton_peer = Some(peer_id);
+						return Some(BlockRequest::Headers {
+							start: self.last_imported_hash.clone(),
+							count: SKELETON_SIZE,
+							skip: (MAX_HEADERS_TO_REQUEST - 2) as u64,
+						});
+					}
+				} else if num_active_peers < MAX_PARALLEL_SUBCHAIN_DOWNLOAD {
 					// Request subchain headers
 					trace!(target: "sync", "Starting sync with better chain");
 					// Request MAX_HEADERS_TO_REQUEST - 2 headers apart so that
@@ -559,4 +601,59 @@ impl BlockDownloader {
 	}
 }
 
-//TODO: module tests
+#[cfg(test)]
+mod tests {
+	use std::collections::VecDeque;
+	use parking_lot::RwLock;
+	use ethcore::client::{BlockChainClient, EachBlockWith, TestBlockChainClient};
+	use tests::helpers::TestIo;
+	use tests::snapshot::TestSnapshotService;
+	use super::*;
+
+	fn subchain_heads_request(request: Option<BlockRequest>) -> (H256, u64) {
+		match request {
+			Some(BlockRequest::Headers { start, count, .. }) => (start, count),
+			_ => panic!("Expected a headers request"),
+		}
+	}
+
+	#[test]
+	fn requests_skeleton_from_single_peer() {
+		let client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Nothing);
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&client, &ss, &queue, None);
+		let start = client.block_hash(BlockId::Number(5)).unwrap();
+		let mut downloader = BlockDownloader::with_skeleton(&start, 5);
+
+		assert_eq!(subchain_heads_request(downloader.request_blocks(1, &mut io, 0)), (start, SKELETON_SIZE));
+		assert!(downloader.request_blocks(2, &mut io, 1).is_none());
+
+		// the skeleton peer failed to respond, a different one should be asked
+		downloader.clear_header_download(&start);
+		assert!(downloader.request_blocks(1, &mut io, 0).is_none());
+		assert_eq!(subchain_heads_request(downloader.request_blocks(2, &mut io, 0)), (start, SKELETON_SIZE));
+
+		// the new skeleton peer replied with no headers, the first one may be asked again
+		downloader.clear_header_download(&start);
+		assert!(downloader.import_headers(&mut io, &Rlp::new(&::rlp::EMPTY_LIST_RLP), Some(start)).is_ok());
+		assert!(downloader.request_blocks(2, &mut io, 0).is_none());
+		assert_eq!(subchain_heads_request(downloader.request_blocks(1, &mut io, 0)), (start, SKELETON_SIZE));
+	}
+
+	#[test]
+	fn requests_subchain_heads_from_multiple_peers() {
+		let client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Nothing);
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&client, &ss, &queue, None);
+		let start = client.block_hash(BlockId::Number(5)).unwrap();
+		let mut downloader = BlockDownloader::with_unlimited_reorg(true, &start, 5);
+
+		assert_eq!(subchain_heads_request(downloader.request_blocks(1, &mut io, 0)), (start, SUBCHAIN_SIZE));
+		assert_eq!(subchain_heads_request(downloader.request_blocks(2, &mut io, 1)), (start, SUBCHAIN_SIZE));
+		assert!(downloader.request_blocks(3, &mut io, MAX_PARALLEL_SUBCHAIN_DOWNLOAD).is_none());
+	}
+}
@@ -30,6 +30,9 @@ use std::cmp;
 use std::collections::HashSet;
 use std::time::Instant;
 use sync_io::SyncIo;
+use triehash::ordered_trie_root;
+
+use state_sync::StatePivot;
 
 use super::{
 	BlockSet,
@@ -44,12 +47,19 @@ use super::{
 	ETH_PROTOCOL_VERSION_63,
 	MAX_NEW_BLOCK_AGE,
 	MAX_NEW_HASHES,
+	MAX_POOLED_TRANSACTIONS_TO_REQUEST,
+	REPUTATION_INVALID_DATA,
+	STATE_SYNC_PIVOT_DISTANCE,
+	STATE_SYNC_THRESHOLD,
 	PAR_PROTOCOL_VERSION_1,
-	PAR_PROTOCOL_VERSION_3,
+	PAR_PROTOCOL_VERSION_4,
 	BLOCK_BODIES_PACKET,
 	BLOCK_HEADERS_PACKET,
 	NEW_BLOCK_HASHES_PACKET,
 	NEW_BLOCK_PACKET,
+	NEW_POOLED_TRANSACTION_HASHES_PACKET,
+	NODE_DATA_PACKET,
+	POOLED_TRANSACTIONS_PACKET,
 	PRIVATE_TRANSACTION_PACKET,
 	RECEIPTS_PACKET,
 	SIGNED_PRIVATE_TRANSACTION_PACKET,
@@ -73,11 +83,14 @@ impl SyncHandler {
 		let result = match packet_id {
 			STATUS_PACKET => SyncHandler::on_peer_status(sync, io, peer, &rlp),
 			TRANSACTIONS_PACKET => SyncHandler::on_peer_transactions(sync, io, peer, &rlp),
+			NEW_POOLED_TRANSACTION_HASHES_PACKET => SyncHandler::on_peer_new_pooled_transaction_hashes(sync, io, peer, &rlp),
+			POOLED_TRANSACTIONS_PACKET => SyncHandler::on_peer_pooled_transactions(sync, io, peer, &rlp),
 			BLOCK_HEADERS_PACKET => SyncHandler::on_peer_block_headers(sync, io, peer, &rlp),
 			BLOCK_BODIES_PACKET => SyncHandler::on_peer_block_bodies(sync, io, peer, &rlp),
 			RECEIPTS_PACKET => SyncHandler::on_peer_block_receipts(sync, io, peer, &rlp),
 			NEW_BLOCK_PACKET => SyncHandler::on_peer_new_block(sync, io, peer, &rlp),
 			NEW_BLOCK_HASHES_PACKET => SyncHandler::on_peer_new_hashes(sync, io, peer, &rlp),
+			NODE_DATA_PACKET => SyncHandler::on_peer_node_data(sync, io, peer, &rlp),
 			SNAPSHOT_MANIFEST_PACKET => SyncHandler::on_snapshot_manifest(sync, io, peer, &rlp),
 			SNAPSHOT_DATA_PACKET => SyncHandler::on_snapshot_data(sync, io, peer, &rlp),
 			PRIVATE_TRANSACTION_PACKET => SyncHandler::on_private_transaction(sync, io, peer, &rlp),
@@ -108,6 +121,7 @@ impl SyncHandler {
 			sync.clear_peer_download(peer_id);
 			sync.peers.remove(&peer_id);
 			sync.active_peers.remove(&peer_id);
+			sync.pooled_transactions_requested.retain(|_, &mut (asked, _)| asked != peer_id);
 
 			if sync.state == SyncState::SnapshotManifest {
 				// Check if we are asking other peers for
@@ -118,7 +132,7 @@ impl SyncHandler {
 					.next().is_none();
 
 				if still_asking_manifest {
-					sync.state = ChainSync::get_init_state(sync.warp_sync, io.chain());
+					sync.state = ChainSync::get_init_state(sync.warp_sync, sync.state_sync_enabled, io.chain());
 				}
 			}
 			sync.continue_sync(io);
@@ -280,6 +294,9 @@ impl SyncHandler {
 
 	/// Called by peer once it has new block bodies
 	fn on_peer_block_bodies(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		if sync.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::StatePivotBody) {
+			return SyncHandler::on_state_pivot_body(sync, io, peer_id, r);
+		}
 		sync.clear_peer_download(peer_id);
 		let block_set = sync.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
 		if !sync.reset_peer_asking(peer_id, PeerAsking::BlockBodies) {
@@ -314,6 +331,7 @@ impl SyncHandler {
 
 			match result {
 				Err(DownloaderImportError::Invalid) => {
+					sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
 					io.disable_peer(peer_id);
 					sync.deactivate_peer(io, peer_id);
 					sync.continue_sync(io);
@@ -322,7 +340,9 @@ impl SyncHandler {
 				Err(DownloaderImportError::Useless) => {
 					sync.deactivate_peer(io, peer_id);
 				},
-				Ok(()) => (),
+				Ok(()) => {
+					sync.note_useful_response(io, peer_id);
+				},
 			}
 
 			sync.collect_blocks(io, block_set);
@@ -375,6 +395,9 @@ impl SyncHandler {
 		if is_fork_header_request {
 			return SyncHandler::on_peer_fork_header(sync, io, peer_id, r);
 		}
+		if sync.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::StatePivotHeaders) {
+			return SyncHandler::on_state_pivot_headers(sync, io, peer_id, r);
+		}
 
 		sync.clear_peer_download(peer_id);
 		let expected_hash = sync.peers.get(&peer_id).and_then(|p| p.asking_hash);
@@ -420,6 +443,7 @@ impl SyncHandler {
 				sync.deactivate_peer(io, peer_id);
 			},
 			Err(DownloaderImportError::Invalid) => {
+				sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
 				io.disable_peer(peer_id);
 				sync.deactivate_peer(io, peer_id);
 				sync.continue_sync(io);
@@ -431,9 +455,13 @@ impl SyncHandler {
 				for (_, ref mut p) in sync.peers.iter_mut().filter(|&(_, ref p)| p.block_set == Some(block_set)) {
 					p.reset_asking();
 				}
-
+				sync.note_useful_response(io, peer_id);
 			}
-			Ok(DownloadAction::None) => {},
+			Ok(DownloadAction::None) => {
+				if item_count != 0 {
+					sync.note_useful_response(io, peer_id);
+				}
+			},
 		}
 
 		sync.collect_blocks(io, block_set);
@@ -446,6 +474,9 @@ impl SyncHandler {
 
 	/// Called by peer once it has new block receipts
 	fn on_peer_block_receipts(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		if sync.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::StatePivotReceipts) {
+			return SyncHandler::on_state_pivot_receipts(sync, io, peer_id, r);
+		}
 		sync.clear_peer_download(peer_id);
 		let block_set = sync.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
 		if !sync.reset_peer_asking(peer_id, PeerAsking::BlockReceipts) {
@@ -480,6 +511,7 @@ impl SyncHandler {
 
 			match result {
 				Err(DownloaderImportError::Invalid) => {
+					sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
 					io.disable_peer(peer_id);
 					sync.deactivate_peer(io, peer_id);
 					sync.continue_sync(io);
@@ -488,7 +520,9 @@ impl SyncHandler {
 				Err(DownloaderImportError::Useless) => {
 					sync.deactivate_peer(io, peer_id);
 				},
-				Ok(()) => (),
+				Ok(()) => {
+					sync.note_useful_response(io, peer_id);
+				},
 			}
 
 			sync.collect_blocks(io, block_set);
@@ -498,6 +532,186 @@ impl SyncHandler {
 		Ok(())
 	}
 
+	/// Called when the headers from the peer head down to the state sync pivot are downloaded from a peer.
+	fn on_state_pivot_headers(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		let expected_hash = sync.peers.get(&peer_id).and_then(|p| p.asking_hash);
+		sync.reset_peer_asking(peer_id, PeerAsking::StatePivotHeaders);
+		if let Some(ref mut peer) = sync.peers.get_mut(&peer_id) {
+			peer.asking_hash = None;
+		}
+		if sync.state != SyncState::StatePivot || sync.state_pivot.is_some() {
+			trace!(target: "sync", "{}: Ignored unexpected pivot headers", peer_id);
+			sync.continue_sync(io);
+			return Ok(());
+		}
+
+		// headers are sent from the peer head down to the pivot.
+		let mut headers: Vec<BlockHeader> = r.as_list()?;
+		headers.reverse();
+		let target = sync.state_pivot_target.take();
+		let is_valid = match (headers.last(), target) {
+			(Some(head), Some((hash, _))) => headers.len() as BlockNumber == STATE_SYNC_PIVOT_DISTANCE + 1
+				&& Some(head.hash()) == expected_hash
+				&& head.hash() == hash,
+			_ => false,
+		};
+		let verified = is_valid && match io.chain().verify_header_chain(&headers) {
+			Ok(()) => true,
+			Err(e) => {
+				debug!(target: "sync", "{}: Pivot headers verification failed: {}", peer_id, e);
+				false
+			},
+		};
+		if !verified {
+			trace!(target: "sync", "{}: Invalid pivot headers", peer_id);
+			io.disable_peer(peer_id);
+			sync.state = SyncState::WaitingPeers;
+			sync.continue_sync(io);
+			return Ok(());
+		}
+		// the peer advertised the total difficulty of its head.
+		let head_total_difficulty = target.expect("target is checked above; qed").1;
+		let total_difficulty = headers[1..].iter().fold(head_total_difficulty, |td, h| td.saturating_sub(*h.difficulty()));
+		let head_number = headers[headers.len() - 1].number();
+		let header = headers.swap_remove(0);
+
+		let best_block = io.chain().chain_info().best_block_number;
+		if header.number() <= best_block + STATE_SYNC_THRESHOLD {
+			trace!(target: "sync", "Pivot #{} is too close to our best block #{}, skipping state sync", header.number(), best_block);
+			sync.disable_state_sync(io);
+			return Ok(());
+		}
+		trace!(target: "sync", "{}: Got state sync pivot #{} {}", peer_id, header.number(), header.hash());
+		if head_number > sync.highest_block.unwrap_or(0) {
+			sync.highest_block = Some(head_number);
+		}
+		sync.state_pivot = Some(StatePivot::new(header, total_difficulty));
+		sync.sync_peer(io, peer_id, false);
+		sync.continue_sync(io);
+		Ok(())
+	}
+
+	/// Called when the state sync pivot body is downloaded from a peer.
+	fn on_state_pivot_body(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		sync.reset_peer_asking(peer_id, PeerAsking::StatePivotBody);
+		let is_valid = match sync.state_pivot {
+			Some(ref pivot) if sync.state == SyncState::StatePivot && pivot.body.is_none() => {
+				if r.item_count()? != 1 {
+					Some(false)
+				} else {
+					let body = r.at(0)?;
+					let transactions_root = ordered_trie_root(body.at(0)?.iter().map(|r| r.as_raw()));
+					let uncles_hash = keccak(body.at(1)?.as_raw());
+					Some(&transactions_root == pivot.header.transactions_root() && &uncles_hash == pivot.header.uncles_hash())
+				}
+			},
+			_ => None,
+		};
+		let is_valid = match is_valid {
+			Some(is_valid) => is_valid,
+			None => {
+				trace!(target: "sync", "{}: Ignored unexpected pivot body", peer_id);
+				sync.continue_sync(io);
+				return Ok(());
+			}
+		};
+
+		if !is_valid {
+			trace!(target: "sync", "{}: Invalid pivot body", peer_id);
+			sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
+			io.disable_peer(peer_id);
+		} else if let Some(ref mut pivot) = sync.state_pivot {
+			pivot.body = Some(r.at(0)?.as_raw().to_vec());
+		}
+		sync.sync_peer(io, peer_id, false);
+		sync.continue_sync(io);
+		Ok(())
+	}
+
+	/// Called when the state sync pivot receipts are downloaded from a peer.
+	fn on_state_pivot_receipts(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		sync.reset_peer_asking(peer_id, PeerAsking::StatePivotReceipts);
+		let is_valid = match sync.state_pivot {
+			Some(ref pivot) if sync.state == SyncState::StatePivot && pivot.receipts.is_none() => {
+				if r.item_count()? != 1 {
+					Some(false)
+				} else {
+					let receipts_root = ordered_trie_root(r.at(0)?.iter().map(|r| r.as_raw()));
+					Some(&receipts_root == pivot.header.receipts_root())
+				}
+			},
+			_ => None,
+		};
+		let is_valid = match is_valid {
+			Some(is_valid) => is_valid,
+			None => {
+				trace!(target: "sync", "{}: Ignored unexpected pivot receipts", peer_id);
+				sync.continue_sync(io);
+				return Ok(());
+			}
+		};
+
+		if !is_valid {
+			trace!(target: "sync", "{}: Invalid pivot receipts", peer_id);
+			sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
+			io.disable_peer(peer_id);
+		} else if let Some(ref mut pivot) = sync.state_pivot {
+			pivot.receipts = Some(r.at(0)?.as_raw().to_vec());
+		}
+
+		if sync.state_pivot.as_ref().map_or(false, |p| p.body.is_some() && p.receipts.is_some()) {
+			sync.start_state_download(io);
+		} else {
+			sync.sync_peer(io, peer_id, false);
+			sync.continue_sync(io);
+		}
+		Ok(())
+	}
+
+	/// Called when state trie nodes are downloaded from a peer.
+	fn on_peer_node_data(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		sync.clear_peer_download(peer_id);
+		if !sync.reset_peer_asking(peer_id, PeerAsking::NodeData) || sync.state != SyncState::StateNodes {
+			trace!(target: "sync", "{}: Ignored unexpected node data", peer_id);
+			sync.continue_sync(io);
+			return Ok(());
+		}
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{} -> NodeData ({} entries)", peer_id, item_count);
+		if item_count == 0 {
+			// The peer has likely pruned the pivot state already.
+			sync.state_sync_empty_responses += 1;
+			sync.deactivate_peer(io, peer_id);
+		} else {
+			let nodes = r.iter().map(|n| n.as_val()).collect::<Result<Vec<Bytes>, _>>()?;
+			let result = {
+				let chain = io.chain();
+				sync.state_sync.import_nodes(&nodes, |hash, account| chain.has_state_node(hash, account))
+			};
+			match result {
+				Err(e) => {
+					trace!(target: "sync", "{}: Invalid node data: {}", peer_id, e);
+					sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
+					io.disable_peer(peer_id);
+					sync.deactivate_peer(io, peer_id);
+				},
+				Ok(0) => {
+					trace!(target: "sync", "{}: Useless node data", peer_id);
+					sync.deactivate_peer(io, peer_id);
+				},
+				Ok(_) => {
+					sync.state_sync_empty_responses = 0;
+					sync.note_useful_response(io, peer_id);
+				},
+			}
+			sync.import_completed_state_nodes(io);
+		}
+		sync.check_state_pivot(io);
+		sync.sync_peer(io, peer_id, false);
+		sync.continue_sync(io);
+		Ok(())
+	}
+
 	/// Called when snapshot manifest is downloaded from a peer.
 	fn on_snapshot_manifest(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
 		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -515,6 +729,7 @@ impl SyncHandler {
 		let manifest = match ManifestData::from_rlp(manifest_rlp.as_raw()) {
 			Err(e) => {
 				trace!(target: "sync", "{}: Ignored bad manifest: {:?}", peer_id, e);
+				sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
 				io.disable_peer(peer_id);
 				sync.continue_sync(io);
 				return Ok(());
@@ -586,13 +801,16 @@ impl SyncHandler {
 			Ok(ChunkType::Block(hash)) => {
 				trace!(target: "sync", "{}: Processing block chunk", peer_id);
 				io.snapshot_service().restore_block_chunk(hash, snapshot_data);
+				sync.note_useful_response(io, peer_id);
 			}
 			Ok(ChunkType::State(hash)) => {
 				trace!(target: "sync", "{}: Processing state chunk", peer_id);
 				io.snapshot_service().restore_state_chunk(hash, snapshot_data);
+				sync.note_useful_response(io, peer_id);
 			}
 			Err(()) => {
 				trace!(target: "sync", "{}: Got bad snapshot chunk", peer_id);
+				sync.note_peer_reputation(io, peer_id, REPUTATION_INVALID_DATA);
 				io.disconnect_peer(peer_id);
 				sync.continue_sync(io);
 				return Ok(());
@@ -632,6 +850,8 @@ impl SyncHandler {
 			snapshot_hash: if warp_protocol { Some(r.val_at(5)?) } else { None },
 			snapshot_number: if warp_protocol { Some(r.val_at(6)?) } else { None },
 			block_set: None,
+			latency: None,
+			reputation: io.peer_reputation(peer_id),
 		};
 
 		trace!(target: "sync", "New peer {} (protocol: {}, network: {:?}, difficulty: {:?}, latest:{}, genesis:{}, snapshot:{:?})",
@@ -658,7 +878,7 @@ impl SyncHandler {
 		}
 
 		if false
-			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_3.0))
+			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_4.0))
 			|| (!warp_protocol && (peer.protocol_version < ETH_PROTOCOL_VERSION_62.0 || peer.protocol_version > ETH_PROTOCOL_VERSION_63.0))
 		{
 			io.disable_peer(peer_id);
@@ -714,6 +934,74 @@ impl SyncHandler {
 		Ok(())
 	}
 
+	/// Called when peer announces hashes of transactions from its pool
+	fn on_peer_new_pooled_transaction_hashes(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		// Fetch transactions only when fully synced
+		if !io.is_chain_queue_empty() || (sync.state != SyncState::Idle && sync.state != SyncState::NewBlocks) {
+			trace!(target: "sync", "{} Ignoring transaction hashes while syncing", peer_id);
+			return Ok(());
+		}
+		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
+			trace!(target: "sync", "{} Ignoring transaction hashes from unconfirmed/unknown peer", peer_id);
+			return Ok(());
+		}
+
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{:02} -> NewPooledTransactionHashes ({} entries)", peer_id, item_count);
+		let mut hashes = Vec::with_capacity(item_count);
+		for i in 0 .. item_count {
+			hashes.push(r.val_at::<H256>(i)?);
+		}
+		if let Some(peer) = sync.peers.get_mut(&peer_id) {
+			// The peer has these already, never announce or send them back
+			peer.last_sent_transactions.extend(hashes.iter().cloned());
+		}
+
+		// Skip transactions we have or are already fetching from another peer
+		let mut unknown = HashSet::new();
+		for hash in hashes {
+			if unknown.len() >= MAX_POOLED_TRANSACTIONS_TO_REQUEST {
+				break;
+			}
+			if sync.pooled_transactions_requested.contains_key(&hash) || io.chain().pooled_transaction(&hash).is_some() {
+				continue;
+			}
+			unknown.insert(hash);
+		}
+		if !unknown.is_empty() {
+			let unknown: Vec<_> = unknown.into_iter().collect();
+			SyncRequester::request_pooled_transactions(sync, io, peer_id, &unknown);
+		}
+		Ok(())
+	}
+
+	/// Called when peer sends us transactions requested by hash
+	fn on_peer_pooled_transactions(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		// Accept transactions only when fully synced
+		if !io.is_chain_queue_empty() || (sync.state != SyncState::Idle && sync.state != SyncState::NewBlocks) {
+			trace!(target: "sync", "{} Ignoring pooled transactions while syncing", peer_id);
+			return Ok(());
+		}
+		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
+			trace!(target: "sync", "{} Ignoring transactions from unconfirmed/unknown peer", peer_id);
+			return Ok(());
+		}
+
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{:02} -> PooledTransactions ({} entries)", peer_id, item_count);
+		let mut transactions = Vec::with_capacity(item_count);
+		for i in 0 .. item_count {
+			let tx = r.at(i)?.as_raw().to_vec();
+			let hash = keccak(&tx);
+			if sync.pooled_transactions_requested.get(&hash).map_or(false, |&(asked, _)| asked == peer_id) {
+				sync.pooled_transactions_requested.remove(&hash);
+			}
+			transactions.push(tx);
+		}
+		io.chain().queue_transactions(transactions, peer_id);
+		Ok(())
+	}
+
 	/// Called when peer sends us signed private transaction packet
 	fn on_signed_private_transaction(sync: &ChainSync, _io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
 		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -748,17 +1036,19 @@ impl SyncHandler {
 mod tests {
 	use ethcore::client::{ChainInfo, EachBlockWith, TestBlockChainClient};
 	use parking_lot::RwLock;
-	use rlp::{Rlp};
+	use rlp::{Rlp, RlpStream};
 	use std::collections::{VecDeque};
 	use tests::helpers::{TestIo};
 	use tests::snapshot::TestSnapshotService;
 
 	use super::*;
+	use super::super::GET_POOLED_TRANSACTIONS_PACKET;
 	use super::super::tests::{
 		dummy_sync_with_peer,
 		get_dummy_block,
 		get_dummy_blocks,
 		get_dummy_hashes,
+		insert_dummy_peer,
 	};
 
 	#[test]
@@ -850,4 +1140,29 @@ mod tests {
 
 		assert!(result.is_ok());
 	}
+
+	#[test]
+	fn requests_announced_transactions_once() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(5), &client);
+		insert_dummy_peer(&mut sync, 1, client.block_hash_delta_minus(5));
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+
+		let mut hashes = RlpStream::new_list(2);
+		hashes.append(&H256::from(1u64));
+		hashes.append(&H256::from(1u64));
+		let hashes_data = hashes.out();
+
+		SyncHandler::on_peer_new_pooled_transaction_hashes(&mut sync, &mut io, 0, &Rlp::new(&hashes_data)).unwrap();
+		SyncHandler::on_peer_new_pooled_transaction_hashes(&mut sync, &mut io, 1, &Rlp::new(&hashes_data)).unwrap();
+
+		// the transaction is requested only once, from the first peer announcing it
+		assert_eq!(1, io.packets.len());
+		assert_eq!(GET_POOLED_TRANSACTIONS_PACKET, io.packets[0].packet_id);
+		assert_eq!(0, io.packets[0].recipient);
+		assert_eq!(Ok(1), Rlp::new(&io.packets[0].data).item_count());
+	}
 }
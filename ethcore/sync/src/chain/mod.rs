@@ -86,6 +86,16 @@
 //! 	Set s to `ChainHead` and restart.
 //!
 //! All other messages are ignored.
+//!
+//! Workflow for state sync.
+//! When enabled on an empty database, the block `STATE_SYNC_PIVOT_DISTANCE` below the best peer's head
+//! is picked as a pivot. The headers from the head down to the pivot are downloaded and their seals and
+//! links verified, then the pivot body and receipts are downloaded and verified, and the state trie of the pivot
+//! is downloaded node by node with `GetNodeData` (see `state_sync` module). If the chain moves too far
+//! ahead of the pivot, or peers stop serving its state, a new pivot is picked and the download resumes
+//! from the new state root, skipping the subtries which are already in the database. Once the state is
+//! complete the pivot is imported as the best block and the sync continues with the new blocks,
+//! while the ancient blocks are downloaded in the background.
 
 mod handler;
 mod propagator;
@@ -112,6 +122,7 @@ use super::{WarpSync, SyncConfig};
 use block_sync::{BlockDownloader, BlockDownloaderImportError as DownloaderImportError};
 use rand::Rng;
 use snapshot::{Snapshot};
+use state_sync::{StateSync, StatePivot};
 use api::{EthProtocolInfo as PeerInfoDigest, WARP_SYNC_PROTOCOL_ID};
 use private_tx::PrivateTxHandler;
 use transactions_stats::{TransactionsStats, Stats as TransactionStats};
@@ -136,6 +147,8 @@ pub const PAR_PROTOCOL_VERSION_1: (u8, u8) = (1, 0x15);
 pub const PAR_PROTOCOL_VERSION_2: (u8, u8) = (2, 0x16);
 /// 3 version of Parity protocol (private transactions messages added).
 pub const PAR_PROTOCOL_VERSION_3: (u8, u8) = (3, 0x18);
+/// 4 version of Parity protocol (pooled transaction announcements added).
+pub const PAR_PROTOCOL_VERSION_4: (u8, u8) = (4, 0x1b);
 
 pub const MAX_BODIES_TO_SEND: usize = 256;
 pub const MAX_HEADERS_TO_SEND: usize = 512;
@@ -155,9 +168,35 @@ const MAX_TRANSACTION_PACKET_SIZE: usize = 8 * 1024 * 1024;
 const MAX_TRANSACTIONS_TO_QUERY: usize = 4096;
 // Maximal number of transactions in sent in single packet.
 const MAX_TRANSACTIONS_TO_PROPAGATE: usize = 64;
+// Maximal number of transaction hashes announced in single packet.
+const MAX_TRANSACTION_HASHES_TO_ANNOUNCE: usize = 4096;
+// Maximal number of pooled transactions requested from a peer at once.
+const MAX_POOLED_TRANSACTIONS_TO_REQUEST: usize = 256;
+const MAX_POOLED_TRANSACTIONS_TO_SEND: usize = 256;
 // Min number of blocks to be behind for a snapshot sync
 const SNAPSHOT_RESTORE_THRESHOLD: BlockNumber = 30000;
 const SNAPSHOT_MIN_PEERS: usize = 3;
+// Min number of blocks to be behind for a state sync
+const STATE_SYNC_THRESHOLD: BlockNumber = 1024;
+const STATE_SYNC_MIN_PEERS: usize = 3;
+// Number of blocks between the best peer's head and the state sync pivot
+const STATE_SYNC_PIVOT_DISTANCE: BlockNumber = 64;
+// Max number of blocks the state sync pivot may fall behind the highest known block, past the distance above
+const STATE_SYNC_PIVOT_MAX_AGE: BlockNumber = 32;
+// Number of empty `NodeData` responses in a row after which the pivot is considered pruned by peers
+const STATE_SYNC_MAX_EMPTY_RESPONSES: usize = 8;
+const MAX_NODE_DATA_TO_REQUEST: usize = 384;
+// Peers responding this many times slower than the fastest one are not asked for old blocks
+const SLOW_PEER_LATENCY_FACTOR: u32 = 8;
+// Peers are never considered slow if responding faster than this
+const SLOW_PEER_MIN_LATENCY: Duration = Duration::from_secs(1);
+// Peer reputation adjustments
+const REPUTATION_USEFUL_DATA: i32 = 1;
+const REPUTATION_SLOW_RESPONSE: i32 = -2;
+const REPUTATION_TIMEOUT: i32 = -10;
+const REPUTATION_INVALID_DATA: i32 = -50;
+// Peers with lower reputation are not used for snapshot sync
+const MIN_SNAPSHOT_PEER_REPUTATION: i32 = -100;
 
 const STATUS_PACKET: u8 = 0x00;
 const NEW_BLOCK_HASHES_PACKET: u8 = 0x01;
@@ -180,6 +219,9 @@ pub const SNAPSHOT_DATA_PACKET: u8 = 0x14;
 pub const CONSENSUS_DATA_PACKET: u8 = 0x15;
 const PRIVATE_TRANSACTION_PACKET: u8 = 0x16;
 const SIGNED_PRIVATE_TRANSACTION_PACKET: u8 = 0x17;
+const NEW_POOLED_TRANSACTION_HASHES_PACKET: u8 = 0x18;
+const GET_POOLED_TRANSACTIONS_PACKET: u8 = 0x19;
+const POOLED_TRANSACTIONS_PACKET: u8 = 0x1a;
 
 const MAX_SNAPSHOT_CHUNKS_DOWNLOAD_AHEAD: usize = 3;
 
@@ -191,6 +233,8 @@ const RECEIPTS_TIMEOUT: Duration = Duration::from_secs(10);
 const FORK_HEADER_TIMEOUT: Duration = Duration::from_secs(3);
 const SNAPSHOT_MANIFEST_TIMEOUT: Duration = Duration::from_secs(5);
 const SNAPSHOT_DATA_TIMEOUT: Duration = Duration::from_secs(120);
+const NODE_DATA_TIMEOUT: Duration = Duration::from_secs(10);
+const POOLED_TRANSACTIONS_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 /// Sync state
@@ -211,6 +255,10 @@ pub enum SyncState {
 	Waiting,
 	/// Downloading blocks learned from `NewHashes` packet
 	NewBlocks,
+	/// Downloading the state sync pivot block
+	StatePivot,
+	/// Downloading state trie nodes
+	StateNodes,
 }
 
 /// Syncing status and statistics
@@ -244,6 +292,10 @@ pub struct SyncStatus {
 	pub snapshot_chunks_done: usize,
 	/// Last fully downloaded and imported ancient block number (if any).
 	pub last_imported_old_block_number: Option<BlockNumber>,
+	/// State trie nodes downloaded
+	pub state_nodes_done: usize,
+	/// State trie nodes known to be missing
+	pub state_nodes_pending: usize,
 }
 
 impl SyncStatus {
@@ -257,6 +309,14 @@ impl SyncStatus {
 		}
 	}
 
+	/// Indicates if state trie download is in progress
+	pub fn is_state_syncing(&self) -> bool {
+		match self.state {
+			SyncState::StatePivot | SyncState::StateNodes => true,
+			_ => false,
+		}
+	}
+
 	/// Returns max no of peers to display in informants
 	pub fn current_max_peers(&self, min_peers: u32, max_peers: u32) -> u32 {
 		if self.num_peers as u32 > min_peers {
@@ -284,6 +344,10 @@ pub enum PeerAsking {
 	BlockReceipts,
 	SnapshotManifest,
 	SnapshotData,
+	StatePivotHeaders,
+	StatePivotBody,
+	StatePivotReceipts,
+	NodeData,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -339,6 +403,10 @@ pub struct PeerInfo {
 	snapshot_number: Option<BlockNumber>,
 	/// Block set requested
 	block_set: Option<BlockSet>,
+	/// Average time it takes the peer to respond to a request
+	latency: Option<Duration>,
+	/// Peer reputation, persisted across sessions
+	reputation: i32,
 }
 
 impl PeerInfo {
@@ -412,6 +480,18 @@ pub struct ChainSync {
 	private_tx_handler: Arc<PrivateTxHandler>,
 	/// Enable warp sync.
 	warp_sync: WarpSync,
+	/// Enable state sync. Disabled once the state is downloaded.
+	state_sync_enabled: bool,
+	/// State trie downloader.
+	state_sync: StateSync,
+	/// State sync pivot block, once its header is known.
+	state_pivot: Option<StatePivot>,
+	/// Hash and total difficulty of the requested pivot header.
+	state_pivot_target: Option<(H256, U256)>,
+	/// Number of empty `NodeData` responses received in a row.
+	state_sync_empty_responses: usize,
+	/// Announced transactions requested from peers, with the peer asked and the request time.
+	pooled_transactions_requested: H256FastMap<(PeerId, Instant)>,
 }
 
 impl ChainSync {
@@ -419,7 +499,7 @@ impl ChainSync {
 	pub fn new(config: SyncConfig, chain: &BlockChainClient, private_tx_handler: Arc<PrivateTxHandler>) -> ChainSync {
 		let chain_info = chain.chain_info();
 		let best_block = chain.chain_info().best_block_number;
-		let state = ChainSync::get_init_state(config.warp_sync, chain);
+		let state = ChainSync::get_init_state(config.warp_sync, config.state_sync, chain);
 
 		let mut sync = ChainSync {
 			state,
@@ -439,16 +519,24 @@ impl ChainSync {
 			transactions_stats: TransactionsStats::default(),
 			private_tx_handler,
 			warp_sync: config.warp_sync,
+			state_sync_enabled: config.state_sync,
+			state_sync: StateSync::new(),
+			state_pivot: None,
+			state_pivot_target: None,
+			state_sync_empty_responses: 0,
+			pooled_transactions_requested: H256FastMap::default(),
 		};
 		sync.update_targets(chain);
 		sync
 	}
 
-	fn get_init_state(warp_sync: WarpSync, chain: &BlockChainClient) -> SyncState {
+	fn get_init_state(warp_sync: WarpSync, state_sync: bool, chain: &BlockChainClient) -> SyncState {
 		let best_block = chain.chain_info().best_block_number;
 		match warp_sync {
 			WarpSync::Enabled => SyncState::WaitingPeers,
 			WarpSync::OnlyAndAfter(block) if block > best_block => SyncState::WaitingPeers,
+			// State sync only starts from an empty database.
+			_ if state_sync && best_block == 0 => SyncState::WaitingPeers,
 			_ => SyncState::Idle,
 		}
 	}
@@ -470,6 +558,8 @@ impl ChainSync {
 			num_active_peers: self.peers.values().filter(|p| p.is_allowed() && p.asking != PeerAsking::Nothing).count(),
 			num_snapshot_chunks: self.snapshot.total_chunks(),
 			snapshot_chunks_done: self.snapshot.done_chunks(),
+			state_nodes_done: self.state_sync.done_nodes(),
+			state_nodes_pending: self.state_sync.pending_nodes(),
 			mem_used:
 				self.new_blocks.heap_size()
 				+ self.old_blocks.as_ref().map_or(0, |d| d.heap_size())
@@ -519,7 +609,7 @@ impl ChainSync {
 				}
 			}
 		}
-		self.state = ChainSync::get_init_state(self.warp_sync, io.chain());
+		self.state = ChainSync::get_init_state(self.warp_sync, self.state_sync_enabled, io.chain());
 		// Reactivate peers only if some progress has been made
 		// since the last sync round of if starting fresh.
 		self.active_peers = self.peers.keys().cloned().collect();
@@ -565,7 +655,7 @@ impl ChainSync {
 			};
 			//collect snapshot infos from peers
 			let snapshots = self.peers.iter()
-				.filter(|&(_, p)| p.is_allowed() && p.snapshot_number.map_or(false, |sn|
+				.filter(|&(_, p)| p.is_allowed() && p.reputation >= MIN_SNAPSHOT_PEER_REPUTATION && p.snapshot_number.map_or(false, |sn|
 					// Snapshot must be old enough that it's usefull to sync with it
 					our_best_block < sn && (sn - our_best_block) > SNAPSHOT_RESTORE_THRESHOLD &&
 					// Snapshot must have been taken after the Fork
@@ -626,6 +716,141 @@ impl ChainSync {
 		}
 	}
 
+	fn maybe_start_state_sync(&mut self, io: &mut SyncIo) {
+		if !self.state_sync_enabled || self.state != SyncState::WaitingPeers {
+			return;
+		}
+		if self.peers.values().any(|p| p.asking == PeerAsking::StatePivotHeaders) {
+			trace!(target: "sync", "Already requested state sync pivot");
+			return;
+		}
+
+		let timeout = self.sync_start_time.map_or(false, |t| t.elapsed() > WAIT_PEERS_TIMEOUT);
+		let (num_peers, best_peer) = {
+			let peers: Vec<_> = self.peers.iter()
+				.filter(|&(id, p)| self.active_peers.contains(id) && p.can_sync() && p.asking == PeerAsking::Nothing)
+				.filter_map(|(id, p)| p.difficulty.map(|td| (*id, p.latest_hash, td)))
+				.collect();
+			(peers.len(), peers.into_iter().max_by_key(|&(_, _, td)| td))
+		};
+		if num_peers < STATE_SYNC_MIN_PEERS && !timeout {
+			trace!(target: "sync", "Waiting for more peers to start state sync: {}", num_peers);
+			return;
+		}
+
+		let our_difficulty = io.chain().chain_info().total_difficulty;
+		match best_peer {
+			Some((peer_id, hash, td)) if td > our_difficulty => {
+				trace!(target: "sync", "Starting state sync with {}, pivot {}", peer_id, hash);
+				self.state_pivot = None;
+				self.state_pivot_target = Some((hash, td));
+				self.state = SyncState::StatePivot;
+				SyncRequester::request_state_pivot_headers(self, io, peer_id, &hash);
+			},
+			Some(_) => {
+				trace!(target: "sync", "No peers with higher difficulty, skipping state sync");
+				self.disable_state_sync(io);
+			},
+			None if timeout => {
+				trace!(target: "sync", "No peers for state sync, starting full sync");
+				self.disable_state_sync(io);
+			},
+			None => (),
+		}
+	}
+
+	/// Turn off state sync and continue with the full sync.
+	fn disable_state_sync(&mut self, io: &mut SyncIo) {
+		self.state_sync_enabled = false;
+		self.state_sync.clear();
+		self.state_pivot = None;
+		self.state_pivot_target = None;
+		self.state = SyncState::Idle;
+		self.continue_sync(io);
+	}
+
+	/// Request missing pivot block data from the peer, unless already requested from another one.
+	fn request_state_pivot_data(&mut self, io: &mut SyncIo, peer_id: PeerId) {
+		let asking = match self.state_pivot {
+			Some(ref pivot) if pivot.body.is_none() => PeerAsking::StatePivotBody,
+			Some(ref pivot) if pivot.receipts.is_none() => PeerAsking::StatePivotReceipts,
+			_ => return,
+		};
+		if self.peers.values().any(|p| p.asking == asking) {
+			return;
+		}
+		let hash = self.state_pivot.as_ref().expect("state_pivot is checked above; qed").header.hash();
+		match asking {
+			PeerAsking::StatePivotBody => SyncRequester::request_state_pivot_body(self, io, peer_id, hash),
+			_ => SyncRequester::request_state_pivot_receipts(self, io, peer_id, hash),
+		}
+	}
+
+	/// Called when the pivot header, body and receipts are downloaded.
+	fn start_state_download(&mut self, io: &mut SyncIo) {
+		let root = *self.state_pivot.as_ref().expect("called once the pivot is downloaded; qed").header.state_root();
+		trace!(target: "sync", "Downloading state {}", root);
+		self.state_sync_empty_responses = 0;
+		let result = {
+			let chain = io.chain();
+			self.state_sync.reset_to(root, |hash, account| chain.has_state_node(hash, account))
+		};
+		if let Err(e) = result {
+			warn!(target: "sync", "Error restarting state sync from {}: {}", root, e);
+			self.disable_state_sync(io);
+			return;
+		}
+		self.state = SyncState::StateNodes;
+		self.import_completed_state_nodes(io);
+		self.continue_sync(io);
+	}
+
+	/// Pick a new pivot if peers are unlikely to serve the state of the current one anymore.
+	fn check_state_pivot(&mut self, io: &mut SyncIo) {
+		if self.state != SyncState::StateNodes {
+			return;
+		}
+		let pivot_number = self.state_pivot.as_ref().map_or(0, |p| p.header.number());
+		let is_stale = self.highest_block.map_or(false, |highest| highest > pivot_number + STATE_SYNC_PIVOT_DISTANCE + STATE_SYNC_PIVOT_MAX_AGE);
+		if is_stale || self.state_sync_empty_responses >= STATE_SYNC_MAX_EMPTY_RESPONSES {
+			debug!(target: "sync", "Moving state sync pivot from #{}, highest block: {:?}", pivot_number, self.highest_block);
+			self.state_pivot = None;
+			self.state_pivot_target = None;
+			self.state_sync_empty_responses = 0;
+			self.state = SyncState::WaitingPeers;
+			self.active_peers = self.peers.keys().cloned().collect();
+			self.maybe_start_state_sync(io);
+		}
+	}
+
+	/// Write the state nodes that are complete to the database and finish the state sync
+	/// once the whole state is there.
+	fn import_completed_state_nodes(&mut self, io: &mut SyncIo) {
+		let (nodes, accounts) = self.state_sync.drain_completed();
+		if !nodes.is_empty() {
+			if let Err(e) = io.chain().import_state_nodes(nodes, accounts) {
+				warn!(target: "sync", "Error importing state nodes: {}", e);
+				self.disable_state_sync(io);
+				return;
+			}
+		}
+		if !self.state_sync.is_complete() {
+			return;
+		}
+
+		let pivot = self.state_pivot.take().expect("state download is started after the pivot is downloaded; qed");
+		let block = pivot.block().expect("state download is started after the pivot body is downloaded; qed");
+		let receipts = pivot.receipts.clone().expect("state download is started after the pivot receipts are downloaded; qed");
+		match io.chain().import_state_sync_pivot(block, receipts, pivot.parent_total_difficulty()) {
+			Ok(hash) => info!(target: "sync", "State sync complete at #{} ({})", pivot.header.number(), hash),
+			Err(e) => warn!(target: "sync", "Error importing state sync pivot #{}: {}", pivot.header.number(), e),
+		}
+		self.state_sync_enabled = false;
+		self.state_sync.clear();
+		self.state_pivot_target = None;
+		self.restart(io);
+	}
+
 	/// Restart sync disregarding the block queue status. May end up re-downloading up to QUEUE_SIZE blocks
 	pub fn restart(&mut self, io: &mut SyncIo) {
 		self.update_targets(io.chain());
@@ -642,7 +867,7 @@ impl ChainSync {
 			if let (Some(ancient_block_hash), Some(ancient_block_number)) = (chain.ancient_block_hash, chain.ancient_block_number) {
 
 				trace!(target: "sync", "Downloading old blocks from {:?} (#{}) till {:?} (#{:?})", ancient_block_hash, ancient_block_number, chain.first_block_hash, chain.first_block_number);
-				let mut downloader = BlockDownloader::with_unlimited_reorg(true, &ancient_block_hash, ancient_block_number);
+				let mut downloader = BlockDownloader::with_skeleton(&ancient_block_hash, ancient_block_number);
 				if let Some(hash) = chain.first_block_hash {
 					trace!(target: "sync", "Downloader target set to {:?}", hash);
 					downloader.set_target(&hash);
@@ -678,9 +903,17 @@ impl ChainSync {
 				self.active_peers.contains(&peer_id)
 			).map(|v| *v).collect();
 
-			random::new().shuffle(&mut peers); //TODO: sort by rating
-			// prefer peers with higher protocol version
-			peers.sort_by(|&(_, ref v1), &(_, ref v2)| v1.cmp(v2));
+			random::new().shuffle(&mut peers);
+			// prefer peers with higher protocol version, then the ones with better reputation and responding faster,
+			// peers with no measured latency go last
+			let ratings: HashMap<PeerId, (i32, Option<Duration>)> = peers.iter()
+				.filter_map(|&(peer_id, _)| self.peers.get(&peer_id).map(|p| (peer_id, (p.reputation, p.latency))))
+				.collect();
+			peers.sort_by(|&(ref p1, ref v1), &(ref p2, ref v2)| v1.cmp(v2)
+				.then_with(|| ratings[p2].0.cmp(&ratings[p1].0))
+				.then_with(|| ratings[p1].1.is_none().cmp(&ratings[p2].1.is_none()))
+				.then_with(|| ratings[p1].1.cmp(&ratings[p2].1))
+			);
 
 			for (peer_id, _) in peers {
 				self.sync_peer(io, peer_id, false);
@@ -740,6 +973,16 @@ impl ChainSync {
 						peer_id
 					);
 					self.maybe_start_snapshot_sync(io);
+					self.maybe_start_state_sync(io);
+				},
+				SyncState::StatePivot => {
+					self.request_state_pivot_data(io, peer_id);
+				},
+				SyncState::StateNodes => {
+					let hashes = self.state_sync.needed_nodes(MAX_NODE_DATA_TO_REQUEST);
+					if !hashes.is_empty() {
+						SyncRequester::request_node_data(self, io, peer_id, hashes);
+					}
 				},
 				SyncState::Idle | SyncState::Blocks | SyncState::NewBlocks => {
 					if io.chain().queue_info().is_full() {
@@ -752,7 +995,7 @@ impl ChainSync {
 					if !have_latest && (higher_difficulty || force || self.state == SyncState::NewBlocks) {
 						// check if got new blocks to download
 						trace!(target: "sync", "Syncing with peer {}, force={}, td={:?}, our td={}, state={:?}", peer_id, force, peer_difficulty, syncing_difficulty, self.state);
-						if let Some(request) = self.new_blocks.request_blocks(io, num_active_peers) {
+						if let Some(request) = self.new_blocks.request_blocks(peer_id, io, num_active_peers) {
 							SyncRequester::request_blocks(self, io, peer_id, request, BlockSet::NewBlocks);
 							if self.state == SyncState::Idle {
 								self.state = SyncState::Blocks;
@@ -763,7 +1006,11 @@ impl ChainSync {
 
 					// Only ask for old blocks if the peer has a higher difficulty
 					if force || higher_difficulty {
-						if let Some(request) = self.old_blocks.as_mut().and_then(|d| d.request_blocks(io, num_active_peers)) {
+						if self.old_blocks.is_some() && self.is_slow_peer(peer_id) {
+							trace!(target: "sync", "peer {} is too slow for asking old blocks", peer_id);
+							return;
+						}
+						if let Some(request) = self.old_blocks.as_mut().and_then(|d| d.request_blocks(peer_id, io, num_active_peers)) {
 							SyncRequester::request_blocks(self, io, peer_id, request, BlockSet::OldBlocks);
 							return;
 						}
@@ -835,6 +1082,14 @@ impl ChainSync {
 						self.snapshot.clear_chunk_download(&hash);
 					}
 				},
+				PeerAsking::StatePivotHeaders => {
+					if self.state == SyncState::StatePivot && self.state_pivot.is_none() {
+						self.state = SyncState::WaitingPeers;
+					}
+				},
+				PeerAsking::NodeData => {
+					self.state_sync.clear_node_download(&peer.asking_blocks);
+				},
 				_ => (),
 			}
 		}
@@ -869,6 +1124,8 @@ impl ChainSync {
 				peer.asking = PeerAsking::Nothing;
 				return false;
 			} else {
+				let elapsed = Instant::now() - peer.ask_time;
+				peer.latency = Some(peer.latency.map_or(elapsed, |latency| (latency * 3 + elapsed) / 4));
 				peer.asking = PeerAsking::Nothing;
 				return true;
 			}
@@ -876,6 +1133,29 @@ impl ChainSync {
 		false
 	}
 
+	/// Adjust peer reputation, both for this session and in the persistent node table.
+	fn note_peer_reputation(&mut self, io: &mut SyncIo, peer_id: PeerId, delta: i32) {
+		if let Some(peer) = self.peers.get_mut(&peer_id) {
+			peer.reputation = peer.reputation.saturating_add(delta);
+		}
+		io.adjust_peer_reputation(peer_id, delta);
+	}
+
+	/// Reward peer for a useful response, unless it responds much slower than others.
+	fn note_useful_response(&mut self, io: &mut SyncIo, peer_id: PeerId) {
+		let delta = if self.is_slow_peer(peer_id) { REPUTATION_SLOW_RESPONSE } else { REPUTATION_USEFUL_DATA };
+		self.note_peer_reputation(io, peer_id, delta);
+	}
+
+	/// Checks if the peer responds much slower than the fastest peer we sync with.
+	fn is_slow_peer(&self, peer_id: PeerId) -> bool {
+		let fastest = self.peers.values().filter(|p| p.can_sync()).filter_map(|p| p.latency).min();
+		match (self.peers.get(&peer_id).and_then(|p| p.latency), fastest) {
+			(Some(latency), Some(fastest)) => latency > cmp::max(fastest * SLOW_PEER_LATENCY_FACTOR, SLOW_PEER_MIN_LATENCY),
+			_ => false,
+		}
+	}
+
 	/// Send Status message
 	fn send_status(&mut self, io: &mut SyncIo, peer: PeerId) -> Result<(), network::Error> {
 		let warp_protocol_version = io.protocol_version(&WARP_SYNC_PROTOCOL_ID, peer);
@@ -912,6 +1192,10 @@ impl ChainSync {
 				PeerAsking::ForkHeader => elapsed > FORK_HEADER_TIMEOUT,
 				PeerAsking::SnapshotManifest => elapsed > SNAPSHOT_MANIFEST_TIMEOUT,
 				PeerAsking::SnapshotData => elapsed > SNAPSHOT_DATA_TIMEOUT,
+				PeerAsking::StatePivotHeaders => elapsed > HEADERS_TIMEOUT,
+				PeerAsking::StatePivotBody => elapsed > BODIES_TIMEOUT,
+				PeerAsking::StatePivotReceipts => elapsed > RECEIPTS_TIMEOUT,
+				PeerAsking::NodeData => elapsed > NODE_DATA_TIMEOUT,
 			};
 			if timeout {
 				debug!(target:"sync", "Timeout {}", peer_id);
@@ -920,9 +1204,13 @@ impl ChainSync {
 			}
 		}
 		for p in aborting {
+			self.note_peer_reputation(io, p, REPUTATION_TIMEOUT);
 			SyncHandler::on_peer_aborting(self, io, p);
 		}
 
+		// Announced transactions not delivered in time may be requested from another peer
+		self.pooled_transactions_requested.retain(|_, &mut (_, ask_time)| tick - ask_time <= POOLED_TRANSACTIONS_TIMEOUT);
+
 		// Check for handshake timeouts
 		for (peer, &ask_time) in &self.handshaking_peers {
 			let elapsed = (tick - ask_time) / 1_000_000_000;
@@ -1055,9 +1343,15 @@ impl ChainSync {
 		self.peers.iter().filter_map(|(id, p)| if p.protocol_version >= PAR_PROTOCOL_VERSION_3.0 { Some(*id) } else { None }).collect()
 	}
 
+	fn get_pooled_transactions_peers(&self) -> Vec<PeerId> {
+		self.peers.iter().filter_map(|(id, p)| if p.protocol_version >= PAR_PROTOCOL_VERSION_4.0 { Some(*id) } else { None }).collect()
+	}
+
 	/// Maintain other peers. Send out any new blocks and transactions
 	pub fn maintain_sync(&mut self, io: &mut SyncIo) {
 		self.maybe_start_snapshot_sync(io);
+		self.maybe_start_state_sync(io);
+		self.check_state_pivot(io);
 		self.check_resume(io);
 	}
 
@@ -1217,6 +1511,8 @@ pub mod tests {
 			num_snapshot_chunks: 0,
 			snapshot_chunks_done: 0,
 			last_imported_old_block_number: None,
+			state_nodes_done: 0,
+			state_nodes_pending: 0,
 		}
 	}
 
@@ -1257,10 +1553,239 @@ pub mod tests {
 				snapshot_hash: None,
 				asking_snapshot_data: None,
 				block_set: None,
+				latency: None,
+				reputation: 0,
 			});
 
 	}
 
+	fn state_sync_config() -> SyncConfig {
+		let mut config = SyncConfig::default();
+		config.state_sync = true;
+		config
+	}
+
+	#[test]
+	fn starts_state_sync_with_best_peer() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut sync = ChainSync::new(state_sync_config(), &client, Arc::new(NoopPrivateTxHandler));
+		assert_eq!(sync.state, SyncState::WaitingPeers);
+		for peer in 0..STATE_SYNC_MIN_PEERS {
+			insert_dummy_peer(&mut sync, peer, H256::from(peer as u64 + 1));
+			sync.peers.get_mut(&peer).unwrap().difficulty = Some(U256::from(1_000_000 + peer as u64));
+			sync.active_peers.insert(peer);
+		}
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+
+		sync.maintain_sync(&mut io);
+
+		let best_peer = STATE_SYNC_MIN_PEERS - 1;
+		assert_eq!(sync.state, SyncState::StatePivot);
+		assert_eq!(io.packets.len(), 1);
+		assert_eq!(io.packets[0].packet_id, GET_BLOCK_HEADERS_PACKET);
+		assert_eq!(io.packets[0].recipient, best_peer);
+		assert_eq!(sync.peers[&best_peer].asking, PeerAsking::StatePivotHeaders);
+	}
+
+	/// Linked headers from the pivot up to the head with the given number, oldest first.
+	fn pivot_headers(head_number: BlockNumber) -> Vec<Header> {
+		let mut headers: Vec<Header> = Vec::new();
+		for number in (head_number - STATE_SYNC_PIVOT_DISTANCE)..(head_number + 1) {
+			let mut header = Header::new();
+			header.set_number(number);
+			header.set_timestamp(number);
+			header.set_gas_limit(1_000_000.into());
+			header.set_difficulty(10.into());
+			if let Some(parent) = headers.last() {
+				header.set_parent_hash(parent.hash());
+			}
+			headers.push(header);
+		}
+		headers
+	}
+
+	/// Response to the pivot headers request, sent from the head down.
+	fn pivot_headers_packet(headers: &[Header]) -> Bytes {
+		let mut rlp = RlpStream::new_list(headers.len());
+		for header in headers.iter().rev() {
+			rlp.append(header);
+		}
+		rlp.out()
+	}
+
+	/// Sync in the `StatePivot` state, waiting for the pivot headers ending with `head` from peer 0.
+	fn sync_asking_pivot_headers(io: &mut TestIo<TestBlockChainClient>, head: &Header) -> ChainSync {
+		let mut sync = ChainSync::new(state_sync_config(), io.chain, Arc::new(NoopPrivateTxHandler));
+		insert_dummy_peer(&mut sync, 0, head.hash());
+		sync.peers.get_mut(&0).unwrap().difficulty = Some(U256::from(1_000_000));
+		sync.active_peers.insert(0);
+		sync.sync_start_time = Some(Instant::now() - WAIT_PEERS_TIMEOUT * 2);
+		sync.maintain_sync(io);
+		assert_eq!(sync.state, SyncState::StatePivot);
+		assert_eq!(sync.peers[&0].asking, PeerAsking::StatePivotHeaders);
+		sync
+	}
+
+	#[test]
+	fn skips_state_sync_when_close_to_pivot() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let headers = pivot_headers(100);
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		let mut sync = sync_asking_pivot_headers(&mut io, headers.last().unwrap());
+
+		sync.on_packet(&mut io, 0, BLOCK_HEADERS_PACKET, &pivot_headers_packet(&headers));
+
+		assert!(!sync.state_sync_enabled);
+		assert!(sync.state != SyncState::StatePivot && sync.state != SyncState::StateNodes);
+	}
+
+	#[test]
+	fn picks_verified_pivot_below_peer_head() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let headers = pivot_headers(5_000);
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		let mut sync = sync_asking_pivot_headers(&mut io, headers.last().unwrap());
+
+		sync.on_packet(&mut io, 0, BLOCK_HEADERS_PACKET, &pivot_headers_packet(&headers));
+
+		let pivot = sync.state_pivot.as_ref().unwrap();
+		assert_eq!(pivot.header, headers[0]);
+		assert_eq!(pivot.header.number(), 5_000 - STATE_SYNC_PIVOT_DISTANCE);
+		assert_eq!(pivot.total_difficulty, U256::from(1_000_000 - 10 * STATE_SYNC_PIVOT_DISTANCE));
+		assert_eq!(sync.highest_block, Some(5_000));
+		assert!(io.to_disconnect.is_empty());
+	}
+
+	#[test]
+	fn rejects_unlinked_pivot_headers() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut headers = pivot_headers(5_000);
+		// replace the pivot with a header the rest of the chain does not build on.
+		headers[0].set_state_root(H256::from(1));
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		let mut sync = sync_asking_pivot_headers(&mut io, headers.last().unwrap());
+
+		sync.on_packet(&mut io, 0, BLOCK_HEADERS_PACKET, &pivot_headers_packet(&headers));
+
+		assert!(sync.state_pivot.is_none());
+		assert_eq!(sync.state, SyncState::WaitingPeers);
+		assert!(io.to_disconnect.contains(&0));
+	}
+
+	#[test]
+	fn downloads_state_and_imports_pivot() {
+		use ethcore::basic_account::BasicAccount;
+		use ethcore::views::BlockView;
+		use hash::{KECCAK_EMPTY, KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
+		use hashdb::HashDB;
+		use memorydb::MemoryDB;
+		use trie::{SecTrieDBMut, TrieMut};
+
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut sync = ChainSync::new(state_sync_config(), &client, Arc::new(NoopPrivateTxHandler));
+		insert_dummy_peer(&mut sync, 0, H256::zero());
+		sync.active_peers.insert(0);
+
+		let mut db = MemoryDB::new();
+		let mut state_root = KECCAK_NULL_RLP;
+		{
+			let account = BasicAccount {
+				nonce: 0.into(),
+				balance: 1_000.into(),
+				storage_root: KECCAK_NULL_RLP,
+				code_hash: KECCAK_EMPTY,
+			};
+			let mut trie = SecTrieDBMut::new(&mut db, &mut state_root);
+			trie.insert(&Address::from(1), &::rlp::encode(&account)).unwrap();
+		}
+		let mut header = Header::new();
+		header.set_number(2_000);
+		header.set_difficulty(100.into());
+		header.set_state_root(state_root);
+		header.set_transactions_root(KECCAK_NULL_RLP);
+		header.set_uncles_hash(KECCAK_EMPTY_LIST_RLP);
+		header.set_receipts_root(KECCAK_NULL_RLP);
+		sync.state_pivot = Some(StatePivot::new(header.clone(), 1_000.into()));
+		sync.state = SyncState::StatePivot;
+
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		sync.continue_sync(&mut io);
+		assert_eq!(io.packets.last().unwrap().packet_id, GET_BLOCK_BODIES_PACKET);
+
+		let mut bodies = RlpStream::new_list(1);
+		bodies.begin_list(2);
+		bodies.begin_list(0);
+		bodies.begin_list(0);
+		sync.on_packet(&mut io, 0, BLOCK_BODIES_PACKET, &bodies.out());
+		assert_eq!(io.packets.last().unwrap().packet_id, GET_RECEIPTS_PACKET);
+
+		let mut receipts = RlpStream::new_list(1);
+		receipts.begin_list(0);
+		sync.on_packet(&mut io, 0, RECEIPTS_PACKET, &receipts.out());
+		assert_eq!(sync.state, SyncState::StateNodes);
+		assert_eq!(io.packets.last().unwrap().packet_id, GET_NODE_DATA_PACKET);
+
+		let mut nodes = RlpStream::new_list(1);
+		nodes.append(&db.get(&state_root).unwrap().into_vec());
+		sync.on_packet(&mut io, 0, NODE_DATA_PACKET, &nodes.out());
+
+		assert!(!sync.state_sync_enabled);
+		assert!(io.chain.has_state_node(&state_root, None));
+		let pivot = io.chain.state_sync_pivot.read().clone().unwrap();
+		assert_eq!(pivot.2, U256::from(900));
+		assert_eq!(view!(BlockView, &pivot.0).header(), header);
+	}
+
+	#[test]
+	fn detects_slow_peers() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(10), &client);
+		insert_dummy_peer(&mut sync, 1, client.block_hash_delta_minus(10));
+		insert_dummy_peer(&mut sync, 2, client.block_hash_delta_minus(10));
+		sync.peers.get_mut(&0).unwrap().latency = Some(Duration::from_millis(200));
+		sync.peers.get_mut(&1).unwrap().latency = Some(Duration::from_millis(900));
+		sync.peers.get_mut(&2).unwrap().latency = Some(Duration::from_secs(5));
+
+		assert!(!sync.is_slow_peer(0));
+		assert!(!sync.is_slow_peer(1));
+		assert!(sync.is_slow_peer(2));
+		// peers without measured latency are never considered slow
+		insert_dummy_peer(&mut sync, 3, client.block_hash_delta_minus(10));
+		assert!(!sync.is_slow_peer(3));
+	}
+
+	#[test]
+	fn penalizes_timed_out_peers() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(10), &client);
+		{
+			let peer = sync.peers.get_mut(&0).unwrap();
+			peer.asking = PeerAsking::BlockHeaders;
+			peer.ask_time = Instant::now() - HEADERS_TIMEOUT * 2;
+		}
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+
+		sync.maintain_peers(&mut io);
+
+		assert_eq!(io.peer_reputation(0), REPUTATION_TIMEOUT);
+		assert!(io.to_disconnect.contains(&0));
+		assert!(!sync.peers.contains_key(&0));
+	}
+
 	#[test]
 	fn finds_lagging_peers() {
 		let mut client = TestBlockChainClient::new();
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use api::WARP_SYNC_PROTOCOL_ID;
 use bytes::Bytes;
 use ethereum_types::H256;
 use ethcore::client::BlockChainInfo;
@@ -31,6 +32,7 @@ use super::{
 	ChainSync,
 	MAX_PEER_LAG_PROPAGATION,
 	MAX_PEERS_PROPAGATION,
+	MAX_TRANSACTION_HASHES_TO_ANNOUNCE,
 	MAX_TRANSACTION_PACKET_SIZE,
 	MAX_TRANSACTIONS_TO_PROPAGATE,
 	MAX_TRANSACTIONS_TO_QUERY,
@@ -38,6 +40,7 @@ use super::{
 	CONSENSUS_DATA_PACKET,
 	NEW_BLOCK_HASHES_PACKET,
 	NEW_BLOCK_PACKET,
+	NEW_POOLED_TRANSACTION_HASHES_PACKET,
 	PRIVATE_TRANSACTION_PACKET,
 	SIGNED_PRIVATE_TRANSACTION_PACKET,
 	TRANSACTIONS_PACKET,
@@ -125,18 +128,25 @@ impl SyncPropagator {
 			.partition(|tx| !tx.gas_price.is_zero());
 
 		// usual transactions could be propagated to all peers
+		// the rest of the peers only receive hashes and fetch the transactions they are missing
 		let mut affected_peers = HashSet::new();
 		if !transactions.is_empty() {
 			let peers = SyncPropagator::select_peers_for_transactions(sync, |_| true);
-			affected_peers = SyncPropagator::propagate_transactions_to_peers(sync, io, peers, transactions);
+			let announce_peers = SyncPropagator::select_peers_for_announcement(sync, &peers, |_| true);
+			affected_peers = SyncPropagator::propagate_transactions_to_peers(sync, io, peers, transactions.clone());
+			let announced_peers = SyncPropagator::announce_transactions_to_peers(sync, io, announce_peers, &transactions);
+			affected_peers.extend(&announced_peers);
 		}
 
 		// most of times service_transactions will be empty
 		// => there's no need to merge packets
 		if !service_transactions.is_empty() {
 			let service_transactions_peers = SyncPropagator::select_peers_for_transactions(sync, |peer_id| accepts_service_transaction(&io.peer_info(*peer_id)));
-			let service_transactions_affected_peers = SyncPropagator::propagate_transactions_to_peers(sync, io, service_transactions_peers, service_transactions);
+			let service_announce_peers = SyncPropagator::select_peers_for_announcement(sync, &service_transactions_peers, |peer_id| accepts_service_transaction(&io.peer_info(*peer_id)));
+			let service_transactions_affected_peers = SyncPropagator::propagate_transactions_to_peers(sync, io, service_transactions_peers, service_transactions.clone());
 			affected_peers.extend(&service_transactions_affected_peers);
+			let service_announced_peers = SyncPropagator::announce_transactions_to_peers(sync, io, service_announce_peers, &service_transactions);
+			affected_peers.extend(&service_announced_peers);
 		}
 
 		affected_peers.len()
@@ -244,6 +254,50 @@ impl SyncPropagator {
 		peers
 	}
 
+	fn announce_transactions_to_peers(sync: &mut ChainSync, io: &mut SyncIo, peers: Vec<PeerId>, transactions: &[&SignedTransaction]) -> HashSet<PeerId> {
+		let all_transactions_hashes = transactions.iter()
+			.map(|tx| tx.hash())
+			.collect::<HashSet<H256>>();
+
+		let mut announced = HashSet::new();
+		let mut max_sent = 0;
+		for peer_id in peers {
+			let to_announce = {
+				let peer_info = match sync.peers.get_mut(&peer_id) {
+					Some(peer_info) => peer_info,
+					None => continue,
+				};
+				let to_announce = all_transactions_hashes.difference(&peer_info.last_sent_transactions)
+					.take(MAX_TRANSACTION_HASHES_TO_ANNOUNCE)
+					.cloned()
+					.collect::<Vec<_>>();
+				if to_announce.is_empty() {
+					continue;
+				}
+				peer_info.last_sent_transactions = all_transactions_hashes
+					.intersection(&peer_info.last_sent_transactions)
+					.chain(&to_announce)
+					.cloned()
+					.collect();
+				to_announce
+			};
+
+			let mut packet = RlpStream::new_list(to_announce.len());
+			for hash in &to_announce {
+				packet.append(hash);
+			}
+			SyncPropagator::send_warp_packet(io, peer_id, NEW_POOLED_TRANSACTION_HASHES_PACKET, packet.out());
+			trace!(target: "sync", "{:02} <- NewPooledTransactionHashes ({} entries)", peer_id, to_announce.len());
+			max_sent = cmp::max(max_sent, to_announce.len());
+			announced.insert(peer_id);
+		}
+		if !announced.is_empty() {
+			debug!(target: "sync", "Announced up to {} transactions to {} peers.", max_sent, announced.len());
+		}
+
+		announced
+	}
+
 	pub fn propagate_latest_blocks(sync: &mut ChainSync, io: &mut SyncIo, sealed: &[H256]) {
 		let chain_info = io.chain().chain_info();
 		if (((chain_info.best_block_number as i64) - (sync.last_sent_block_number as i64)).abs() as BlockNumber) < MAX_PEER_LAG_PROPAGATION {
@@ -321,6 +375,23 @@ impl SyncPropagator {
 			.collect()
 	}
 
+	/// Peers supporting transaction announcements which were not chosen to receive full transactions
+	fn select_peers_for_announcement<F>(sync: &ChainSync, lucky_peers: &[PeerId], filter: F) -> Vec<PeerId>
+		where F: Fn(&PeerId) -> bool {
+		sync.get_pooled_transactions_peers().into_iter()
+			.filter(|peer_id| !lucky_peers.contains(peer_id))
+			.filter(filter)
+			.collect()
+	}
+
+	/// Sender for packets available in the warp sync protocol only
+	fn send_warp_packet(sync: &mut SyncIo, peer_id: PeerId, packet_id: PacketId, packet: Bytes) {
+		if let Err(e) = sync.send_protocol(WARP_SYNC_PROTOCOL_ID, peer_id, packet_id, packet) {
+			debug!(target:"sync", "Error sending packet: {:?}", e);
+			sync.disconnect_peer(peer_id);
+		}
+	}
+
 	/// Generic packet sender
 	fn send_packet(sync: &mut SyncIo, peer_id: PeerId, packet_id: PacketId, packet: Bytes) {
 		if let Err(e) = sync.send(peer_id, packet_id, packet) {
@@ -430,6 +501,8 @@ mod tests {
 				snapshot_hash: None,
 				asking_snapshot_data: None,
 				block_set: None,
+				latency: None,
+				reputation: 0,
 			});
 		let ss = TestSnapshotService::new();
 		let mut io = TestIo::new(&mut client, &ss, &queue, None);
@@ -547,6 +620,31 @@ mod tests {
 		assert_eq!(0x02, queue.read()[1].packet_id);
 	}
 
+	#[test]
+	fn announces_transactions_to_peers_not_receiving_them() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		client.insert_transaction_to_queue();
+		let block_hash = client.block_hash_delta_minus(1);
+		let mut sync = ChainSync::new(SyncConfig::default(), &client, Arc::new(NoopPrivateTxHandler));
+		for peer_id in 0..16 {
+			insert_dummy_peer(&mut sync, peer_id, block_hash);
+			sync.peers.get_mut(&peer_id).unwrap().protocol_version = PAR_PROTOCOL_VERSION_4.0;
+		}
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		let peer_count = SyncPropagator::propagate_new_transactions(&mut sync, &mut io);
+		// Announced transactions should not be propagated again
+		let peer_count2 = SyncPropagator::propagate_new_transactions(&mut sync, &mut io);
+
+		// every peer receives either the transaction or its hash
+		assert_eq!(16, peer_count);
+		assert_eq!(0, peer_count2);
+		assert_eq!(16, io.packets.len());
+		assert!(io.packets.iter().any(|p| p.packet_id == 0x18)); // NEW_POOLED_TRANSACTION_HASHES_PACKET
+	}
+
 	#[test]
 	fn should_maintain_transations_propagation_stats() {
 		let mut client = TestBlockChainClient::new();
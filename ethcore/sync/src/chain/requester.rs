@@ -31,7 +31,10 @@ use super::{
 	ETH_PROTOCOL_VERSION_63,
 	GET_BLOCK_BODIES_PACKET,
 	GET_BLOCK_HEADERS_PACKET,
+	GET_NODE_DATA_PACKET,
+	GET_POOLED_TRANSACTIONS_PACKET,
 	GET_RECEIPTS_PACKET,
+	STATE_SYNC_PIVOT_DISTANCE,
 	GET_SNAPSHOT_DATA_PACKET,
 	GET_SNAPSHOT_MANIFEST_PACKET,
 };
@@ -97,6 +100,49 @@ impl SyncRequester {
 		SyncRequester::send_request(sync, io, peer_id, PeerAsking::SnapshotManifest, GET_SNAPSHOT_MANIFEST_PACKET, rlp.out());
 	}
 
+	/// Request the headers from the peer head down to the state sync pivot.
+	pub fn request_state_pivot_headers(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, hash: &H256) {
+		trace!(target: "sync", "{} <- GetStatePivotHeaders: {}", peer_id, hash);
+		let mut rlp = RlpStream::new_list(4);
+		rlp.append(hash);
+		rlp.append(&(STATE_SYNC_PIVOT_DISTANCE as u32 + 1));
+		rlp.append(&0u32);
+		rlp.append(&1u32);
+		SyncRequester::send_request(sync, io, peer_id, PeerAsking::StatePivotHeaders, GET_BLOCK_HEADERS_PACKET, rlp.out());
+		if let Some(ref mut peer) = sync.peers.get_mut(&peer_id) {
+			peer.asking_hash = Some(hash.clone());
+		}
+	}
+
+	/// Request state sync pivot body from a peer.
+	pub fn request_state_pivot_body(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, hash: H256) {
+		trace!(target: "sync", "{} <- GetStatePivotBody: {}", peer_id, hash);
+		let mut rlp = RlpStream::new_list(1);
+		rlp.append(&hash);
+		SyncRequester::send_request(sync, io, peer_id, PeerAsking::StatePivotBody, GET_BLOCK_BODIES_PACKET, rlp.out());
+	}
+
+	/// Request state sync pivot receipts from a peer.
+	pub fn request_state_pivot_receipts(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, hash: H256) {
+		trace!(target: "sync", "{} <- GetStatePivotReceipts: {}", peer_id, hash);
+		let mut rlp = RlpStream::new_list(1);
+		rlp.append(&hash);
+		SyncRequester::send_request(sync, io, peer_id, PeerAsking::StatePivotReceipts, GET_RECEIPTS_PACKET, rlp.out());
+	}
+
+	/// Request state trie nodes from a peer.
+	pub fn request_node_data(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, hashes: Vec<H256>) {
+		trace!(target: "sync", "{} <- GetNodeData: {} entries starting from {:?}", peer_id, hashes.len(), hashes.first());
+		let mut rlp = RlpStream::new_list(hashes.len());
+		for h in &hashes {
+			rlp.append(h);
+		}
+		SyncRequester::send_request(sync, io, peer_id, PeerAsking::NodeData, GET_NODE_DATA_PACKET, rlp.out());
+		if let Some(ref mut peer) = sync.peers.get_mut(&peer_id) {
+			peer.asking_blocks = hashes;
+		}
+	}
+
 	/// Request headers from a peer by block hash
 	fn request_headers_by_hash(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, h: &H256, count: u64, skip: u64, reverse: bool, set: BlockSet) {
 		trace!(target: "sync", "{} <- GetBlockHeaders: {} entries starting from {}, set = {:?}", peer_id, count, h, set);
@@ -132,6 +178,22 @@ impl SyncRequester {
 		SyncRequester::send_request(sync, io, peer_id, PeerAsking::SnapshotData, GET_SNAPSHOT_DATA_PACKET, rlp.out());
 	}
 
+	/// Request announced transactions which are missing from our pool.
+	pub fn request_pooled_transactions(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, hashes: &[H256]) {
+		trace!(target: "sync", "{} <- GetPooledTransactions: {} entries", peer_id, hashes.len());
+		let ask_time = Instant::now();
+		let mut rlp = RlpStream::new_list(hashes.len());
+		for h in hashes {
+			rlp.append(h);
+			sync.pooled_transactions_requested.insert(*h, (peer_id, ask_time));
+		}
+		// Not tracked by `PeerAsking`: transaction requests may overlap with block downloads.
+		if let Err(e) = io.send_protocol(WARP_SYNC_PROTOCOL_ID, peer_id, GET_POOLED_TRANSACTIONS_PACKET, rlp.out()) {
+			debug!(target:"sync", "Error sending request: {:?}", e);
+			io.disconnect_peer(peer_id);
+		}
+	}
+
 	/// Generic request sender
 	fn send_request(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, asking: PeerAsking,  packet_id: PacketId, packet: Bytes) {
 		if let Some(ref mut peer) = sync.peers.get_mut(&peer_id) {
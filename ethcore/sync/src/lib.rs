@@ -39,12 +39,15 @@ extern crate rlp;
 extern crate ipnetwork;
 extern crate keccak_hash as hash;
 extern crate triehash;
+extern crate patricia_trie as trie;
 extern crate kvdb;
 
 extern crate ethcore_light as light;
 
 #[cfg(test)] extern crate ethkey;
 #[cfg(test)] extern crate kvdb_memorydb;
+#[cfg(test)] extern crate hashdb;
+#[cfg(test)] extern crate memorydb;
 #[cfg(test)] extern crate rustc_hex;
 #[cfg(test)] extern crate ethcore_private_tx;
 
@@ -63,6 +66,7 @@ mod block_sync;
 mod sync_io;
 mod private_tx;
 mod snapshot;
+mod state_sync;
 mod transactions_stats;
 
 pub mod light_sync;
@@ -75,5 +79,5 @@ mod api;
 pub use api::*;
 pub use chain::{SyncStatus, SyncState};
 pub use devp2p::{validate_node_url, ConnectionFilter, ConnectionDirection};
-pub use network::{NonReservedPeerMode, Error, ErrorKind};
+pub use network::{NonReservedPeerMode, Error, ErrorKind, NodeReputation};
 pub use private_tx::{PrivateTxHandler, NoopPrivateTxHandler, SimplePrivateTxHandler};
@@ -68,6 +68,10 @@ impl<'a> IoContext for TestIoContext<'a> {
 	fn protocol_version(&self, _peer: PeerId) -> Option<u8> { Some(::light::net::MAX_PROTOCOL_VERSION) }
 
 	fn persistent_peer_id(&self, _peer: PeerId) -> Option<NodeId> { unimplemented!() }
+
+	fn peer_reputation(&self, _peer: PeerId) -> i32 { 0 }
+
+	fn adjust_peer_reputation(&self, _peer: PeerId, _delta: i32) {}
 }
 
 // peer-specific data.
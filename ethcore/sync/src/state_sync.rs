@@ -0,0 +1,615 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State trie downloader.
+//!
+//! Walks the state trie of a pivot block top-down and collects the nodes which are
+//! missing from the local database. Nodes are requested with `GetNodeData` by hash,
+//! deepest first, so that the amount of nodes kept in memory stays low.
+//!
+//! A downloaded node is only handed out for writing once all of its children
+//! (including the storage trie and code of the accounts it contains) have been
+//! handed out. Thus any node found in the database is the root of a complete
+//! subtrie, which allows to skip it entirely when the pivot moves to a newer block
+//! and the walk has to be restarted from the new state root.
+
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use bytes::Bytes;
+use ethcore::basic_account::BasicAccount;
+use ethcore::header::Header as BlockHeader;
+use ethereum_types::{H256, U256};
+use hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+use rlp::{Rlp, DecoderError};
+use trie::node::Node;
+
+/// Node identifier: the node hash and the address hash of the account owning it.
+/// Account trie nodes have no owner.
+type NodeKey = (H256, Option<H256>);
+
+/// Block the state is being downloaded for.
+#[derive(Clone, Debug)]
+pub struct StatePivot {
+	/// Pivot block header.
+	pub header: BlockHeader,
+	/// Total difficulty of the pivot block.
+	pub total_difficulty: U256,
+	/// Pivot block body RLP, once downloaded.
+	pub body: Option<Bytes>,
+	/// Pivot block receipts RLP, once downloaded.
+	pub receipts: Option<Bytes>,
+}
+
+impl StatePivot {
+	/// Create a new pivot with no body and receipts.
+	pub fn new(header: BlockHeader, total_difficulty: U256) -> StatePivot {
+		StatePivot {
+			header,
+			total_difficulty,
+			body: None,
+			receipts: None,
+		}
+	}
+
+	/// Total difficulty of the pivot parent.
+	pub fn parent_total_difficulty(&self) -> U256 {
+		self.total_difficulty.saturating_sub(*self.header.difficulty())
+	}
+
+	/// Full block RLP, if the body has been downloaded.
+	pub fn block(&self) -> Option<Bytes> {
+		self.body.as_ref().map(|body| {
+			let body = Rlp::new(body);
+			let mut block = ::rlp::RlpStream::new_list(3);
+			block.append(&self.header);
+			block.append_raw(body.at(0).expect("body is verified against the header when downloaded; qed").as_raw(), 1);
+			block.append_raw(body.at(1).expect("body is verified against the header when downloaded; qed").as_raw(), 1);
+			block.out()
+		})
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum NodeKind {
+	/// Account trie node with the nibble path leading to it.
+	Account(Vec<u8>),
+	/// Storage trie node.
+	Storage,
+	/// Contract code.
+	Code,
+}
+
+struct NodeRequest {
+	kind: NodeKind,
+	/// Depth of the node in the trie. Code and storage roots are one level below their account.
+	depth: usize,
+	/// Node data, once downloaded.
+	data: Option<Bytes>,
+	/// Number of children which have not been handed out yet.
+	pending_children: usize,
+	/// Nodes waiting for this one to be handed out.
+	parents: Vec<NodeKey>,
+}
+
+/// Reference found while walking a trie node.
+enum TrieRef {
+	/// Child node hash with the nibble path leading to it.
+	Node(H256, Vec<u8>),
+	/// Leaf value with the full nibble path of its key.
+	Value(Vec<u8>, Bytes),
+}
+
+/// State trie downloader.
+pub struct StateSync {
+	/// State root being downloaded.
+	root: Option<H256>,
+	/// Nodes which have not been handed out yet.
+	requests: HashMap<NodeKey, NodeRequest>,
+	/// Same node may be owned by multiple accounts.
+	requests_by_hash: HashMap<H256, Vec<NodeKey>>,
+	/// Nodes to download, deepest first.
+	queue: BinaryHeap<(usize, NodeKey)>,
+	/// Node hashes being downloaded.
+	downloading: HashSet<H256>,
+	/// Nodes downloaded for a previous state root. Reused after the pivot moves.
+	cache: HashMap<H256, Bytes>,
+	/// Nodes that are ready to be written to the database.
+	completed: Vec<(Option<H256>, Bytes)>,
+	/// Keys of the nodes in `completed`.
+	completed_keys: HashSet<NodeKey>,
+	/// Address hashes of the downloaded accounts.
+	accounts: Vec<H256>,
+	/// Total number of nodes handed out.
+	done_nodes: usize,
+}
+
+impl StateSync {
+	/// Create a new instance.
+	pub fn new() -> StateSync {
+		StateSync {
+			root: None,
+			requests: HashMap::new(),
+			requests_by_hash: HashMap::new(),
+			queue: BinaryHeap::new(),
+			downloading: HashSet::new(),
+			cache: HashMap::new(),
+			completed: Vec::new(),
+			completed_keys: HashSet::new(),
+			accounts: Vec::new(),
+			done_nodes: 0,
+		}
+	}
+
+	/// Clear everything.
+	pub fn clear(&mut self) {
+		*self = StateSync::new();
+	}
+
+	/// Start downloading the state with the given root. Any download in progress is
+	/// abandoned, but the nodes downloaded for it are kept and reused where the new
+	/// state shares them. `is_known` checks if a node is already in the database.
+	pub fn reset_to<F>(&mut self, root: H256, is_known: F) -> Result<(), DecoderError>
+		where F: Fn(&H256, Option<&H256>) -> bool
+	{
+		// only keep the nodes of the latest abandoned download, so the cache stays bounded.
+		self.cache.clear();
+		for (key, request) in self.requests.drain() {
+			if let Some(data) = request.data {
+				self.cache.insert(key.0, data);
+			}
+		}
+		self.requests_by_hash.clear();
+		self.queue.clear();
+		self.downloading.clear();
+		self.root = Some(root);
+
+		let mut ready = Vec::new();
+		self.schedule((root, None), NodeKind::Account(Vec::new()), 0, None, &is_known, &mut ready);
+		self.process_ready(ready, &is_known)
+	}
+
+	/// Find nodes to download.
+	pub fn needed_nodes(&mut self, max: usize) -> Vec<H256> {
+		let mut hashes = Vec::new();
+		while hashes.len() < max {
+			let key = match self.queue.pop() {
+				Some((_, key)) => key,
+				None => break,
+			};
+			let needed = self.requests.get(&key).map_or(false, |r| r.data.is_none());
+			if needed && self.downloading.insert(key.0) {
+				hashes.push(key.0);
+			}
+		}
+		hashes
+	}
+
+	/// Mark nodes as no longer being downloaded.
+	pub fn clear_node_download(&mut self, hashes: &[H256]) {
+		for hash in hashes {
+			if !self.downloading.remove(hash) {
+				continue;
+			}
+			for key in self.requests_by_hash.get(hash).into_iter().flat_map(|keys| keys.iter()) {
+				let request = &self.requests[key];
+				if request.data.is_none() {
+					self.queue.push((request.depth, *key));
+				}
+			}
+		}
+	}
+
+	/// Import downloaded nodes. Returns the number of nodes that were requested.
+	pub fn import_nodes<F>(&mut self, nodes: &[Bytes], is_known: F) -> Result<usize, DecoderError>
+		where F: Fn(&H256, Option<&H256>) -> bool
+	{
+		let mut ready = Vec::new();
+		for data in nodes {
+			let hash = keccak(data);
+			self.downloading.remove(&hash);
+			if let Some(keys) = self.requests_by_hash.get(&hash) {
+				for key in keys {
+					if self.requests[key].data.is_none() {
+						ready.push((*key, data.clone()));
+					}
+				}
+			}
+		}
+		let imported = ready.len();
+		self.process_ready(ready, &is_known)?;
+		Ok(imported)
+	}
+
+	/// Take the nodes that are ready to be written to the database, along with the
+	/// address hashes of the accounts found since the last call.
+	pub fn drain_completed(&mut self) -> (Vec<(Option<H256>, Bytes)>, Vec<H256>) {
+		self.completed_keys.clear();
+		(::std::mem::replace(&mut self.completed, Vec::new()), ::std::mem::replace(&mut self.accounts, Vec::new()))
+	}
+
+	/// Check if the whole state has been downloaded.
+	pub fn is_complete(&self) -> bool {
+		self.root.is_some() && self.requests.is_empty()
+	}
+
+	/// Number of nodes handed out so far.
+	pub fn done_nodes(&self) -> usize {
+		self.done_nodes
+	}
+
+	/// Number of nodes known to be missing.
+	pub fn pending_nodes(&self) -> usize {
+		self.requests.len()
+	}
+
+	/// Add a node to the download set. Returns `true` if the node is not in the database yet,
+	/// in which case `parent` has to wait for it.
+	fn schedule<F>(&mut self, key: NodeKey, kind: NodeKind, depth: usize, parent: Option<NodeKey>, is_known: &F, ready: &mut Vec<(NodeKey, Bytes)>) -> bool
+		where F: Fn(&H256, Option<&H256>) -> bool
+	{
+		if let Some(request) = self.requests.get_mut(&key) {
+			request.parents.extend(parent);
+			return true;
+		}
+		if self.completed_keys.contains(&key) || is_known(&key.0, key.1.as_ref()) {
+			return false;
+		}
+		self.requests.insert(key, NodeRequest {
+			kind,
+			depth,
+			data: None,
+			pending_children: 0,
+			parents: parent.into_iter().collect(),
+		});
+		self.requests_by_hash.entry(key.0).or_insert_with(Vec::new).push(key);
+		match self.cache.get(&key.0) {
+			Some(data) => ready.push((key, data.clone())),
+			None => self.queue.push((depth, key)),
+		}
+		true
+	}
+
+	/// Process downloaded nodes, scheduling their children.
+	fn process_ready<F>(&mut self, mut ready: Vec<(NodeKey, Bytes)>, is_known: &F) -> Result<(), DecoderError>
+		where F: Fn(&H256, Option<&H256>) -> bool
+	{
+		while let Some((key, data)) = ready.pop() {
+			let (kind, depth) = match self.requests.get(&key) {
+				Some(ref request) if request.data.is_none() => (request.kind.clone(), request.depth),
+				_ => continue,
+			};
+
+			let mut children = Vec::new();
+			match kind {
+				NodeKind::Code => (),
+				NodeKind::Storage => {
+					let mut refs = Vec::new();
+					walk_node(&data, &mut Vec::new(), &mut refs)?;
+					for r in refs {
+						if let TrieRef::Node(hash, _) = r {
+							children.push(((hash, key.1), NodeKind::Storage));
+						}
+					}
+				},
+				NodeKind::Account(mut path) => {
+					let mut refs = Vec::new();
+					walk_node(&data, &mut path, &mut refs)?;
+					for r in refs {
+						match r {
+							TrieRef::Node(hash, path) => children.push(((hash, None), NodeKind::Account(path))),
+							TrieRef::Value(path, value) => {
+								let account_hash = account_hash(&path)?;
+								let account: BasicAccount = Rlp::new(&value).as_val()?;
+								if account.storage_root != KECCAK_NULL_RLP {
+									children.push(((account.storage_root, Some(account_hash)), NodeKind::Storage));
+								}
+								if account.code_hash != KECCAK_EMPTY {
+									children.push(((account.code_hash, Some(account_hash)), NodeKind::Code));
+								}
+								self.accounts.push(account_hash);
+							},
+						}
+					}
+				},
+			}
+
+			let mut pending = 0;
+			for (child, kind) in children {
+				if self.schedule(child, kind, depth + 1, Some(key), is_known, &mut ready) {
+					pending += 1;
+				}
+			}
+
+			{
+				let request = self.requests.get_mut(&key).expect("request presence is checked above; qed");
+				request.data = Some(data);
+				request.pending_children += pending;
+			}
+			if pending == 0 {
+				self.complete(key);
+			}
+		}
+		Ok(())
+	}
+
+	/// Hand out a node whose children are all handed out, followed by any parents
+	/// this unblocks.
+	fn complete(&mut self, key: NodeKey) {
+		let mut keys = vec![key];
+		while let Some(key) = keys.pop() {
+			let request = match self.requests.remove(&key) {
+				Some(request) => request,
+				None => continue,
+			};
+			let remove_hash = match self.requests_by_hash.get_mut(&key.0) {
+				Some(keys) => {
+					keys.retain(|k| k != &key);
+					keys.is_empty()
+				},
+				None => false,
+			};
+			if remove_hash {
+				self.requests_by_hash.remove(&key.0);
+			}
+			self.completed.push((key.1, request.data.expect("only downloaded nodes are completed; qed")));
+			self.completed_keys.insert(key);
+			self.done_nodes += 1;
+
+			for parent in request.parents {
+				if let Some(parent_request) = self.requests.get_mut(&parent) {
+					parent_request.pending_children -= 1;
+					if parent_request.pending_children == 0 && parent_request.data.is_some() {
+						keys.push(parent);
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Collect child node hashes and leaf values of a trie node, descending into inline nodes.
+fn walk_node(node_rlp: &[u8], path: &mut Vec<u8>, refs: &mut Vec<TrieRef>) -> Result<(), DecoderError> {
+	match Node::decoded(node_rlp)? {
+		Node::Empty => (),
+		Node::Leaf(partial, value) => {
+			let mut key = path.clone();
+			key.extend((0..partial.len()).map(|i| partial.at(i)));
+			refs.push(TrieRef::Value(key, value.to_vec()));
+		},
+		Node::Extension(partial, child) => {
+			let len = path.len();
+			path.extend((0..partial.len()).map(|i| partial.at(i)));
+			walk_child(child, path, refs)?;
+			path.truncate(len);
+		},
+		Node::Branch(children, value) => {
+			for (i, child) in children.iter().enumerate() {
+				path.push(i as u8);
+				walk_child(child, path, refs)?;
+				path.pop();
+			}
+			if let Some(value) = value {
+				refs.push(TrieRef::Value(path.clone(), value.to_vec()));
+			}
+		},
+	}
+	Ok(())
+}
+
+fn walk_child(child_rlp: &[u8], path: &mut Vec<u8>, refs: &mut Vec<TrieRef>) -> Result<(), DecoderError> {
+	match Node::try_decode_hash(child_rlp) {
+		Some(hash) => {
+			refs.push(TrieRef::Node(hash, path.clone()));
+			Ok(())
+		},
+		None => walk_node(child_rlp, path, refs),
+	}
+}
+
+/// Convert the nibble path of an account trie leaf to the account address hash.
+fn account_hash(path: &[u8]) -> Result<H256, DecoderError> {
+	if path.len() != 64 {
+		return Err(DecoderError::Custom("Invalid account key length"));
+	}
+	let mut hash = H256::new();
+	for (i, nibbles) in path.chunks(2).enumerate() {
+		hash[i] = (nibbles[0] << 4) | nibbles[1];
+	}
+	Ok(hash)
+}
+
+#[cfg(test)]
+mod test {
+	use std::collections::HashMap;
+	use bytes::Bytes;
+	use ethcore::basic_account::BasicAccount;
+	use ethereum_types::{H256, U256};
+	use hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+	use hashdb::HashDB;
+	use memorydb::MemoryDB;
+	use trie::{SecTrieDBMut, TrieMut};
+	use super::*;
+
+	/// State with a plain account trie and separate databases for each account storage and code.
+	struct TestState {
+		accounts: MemoryDB,
+		storage: HashMap<H256, MemoryDB>,
+		root: H256,
+	}
+
+	impl TestState {
+		fn new() -> TestState {
+			TestState {
+				accounts: MemoryDB::new(),
+				storage: HashMap::new(),
+				root: KECCAK_NULL_RLP,
+			}
+		}
+
+		fn insert_account(&mut self, seed: u64, storage: &[(u64, u64)], code: Option<Bytes>) {
+			let address_hash = keccak(H256::from(seed));
+			let mut account_db = self.storage.remove(&address_hash).unwrap_or_else(MemoryDB::new);
+			let mut storage_root = KECCAK_NULL_RLP;
+			{
+				let mut trie = SecTrieDBMut::new(&mut account_db, &mut storage_root);
+				for &(k, v) in storage {
+					trie.insert(&H256::from(k), &::rlp::encode(&U256::from(v))).unwrap();
+				}
+			}
+			let code_hash = match code {
+				Some(code) => account_db.insert(&code),
+				None => KECCAK_EMPTY,
+			};
+			self.storage.insert(address_hash, account_db);
+
+			let account = BasicAccount {
+				nonce: seed.into(),
+				balance: (seed * 1000).into(),
+				storage_root,
+				code_hash,
+			};
+			let mut trie = if self.root == KECCAK_NULL_RLP {
+				SecTrieDBMut::new(&mut self.accounts, &mut self.root)
+			} else {
+				SecTrieDBMut::from_existing(&mut self.accounts, &mut self.root).unwrap()
+			};
+			trie.insert(&H256::from(seed), &::rlp::encode(&account)).unwrap();
+		}
+
+		fn node_data(&self, hash: &H256) -> Option<Bytes> {
+			self.accounts.get(hash)
+				.or_else(|| self.storage.values().filter_map(|db| db.get(hash)).next())
+				.map(|v| v.into_vec())
+		}
+	}
+
+	/// Download the whole state, writing completed nodes to `local`. Returns the number of requested nodes.
+	fn download(sync: &mut StateSync, remote: &TestState, local: &mut HashMap<NodeKey, Bytes>) -> usize {
+		let mut requested = 0;
+		loop {
+			let hashes = sync.needed_nodes(16);
+			if hashes.is_empty() {
+				break;
+			}
+			requested += hashes.len();
+			let nodes: Vec<_> = hashes.iter().map(|h| remote.node_data(h).unwrap()).collect();
+			let known = local.clone();
+			sync.import_nodes(&nodes, |h, a| known.contains_key(&(*h, a.cloned()))).unwrap();
+			for (owner, data) in sync.drain_completed().0 {
+				local.insert((keccak(&data), owner), data);
+			}
+		}
+		requested
+	}
+
+	fn test_state() -> TestState {
+		let mut state = TestState::new();
+		for i in 0..100 {
+			let storage: Vec<_> = (0..(i % 10)).map(|k| (k, i * k + 1)).collect();
+			let code = if i % 3 == 0 { Some(vec![i as u8; 40]) } else { None };
+			state.insert_account(i, &storage, code);
+		}
+		state
+	}
+
+	#[test]
+	fn downloads_complete_state() {
+		let state = test_state();
+		let mut sync = StateSync::new();
+		let mut local = HashMap::new();
+		sync.reset_to(state.root, |_, _| false).unwrap();
+		assert!(!sync.is_complete());
+
+		download(&mut sync, &state, &mut local);
+
+		assert!(sync.is_complete());
+		assert!(local.contains_key(&(state.root, None)));
+		for (address_hash, db) in &state.storage {
+			for (hash, _) in db.keys() {
+				assert!(local.contains_key(&(hash, Some(*address_hash))), "missing node {} of {}", hash, address_hash);
+			}
+		}
+		assert_eq!(sync.done_nodes(), local.len());
+	}
+
+	#[test]
+	fn parents_are_completed_after_children() {
+		let state = test_state();
+		let mut sync = StateSync::new();
+		sync.reset_to(state.root, |_, _| false).unwrap();
+
+		let hashes = sync.needed_nodes(1);
+		assert_eq!(hashes, vec![state.root]);
+		sync.import_nodes(&[state.node_data(&state.root).unwrap()], |_, _| false).unwrap();
+		// the root can't be completed until all the other nodes are downloaded.
+		assert!(sync.drain_completed().0.is_empty());
+		assert!(sync.pending_nodes() > 1);
+	}
+
+	#[test]
+	fn reuses_downloaded_nodes_when_pivot_moves() {
+		let mut state = test_state();
+		let mut sync = StateSync::new();
+		let mut local = HashMap::new();
+		sync.reset_to(state.root, |_, _| false).unwrap();
+		let total = download(&mut sync, &state, &mut local);
+
+		state.insert_account(5, &[(100, 100)], None);
+		state.insert_account(1000, &[], Some(vec![1, 2, 3]));
+		let known = local.clone();
+		sync.reset_to(state.root, |h, a| known.contains_key(&(*h, a.cloned()))).unwrap();
+		let healed = download(&mut sync, &state, &mut local);
+
+		assert!(sync.is_complete());
+		assert!(healed > 0);
+		assert!(healed < total / 4);
+	}
+
+	#[test]
+	fn forgets_nodes_of_older_pivots() {
+		let state = test_state();
+		let mut other = TestState::new();
+		other.insert_account(1, &[], None);
+		let mut sync = StateSync::new();
+		sync.reset_to(state.root, |_, _| false).unwrap();
+		sync.needed_nodes(1);
+		sync.import_nodes(&[state.node_data(&state.root).unwrap()], |_, _| false).unwrap();
+
+		sync.reset_to(other.root, |_, _| false).unwrap();
+		assert!(sync.cache.contains_key(&state.root));
+		sync.reset_to(KECCAK_EMPTY, |_, _| false).unwrap();
+		assert!(sync.cache.is_empty());
+	}
+
+	#[test]
+	fn rerequests_cleared_downloads() {
+		let state = test_state();
+		let mut sync = StateSync::new();
+		sync.reset_to(state.root, |_, _| false).unwrap();
+
+		let hashes = sync.needed_nodes(1);
+		assert!(sync.needed_nodes(1).is_empty());
+		sync.clear_node_download(&hashes);
+		assert_eq!(sync.needed_nodes(1), hashes);
+	}
+
+	#[test]
+	fn ignores_unrequested_nodes() {
+		let state = test_state();
+		let mut sync = StateSync::new();
+		sync.reset_to(state.root, |_, _| false).unwrap();
+		assert_eq!(sync.import_nodes(&[vec![0xc0]], |_, _| false).unwrap(), 0);
+	}
+}
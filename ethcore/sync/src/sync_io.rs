@@ -24,7 +24,6 @@ use parking_lot::RwLock;
 
 /// IO interface for the syncing handler.
 /// Provides peer connection management and an interface to the blockchain client.
-// TODO: ratings
 pub trait SyncIo {
 	/// Disable a peer
 	fn disable_peer(&mut self, peer_id: PeerId);
@@ -58,6 +57,10 @@ pub trait SyncIo {
 	fn is_expired(&self) -> bool;
 	/// Return sync overlay
 	fn chain_overlay(&self) -> &RwLock<HashMap<BlockNumber, Bytes>>;
+	/// Returns the persistent reputation of a peer
+	fn peer_reputation(&self, peer_id: PeerId) -> i32;
+	/// Adjust the persistent reputation of a peer
+	fn adjust_peer_reputation(&mut self, peer_id: PeerId, delta: i32);
 }
 
 /// Wraps `NetworkContext` and the blockchain client
@@ -135,6 +138,14 @@ impl<'s> SyncIo for NetSyncIo<'s> {
 	fn peer_info(&self, peer_id: PeerId) -> String {
 		self.network.peer_client_version(peer_id)
 	}
+
+	fn peer_reputation(&self, peer_id: PeerId) -> i32 {
+		self.network.peer_reputation(peer_id)
+	}
+
+	fn adjust_peer_reputation(&mut self, peer_id: PeerId, delta: i32) {
+		self.network.adjust_peer_reputation(peer_id, delta);
+	}
 }
 
 
@@ -32,7 +32,7 @@ use ethcore::miner::Miner;
 use sync_io::SyncIo;
 use io::{IoChannel, IoContext, IoHandler};
 use api::WARP_SYNC_PROTOCOL_ID;
-use chain::{ChainSync, ETH_PROTOCOL_VERSION_63, PAR_PROTOCOL_VERSION_3};
+use chain::{ChainSync, ETH_PROTOCOL_VERSION_63, PAR_PROTOCOL_VERSION_4};
 use SyncConfig;
 use private_tx::SimplePrivateTxHandler;
 
@@ -56,6 +56,7 @@ pub struct TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	pub to_disconnect: HashSet<PeerId>,
 	pub packets: Vec<TestPacket>,
 	pub peers_info: HashMap<PeerId, String>,
+	pub reputations: HashMap<PeerId, i32>,
 	overlay: RwLock<HashMap<BlockNumber, Bytes>>,
 }
 
@@ -70,6 +71,7 @@ impl<'p, C> TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 			overlay: RwLock::new(HashMap::new()),
 			packets: Vec::new(),
 			peers_info: HashMap::new(),
+			reputations: HashMap::new(),
 		}
 	}
 }
@@ -138,12 +140,20 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	}
 
 	fn protocol_version(&self, protocol: &ProtocolId, peer_id: PeerId) -> u8 {
-		if protocol == &WARP_SYNC_PROTOCOL_ID { PAR_PROTOCOL_VERSION_3.0 } else { self.eth_protocol_version(peer_id) }
+		if protocol == &WARP_SYNC_PROTOCOL_ID { PAR_PROTOCOL_VERSION_4.0 } else { self.eth_protocol_version(peer_id) }
 	}
 
 	fn chain_overlay(&self) -> &RwLock<HashMap<BlockNumber, Bytes>> {
 		&self.overlay
 	}
+
+	fn peer_reputation(&self, peer_id: PeerId) -> i32 {
+		self.reputations.get(&peer_id).cloned().unwrap_or(0)
+	}
+
+	fn adjust_peer_reputation(&mut self, peer_id: PeerId, delta: i32) {
+		*self.reputations.entry(peer_id).or_insert(0) += delta;
+	}
 }
 
 /// Mock for emulution of async run of new blocks
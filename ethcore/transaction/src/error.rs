@@ -81,6 +81,8 @@ pub enum Error {
 	InvalidRlp(String),
 	/// Inavlid IOLITE metadata
 	InvalidMetadata(String),
+	/// Transaction condition can't be checked by this node.
+	UnsupportedCondition,
 }
 
 impl From<ethkey::Error> for Error {
@@ -122,6 +124,7 @@ impl fmt::Display for Error {
 			TooBig => "Transaction too big".into(),
 			InvalidRlp(ref err) => format!("Transaction has invalid RLP structure: {}.", err),
 			InvalidMetadata(ref err) => format!("Transaction has invalid metadata: {}.", err),
+			UnsupportedCondition => "Transaction condition is not supported".into(),
 		};
 
 		f.write_fmt(format_args!("Transaction error ({})", msg))
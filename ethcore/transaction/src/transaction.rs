@@ -78,6 +78,27 @@ pub enum Condition {
 	Number(BlockNumber),
 	/// Valid at this unix time or later.
 	Timestamp(u64),
+	/// Valid once given transaction has at least `count` confirmations.
+	Confirmations {
+		/// Hash of the transaction to wait for.
+		transaction: H256,
+		/// Required number of confirmations (the including block counts as the first one).
+		count: u64,
+	},
+	/// Valid once balance of given account reaches given value.
+	MinBalance {
+		/// Account to check.
+		address: Address,
+		/// Minimal balance of the account.
+		value: U256,
+	},
+	/// Valid once calling given contract with given data returns `true`.
+	Call {
+		/// Contract to call.
+		to: Address,
+		/// Call data.
+		data: Bytes,
+	},
 }
 
 /// Replay protection logic for v part of transaction's signature
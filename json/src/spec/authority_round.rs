@@ -16,16 +16,44 @@
 
 //! Authority params deserialization.
 
-use ethereum_types::Address;
+use std::collections::BTreeMap;
+use ethereum_types::{Address, U256};
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
 use uint::Uint;
 use super::ValidatorSet;
 
+/// Step duration can be specified either as a `Uint` (in seconds), in which case it will be
+/// constant, or as a list of pairs consisting of a timestamp of type `Uint` and a duration, in
+/// which case the duration of a step will be determined by a mapping arising from that list.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum StepDuration {
+	/// Duration of all steps.
+	Single(Uint),
+	/// Step duration transitions: a mapping of timestamp to step durations.
+	/// Must contain an entry for timestamp 0.
+	Transitions(BTreeMap<Uint, Uint>),
+}
+
+fn validate_step_duration<'de, D>(d: D) -> Result<StepDuration, D::Error> where D: Deserializer<'de> {
+	let value = StepDuration::deserialize(d)?;
+
+	if let StepDuration::Transitions(ref transitions) = value {
+		if !transitions.contains_key(&Uint(U256::from(0))) {
+			return Err(Error::custom("step duration transitions must contain an entry for timestamp 0"))
+		}
+	}
+
+	Ok(value)
+}
+
 /// Authority params deserialization.
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct AuthorityRoundParams {
-	/// Block duration, in seconds.
-	#[serde(rename="stepDuration")]
-	pub step_duration: Uint,
+	/// Block duration, in seconds, or a map of timestamps to block durations.
+	#[serde(rename="stepDuration", deserialize_with="validate_step_duration")]
+	pub step_duration: StepDuration,
 	/// Valid authorities
 	pub validators: ValidatorSet,
 	/// Starting step. Determined automatically if not specified.
@@ -63,6 +91,10 @@ pub struct AuthorityRoundParams {
 	/// Maximum number of accepted empty steps.
 	#[serde(rename="maximumEmptySteps")]
 	pub maximum_empty_steps: Option<Uint>,
+	/// Randomness contract address. If set, validators take part in its
+	/// commit-reveal scheme to produce an on-chain random seed.
+	#[serde(rename="randomnessContractAddress")]
+	pub randomness_contract_address: Option<Address>,
 }
 
 /// Authority engine deserialization.
@@ -79,7 +111,7 @@ mod tests {
 	use serde_json;
 	use hash::Address;
 	use spec::validator_set::ValidatorSet;
-	use spec::authority_round::AuthorityRound;
+	use spec::authority_round::{AuthorityRound, StepDuration};
 
 	#[test]
 	fn authority_round_deserialization() {
@@ -98,7 +130,7 @@ mod tests {
 		}"#;
 
 		let deserialized: AuthorityRound = serde_json::from_str(s).unwrap();
-		assert_eq!(deserialized.params.step_duration, Uint(U256::from(0x02)));
+		assert_eq!(deserialized.params.step_duration, StepDuration::Single(Uint(U256::from(0x02))));
 		assert_eq!(deserialized.params.validators, ValidatorSet::List(vec![Address(H160::from("0xc6d9d2cd449a754c494264e1809c50e34d64562b"))]));
 		assert_eq!(deserialized.params.start_step, Some(Uint(U256::from(24))));
 		assert_eq!(deserialized.params.immediate_transitions, None);
@@ -106,4 +138,58 @@ mod tests {
 		assert_eq!(deserialized.params.maximum_uncle_count, Some(Uint(5.into())));
 
 	}
+
+	#[test]
+	fn authority_round_randomness_contract_deserialization() {
+		let s = r#"{
+			"params": {
+				"stepDuration": "0x02",
+				"validators": {
+					"list" : ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
+				},
+				"randomnessContractAddress": "0x0000000000000000000000000000000000000042"
+			}
+		}"#;
+
+		let deserialized: AuthorityRound = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.params.randomness_contract_address, Some(Address(H160::from("0x0000000000000000000000000000000000000042"))));
+	}
+
+	#[test]
+	fn authority_round_step_duration_transitions_deserialization() {
+		let s = r#"{
+			"params": {
+				"stepDuration": {
+					"0": 5,
+					"0x5c8d3a00": 2
+				},
+				"validators": {
+					"list" : ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
+				}
+			}
+		}"#;
+
+		let deserialized: AuthorityRound = serde_json::from_str(s).unwrap();
+		let transitions = vec![
+			(Uint(U256::from(0)), Uint(U256::from(5))),
+			(Uint(U256::from(0x5c8d3a00u64)), Uint(U256::from(2))),
+		].into_iter().collect();
+		assert_eq!(deserialized.params.step_duration, StepDuration::Transitions(transitions));
+	}
+
+	#[test]
+	fn authority_round_step_duration_transitions_without_genesis_entry() {
+		let s = r#"{
+			"params": {
+				"stepDuration": {
+					"0x5c8d3a00": 2
+				},
+				"validators": {
+					"list" : ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
+				}
+			}
+		}"#;
+
+		assert!(serde_json::from_str::<AuthorityRound>(s).is_err());
+	}
 }
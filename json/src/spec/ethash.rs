@@ -47,6 +47,13 @@ pub struct EthashParams {
 	/// Reward per block in wei.
 	#[serde(rename="blockReward")]
 	pub block_reward: Option<Uint>,
+	/// Block at which the block reward contract should start being used.
+	#[serde(rename="blockRewardContractTransition")]
+	pub block_reward_contract_transition: Option<Uint>,
+	/// Block reward contract address (setting the block reward contract
+	/// overrides all other block reward parameters).
+	#[serde(rename="blockRewardContractAddress")]
+	pub block_reward_contract_address: Option<Address>,
 
 	/// See main EthashParams docs.
 	#[serde(rename="daoHardforkTransition")]
@@ -163,6 +170,8 @@ mod tests {
 				"durationLimit": "0x0d",
 				"homesteadTransition": "0x42",
 				"blockReward": "0x100",
+				"blockRewardContractTransition": "0x4",
+				"blockRewardContractAddress": "0x0000000000000000000000000000000000000042",
 				"daoHardforkTransition": "0x08",
 				"daoHardforkBeneficiary": "0xabcabcabcabcabcabcabcabcabcabcabcabcabca",
 				"daoHardforkAccounts": [
@@ -209,6 +218,8 @@ mod tests {
 				duration_limit: Some(Uint(U256::from(0x0d))),
 				homestead_transition: Some(Uint(U256::from(0x42))),
 				block_reward: Some(Uint(U256::from(0x100))),
+				block_reward_contract_transition: Some(Uint(U256::from(0x4))),
+				block_reward_contract_address: Some(Address(H160::from("0x0000000000000000000000000000000000000042"))),
 				dao_hardfork_transition: Some(Uint(U256::from(0x08))),
 				dao_hardfork_beneficiary: Some(Address(H160::from("0xabcabcabcabcabcabcabcabcabcabcabcabcabca"))),
 				dao_hardfork_accounts: Some(vec![
@@ -278,6 +289,8 @@ mod tests {
 				duration_limit: None,
 				homestead_transition: None,
 				block_reward: None,
+				block_reward_contract_transition: None,
+				block_reward_contract_address: None,
 				dao_hardfork_transition: None,
 				dao_hardfork_beneficiary: None,
 				dao_hardfork_accounts: None,
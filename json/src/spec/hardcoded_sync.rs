@@ -16,6 +16,9 @@
 
 //! Spec hardcoded synchronization deserialization for the light client.
 
+use std::io::Read;
+use serde_json;
+use serde_json::Error;
 use hash::H256;
 use uint::Uint;
 
@@ -30,6 +33,16 @@ pub struct HardcodedSync {
 	/// Ordered trie roots of blocks before and including `header`.
 	#[serde(rename="CHTs")]
 	pub chts: Vec<H256>,
+	/// Ordered roots of the bloom-bit sections covering the same blocks as the CHTs.
+	#[serde(rename="bloomBits", default)]
+	pub bloom_bits: Vec<H256>,
+}
+
+impl HardcodedSync {
+	/// Loads hardcoded sync parameters (e.g. a light client checkpoint) from json.
+	pub fn load<R>(reader: R) -> Result<Self, Error> where R: Read {
+		serde_json::from_reader(reader)
+	}
 }
 
 #[cfg(test)]
@@ -57,7 +70,8 @@ mod tests {
 			chts: vec![
 				H256(Eth256::from("0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa")),
 				H256(Eth256::from("0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544")),
-			]
+			],
+			bloom_bits: vec![],
 		});
 	}
 }
@@ -17,6 +17,7 @@
 //! Tendermint params deserialization.
 
 use uint::Uint;
+use hash::Address;
 use super::ValidatorSet;
 
 /// Tendermint params deserialization.
@@ -39,6 +40,13 @@ pub struct TendermintParams {
 	/// Reward per block.
 	#[serde(rename="blockReward")]
 	pub block_reward: Option<Uint>,
+	/// Block at which the block reward contract should start being used.
+	#[serde(rename="blockRewardContractTransition")]
+	pub block_reward_contract_transition: Option<Uint>,
+	/// Block reward contract address (setting the block reward contract
+	/// overrides the static block reward definition).
+	#[serde(rename="blockRewardContractAddress")]
+	pub block_reward_contract_address: Option<Address>,
 }
 
 /// Tendermint engine deserialization.
@@ -51,8 +59,9 @@ pub struct Tendermint {
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use ethereum_types::H160;
+	use ethereum_types::{H160, U256};
 	use hash::Address;
+	use uint::Uint;
 	use spec::tendermint::Tendermint;
 	use spec::validator_set::ValidatorSet;
 
@@ -62,12 +71,16 @@ mod tests {
 			"params": {
 				"validators": {
 					"list": ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
-				}
+				},
+				"blockRewardContractTransition": "0x10",
+				"blockRewardContractAddress": "0x0000000000000000000000000000000000000042"
 			}
 		}"#;
 
 		let deserialized: Tendermint = serde_json::from_str(s).unwrap();
 		let vs = ValidatorSet::List(vec![Address(H160::from("0xc6d9d2cd449a754c494264e1809c50e34d64562b"))]);
 		assert_eq!(deserialized.params.validators, vs);
+		assert_eq!(deserialized.params.block_reward_contract_transition, Some(Uint(U256::from(0x10))));
+		assert_eq!(deserialized.params.block_reward_contract_address, Some(Address(H160::from("0x0000000000000000000000000000000000000042"))));
 	}
 }
@@ -14,11 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Manages local node data: pending local transactions, sync security level
+//! Manages local node data: pending local transactions, transaction pool journal, sync security level
 
 use std::sync::Arc;
 use std::fmt;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use transaction::{
 	SignedTransaction, PendingTransaction, UnverifiedTransaction,
@@ -49,6 +49,7 @@ extern crate ethkey;
 extern crate kvdb_memorydb;
 
 const LOCAL_TRANSACTIONS_KEY: &'static [u8] = &*b"LOCAL_TXS";
+const POOL_TRANSACTIONS_KEY: &'static [u8] = &*b"POOL_TXS";
 
 const UPDATE_TIMER: ::io::TimerToken = 0;
 const UPDATE_TIMEOUT: Duration = Duration::from_secs(15 * 60); // once every 15 minutes.
@@ -71,10 +72,14 @@ impl fmt::Display for Error {
 	}
 }
 
+// hashes, addresses and values are stored as big-endian bytes.
 #[derive(Serialize, Deserialize)]
 enum Condition {
 	Number(::ethcore::header::BlockNumber),
 	Timestamp(u64),
+	Confirmations { transaction: Vec<u8>, count: u64 },
+	MinBalance { address: Vec<u8>, value: Vec<u8> },
+	Call { to: Vec<u8>, data: Vec<u8> },
 }
 
 impl From<TransactionCondition> for Condition {
@@ -82,16 +87,46 @@ impl From<TransactionCondition> for Condition {
 		match cond {
 			TransactionCondition::Number(num) => Condition::Number(num),
 			TransactionCondition::Timestamp(tm) => Condition::Timestamp(tm),
+			TransactionCondition::Confirmations { transaction, count } => Condition::Confirmations {
+				transaction: transaction.to_vec(),
+				count,
+			},
+			TransactionCondition::MinBalance { address, value } => {
+				let mut bytes = [0u8; 32];
+				value.to_big_endian(&mut bytes);
+				Condition::MinBalance {
+					address: address.to_vec(),
+					value: bytes.to_vec(),
+				}
+			},
+			TransactionCondition::Call { to, data } => Condition::Call {
+				to: to.to_vec(),
+				data,
+			},
 		}
 	}
 }
 
-impl Into<TransactionCondition> for Condition {
-	fn into(self) -> TransactionCondition {
-		match self {
+impl Condition {
+	// returns `None` if stored hash, address or value has invalid length.
+	fn into_condition(self) -> Option<TransactionCondition> {
+		Some(match self {
 			Condition::Number(num) => TransactionCondition::Number(num),
 			Condition::Timestamp(tm) => TransactionCondition::Timestamp(tm),
-		}
+			Condition::Confirmations { ref transaction, count } if transaction.len() == 32 => TransactionCondition::Confirmations {
+				transaction: transaction[..].into(),
+				count,
+			},
+			Condition::MinBalance { ref address, ref value } if address.len() == 20 && value.len() <= 32 => TransactionCondition::MinBalance {
+				address: address[..].into(),
+				value: value[..].into(),
+			},
+			Condition::Call { ref to, ref data } if to.len() == 20 => TransactionCondition::Call {
+				to: to[..].into(),
+				data: data.clone(),
+			},
+			_ => return None,
+		})
 	}
 }
 
@@ -112,8 +147,19 @@ impl TransactionEntry {
 		};
 
 		let hash = tx.hash();
+		let condition = match self.condition {
+			Some(condition) => match condition.into_condition() {
+				Some(condition) => Some(condition),
+				None => {
+					warn!(target: "local_store", "Invalid condition of persistent transaction: {}", hash);
+					return None
+				}
+			},
+			None => None,
+		};
+
 		match SignedTransaction::new(tx) {
-			Ok(tx) => Some(PendingTransaction::new(tx, self.condition.map(Into::into))),
+			Ok(tx) => Some(PendingTransaction::new(tx, condition)),
 			Err(_) => {
 				warn!(target: "local_store", "Bad signature on persistent transaction: {}", hash);
 				return None
@@ -131,10 +177,77 @@ impl From<PendingTransaction> for TransactionEntry {
 	}
 }
 
+/// Priority of a transaction in the pool.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Priority {
+	/// Transaction of local origin.
+	Local,
+	/// Transaction from a retracted block.
+	Retracted,
+	/// Transaction received over the network.
+	Regular,
+}
+
+/// A transaction from the pool.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PoolTransaction {
+	/// The transaction.
+	pub pending: PendingTransaction,
+	/// Time the transaction was inserted to the pool.
+	pub inserted_at: SystemTime,
+	/// Priority of the transaction.
+	pub priority: Priority,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PoolTransactionEntry {
+	transaction: TransactionEntry,
+	sender: String,
+	inserted_at: u64,
+	priority: Priority,
+}
+
+impl PoolTransactionEntry {
+	fn into_pool_transaction(self, max_age: Duration) -> Option<PoolTransaction> {
+		let inserted_at = UNIX_EPOCH + Duration::from_secs(self.inserted_at);
+		match SystemTime::now().duration_since(inserted_at) {
+			Ok(age) if age > max_age => return None,
+			_ => {},
+		}
+
+		let (sender, priority) = (self.sender, self.priority);
+		let pending = self.transaction.into_pending()?;
+		if format!("{:x}", pending.sender()) != sender {
+			warn!(target: "local_store", "Sender mismatch of journaled transaction: {}", pending.hash());
+			return None
+		}
+
+		Some(PoolTransaction {
+			pending,
+			inserted_at,
+			priority,
+		})
+	}
+}
+
+impl From<PoolTransaction> for PoolTransactionEntry {
+	fn from(tx: PoolTransaction) -> Self {
+		PoolTransactionEntry {
+			sender: format!("{:x}", tx.pending.sender()),
+			inserted_at: tx.inserted_at.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+			priority: tx.priority,
+			transaction: tx.pending.into(),
+		}
+	}
+}
+
 /// Something which can provide information about the local node.
 pub trait NodeInfo: Send + Sync {
 	/// Get all pending transactions of local origin.
 	fn pending_transactions(&self) -> Vec<PendingTransaction>;
+
+	/// Get all transactions in the pool to journal, `None` if journaling is disabled.
+	fn pool_transactions(&self) -> Option<Vec<PoolTransaction>>;
 }
 
 /// Create a new local data store, given a database, a column to write to, and a node.
@@ -173,6 +286,22 @@ impl<T: NodeInfo> LocalDataStore<T> {
 		}
 	}
 
+	/// Attempt to read the journaled transaction pool out of the local store.
+	/// Transactions inserted to the pool more than `max_age` ago are dropped.
+	pub fn pool_transactions(&self, max_age: Duration) -> Result<Vec<PoolTransaction>, Error> {
+		if let Some(val) = self.db.get(self.col, POOL_TRANSACTIONS_KEY).map_err(Error::Database)? {
+			let pool_txs: Vec<_> = ::serde_json::from_slice::<Vec<PoolTransactionEntry>>(&val)
+				.map_err(Error::Json)?
+				.into_iter()
+				.filter_map(|entry| entry.into_pool_transaction(max_age))
+				.collect();
+
+			Ok(pool_txs)
+		} else {
+			Ok(Vec::new())
+		}
+	}
+
 	/// Update the entries in the database.
 	pub fn update(&self) -> Result<(), Error> {
 		trace!(target: "local_store", "Updating local store entries.");
@@ -182,24 +311,34 @@ impl<T: NodeInfo> LocalDataStore<T> {
 			.map(Into::into)
 			.collect();
 
-		self.write_txs(&local_entries)
+		let pool_entries: Option<Vec<PoolTransactionEntry>> = self.node.pool_transactions()
+			.map(|txs| txs.into_iter().map(Into::into).collect());
+
+		self.write_txs(&local_entries, pool_entries.as_ref().map(|txs| &txs[..]))
 	}
 
 	/// Clear data in this column.
 	pub fn clear(&self) -> Result<(), Error> {
 		trace!(target: "local_store", "Clearing local store entries.");
 
-		self.write_txs(&[])
+		self.write_txs(&[], Some(&[]))
 	}
 
-	// helper for writing a vector of transaction entries to disk.
-	fn write_txs(&self, txs: &[TransactionEntry]) -> Result<(), Error> {
+	// helper for writing vectors of transaction entries to disk.
+	// the pool journal is left untouched if `pool_txs` is `None`.
+	fn write_txs(&self, txs: &[TransactionEntry], pool_txs: Option<&[PoolTransactionEntry]>) -> Result<(), Error> {
 		let mut batch = self.db.transaction();
 
 		let local_json = ::serde_json::to_value(txs).map_err(Error::Json)?;
 		let json_str = format!("{}", local_json);
-
 		batch.put_vec(self.col, LOCAL_TRANSACTIONS_KEY, json_str.into_bytes());
+
+		if let Some(pool_txs) = pool_txs {
+			let pool_json = ::serde_json::to_value(pool_txs).map_err(Error::Json)?;
+			let json_str = format!("{}", pool_json);
+			batch.put_vec(self.col, POOL_TRANSACTIONS_KEY, json_str.into_bytes());
+		}
+
 		self.db.write(batch).map_err(Error::Database)
 	}
 }
@@ -230,9 +369,10 @@ impl<T: NodeInfo> Drop for LocalDataStore<T> {
 
 #[cfg(test)]
 mod tests {
-	use super::NodeInfo;
+	use super::{NodeInfo, PoolTransaction, Priority, TransactionEntry, Condition as StoredCondition};
 
 	use std::sync::Arc;
+	use std::time::{Duration, SystemTime, UNIX_EPOCH};
 	use transaction::{Transaction, Condition, PendingTransaction};
 	use ethkey::{Brain, Generator};
 
@@ -242,6 +382,13 @@ mod tests {
 	struct Dummy(Vec<PendingTransaction>);
 	impl NodeInfo for Dummy {
 		fn pending_transactions(&self) -> Vec<PendingTransaction> { self.0.clone() }
+		fn pool_transactions(&self) -> Option<Vec<PoolTransaction>> { None }
+	}
+
+	struct DummyPool(Vec<PoolTransaction>);
+	impl NodeInfo for DummyPool {
+		fn pending_transactions(&self) -> Vec<PendingTransaction> { Vec::new() }
+		fn pool_transactions(&self) -> Option<Vec<PoolTransaction>> { Some(self.0.clone()) }
 	}
 
 	#[test]
@@ -269,6 +416,9 @@ mod tests {
 			let signed = tx.sign(keypair.secret(), None);
 			let condition = match nonce {
 				5 => Some(Condition::Number(100_000)),
+				6 => Some(Condition::Confirmations { transaction: 5.into(), count: 12 }),
+				7 => Some(Condition::MinBalance { address: 10.into(), value: 1_000_000.into() }),
+				8 => Some(Condition::Call { to: 10.into(), data: vec![1, 2, 3] }),
 				_ => None,
 			};
 
@@ -328,4 +478,61 @@ mod tests {
 			assert_eq!(loaded, transactions);
 		}
 	}
+
+	#[test]
+	fn skips_transactions_with_invalid_conditions() {
+		let keypair = Brain::new("abcd".into()).generate().unwrap();
+		let signed = Transaction::default().sign(keypair.secret(), None);
+		let entry = |condition| TransactionEntry {
+			rlp_bytes: ::rlp::encode(&signed).into_vec(),
+			condition: Some(condition),
+		};
+
+		assert!(entry(StoredCondition::Call { to: vec![1; 20], data: vec![] }).into_pending().is_some());
+		assert!(entry(StoredCondition::Call { to: vec![1; 19], data: vec![] }).into_pending().is_none());
+		assert!(entry(StoredCondition::Confirmations { transaction: vec![1; 33], count: 1 }).into_pending().is_none());
+		assert!(entry(StoredCondition::MinBalance { address: vec![1; 20], value: vec![1; 33] }).into_pending().is_none());
+	}
+
+	#[test]
+	fn journals_pool_and_drops_stale() {
+		let keypair = Brain::new("abcd".into()).generate().unwrap();
+		let now = UNIX_EPOCH + Duration::from_secs(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+		let transactions: Vec<_> = (0..4u64).map(|nonce| {
+			let mut tx = Transaction::default();
+			tx.nonce = nonce.into();
+
+			PoolTransaction {
+				pending: PendingTransaction::new(tx.sign(keypair.secret(), None), None),
+				inserted_at: now - Duration::from_secs(nonce * 600),
+				priority: match nonce {
+					0 => Priority::Local,
+					_ => Priority::Regular,
+				},
+			}
+		}).collect();
+
+		let db = Arc::new(::kvdb_memorydb::create(0));
+		{
+			// nothing written yet, will journal the pool.
+			let store = super::create(db.clone(), None, DummyPool(transactions.clone()));
+			assert_eq!(store.pool_transactions(Duration::from_secs(3600)).unwrap(), vec![])
+		}
+		{
+			// transactions older than 15 minutes are dropped.
+			let store = super::create(db.clone(), None, Dummy(vec![]));
+			assert_eq!(store.pending_transactions().unwrap(), vec![]);
+			assert_eq!(store.pool_transactions(Duration::from_secs(900)).unwrap(), transactions[..2].to_vec());
+		}
+		{
+			// journaling disabled above, the journal is left untouched. will journal an empty pool.
+			let store = super::create(db.clone(), None, DummyPool(vec![]));
+			assert_eq!(store.pool_transactions(Duration::from_secs(900)).unwrap(), transactions[..2].to_vec());
+		}
+		{
+			// journal cleared.
+			let store = super::create(db.clone(), None, Dummy(vec![]));
+			assert_eq!(store.pool_transactions(Duration::from_secs(3600)).unwrap(), vec![])
+		}
+	}
 }
@@ -16,6 +16,7 @@
 
 //! Auto-updates minimal gas price requirement.
 
+use std::cmp;
 use std::time::{Instant, Duration};
 
 use ansi_term::Colour;
@@ -63,6 +64,70 @@ impl GasPriceCalibrator {
 	}
 }
 
+/// Options for the gas price oracle.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GasPriceOracleOptions {
+	/// Number of recent blocks whose included transactions are sampled.
+	pub blocks: usize,
+	/// Percentile of sampled gas prices suggested for slow inclusion.
+	pub slow_percentile: usize,
+	/// Percentile of sampled gas prices suggested for standard inclusion.
+	pub standard_percentile: usize,
+	/// Percentile of sampled gas prices suggested for fast inclusion.
+	pub fast_percentile: usize,
+}
+
+impl Default for GasPriceOracleOptions {
+	fn default() -> Self {
+		GasPriceOracleOptions {
+			blocks: 100,
+			slow_percentile: 25,
+			standard_percentile: 50,
+			fast_percentile: 90,
+		}
+	}
+}
+
+impl GasPriceOracleOptions {
+	/// Suggest gas prices given sorted gas prices of the sampled transactions.
+	/// Returns `None` if there are no samples.
+	pub fn tiers(&self, sorted_prices: &[U256]) -> Option<GasPriceTiers> {
+		if sorted_prices.is_empty() {
+			return None;
+		}
+
+		// nearest-rank percentile.
+		let percentile = |pct: usize| {
+			let rank = (pct * sorted_prices.len() + 99) / 100;
+			sorted_prices[cmp::max(cmp::min(rank, sorted_prices.len()), 1) - 1]
+		};
+
+		Some(GasPriceTiers {
+			slow: percentile(self.slow_percentile),
+			standard: percentile(self.standard_percentile),
+			fast: percentile(self.fast_percentile),
+		})
+	}
+}
+
+/// Gas prices suggested by the oracle.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GasPriceTiers {
+	/// Gas price for slow inclusion.
+	pub slow: U256,
+	/// Gas price for standard inclusion.
+	pub standard: U256,
+	/// Gas price for fast inclusion.
+	pub fast: U256,
+}
+
+/// The gas price oracle variant for a `GasPricer`.
+#[derive(Debug, PartialEq)]
+pub struct GasPriceOracle {
+	options: GasPriceOracleOptions,
+	minimal_gas_price: U256,
+}
+
 /// Struct to look after updating the acceptable gas price of a miner.
 #[derive(Debug, PartialEq)]
 pub enum GasPricer {
@@ -70,6 +135,8 @@ pub enum GasPricer {
 	Fixed(U256),
 	/// Gas price is calibrated according to a fixed amount of USD.
 	Calibrated(GasPriceCalibrator),
+	/// A fixed minimal gas price, suggested gas prices are derived from recent blocks.
+	Oracle(GasPriceOracle),
 }
 
 impl GasPricer {
@@ -87,11 +154,70 @@ impl GasPricer {
 		GasPricer::Fixed(gas_price)
 	}
 
+	/// Create a new Oracle `GasPricer` with the given minimal gas price.
+	pub fn new_oracle(options: GasPriceOracleOptions, minimal_gas_price: U256) -> GasPricer {
+		GasPricer::Oracle(GasPriceOracle {
+			options: options,
+			minimal_gas_price: minimal_gas_price,
+		})
+	}
+
+	/// Options of the gas price oracle, if it's used.
+	pub fn oracle_options(&self) -> Option<&GasPriceOracleOptions> {
+		match *self {
+			GasPricer::Oracle(ref oracle) => Some(&oracle.options),
+			_ => None,
+		}
+	}
+
 	/// Recalibrate current gas price.
 	pub fn recalibrate<F: FnOnce(U256) + Sync + Send + 'static>(&mut self, set_price: F) {
 		match *self {
 			GasPricer::Fixed(ref max) => set_price(max.clone()),
 			GasPricer::Calibrated(ref mut cal) => cal.recalibrate(set_price),
+			GasPricer::Oracle(ref oracle) => set_price(oracle.minimal_gas_price),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::U256;
+	use super::{GasPriceOracleOptions, GasPriceTiers, GasPricer};
+
+	#[test]
+	fn should_suggest_gas_price_tiers() {
+		let options = GasPriceOracleOptions::default();
+		assert_eq!(options.tiers(&[]), None);
+
+		let prices: Vec<U256> = (1..101).map(U256::from).collect();
+		assert_eq!(options.tiers(&prices), Some(GasPriceTiers {
+			slow: 25.into(),
+			standard: 50.into(),
+			fast: 90.into(),
+		}));
+
+		let prices = vec![U256::from(7)];
+		assert_eq!(options.tiers(&prices), Some(GasPriceTiers {
+			slow: 7.into(),
+			standard: 7.into(),
+			fast: 7.into(),
+		}));
+
+		let options = GasPriceOracleOptions { slow_percentile: 0, fast_percentile: 100, ..options };
+		let prices = vec![U256::from(1), U256::from(2), U256::from(3)];
+		assert_eq!(options.tiers(&prices), Some(GasPriceTiers {
+			slow: 1.into(),
+			standard: 2.into(),
+			fast: 3.into(),
+		}));
+	}
+
+	#[test]
+	fn oracle_should_keep_minimal_gas_price() {
+		let mut pricer = GasPricer::new_oracle(GasPriceOracleOptions::default(), 5.into());
+		assert_eq!(pricer.oracle_options(), Some(&GasPriceOracleOptions::default()));
+		pricer.recalibrate(|price| assert_eq!(price, U256::from(5)));
+		assert_eq!(GasPricer::new_fixed(5.into()).oracle_options(), None);
+	}
+}
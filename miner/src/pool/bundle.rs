@@ -0,0 +1,100 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction bundles.
+//!
+//! A bundle is a group of transactions that has to be included in a block
+//! all together and in the given order, or not at all.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ethereum_types::{H256, U256};
+use hash::keccak;
+use transaction;
+
+use pool::VerifiedTransaction;
+
+/// Atomic group of verified transactions.
+#[derive(Debug)]
+pub struct Bundle {
+	hash: H256,
+	transactions: Vec<Arc<VerifiedTransaction>>,
+	target_block: Option<u64>,
+	imported: Instant,
+}
+
+impl Bundle {
+	/// Create new bundle out of given (non-empty) list of transactions.
+	///
+	/// If `target_block` is given the bundle will only be included in block with that number.
+	pub fn new(transactions: Vec<Arc<VerifiedTransaction>>, target_block: Option<u64>) -> Self {
+		assert!(!transactions.is_empty(), "Bundle needs to contain at least one transaction.");
+
+		let hashes = transactions.iter()
+			.flat_map(|tx| tx.signed().hash().to_vec())
+			.collect::<Vec<_>>();
+
+		Bundle {
+			hash: keccak(&hashes),
+			transactions,
+			target_block,
+			imported: Instant::now(),
+		}
+	}
+
+	/// Bundle hash (hash of concatenated hashes of its transactions).
+	pub fn hash(&self) -> &H256 {
+		&self.hash
+	}
+
+	/// Transactions of the bundle in the order they need to be executed.
+	pub fn transactions(&self) -> &[Arc<VerifiedTransaction>] {
+		&self.transactions
+	}
+
+	/// Signed transactions of the bundle in the order they need to be executed.
+	pub fn signed(&self) -> Vec<transaction::SignedTransaction> {
+		self.transactions.iter().map(|tx| tx.signed().clone()).collect()
+	}
+
+	/// Block number the bundle is targeting (if any).
+	pub fn target_block(&self) -> Option<u64> {
+		self.target_block
+	}
+
+	/// Total gas of all transactions in the bundle.
+	pub fn gas(&self) -> U256 {
+		self.transactions.iter().fold(U256::zero(), |acc, tx| acc.saturating_add(tx.signed().gas))
+	}
+
+	/// Returns true if the bundle can be included in a block with given number.
+	pub fn is_ready(&self, block_number: u64) -> bool {
+		self.target_block.map_or(true, |target| target == block_number)
+	}
+
+	/// Returns true if the bundle can no longer be included in any block
+	/// following the block with given number.
+	pub fn is_outdated(&self, best_block_number: u64) -> bool {
+		self.target_block.map_or(false, |target| target <= best_block_number)
+	}
+
+	/// Returns true if the bundle was imported more than `lifetime` ago.
+	pub fn is_expired(&self, lifetime: Duration) -> bool {
+		self.imported.elapsed() > lifetime
+	}
+}
+
@@ -73,3 +73,16 @@ pub trait NonceClient: fmt::Debug + Sync {
 	/// Fetch only account nonce for given sender.
 	fn account_nonce(&self, address: &Address) -> U256;
 }
+
+/// State client used to check conditions of pending transactions.
+pub trait ConditionClient: fmt::Debug + Sync {
+	/// Number of the block that included transaction with given hash (if any).
+	fn transaction_block_number(&self, hash: &H256) -> Option<u64>;
+
+	/// Fetch latest balance of given account.
+	fn account_balance(&self, address: &Address) -> U256;
+
+	/// Call given contract at the latest block and return the output.
+	/// Should return an error if the execution threw an exception.
+	fn call_contract(&self, address: &Address, data: &[u8]) -> Result<Vec<u8>, String>;
+}
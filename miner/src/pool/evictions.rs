@@ -0,0 +1,126 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recent evictions from the transaction pool.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use ethereum_types::{H256, Address};
+use pool::VerifiedTransaction as Transaction;
+use txpool::{self, VerifiedTransaction};
+
+/// Transaction evicted from the pool to make room for another one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Eviction {
+	/// Hash of the evicted transaction.
+	pub hash: H256,
+	/// Sender of the evicted transaction.
+	pub sender: Address,
+	/// Pool limit that caused the eviction.
+	pub limit: txpool::Limit,
+	/// Time of the eviction.
+	pub time: SystemTime,
+}
+
+/// Keeps track of recent evictions from the pool.
+#[derive(Debug)]
+pub struct EvictionsList {
+	max_len: usize,
+	evictions: VecDeque<Eviction>,
+}
+
+impl Default for EvictionsList {
+	fn default() -> Self {
+		Self::new(256)
+	}
+}
+
+impl EvictionsList {
+	/// Create a new list keeping at most `max_len` recent evictions.
+	pub fn new(max_len: usize) -> Self {
+		EvictionsList {
+			max_len,
+			evictions: VecDeque::with_capacity(max_len),
+		}
+	}
+
+	/// Returns recent evictions, the oldest first.
+	pub fn evictions(&self) -> &VecDeque<Eviction> {
+		&self.evictions
+	}
+}
+
+impl txpool::Listener<Transaction> for EvictionsList {
+	fn evicted(&mut self, tx: &Arc<Transaction>, limit: &txpool::Limit) {
+		if self.evictions.len() >= self.max_len {
+			self.evictions.pop_front();
+		}
+
+		self.evictions.push_back(Eviction {
+			hash: *tx.hash(),
+			sender: *tx.sender(),
+			limit: *limit,
+			time: SystemTime::now(),
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::U256;
+	use ethkey::{Random, Generator};
+	use transaction;
+	use txpool::Listener;
+
+	#[test]
+	fn should_keep_only_recent_evictions() {
+		// given
+		let mut list = EvictionsList::new(2);
+		let tx1 = new_tx(10);
+		let tx2 = new_tx(11);
+		let tx3 = new_tx(12);
+
+		// when
+		list.evicted(&tx1, &txpool::Limit::Count);
+		list.evicted(&tx2, &txpool::Limit::PerSender);
+		list.evicted(&tx3, &txpool::Limit::Origin(txpool::Origin::External));
+
+		// then
+		let evictions = list.evictions();
+		assert_eq!(evictions.len(), 2);
+		assert_eq!(evictions[0].hash, *tx2.hash());
+		assert_eq!(evictions[0].limit, txpool::Limit::PerSender);
+		assert_eq!(evictions[1].hash, *tx3.hash());
+		assert_eq!(evictions[1].sender, *tx3.sender());
+	}
+
+	fn new_tx<T: Into<U256>>(nonce: T) -> Arc<Transaction> {
+		let keypair = Random.generate().unwrap();
+		let signed = transaction::Transaction {
+			action: transaction::Action::Create,
+			value: U256::from(100),
+			data: Default::default(),
+			gas: U256::from(10),
+			gas_price: U256::from(1245),
+			nonce: nonce.into(),
+		}.sign(keypair.secret(), None);
+
+		Arc::new(Transaction::from_pending_block_transaction(signed))
+	}
+}
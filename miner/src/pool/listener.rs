@@ -100,6 +100,10 @@ impl txpool::Listener<Transaction> for Logger {
 		}
 	}
 
+	fn evicted(&mut self, tx: &Arc<Transaction>, limit: &txpool::Limit) {
+		debug!(target: "txqueue", "[{:?}] Evicted because of {:?} limit.", tx.hash(), limit);
+	}
+
 	fn invalid(&mut self, tx: &Arc<Transaction>) {
 		debug!(target: "txqueue", "[{:?}] Marked as invalid by executor.", tx.hash());
 	}
@@ -16,33 +16,78 @@
 
 //! Transaction Pool
 
+use std::collections::HashSet;
+use std::time::SystemTime;
+
 use ethereum_types::{U256, H256, Address};
 use heapsize::HeapSizeOf;
 use transaction;
 use txpool;
 
+mod bundle;
 mod listener;
 mod queue;
 mod ready;
 mod scoring;
 
 pub mod client;
+pub mod evictions;
 pub mod local_transactions;
 pub mod verifier;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::bundle::Bundle;
 pub use self::queue::{TransactionQueue, Status as QueueStatus};
 pub use self::txpool::{VerifiedTransaction as PoolVerifiedTransaction, Options};
 
 /// How to prioritize transactions in the pool
-///
-/// TODO [ToDr] Implement more strategies.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum PrioritizationStrategy {
 	/// Simple gas-price based prioritization.
 	GasPriceOnly,
+	/// Gas-price based prioritization, with each queued transaction of a sender
+	/// lowering the priority of its later ones, so that senders take turns.
+	SenderFairness,
+	/// Gas-price based prioritization, with transactions sent from
+	/// or calling any of the given addresses boosted.
+	PriorityAddresses(HashSet<Address>),
+	/// Prioritization by the reward paid to the miner per unit of block gas.
+	///
+	/// Transactions with the same gas price are ordered by gas limit, smaller ones first.
+	/// Service transactions (zero gas price) pay no reward and are ordered after all paying ones.
+	EffectiveReward,
+}
+
+/// Rules for replacing a transaction with one from the same sender and with the same nonce.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ReplacementPolicy {
+	/// Minimal gas price increase of the replacement, in basis points of the replaced transaction gas price.
+	pub min_gas_price_bump: u32,
+	/// Allow local transactions to replace local ones without increasing the gas price.
+	pub local_without_bump: bool,
+}
+
+impl Default for ReplacementPolicy {
+	fn default() -> Self {
+		ReplacementPolicy {
+			min_gas_price_bump: 1250,
+			local_without_bump: false,
+		}
+	}
+}
+
+impl ReplacementPolicy {
+	/// Minimal gas price of a transaction replacing one with given gas price.
+	pub fn min_replacement_gas_price(&self, old_gas_price: U256, both_local: bool) -> U256 {
+		if both_local && self.local_without_bump {
+			return old_gas_price
+		}
+
+		let bump = old_gas_price.saturating_mul(self.min_gas_price_bump.into()) / U256::from(10_000);
+		old_gas_price.saturating_add(bump)
+	}
 }
 
 /// Transaction ordering when requesting pending set.
@@ -84,7 +129,7 @@ impl PendingSettings {
 
 /// Transaction priority.
 #[derive(Debug, PartialEq, Eq, PartialOrd,  Clone, Copy)]
-pub(crate) enum Priority {
+pub enum Priority {
 	/// Regular transactions received over the network. (no priority boost)
 	Regular,
 	/// Transactions from retracted blocks (medium priority)
@@ -117,6 +162,7 @@ pub struct VerifiedTransaction {
 	sender: Address,
 	priority: Priority,
 	insertion_id: usize,
+	insertion_time: SystemTime,
 }
 
 impl VerifiedTransaction {
@@ -134,11 +180,12 @@ impl VerifiedTransaction {
 			sender,
 			priority: Priority::Retracted,
 			insertion_id: 0,
+			insertion_time: SystemTime::now(),
 		}
 	}
 
 	/// Gets transaction priority.
-	pub(crate) fn priority(&self) -> Priority {
+	pub fn priority(&self) -> Priority {
 		self.priority
 	}
 
@@ -147,6 +194,11 @@ impl VerifiedTransaction {
 		self.insertion_id
 	}
 
+	/// Gets the time the transaction was verified for insertion to the pool.
+	pub fn insertion_time(&self) -> SystemTime {
+		self.insertion_time
+	}
+
 	/// Gets wrapped `SignedTransaction`
 	pub fn signed(&self) -> &transaction::SignedTransaction {
 		&self.transaction
@@ -174,4 +226,17 @@ impl txpool::VerifiedTransaction for VerifiedTransaction {
 	fn sender(&self) -> &Address {
 		&self.sender
 	}
+
+	fn origin(&self) -> txpool::Origin {
+		match self.priority {
+			Priority::Local => txpool::Origin::Local,
+			Priority::Retracted => txpool::Origin::Retracted,
+			Priority::Regular => txpool::Origin::External,
+		}
+	}
+
+	fn gas(&self) -> u64 {
+		let gas = self.transaction.gas;
+		if gas > U256::from(u64::max_value()) { u64::max_value() } else { gas.low_u64() }
+	}
 }
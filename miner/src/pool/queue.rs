@@ -19,6 +19,7 @@
 use std::{cmp, fmt};
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicUsize};
+use std::time::Duration;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use ethereum_types::{H256, U256, Address};
@@ -28,11 +29,12 @@ use txpool::{self, Verifier};
 
 use pool::{
 	self, scoring, verifier, client, ready, listener,
-	PrioritizationStrategy, PendingOrdering, PendingSettings,
+	PrioritizationStrategy, ReplacementPolicy, PendingOrdering, PendingSettings,
 };
+use pool::evictions::{Eviction, EvictionsList};
 use pool::local_transactions::LocalTransactionsList;
 
-type Listener = (LocalTransactionsList, (listener::Notifier, listener::Logger));
+type Listener = (LocalTransactionsList, (listener::Notifier, (listener::Logger, EvictionsList)));
 type Pool = txpool::Pool<pool::VerifiedTransaction, scoring::NonceAndGasPrice, Listener>;
 
 /// Max cache time in milliseconds for pending transactions.
@@ -51,6 +53,12 @@ const TIMESTAMP_CACHE: u64 = 1000;
 /// This parameter controls how many (best) senders at once will be processed.
 const CULL_SENDERS_CHUNK: usize = 1024;
 
+/// Maximal number of bundles kept in the queue.
+pub(crate) const MAX_BUNDLES: usize = 64;
+
+/// Time after which bundles that were not included are dropped.
+const BUNDLE_LIFETIME: Duration = Duration::from_secs(30 * 60);
+
 /// Transaction queue status.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Status {
@@ -60,6 +68,8 @@ pub struct Status {
 	pub status: txpool::LightStatus,
 	/// Current limits of the transaction pool.
 	pub limits: txpool::Options,
+	/// Replacement policy of the transaction pool.
+	pub replacement: ReplacementPolicy,
 }
 
 impl fmt::Display for Status {
@@ -203,6 +213,7 @@ pub struct TransactionQueue {
 	options: RwLock<verifier::Options>,
 	cached_pending: RwLock<CachedPending>,
 	recently_rejected: RecentlyRejected,
+	bundles: RwLock<Vec<Arc<pool::Bundle>>>,
 }
 
 impl TransactionQueue {
@@ -211,14 +222,16 @@ impl TransactionQueue {
 		limits: txpool::Options,
 		verification_options: verifier::Options,
 		strategy: PrioritizationStrategy,
+		replacement: ReplacementPolicy,
 	) -> Self {
 		let max_count = limits.max_count;
 		TransactionQueue {
 			insertion_id: Default::default(),
-			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring::NonceAndGasPrice(strategy), limits)),
+			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring::NonceAndGasPrice(strategy, replacement), limits)),
 			options: RwLock::new(verification_options),
 			cached_pending: RwLock::new(CachedPending::none()),
 			recently_rejected: RecentlyRejected::new(cmp::max(MIN_REJECTED_CACHE_SIZE, max_count / 4)),
+			bundles: Default::default(),
 		}
 	}
 
@@ -301,6 +314,91 @@ impl TransactionQueue {
 		results
 	}
 
+	/// Import an atomic bundle of transactions.
+	///
+	/// All transactions of the (non-empty) bundle are verified, but they are not inserted to the pool.
+	/// Instead the bundle is kept as a whole until it gets outdated, its transactions are included in the chain
+	/// or it's removed because it failed to execute.
+	/// Bundles not included within `BUNDLE_LIFETIME` are dropped and at most `MAX_BUNDLES`
+	/// bundles are kept at a time.
+	/// Returns hash of the imported bundle.
+	pub fn import_bundle<C: client::Client>(
+		&self,
+		client: C,
+		transactions: Vec<verifier::Transaction>,
+		target_block: Option<u64>,
+	) -> Result<H256, transaction::Error> {
+		trace_time!("pool::import_bundle");
+		let options = self.options.read().clone();
+
+		let verifier = verifier::Verifier::new(
+			client,
+			options,
+			self.insertion_id.clone(),
+			None,
+		);
+
+		let transactions = transactions
+			.into_iter()
+			.map(|transaction| verifier.verify_transaction(transaction).map(Arc::new))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let bundle = pool::Bundle::new(transactions, target_block);
+		let hash = *bundle.hash();
+
+		let mut bundles = self.bundles.write();
+		if bundles.iter().any(|b| b.hash() == &hash) {
+			return Err(transaction::Error::AlreadyImported);
+		}
+
+		bundles.retain(|bundle| !bundle.is_expired(BUNDLE_LIFETIME));
+		if bundles.len() >= MAX_BUNDLES {
+			debug!(target: "txqueue", "[{:?}] Rejected bundle, limit of {} bundles reached.", hash, MAX_BUNDLES);
+			return Err(transaction::Error::LimitReached);
+		}
+
+		debug!(target: "txqueue", "[{:?}] Imported bundle of {} transactions.", hash, bundle.transactions().len());
+		bundles.push(Arc::new(bundle));
+		Ok(hash)
+	}
+
+	/// Returns bundles that can be included in a block with given number,
+	/// in the order they were imported.
+	pub fn pending_bundles(&self, block_number: u64) -> Vec<Arc<pool::Bundle>> {
+		self.bundles.read()
+			.iter()
+			.filter(|bundle| bundle.is_ready(block_number))
+			.cloned()
+			.collect()
+	}
+
+	/// Remove a bundle from the queue.
+	pub fn remove_bundle(&self, hash: &H256) -> Option<Arc<pool::Bundle>> {
+		let mut bundles = self.bundles.write();
+		let index = bundles.iter().position(|bundle| bundle.hash() == hash)?;
+		Some(bundles.remove(index))
+	}
+
+	/// Culls bundles that can't be included in blocks following the block with given number.
+	///
+	/// Those are the bundles targeting already imported blocks, expired bundles
+	/// and bundles with any transaction already included in the chain.
+	pub fn cull_bundles<C: client::NonceClient>(
+		&self,
+		client: C,
+		best_block_number: u64,
+	) {
+		let mut bundles = self.bundles.write();
+		let before = bundles.len();
+		bundles.retain(|bundle| {
+			!bundle.is_outdated(best_block_number) && !bundle.is_expired(BUNDLE_LIFETIME) && bundle.transactions().iter().all(|tx| {
+				let tx = tx.signed();
+				tx.nonce >= client.account_nonce(&tx.sender())
+			})
+		});
+		debug!(target: "txqueue", "Removed {} stalled bundles.", before - bundles.len());
+	}
+
 	/// Returns all transactions in the queue without explicit ordering.
 	pub fn all_transactions(&self) -> Vec<Arc<pool::VerifiedTransaction>> {
 		let ready = |_tx: &pool::VerifiedTransaction| txpool::Readiness::Ready;
@@ -330,7 +428,7 @@ impl TransactionQueue {
 		client: C,
 		settings: PendingSettings,
 	) -> Vec<Arc<pool::VerifiedTransaction>> where
-		C: client::NonceClient,
+		C: client::NonceClient + client::ConditionClient + Clone,
 	{
 		let PendingSettings { block_number, current_timestamp, nonce_cap, max_len, ordering } = settings;
 		if let Some(pending) = self.cached_pending.read().pending(block_number, current_timestamp, nonce_cap.as_ref(), max_len) {
@@ -378,10 +476,10 @@ impl TransactionQueue {
 		nonce_cap: Option<U256>,
 		collect: F,
 	) -> T where
-		C: client::NonceClient,
+		C: client::NonceClient + client::ConditionClient + Clone,
 		F: FnOnce(txpool::PendingIterator<
 			pool::VerifiedTransaction,
-			(ready::Condition, ready::State<C>),
+			(ready::Condition<C>, ready::State<C>),
 			scoring::NonceAndGasPrice,
 			Listener,
 		>) -> T,
@@ -397,10 +495,10 @@ impl TransactionQueue {
 		block_number: u64,
 		current_timestamp: u64,
 		nonce_cap: Option<U256>,
-	) -> (ready::Condition, ready::State<C>) where
-		C: client::NonceClient,
+	) -> (ready::Condition<C>, ready::State<C>) where
+		C: client::NonceClient + client::ConditionClient + Clone,
 	{
-		let pending_readiness = ready::Condition::new(block_number, current_timestamp);
+		let pending_readiness = ready::Condition::new(client.clone(), block_number, current_timestamp);
 		// don't mark any transactions as stale at this point.
 		let stale_id = None;
 		let state_readiness = ready::State::new(client, stale_id, nonce_cap);
@@ -507,6 +605,7 @@ impl TransactionQueue {
 	/// Clear the entire pool.
 	pub fn clear(&self) {
 		self.pool.write().clear();
+		self.bundles.write().clear();
 	}
 
 	/// Penalize given senders.
@@ -530,12 +629,14 @@ impl TransactionQueue {
 		let pool = self.pool.read();
 		let status = pool.light_status();
 		let limits = pool.options();
+		let replacement = pool.scoring().1;
 		let options = self.options.read().clone();
 
 		Status {
 			options,
 			status,
 			limits,
+			replacement,
 		}
 	}
 
@@ -555,6 +656,13 @@ impl TransactionQueue {
 		self.pool.read().listener().0.all_transactions().iter().map(|(a, b)| (*a, b.clone())).collect()
 	}
 
+	/// Returns recent evictions from the pool, the oldest first.
+	pub fn evictions(&self) -> Vec<Eviction> {
+		let pool = self.pool.read();
+		let evictions = ((pool.listener().1).1).1.evictions().iter().cloned().collect();
+		evictions
+	}
+
 	/// Add a callback to be notified about all transactions entering the pool.
 	pub fn add_listener(&self, f: Box<Fn(&[H256]) + Send + Sync>) {
 		let mut pool = self.pool.write();
@@ -576,6 +684,7 @@ fn convert_error(err: txpool::Error) -> transaction::Error {
 		ErrorKind::AlreadyImported(..) => transaction::Error::AlreadyImported,
 		ErrorKind::TooCheapToEnter(..) => transaction::Error::LimitReached,
 		ErrorKind::TooCheapToReplace(..) => transaction::Error::TooCheapToReplace,
+		ErrorKind::SenderGasLimitReached(..) => transaction::Error::LimitReached,
 		ref e => {
 			warn!(target: "txqueue", "Unknown import error: {:?}", e);
 			transaction::Error::NotAllowed
@@ -590,7 +699,7 @@ mod tests {
 
 	#[test]
 	fn should_get_pending_transactions() {
-		let queue = TransactionQueue::new(txpool::Options::default(), verifier::Options::default(), PrioritizationStrategy::GasPriceOnly);
+		let queue = TransactionQueue::new(txpool::Options::default(), verifier::Options::default(), PrioritizationStrategy::GasPriceOnly, ReplacementPolicy::default());
 
 		let pending: Vec<_> = queue.pending(TestClient::default(), PendingSettings::all_prioritized(0, 0));
 
@@ -45,7 +45,7 @@ use ethereum_types::{U256, H160 as Address};
 use transaction;
 use txpool::{self, VerifiedTransaction as PoolVerifiedTransaction};
 
-use super::client::NonceClient;
+use super::client::{NonceClient, ConditionClient};
 use super::VerifiedTransaction;
 
 /// Checks readiness of transactions by comparing the nonce to state nonce.
@@ -103,29 +103,72 @@ impl<C: NonceClient> txpool::Ready<VerifiedTransaction> for State<C> {
 	}
 }
 
-/// Checks readines of Pending transactions by comparing it with current time and block number.
+/// Checks readines of Pending transactions by comparing it with current time, block number
+/// and the state at that block.
 #[derive(Debug)]
-pub struct Condition {
+pub struct Condition<C> {
 	block_number: u64,
 	now: u64,
+	client: C,
 }
 
-impl Condition {
-	/// Create a new condition checker given current block number and UTC timestamp.
-	pub fn new(block_number: u64, now: u64) -> Self {
+impl<C> Condition<C> {
+	/// Create a new condition checker given state client, current block number and UTC timestamp.
+	pub fn new(client: C, block_number: u64, now: u64) -> Self {
 		Condition {
 			block_number,
 			now,
+			client,
 		}
 	}
 }
 
-impl txpool::Ready<VerifiedTransaction> for Condition {
+/// Decodes ABI-encoded `bool` (exactly one 32-byte word equal to 0 or 1).
+fn decode_bool(output: &[u8]) -> Option<bool> {
+	if output.len() != 32 || output[..31].iter().any(|byte| *byte != 0) {
+		return None;
+	}
+
+	match output[31] {
+		0 => Some(false),
+		1 => Some(true),
+		_ => None,
+	}
+}
+
+impl<C: ConditionClient> txpool::Ready<VerifiedTransaction> for Condition<C> {
 	fn is_ready(&mut self, tx: &VerifiedTransaction) -> txpool::Readiness {
-		match tx.transaction.condition {
-			Some(transaction::Condition::Number(block)) if block > self.block_number => txpool::Readiness::Future,
-			Some(transaction::Condition::Timestamp(time)) if time > self.now => txpool::Readiness::Future,
-			_ => txpool::Readiness::Ready,
+		let is_ready = match tx.transaction.condition {
+			None => true,
+			Some(transaction::Condition::Number(block)) => block <= self.block_number,
+			Some(transaction::Condition::Timestamp(time)) => time <= self.now,
+			Some(transaction::Condition::Confirmations { ref transaction, count }) => {
+				let block_number = self.block_number;
+				self.client.transaction_block_number(transaction)
+					.map_or(false, |included| block_number.saturating_sub(included) + 1 >= count)
+			},
+			Some(transaction::Condition::MinBalance { ref address, ref value }) => {
+				self.client.account_balance(address) >= *value
+			},
+			Some(transaction::Condition::Call { ref to, ref data }) => match self.client.call_contract(to, data) {
+				Ok(output) => match decode_bool(&output) {
+					Some(value) => value,
+					None => {
+						debug!(target: "txqueue", "[{:?}] Transaction condition returned invalid output: {:?}", tx.hash(), output);
+						false
+					},
+				},
+				Err(err) => {
+					debug!(target: "txqueue", "[{:?}] Unable to check transaction condition: {}", tx.hash(), err);
+					false
+				},
+			},
+		};
+
+		if is_ready {
+			txpool::Readiness::Ready
+		} else {
+			txpool::Readiness::Future
 		}
 	}
 }
@@ -240,10 +283,50 @@ mod tests {
 		let tx3 = v(transaction::PendingTransaction::new(tx.clone(), None));
 
 		// when/then
-		assert_eq!(Condition::new(0, 0).is_ready(&tx1), txpool::Readiness::Future);
-		assert_eq!(Condition::new(0, 0).is_ready(&tx2), txpool::Readiness::Future);
-		assert_eq!(Condition::new(0, 0).is_ready(&tx3), txpool::Readiness::Ready);
-		assert_eq!(Condition::new(5, 0).is_ready(&tx1), txpool::Readiness::Ready);
-		assert_eq!(Condition::new(0, 3).is_ready(&tx2), txpool::Readiness::Ready);
+		assert_eq!(Condition::new(TestClient::new(), 0, 0).is_ready(&tx1), txpool::Readiness::Future);
+		assert_eq!(Condition::new(TestClient::new(), 0, 0).is_ready(&tx2), txpool::Readiness::Future);
+		assert_eq!(Condition::new(TestClient::new(), 0, 0).is_ready(&tx3), txpool::Readiness::Ready);
+		assert_eq!(Condition::new(TestClient::new(), 5, 0).is_ready(&tx1), txpool::Readiness::Ready);
+		assert_eq!(Condition::new(TestClient::new(), 0, 3).is_ready(&tx2), txpool::Readiness::Ready);
+	}
+
+	#[test]
+	fn should_check_readiness_of_state_conditions() {
+		// given
+		let tx = Tx::default().signed();
+		let v = |condition: transaction::Condition| TestClient::new().verify(transaction::PendingTransaction::new(tx.clone(), Some(condition)));
+		let tx1 = v(transaction::Condition::Confirmations { transaction: 5.into(), count: 3 });
+		let tx2 = v(transaction::Condition::MinBalance { address: 1.into(), value: 100_000.into() });
+		let tx3 = v(transaction::Condition::Call { to: 1.into(), data: vec![1, 2] });
+
+		// when/then
+		assert_eq!(Condition::new(TestClient::new(), 12, 0).is_ready(&tx1), txpool::Readiness::Future);
+		assert_eq!(Condition::new(TestClient::new().with_included_transaction(5.into(), 11), 12, 0).is_ready(&tx1), txpool::Readiness::Future);
+		assert_eq!(Condition::new(TestClient::new().with_included_transaction(5.into(), 10), 12, 0).is_ready(&tx1), txpool::Readiness::Ready);
+
+		assert_eq!(Condition::new(TestClient::new(), 0, 0).is_ready(&tx2), txpool::Readiness::Future);
+		assert_eq!(Condition::new(TestClient::new().with_balance(100_000), 0, 0).is_ready(&tx2), txpool::Readiness::Ready);
+
+		assert_eq!(Condition::new(TestClient::new(), 0, 0).is_ready(&tx3), txpool::Readiness::Future);
+		assert_eq!(Condition::new(TestClient::new().with_call_output(vec![0; 32]), 0, 0).is_ready(&tx3), txpool::Readiness::Future);
+		let mut output = vec![0; 32];
+		output[31] = 1;
+		assert_eq!(Condition::new(TestClient::new().with_call_output(output), 0, 0).is_ready(&tx3), txpool::Readiness::Ready);
+	}
+
+	#[test]
+	fn should_require_abi_encoded_true_from_call_condition() {
+		assert_eq!(decode_bool(&[]), None);
+		assert_eq!(decode_bool(&[1]), None);
+		assert_eq!(decode_bool(&[0xff; 32]), None);
+		assert_eq!(decode_bool(&[0x08, 0xc3, 0x79, 0xa0]), None);
+		let mut output = vec![0; 32];
+		output[31] = 2;
+		assert_eq!(decode_bool(&output), None);
+		output[31] = 1;
+		assert_eq!(decode_bool(&output), Some(true));
+		output.push(0);
+		assert_eq!(decode_bool(&output), None);
+		assert_eq!(decode_bool(&[0; 32]), Some(false));
 	}
 }
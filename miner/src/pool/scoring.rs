@@ -20,27 +20,34 @@
 //! Low nonces need to be included first. If there are two transactions from the same sender
 //! and with the same `nonce` only one of them can be included.
 //! We choose the one with higher gas price, but also require that gas price increment
-//! is high enough to prevent attacking miners by requiring them to reshuffle/reexecute
-//! the queue too often.
+//! is high enough (as configured by `ReplacementPolicy`) to prevent attacking miners
+//! by requiring them to reshuffle/reexecute the queue too often.
 //!
 //! Transactions between senders are prioritized using `gas price`. Higher `gas price`
 //! yields more profits for miners. Additionally we prioritize transactions that originate
 //! from our local node (own transactions).
+//!
+//! Other `PrioritizationStrategy`s adjust the score: by the position of the transaction
+//! in the sender's queue, by configured priority addresses or by preferring smaller
+//! transactions among the ones with the same `gas price`.
 
 use std::cmp;
 
 use ethereum_types::U256;
+use transaction::Action;
 use txpool::{self, scoring};
-use super::{verifier, PrioritizationStrategy, VerifiedTransaction};
+use super::{verifier, PrioritizationStrategy, ReplacementPolicy, VerifiedTransaction};
 
-/// Transaction with the same (sender, nonce) can be replaced only if
-/// `new_gas_price > old_gas_price + old_gas_price >> SHIFT`
-const GAS_PRICE_BUMP_SHIFT: usize = 3; // 2 = 25%, 3 = 12.5%, 4 = 6.25%
+/// Additional score shift of transactions matching `PrioritizationStrategy::PriorityAddresses`.
+const PRIORITY_ADDRESS_BOOST_SHIFT: usize = 10;
 
-/// Calculate minimal gas price requirement.
-#[inline]
-fn bump_gas_price(old_gp: U256) -> U256 {
-	old_gp.saturating_add(old_gp >> GAS_PRICE_BUMP_SHIFT)
+/// Reward of the transaction per unit of block gas, preferring smaller transactions if the gas price is equal.
+///
+/// The gas limit is only used to order transactions with the same gas price, so raising it
+/// never improves the score. Service transactions (zero gas price) are ordered after all paying ones.
+fn effective_reward(gas_price: &U256, gas: &U256) -> U256 {
+	let gas = cmp::min(*gas, U256::from(u64::max_value()));
+	gas_price.saturating_mul(U256::one() << 64).saturating_add(U256::from(u64::max_value()) - gas)
 }
 
 /// Simple, gas-price based scoring for transactions.
@@ -48,7 +55,7 @@ fn bump_gas_price(old_gp: U256) -> U256 {
 /// NOTE: Currently penalization does not apply to new transactions that enter the pool.
 /// We might want to store penalization status in some persistent state.
 #[derive(Debug, Clone)]
-pub struct NonceAndGasPrice(pub PrioritizationStrategy);
+pub struct NonceAndGasPrice(pub PrioritizationStrategy, pub ReplacementPolicy);
 
 impl NonceAndGasPrice {
 	/// Decide if the transaction should even be considered into the pool (if the pool is full).
@@ -63,7 +70,50 @@ impl NonceAndGasPrice {
 			return true
 		}
 
-		&old.transaction.gas_price > new.gas_price()
+		match self.0 {
+			// Sender is not recovered yet, so we can't tell if the transaction would be boosted.
+			PrioritizationStrategy::PriorityAddresses(_) => false,
+			PrioritizationStrategy::EffectiveReward =>
+				effective_reward(&old.transaction.gas_price, &old.transaction.gas) > effective_reward(new.gas_price(), new.gas()),
+			_ => &old.transaction.gas_price > new.gas_price(),
+		}
+	}
+
+	/// Value of the transaction for the miner, before any boosts.
+	fn value(&self, tx: &VerifiedTransaction) -> U256 {
+		match self.0 {
+			PrioritizationStrategy::EffectiveReward => effective_reward(&tx.transaction.gas_price, &tx.transaction.gas),
+			_ => tx.transaction.gas_price,
+		}
+	}
+
+	/// Score shift of the transaction.
+	fn boost(&self, tx: &VerifiedTransaction) -> usize {
+		let boost = match tx.priority() {
+			super::Priority::Local => 15,
+			super::Priority::Retracted => 10,
+			super::Priority::Regular => 0,
+		};
+
+		match self.0 {
+			PrioritizationStrategy::PriorityAddresses(ref addresses) => {
+				let is_priority = addresses.contains(&tx.sender) || match tx.transaction.action {
+					Action::Call(ref to) => addresses.contains(to),
+					Action::Create => false,
+				};
+				if is_priority { boost + PRIORITY_ADDRESS_BOOST_SHIFT } else { boost }
+			},
+			_ => boost,
+		}
+	}
+
+	/// Score of the transaction at given position in the sender's queue.
+	fn score(&self, tx: &VerifiedTransaction, position: usize) -> U256 {
+		let score = self.value(tx).saturating_mul(U256::one() << self.boost(tx));
+		match self.0 {
+			PrioritizationStrategy::SenderFairness => score / U256::from(position + 1),
+			_ => score,
+		}
 	}
 }
 
@@ -83,7 +133,8 @@ impl txpool::Scoring<VerifiedTransaction> for NonceAndGasPrice {
 		let old_gp = old.transaction.gas_price;
 		let new_gp = new.transaction.gas_price;
 
-		let min_required_gp = bump_gas_price(old_gp);
+		let both_local = old.priority().is_local() && new.priority().is_local();
+		let min_required_gp = self.1.min_replacement_gas_price(old_gp, both_local);
 
 		match min_required_gp.cmp(&new_gp) {
 			cmp::Ordering::Greater => scoring::Choice::RejectNew,
@@ -94,20 +145,29 @@ impl txpool::Scoring<VerifiedTransaction> for NonceAndGasPrice {
 	fn update_scores(&self, txs: &[txpool::Transaction<VerifiedTransaction>], scores: &mut [U256], change: scoring::Change) {
 		use self::scoring::Change;
 
+		// Scores depending on the position in the sender's queue need to be recomputed
+		// for all transactions that were shifted.
+		let rescore_from = |scores: &mut [U256], from: usize| {
+			for i in from..txs.len() {
+				scores[i] = self.score(&txs[i], i);
+			}
+		};
+		let by_position = self.0 == PrioritizationStrategy::SenderFairness;
+
 		match change {
+			Change::Culled(_) if by_position => rescore_from(scores, 0),
+			Change::RemovedAt(i) if by_position => rescore_from(scores, i),
 			Change::Culled(_) => {},
 			Change::RemovedAt(_) => {}
 			Change::InsertedAt(i) | Change::ReplacedAt(i) => {
 				assert!(i < txs.len());
 				assert!(i < scores.len());
 
-				scores[i] = txs[i].transaction.transaction.gas_price;
-				let boost = match txs[i].priority() {
-					super::Priority::Local => 15,
-					super::Priority::Retracted => 10,
-					super::Priority::Regular => 0,
-				};
-				scores[i] = scores[i] << boost;
+				if by_position {
+					rescore_from(scores, i);
+				} else {
+					scores[i] = self.score(&txs[i], i);
+				}
 			},
 			// We are only sending an event in case of penalization.
 			// So just lower the priority of all non-local transactions.
@@ -134,8 +194,8 @@ impl txpool::Scoring<VerifiedTransaction> for NonceAndGasPrice {
 			// accept local transactions over the limit
 			scoring::Choice::InsertNew
 		} else {
-			let old_score = (old.priority(), old.transaction.gas_price);
-			let new_score = (new.priority(), new.transaction.gas_price);
+			let old_score = (self.boost(old), self.value(old));
+			let new_score = (self.boost(new), self.value(new));
 			if new_score > old_score {
 				scoring::Choice::ReplaceOld
 			} else {
@@ -152,12 +212,22 @@ mod tests {
 	use std::sync::Arc;
 	use ethkey::{Random, Generator};
 	use pool::tests::tx::{Tx, TxExt};
+	use transaction::SignedTransaction;
 	use txpool::Scoring;
 	use txpool::scoring::Choice::*;
 
+	fn regular(tx: SignedTransaction, insertion_id: u64) -> txpool::Transaction<VerifiedTransaction> {
+		let mut verified = tx.verified();
+		verified.priority = ::pool::Priority::Regular;
+		txpool::Transaction {
+			insertion_id,
+			transaction: Arc::new(verified),
+		}
+	}
+
 	#[test]
 	fn should_replace_same_sender_by_nonce() {
-		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly);
+		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly, Default::default());
 
 		let tx1 = Tx {
 			nonce: 1,
@@ -199,10 +269,32 @@ mod tests {
 		assert_eq!(scoring.should_replace(&txs[3], &txs[1]), RejectNew);
 	}
 
+	#[test]
+	fn should_replace_according_to_replacement_policy() {
+		// given
+		let policy = ReplacementPolicy { min_gas_price_bump: 5000, local_without_bump: true };
+		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly, policy);
+		let keypair = Random.generate().unwrap();
+		let tx = |gas_price, local| {
+			let mut verified = Tx { nonce: 1, gas_price, ..Default::default() }.unsigned().sign(keypair.secret(), None).verified();
+			verified.priority = if local { ::pool::Priority::Local } else { ::pool::Priority::Regular };
+			verified
+		};
+
+		// then
+		assert_eq!(scoring.choose(&tx(100, false), &tx(149, false)), RejectNew);
+		assert_eq!(scoring.choose(&tx(100, false), &tx(150, false)), ReplaceOld);
+		assert_eq!(scoring.choose(&tx(100, true), &tx(100, false)), RejectNew);
+		assert_eq!(scoring.choose(&tx(100, true), &tx(100, true)), ReplaceOld);
+		assert_eq!(scoring.choose(&tx(100, true), &tx(99, true)), RejectNew);
+		assert_eq!(policy.min_replacement_gas_price(100.into(), false), 150.into());
+		assert_eq!(ReplacementPolicy::default().min_replacement_gas_price(800.into(), false), 900.into());
+	}
+
 	#[test]
 	fn should_replace_different_sender_by_priority_and_gas_price() {
 		// given
-		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly);
+		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly, Default::default());
 		let tx_regular_low_gas = {
 			let tx = Tx {
 				nonce: 1,
@@ -267,7 +359,7 @@ mod tests {
 	#[test]
 	fn should_calculate_score_correctly() {
 		// given
-		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly);
+		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly, Default::default());
 		let (tx1, tx2, tx3) = Tx::default().signed_triple();
 		let transactions = vec![tx1, tx2, tx3].into_iter().enumerate().map(|(i, tx)| {
 			let mut verified = tx.verified();
@@ -316,4 +408,73 @@ mod tests {
 		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(()));
 		assert_eq!(scores, vec![32768.into(), 128.into(), 0.into()]);
 	}
+
+	#[test]
+	fn should_lower_scores_of_later_transactions_with_sender_fairness() {
+		// given
+		let scoring = NonceAndGasPrice(PrioritizationStrategy::SenderFairness, Default::default());
+		let (tx1, tx2, tx3) = Tx::gas_price(6).signed_triple();
+		let transactions = vec![regular(tx1, 0), regular(tx2, 1), regular(tx3, 2)];
+		let mut scores = vec![U256::from(0), 0.into(), 0.into()];
+
+		// when
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::InsertedAt(0));
+
+		// then
+		assert_eq!(scores, vec![6.into(), 3.into(), 2.into()]);
+
+		// when
+		let transactions = transactions[1..].to_vec();
+		let mut scores = scores[1..].to_vec();
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Culled(1));
+
+		// then
+		assert_eq!(scores, vec![6.into(), 3.into()]);
+	}
+
+	#[test]
+	fn should_boost_priority_addresses() {
+		// given
+		let priority = Tx::gas_price(1).signed();
+		let other = Tx::gas_price(10).signed();
+		let addresses = vec![priority.sender()].into_iter().collect();
+		let scoring = NonceAndGasPrice(PrioritizationStrategy::PriorityAddresses(addresses), Default::default());
+		let priority = regular(priority, 0);
+		let other = regular(other, 1);
+
+		// when
+		let mut scores = vec![U256::from(0)];
+		scoring.update_scores(&[priority.clone()], &mut *scores, scoring::Change::InsertedAt(0));
+
+		// then
+		assert_eq!(scores, vec![1024.into()]);
+		assert_eq!(scoring.should_replace(&other, &priority), ReplaceOld);
+		assert_eq!(scoring.should_replace(&priority, &other), RejectNew);
+	}
+
+	#[test]
+	fn should_prioritize_by_effective_reward() {
+		// given
+		let scoring = NonceAndGasPrice(PrioritizationStrategy::EffectiveReward, Default::default());
+		let inflated_gas = regular(Tx { gas: 100_000, gas_price: 5, ..Default::default() }.signed(), 0);
+		let small_gas = regular(Tx { gas: 21_000, gas_price: 5, ..Default::default() }.signed(), 1);
+		let high_gas_price = regular(Tx { gas: 1_000_000, gas_price: 6, ..Default::default() }.signed(), 2);
+		let service = regular(Tx { gas: 21_000, gas_price: 0, ..Default::default() }.signed(), 3);
+		let large_service = regular(Tx { gas: 100_000, gas_price: 0, ..Default::default() }.signed(), 4);
+
+		// when
+		let mut scores = vec![U256::from(0)];
+		scoring.update_scores(&[small_gas.clone()], &mut *scores, scoring::Change::InsertedAt(0));
+
+		// then
+		assert_eq!(scores, vec![(U256::from(5) << 64) + U256::from(u64::max_value() - 21_000)]);
+		// raising the gas limit never improves the score
+		assert_eq!(scoring.should_replace(&inflated_gas, &small_gas), ReplaceOld);
+		assert_eq!(scoring.should_replace(&small_gas, &inflated_gas), RejectNew);
+		assert_eq!(scoring.should_replace(&small_gas, &high_gas_price), ReplaceOld);
+		// service transactions are ordered after all paying ones
+		assert_eq!(scoring.should_replace(&service, &inflated_gas), ReplaceOld);
+		assert_eq!(scoring.should_replace(&inflated_gas, &service), RejectNew);
+		assert_eq!(scoring.should_replace(&large_service, &service), ReplaceOld);
+	}
 }
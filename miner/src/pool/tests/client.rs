@@ -14,7 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
 use std::sync::{atomic, Arc};
+use std::time::SystemTime;
 
 use ethereum_types::{U256, H256, Address};
 use rlp::Rlp;
@@ -33,6 +35,8 @@ pub struct TestClient {
 	is_service_transaction: bool,
 	local_address: Address,
 	max_transaction_size: usize,
+	included_transactions: HashMap<H256, u64>,
+	call_output: Option<Vec<u8>>,
 }
 
 impl Default for TestClient {
@@ -48,6 +52,8 @@ impl Default for TestClient {
 			is_service_transaction: false,
 			local_address: Default::default(),
 			max_transaction_size: MAX_TRANSACTION_SIZE,
+			included_transactions: Default::default(),
+			call_output: None,
 		}
 	}
 }
@@ -82,6 +88,16 @@ impl TestClient {
 		self
 	}
 
+	pub fn with_included_transaction(mut self, hash: H256, block_number: u64) -> Self {
+		self.included_transactions.insert(hash, block_number);
+		self
+	}
+
+	pub fn with_call_output(mut self, output: Vec<u8>) -> Self {
+		self.call_output = Some(output);
+		self
+	}
+
 	pub fn verify<T: Into<transaction::PendingTransaction>>(&self, tx: T) -> pool::VerifiedTransaction {
 		let tx = tx.into();
 		pool::VerifiedTransaction {
@@ -90,6 +106,7 @@ impl TestClient {
 			priority: pool::Priority::Regular,
 			transaction: tx,
 			insertion_id: 1,
+			insertion_time: SystemTime::now(),
 		}
 	}
 
@@ -146,3 +163,17 @@ impl pool::client::NonceClient for TestClient {
 		self.account_details.nonce
 	}
 }
+
+impl pool::client::ConditionClient for TestClient {
+	fn transaction_block_number(&self, hash: &H256) -> Option<u64> {
+		self.included_transactions.get(hash).cloned()
+	}
+
+	fn account_balance(&self, _address: &Address) -> U256 {
+		self.account_details.balance
+	}
+
+	fn call_contract(&self, _address: &Address, _data: &[u8]) -> Result<Vec<u8>, String> {
+		self.call_output.clone().ok_or_else(|| "Contract does not exist".into())
+	}
+}
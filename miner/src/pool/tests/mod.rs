@@ -14,11 +14,14 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::time::{Duration, UNIX_EPOCH};
+
 use ethereum_types::U256;
 use transaction::{self, PendingTransaction};
 use txpool;
 
-use pool::{verifier, TransactionQueue, PrioritizationStrategy, PendingSettings, PendingOrdering};
+use pool::{verifier, Priority, TransactionQueue, PrioritizationStrategy, ReplacementPolicy, PendingSettings, PendingOrdering};
+use pool::queue::MAX_BUNDLES;
 
 pub mod tx;
 pub mod client;
@@ -31,7 +34,8 @@ fn new_queue() -> TransactionQueue {
 		txpool::Options {
 			max_count: 3,
 			max_per_sender: 3,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -39,6 +43,7 @@ fn new_queue() -> TransactionQueue {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	)
 }
 #[test]
@@ -48,7 +53,8 @@ fn should_return_correct_nonces_when_dropped_because_of_limit() {
 		txpool::Options {
 			max_count: 3,
 			max_per_sender: 1,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -56,6 +62,7 @@ fn should_return_correct_nonces_when_dropped_because_of_limit() {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	let (tx1, tx2) = Tx::gas_price(2).signed_pair();
 	let sender = tx1.sender();
@@ -99,7 +106,8 @@ fn should_never_drop_local_transactions_from_different_senders() {
 		txpool::Options {
 			max_count: 3,
 			max_per_sender: 1,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -107,6 +115,7 @@ fn should_never_drop_local_transactions_from_different_senders() {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	let (tx1, tx2) = Tx::gas_price(2).signed_pair();
 	let sender = tx1.sender();
@@ -364,6 +373,51 @@ fn should_handle_min_block() {
 	assert_eq!(top.len(), 2);
 }
 
+#[test]
+fn should_keep_insertion_time_of_journaled_transactions() {
+	// given
+	let txq = new_queue();
+	let (tx, tx2) = Tx::default().signed_pair();
+	let inserted_at = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+	// when
+	let res = txq.import(TestClient::new(), vec![
+		verifier::Transaction::Journaled { transaction: tx.into(), priority: Priority::Local, inserted_at },
+		verifier::Transaction::Journaled { transaction: tx2.into(), priority: Priority::Regular, inserted_at },
+	]);
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+
+	// then
+	let all = txq.all_transactions();
+	assert_eq!(all.len(), 2);
+	assert!(all.iter().all(|tx| tx.insertion_time() == inserted_at));
+	assert_eq!(all.iter().filter(|tx| tx.priority() == Priority::Local).count(), 1);
+}
+
+#[test]
+fn should_restore_priority_of_journaled_transactions() {
+	// given
+	let txq = new_queue();
+	let (tx, tx2, tx3) = Tx::default().signed_triple();
+	let inserted_at = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+	// when
+	let res = txq.import(TestClient::new(), vec![
+		verifier::Transaction::Journaled { transaction: tx.into(), priority: Priority::Local, inserted_at },
+		verifier::Transaction::Journaled { transaction: tx2.into(), priority: Priority::Retracted, inserted_at },
+		verifier::Transaction::Journaled { transaction: tx3.into(), priority: Priority::Regular, inserted_at },
+	]);
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+
+	// then
+	let all = txq.all_transactions();
+	let priorities: Vec<_> = all.iter().map(|tx| tx.priority()).collect();
+	assert_eq!(all.len(), 3);
+	assert!(priorities.contains(&Priority::Local));
+	assert!(priorities.contains(&Priority::Retracted));
+	assert!(priorities.contains(&Priority::Regular));
+}
+
 #[test]
 fn should_correctly_update_futures_when_removing() {
 	// given
@@ -473,7 +527,8 @@ fn should_prefer_current_transactions_when_hitting_the_limit() {
 		txpool::Options {
 			max_count: 1,
 			max_per_sender: 2,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -481,6 +536,7 @@ fn should_prefer_current_transactions_when_hitting_the_limit() {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	let (tx, tx2) = Tx::default().signed_pair();
 	let hash = tx.hash();
@@ -667,12 +723,14 @@ fn should_remove_out_of_date_transactions_occupying_queue() {
 			max_count: 105,
 			max_per_sender: 3,
 			max_mem_usage: 5_000_000,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 10.into(),
 			..Default::default()
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	// that transaction will be occupying the queue
 	let (_, tx) = Tx::default().signed_pair();
@@ -703,13 +761,15 @@ fn should_accept_local_transactions_below_min_gas_price() {
 		txpool::Options {
 			max_count: 3,
 			max_per_sender: 3,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 10.into(),
 			..Default::default()
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	let tx = Tx::gas_price(1).signed();
 
@@ -887,7 +947,8 @@ fn should_include_local_transaction_to_a_full_pool() {
 		txpool::Options {
 			max_count: 1,
 			max_per_sender: 2,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -895,6 +956,7 @@ fn should_include_local_transaction_to_a_full_pool() {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	let tx1 = Tx::gas_price(10_000).signed().unverified();
 	let tx2 = Tx::gas_price(1).signed().local();
@@ -918,7 +980,8 @@ fn should_avoid_verifying_transaction_already_in_pool() {
 		txpool::Options {
 			max_count: 1,
 			max_per_sender: 2,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -926,6 +989,7 @@ fn should_avoid_verifying_transaction_already_in_pool() {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	let client = TestClient::new().with_balance(1_000_000_000);
 	let tx1 = Tx::gas_price(2).signed().unverified();
@@ -952,7 +1016,8 @@ fn should_avoid_reverifying_recently_rejected_transactions() {
 		txpool::Options {
 			max_count: 1,
 			max_per_sender: 2,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -960,6 +1025,7 @@ fn should_avoid_reverifying_recently_rejected_transactions() {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 
 	let client = TestClient::new();
@@ -993,7 +1059,8 @@ fn should_reject_early_in_case_gas_price_is_less_than_min_effective() {
 		txpool::Options {
 			max_count: 1,
 			max_per_sender: 2,
-			max_mem_usage: 50
+			max_mem_usage: 50,
+			..Default::default()
 		},
 		verifier::Options {
 			minimal_gas_price: 1.into(),
@@ -1001,6 +1068,7 @@ fn should_reject_early_in_case_gas_price_is_less_than_min_effective() {
 			tx_gas_limit: 1_000_000.into(),
 		},
 		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
 	);
 	let client = TestClient::new().with_balance(1_000_000_000);
 	let tx1 = Tx::gas_price(2).signed().unverified();
@@ -1023,3 +1091,91 @@ fn should_reject_early_in_case_gas_price_is_less_than_min_effective() {
 	// then
 	assert_eq!(txq.status().status.transaction_count, 1);
 }
+
+#[test]
+fn should_keep_bundles_outside_of_the_pool_until_culled() {
+	// given
+	let txq = new_queue();
+	let (tx1, tx2) = Tx::default().signed_pair();
+	let tx3 = Tx::default().signed();
+	let bundle_tx = tx3.clone();
+
+	// when
+	let untargeted = txq.import_bundle(TestClient::new(), vec![tx1, tx2].local(), None).unwrap();
+	let targeted = txq.import_bundle(TestClient::new(), vec![tx3].local(), Some(10)).unwrap();
+	let duplicate = txq.import_bundle(TestClient::new(), vec![bundle_tx].local(), Some(10));
+
+	// then
+	assert_eq!(duplicate, Err(transaction::Error::AlreadyImported));
+	assert_eq!(txq.status().status.transaction_count, 0);
+	assert_eq!(txq.pending_bundles(9).len(), 1);
+	assert_eq!(txq.pending_bundles(10).len(), 2);
+	assert_eq!(txq.pending_bundles(10)[0].hash(), &untargeted);
+	assert_eq!(txq.pending_bundles(10)[0].transactions().len(), 2);
+
+	// when
+	txq.cull_bundles(TestClient::new(), 10);
+
+	// then
+	assert_eq!(txq.pending_bundles(10).len(), 1);
+	assert!(txq.remove_bundle(&targeted).is_none());
+
+	// when
+	txq.cull_bundles(TestClient::new().with_nonce(124), 10);
+
+	// then
+	assert_eq!(txq.pending_bundles(11).len(), 0);
+}
+
+#[test]
+fn should_limit_number_of_bundles() {
+	// given
+	let txq = new_queue();
+	for _ in 0..MAX_BUNDLES {
+		txq.import_bundle(TestClient::new(), vec![Tx::default().signed()].local(), None).unwrap();
+	}
+
+	// when
+	let res = txq.import_bundle(TestClient::new(), vec![Tx::default().signed()].local(), None);
+
+	// then
+	assert_eq!(res, Err(transaction::Error::LimitReached));
+	assert_eq!(txq.pending_bundles(0).len(), MAX_BUNDLES);
+}
+
+#[test]
+fn should_report_evictions_because_of_origin_limit() {
+	// given
+	let txq = TransactionQueue::new(
+		txpool::Options {
+			max_count: 3,
+			max_per_sender: 3,
+			max_mem_usage: 50,
+			max_external: 1,
+			..Default::default()
+		},
+		verifier::Options {
+			minimal_gas_price: 1.into(),
+			block_gas_limit: 1_000_000.into(),
+			tx_gas_limit: 1_000_000.into(),
+		},
+		PrioritizationStrategy::GasPriceOnly,
+		ReplacementPolicy::default(),
+	);
+	let tx1 = Tx::gas_price(2).signed();
+	let tx2 = Tx::gas_price(3).signed();
+	let hash1 = tx1.hash();
+	let sender1 = tx1.sender();
+
+	// when
+	let res = txq.import(TestClient::new(), vec![tx1, tx2].unverified());
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+
+	// then
+	assert_eq!(txq.status().status.transaction_count, 1);
+	let evictions = txq.evictions();
+	assert_eq!(evictions.len(), 1);
+	assert_eq!(evictions[0].hash, hash1);
+	assert_eq!(evictions[0].sender, sender1);
+	assert_eq!(evictions[0].limit, txpool::Limit::Origin(txpool::Origin::External));
+}
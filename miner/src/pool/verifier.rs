@@ -25,6 +25,7 @@
 use std::cmp;
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicUsize};
+use std::time::SystemTime;
 
 use ethereum_types::{U256, H256};
 use rlp::Encodable;
@@ -75,6 +76,18 @@ pub enum Transaction {
 	///
 	/// We can skip consistency verifications and just verify readiness.
 	Local(transaction::PendingTransaction),
+
+	/// Transaction restored from the pool journal.
+	///
+	/// Verified as it was originally imported, and keeps its original insertion time.
+	Journaled {
+		/// The journaled transaction.
+		transaction: transaction::PendingTransaction,
+		/// Priority the transaction had in the pool.
+		priority: super::Priority,
+		/// Time the transaction was originally inserted to the pool.
+		inserted_at: SystemTime,
+	},
 }
 
 impl Transaction {
@@ -84,6 +97,7 @@ impl Transaction {
 			Transaction::Unverified(ref tx) => tx.hash(),
 			Transaction::Retracted(ref tx) => tx.hash(),
 			Transaction::Local(ref tx) => tx.hash(),
+			Transaction::Journaled { ref transaction, .. } => transaction.hash(),
 		}
 	}
 
@@ -93,14 +107,17 @@ impl Transaction {
 			Transaction::Unverified(ref tx) => &tx.gas_price,
 			Transaction::Retracted(ref tx) => &tx.gas_price,
 			Transaction::Local(ref tx) => &tx.gas_price,
+			Transaction::Journaled { ref transaction, .. } => &transaction.gas_price,
 		}
 	}
 
-	fn gas(&self) -> &U256 {
+	/// Return transaction gas limit
+	pub fn gas(&self) -> &U256 {
 		match *self {
 			Transaction::Unverified(ref tx) => &tx.gas,
 			Transaction::Retracted(ref tx) => &tx.gas,
 			Transaction::Local(ref tx) => &tx.gas,
+			Transaction::Journaled { ref transaction, .. } => &transaction.gas,
 		}
 	}
 
@@ -109,12 +126,14 @@ impl Transaction {
 			Transaction::Unverified(ref tx) => &*tx,
 			Transaction::Retracted(ref tx) => &*tx,
 			Transaction::Local(ref tx) => &*tx,
+			Transaction::Journaled { ref transaction, .. } => &*transaction,
 		}
 	}
 
 	fn is_local(&self) -> bool {
 		match *self {
 			Transaction::Local(..) => true,
+			Transaction::Journaled { priority, .. } => priority == super::Priority::Local,
 			_ => false,
 		}
 	}
@@ -122,6 +141,7 @@ impl Transaction {
 	fn is_retracted(&self) -> bool {
 		match *self {
 			Transaction::Retracted(..) => true,
+			Transaction::Journaled { priority, .. } => priority == super::Priority::Retracted,
 			_ => false,
 		}
 	}
@@ -165,6 +185,16 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 
 		let hash = tx.hash();
 
+		// journaled transactions are verified as they were originally imported.
+		let (tx, insertion_time) = match tx {
+			Transaction::Journaled { transaction, priority, inserted_at } => match priority {
+				super::Priority::Local => (Transaction::Local(transaction), inserted_at),
+				super::Priority::Retracted => (Transaction::Retracted(transaction.transaction.into()), inserted_at),
+				super::Priority::Regular => (Transaction::Unverified(transaction.transaction.into()), inserted_at),
+			},
+			tx => (tx, SystemTime::now()),
+		};
+
 		if self.client.transaction_already_included(&hash) {
 			trace!(target: "txqueue", "[{:?}] Rejected tx already in the blockchain", hash);
 			bail!(transaction::Error::AlreadyImported)
@@ -271,6 +301,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 				},
 			},
 			Transaction::Local(tx) => tx,
+			Transaction::Journaled { .. } => unreachable!("journaled transactions are unwrapped above; qed"),
 		};
 
 		// Verify RLP payload
@@ -341,6 +372,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 			hash,
 			sender,
 			insertion_id: self.id.fetch_add(1, atomic::Ordering::AcqRel),
+			insertion_time,
 		})
 	}
 }
@@ -260,6 +260,10 @@ usage! {
 			"--no-hardcoded-sync",
 			"By default, if there is no existing database the light client will automatically jump to a block hardcoded in the chain's specifications. This disables this feature.",
 
+			ARG arg_light_checkpoint: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.light_checkpoint.clone(),
+			"--light-checkpoint=[FILE]",
+			"Start the light client from a trusted checkpoint instead of the block hardcoded in the chain's specifications. FILE is a JSON file holding a header, its total difficulty and the CHT roots up to it, as returned by the parity_lightCheckpoint RPC of a full node. Only has an effect if there is no existing database.",
+
 			FLAG flag_force_direct: (bool) = false, or |_| None,
 			"--force-direct",
 			"Run the originally installed version of Parity, ignoring any updates that have since been installed.",
@@ -414,6 +418,10 @@ usage! {
 			"--no-warp",
 			"Disable syncing from the snapshot over the network.",
 
+			FLAG flag_state_sync: (bool) = false, or |c: &Config| c.network.as_ref()?.state_sync.clone(),
+			"--state-sync",
+			"Enable syncing the state trie of a recent block from peers instead of executing all blocks. Takes precedence over warp sync.",
+
 			FLAG flag_no_discovery: (bool) = false, or |c: &Config| c.network.as_ref()?.discovery.map(|d| !d).clone(),
 			"--no-discovery",
 			"Disable new peer discovery.",
@@ -430,6 +438,18 @@ usage! {
 			"--no-serve-light",
 			"Disable serving of light peers.",
 
+			ARG arg_light_serve_max_peers: (Option<u16>) = None, or |c: &Config| c.network.as_ref()?.light_serve_max_peers.clone(),
+			"--light-serve-max-peers=[NUM]",
+			"Serve up to NUM light clients at once, not counting priority clients. Each is given an equal share of serving capacity.",
+
+			ARG arg_light_serve_recharge: (u64) = 100000000u64, or |c: &Config| c.network.as_ref()?.light_serve_recharge.clone(),
+			"--light-serve-recharge=[CREDITS]",
+			"Recharge the request credits of each light client by CREDITS per second. Request costs are measured from serving times, which persist across restarts, such that clients recharging at the default rate use an equal share of serving capacity.",
+
+			ARG arg_light_serve_priority_keys: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.light_serve_priority_keys.as_ref().map(|vec| vec.join(",")),
+			"--light-serve-priority-keys=[KEYS]",
+			"Serve light clients with these node IDs without charging request credits, even when the limit of light clients is reached. KEYS should be comma-delimited hex public keys.",
+
 			ARG arg_warp_barrier: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.warp_barrier.clone(),
 			"--warp-barrier=[NUM]",
 			"When warp enabled never attempt regular sync before warping to block NUM.",
@@ -493,7 +513,7 @@ usage! {
 
 			ARG arg_jsonrpc_apis: (String) = "web3,eth,pubsub,net,parity,private,parity_pubsub,traces,rpc,shh,shh_pubsub", or |c: &Config| c.rpc.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-apis=[APIS]",
-			"Specify the APIs available through the JSONRPC interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
+			"Specify the APIs available through the JSONRPC interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, txpool, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, txpool, rpc, shh, shh_pubsub",
 
 			ARG arg_jsonrpc_hosts: (String) = "none", or |c: &Config| c.rpc.as_ref()?.hosts.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-hosts=[HOSTS]",
@@ -526,7 +546,7 @@ usage! {
 
 			ARG arg_ws_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,private,traces,rpc,shh,shh_pubsub", or |c: &Config| c.websockets.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--ws-apis=[APIS]",
-			"Specify the APIs available through the WebSockets interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
+			"Specify the APIs available through the WebSockets interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, txpool, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, txpool, rpc, shh, shh_pubsub",
 
 			ARG arg_ws_origins: (String) = "parity://*,chrome-extension://*,moz-extension://*", or |c: &Config| c.websockets.as_ref()?.origins.as_ref().map(|vec| vec.join(",")),
 			"--ws-origins=[URL]",
@@ -551,7 +571,7 @@ usage! {
 
 			ARG arg_ipc_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,parity_accounts,private,traces,rpc,shh,shh_pubsub", or |c: &Config| c.ipc.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--ipc-apis=[APIS]",
-			"Specify custom API set available via JSON-RPC over IPC using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
+			"Specify custom API set available via JSON-RPC over IPC using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, txpool, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains: web3, net, eth, pubsub, parity, parity_pubsub, traces, txpool, rpc, shh, shh_pubsub",
 
 		["API and console options – Dapps"]
 			FLAG flag_no_dapps: (bool) = false, or |c: &Config| c.dapps.as_ref()?.disable.clone(),
@@ -673,6 +693,14 @@ usage! {
 			"--refuse-service-transactions",
 			"Always refuse service transactions.",
 
+			FLAG flag_tx_queue_local_replacement_without_bump: (bool) = false, or |c: &Config| c.mining.as_ref()?.tx_queue_local_replacement_without_bump.clone(),
+			"--tx-queue-local-replacement-without-bump",
+			"Allow local transactions to replace local transactions with the same nonce in the queue without increasing the gas price.",
+
+			FLAG flag_tx_queue_journal: (bool) = false, or |c: &Config| c.mining.as_ref()?.tx_queue_journal.clone(),
+			"--tx-queue-journal",
+			"Periodically save all transactions in the queue to disk and reload them whenever the node restarts.",
+
 			FLAG flag_infinite_pending_block: (bool) = false, or |c: &Config| c.mining.as_ref()?.infinite_pending_block.clone(),
 			"--infinite-pending-block",
 			"Pending block will be created with maximal possible gas limit and will execute all transactions in the queue. Note that such block is invalid and should never be attempted to be mined.",
@@ -717,6 +745,14 @@ usage! {
 			"--price-update-period=[T]",
 			"T will be allowed to pass between each gas price update. T may be daily, hourly, a number of seconds, or a time string of the form \"2 days\", \"30 minutes\" etc..",
 
+			ARG arg_gas_price_oracle_blocks: (Option<usize>) = None, or |c: &Config| c.mining.as_ref()?.gas_price_oracle_blocks.clone(),
+			"--gas-price-oracle-blocks=[N]",
+			"Suggest gas prices from transactions included in the last N blocks. eth_gasPrice returns the standard suggestion and parity_gasPriceOracle returns all of them.",
+
+			ARG arg_gas_price_oracle_percentiles: (String) = "25,50,90", or |c: &Config| c.mining.as_ref()?.gas_price_oracle_percentiles.clone(),
+			"--gas-price-oracle-percentiles=[SLOW,STANDARD,FAST]",
+			"Percentiles of gas prices in recent blocks suggested for slow, standard and fast inclusion by the gas price oracle.",
+
 			ARG arg_gas_floor_target: (String) = "4700000", or |c: &Config| c.mining.as_ref()?.gas_floor_target.clone(),
 			"--gas-floor-target=[GAS]",
 			"Amount of gas per block to target when sealing a new block.",
@@ -737,13 +773,41 @@ usage! {
 			"--tx-queue-per-sender=[LIMIT]",
 			"Maximum number of transactions per sender in the queue. By default it's 1% of the entire queue, but not less than 16.",
 
+			ARG arg_tx_queue_per_sender_gas: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_per_sender_gas.clone(),
+			"--tx-queue-per-sender-gas=[GAS]",
+			"Maximum total gas of transactions per sender in the queue. Transactions of a sender exceeding the limit are evicted or rejected. Not limited by default.",
+
+			ARG arg_tx_queue_locals: (Option<usize>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_locals.clone(),
+			"--tx-queue-locals=[LIMIT]",
+			"Maximum number of local transactions in the queue. Not limited separately by default.",
+
+			ARG arg_tx_queue_retracted: (Option<usize>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_retracted.clone(),
+			"--tx-queue-retracted=[LIMIT]",
+			"Maximum number of transactions from retracted blocks in the queue. Not limited separately by default.",
+
+			ARG arg_tx_queue_externals: (Option<usize>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_externals.clone(),
+			"--tx-queue-externals=[LIMIT]",
+			"Maximum number of external transactions in the queue. Not limited separately by default.",
+
 			ARG arg_tx_queue_gas: (String) = "off", or |c: &Config| c.mining.as_ref()?.tx_queue_gas.clone(),
 			"--tx-queue-gas=[LIMIT]",
 			"Maximum amount of total gas for external transactions in the queue. LIMIT can be either an amount of gas or 'auto' or 'off'. 'auto' sets the limit to be 20x the current block gas limit.",
 
 			ARG arg_tx_queue_strategy: (String) = "gas_price", or |c: &Config| c.mining.as_ref()?.tx_queue_strategy.clone(),
 			"--tx-queue-strategy=[S]",
-			"Prioritization strategy used to order transactions in the queue. S may be: gas_price - Prioritize txs with high gas price",
+			"Prioritization strategy used to order transactions in the queue. S may be: gas_price - Prioritize txs with high gas price; sender_fairness - Prioritize txs with high gas price, but let senders with many queued txs take turns with others; priority - Prioritize txs with high gas price, boosting txs sent from or to addresses given by --tx-queue-priority; reward - Prioritize txs with high reward for the miner per unit of gas, preferring smaller txs with the same gas price and ordering service txs last",
+
+			ARG arg_tx_queue_priority: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_priority.as_ref().map(|vec| vec.join(",")),
+			"--tx-queue-priority=[ADDRESSES]",
+			"Specify comma-separated list of sender or contract addresses whose transactions are boosted with the priority queue strategy.",
+
+			ARG arg_tx_queue_replacement_bump: (u32) = 1250u32, or |c: &Config| c.mining.as_ref()?.tx_queue_replacement_bump.clone(),
+			"--tx-queue-replacement-bump=[BASIS_POINTS]",
+			"Minimal gas price increase, in basis points (1/100 of a percent), required to replace a transaction with the same sender and nonce in the queue.",
+
+			ARG arg_tx_queue_journal_max_age: (u64) = 10800u64, or |c: &Config| c.mining.as_ref()?.tx_queue_journal_max_age.clone(),
+			"--tx-queue-journal-max-age=[S]",
+			"Transactions inserted to the queue more than S seconds before the restart are not reloaded from the journal.",
 
 			ARG arg_stratum_interface: (String) = "local", or |c: &Config| c.stratum.as_ref()?.interface.clone(),
 			"--stratum-interface=[IP]",
@@ -777,6 +841,14 @@ usage! {
 			"--tx-time-limit=[MS]",
 			"Maximal time for processing single transaction. If enabled senders of transactions offending the limit will get other transactions penalized.",
 
+			ARG arg_block_builder: (String) = "priority", or |c: &Config| c.mining.as_ref()?.block_builder.clone(),
+			"--block-builder=[MODE]",
+			"Strategy of filling pending block with transactions. MODE may be: priority - Push transactions in the order of their priority in the queue; fees - Simulate transactions against the block state and push the ones rewarding the author the most per gas used first, retrying transactions that can't be included yet after others change the state.",
+
+			ARG arg_block_builder_time_budget: (u64) = 500u64, or |c: &Config| c.mining.as_ref()?.block_builder_time_budget.clone(),
+			"--block-builder-time-budget=[MS]",
+			"Maximal time spent on simulating and pushing transactions with the fees block builder. Remaining transactions are pushed in the order of their priority.",
+
 			ARG arg_extra_data: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.extra_data.clone(),
 			"--extra-data=[STRING]",
 			"Specify a custom extra-data for authored blocks, no more than 32 characters.",
@@ -789,6 +861,10 @@ usage! {
 			"--stratum-secret=[STRING]",
 			"Secret for authorizing Stratum server for peers.",
 
+			ARG arg_stratum_share_difficulty: (Option<String>) = None, or |c: &Config| c.stratum.as_ref()?.share_difficulty.clone(),
+			"--stratum-share-difficulty=[DIFF]",
+			"Minimal difficulty of shares submitted by Stratum workers. Workers can raise it with mining.suggest_difficulty. If not set, shares need to meet network difficulty.",
+
 		["Internal Options"]
 			FLAG flag_can_restart: (bool) = false, or |_| None,
 			"--can-restart",
@@ -1081,6 +1157,7 @@ struct Operating {
 	light: Option<bool>,
 	no_persistent_txqueue: Option<bool>,
 	no_hardcoded_sync: Option<bool>,
+	light_checkpoint: Option<String>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1122,6 +1199,7 @@ struct Ui {
 struct Network {
 	warp: Option<bool>,
 	warp_barrier: Option<u64>,
+	state_sync: Option<bool>,
 	port: Option<u16>,
 	min_peers: Option<u16>,
 	max_peers: Option<u16>,
@@ -1136,6 +1214,9 @@ struct Network {
 	reserved_peers: Option<String>,
 	reserved_only: Option<bool>,
 	no_serve_light: Option<bool>,
+	light_serve_max_peers: Option<u16>,
+	light_serve_recharge: Option<u64>,
+	light_serve_priority_keys: Option<Vec<String>>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1229,20 +1310,33 @@ struct Mining {
 	work_queue_size: Option<usize>,
 	tx_gas_limit: Option<String>,
 	tx_time_limit: Option<u64>,
+	block_builder: Option<String>,
+	block_builder_time_budget: Option<u64>,
 	relay_set: Option<String>,
 	min_gas_price: Option<u64>,
 	gas_price_percentile: Option<usize>,
 	usd_per_tx: Option<String>,
 	usd_per_eth: Option<String>,
 	price_update_period: Option<String>,
+	gas_price_oracle_blocks: Option<usize>,
+	gas_price_oracle_percentiles: Option<String>,
 	gas_floor_target: Option<String>,
 	gas_cap: Option<String>,
 	extra_data: Option<String>,
 	tx_queue_size: Option<usize>,
 	tx_queue_per_sender: Option<usize>,
+	tx_queue_per_sender_gas: Option<String>,
+	tx_queue_locals: Option<usize>,
+	tx_queue_retracted: Option<usize>,
+	tx_queue_externals: Option<usize>,
 	tx_queue_mem_limit: Option<u32>,
 	tx_queue_gas: Option<String>,
 	tx_queue_strategy: Option<String>,
+	tx_queue_priority: Option<Vec<String>>,
+	tx_queue_replacement_bump: Option<u32>,
+	tx_queue_local_replacement_without_bump: Option<bool>,
+	tx_queue_journal: Option<bool>,
+	tx_queue_journal_max_age: Option<u64>,
 	tx_queue_ban_count: Option<u16>,
 	tx_queue_ban_time: Option<u16>,
 	tx_queue_no_unfamiliar_locals: Option<bool>,
@@ -1258,6 +1352,7 @@ struct Stratum {
 	interface: Option<String>,
 	port: Option<u16>,
 	secret: Option<String>,
+	share_difficulty: Option<String>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1536,6 +1631,7 @@ mod tests {
 			arg_identity: "".into(),
 			flag_light: false,
 			flag_no_hardcoded_sync: false,
+			arg_light_checkpoint: Some("$HOME/.parity/checkpoint.json".into()),
 			flag_no_persistent_txqueue: false,
 			flag_force_direct: false,
 
@@ -1571,6 +1667,7 @@ mod tests {
 
 			// -- Networking Options
 			flag_no_warp: false,
+			flag_state_sync: false,
 			arg_port: 30303u16,
 			arg_min_peers: Some(25u16),
 			arg_max_peers: Some(50u16),
@@ -1586,6 +1683,9 @@ mod tests {
 			flag_reserved_only: false,
 			flag_no_ancient_blocks: false,
 			flag_no_serve_light: false,
+			arg_light_serve_max_peers: Some(10u16),
+			arg_light_serve_recharge: 100000000u64,
+			arg_light_serve_priority_keys: Some("".into()),
 
 			// -- API and Console Options
 			// RPC
@@ -1653,21 +1753,34 @@ mod tests {
 			arg_work_queue_size: 20usize,
 			arg_tx_gas_limit: Some("6283184".into()),
 			arg_tx_time_limit: Some(100u64),
+			arg_block_builder: "priority".into(),
+			arg_block_builder_time_budget: 500u64,
 			arg_relay_set: "cheap".into(),
 			arg_min_gas_price: Some(0u64),
 			arg_usd_per_tx: "0.0001".into(),
 			arg_gas_price_percentile: 50usize,
 			arg_usd_per_eth: "auto".into(),
 			arg_price_update_period: "hourly".into(),
+			arg_gas_price_oracle_blocks: Some(100usize),
+			arg_gas_price_oracle_percentiles: "25,50,90".into(),
 			arg_gas_floor_target: "4700000".into(),
 			arg_gas_cap: "6283184".into(),
 			arg_extra_data: Some("Parity".into()),
 			flag_tx_queue_no_unfamiliar_locals: false,
 			arg_tx_queue_size: 8192usize,
 			arg_tx_queue_per_sender: None,
+			arg_tx_queue_per_sender_gas: None,
+			arg_tx_queue_locals: None,
+			arg_tx_queue_retracted: None,
+			arg_tx_queue_externals: None,
 			arg_tx_queue_mem_limit: 4u32,
 			arg_tx_queue_gas: "off".into(),
 			arg_tx_queue_strategy: "gas_factor".into(),
+			arg_tx_queue_priority: None,
+			arg_tx_queue_replacement_bump: 1250u32,
+			flag_tx_queue_local_replacement_without_bump: false,
+			flag_tx_queue_journal: false,
+			arg_tx_queue_journal_max_age: 10800u64,
 			arg_tx_queue_ban_count: 1u16,
 			arg_tx_queue_ban_time: 180u16,
 			flag_remove_solved: false,
@@ -1679,6 +1792,7 @@ mod tests {
 			arg_stratum_interface: "local".to_owned(),
 			arg_stratum_port: 8008u16,
 			arg_stratum_secret: None,
+			arg_stratum_share_difficulty: None,
 
 			// -- Footprint Options
 			arg_tracing: "auto".into(),
@@ -1807,6 +1921,7 @@ mod tests {
 				identity: None,
 				light: None,
 				no_hardcoded_sync: None,
+				light_checkpoint: None,
 				no_persistent_txqueue: None,
 			}),
 			account: Some(Account {
@@ -1828,6 +1943,7 @@ mod tests {
 			network: Some(Network {
 				warp: Some(false),
 				warp_barrier: None,
+				state_sync: None,
 				port: None,
 				min_peers: Some(10),
 				max_peers: Some(20),
@@ -1842,6 +1958,9 @@ mod tests {
 				reserved_peers: Some("./path/to/reserved_peers".into()),
 				reserved_only: Some(true),
 				no_serve_light: None,
+				light_serve_max_peers: None,
+				light_serve_recharge: None,
+				light_serve_priority_keys: None,
 			}),
 			websockets: Some(Ws {
 				disable: Some(true),
@@ -1919,18 +2038,31 @@ mod tests {
 				usd_per_tx: None,
 				usd_per_eth: None,
 				price_update_period: Some("hourly".into()),
+				gas_price_oracle_blocks: None,
+				gas_price_oracle_percentiles: None,
 				gas_floor_target: None,
 				gas_cap: None,
 				tx_queue_size: Some(8192),
 				tx_queue_per_sender: None,
+				tx_queue_per_sender_gas: None,
+				tx_queue_locals: None,
+				tx_queue_retracted: None,
+				tx_queue_externals: None,
 				tx_queue_mem_limit: None,
 				tx_queue_gas: Some("off".into()),
 				tx_queue_strategy: None,
+				tx_queue_priority: None,
+				tx_queue_replacement_bump: None,
+				tx_queue_local_replacement_without_bump: None,
+				tx_queue_journal: None,
+				tx_queue_journal_max_age: None,
 				tx_queue_ban_count: None,
 				tx_queue_ban_time: None,
 				tx_queue_no_unfamiliar_locals: None,
 				tx_gas_limit: None,
 				tx_time_limit: None,
+				block_builder: None,
+				block_builder_time_budget: None,
 				extra_data: None,
 				remove_solved: None,
 				notify_work: None,
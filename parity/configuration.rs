@@ -33,12 +33,13 @@ use ethcore::client::{VMType};
 use ethcore::miner::{stratum, MinerOptions};
 use ethcore::verification::queue::VerifierSettings;
 use miner::pool;
+use miner::gas_pricer::GasPriceOracleOptions;
 
 use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration, UiConfiguration};
 use rpc_apis::ApiSet;
 use parity_rpc::NetworkSettings;
 use cache::CacheConfig;
-use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization, passwords_from_files};
+use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization, to_block_builder, passwords_from_files};
 use dir::helpers::{replace_home, replace_home_and_local};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
 use ethcore_logger::Config as LogConfig;
@@ -372,6 +373,7 @@ impl Configuration {
 				vm_type: vm_type,
 				warp_sync: warp_sync,
 				warp_barrier: self.args.arg_warp_barrier,
+				state_sync: self.args.flag_state_sync,
 				public_node: public_node,
 				geth_compatibility: geth_compatibility,
 				net_settings: self.network_settings()?,
@@ -391,10 +393,16 @@ impl Configuration {
 				download_old_blocks: !self.args.flag_no_ancient_blocks,
 				verifier_settings: verifier_settings,
 				serve_light: !self.args.flag_no_serve_light,
+				light_serve_conf: self.light_serve_config()?,
 				light: self.args.flag_light,
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
+				tx_queue_journal: match self.args.flag_tx_queue_journal {
+					true => Some(Duration::from_secs(self.args.arg_tx_queue_journal_max_age)),
+					false => None,
+				},
 				whisper: whisper_config,
 				no_hardcoded_sync: self.args.flag_no_hardcoded_sync,
+				light_checkpoint: self.args.arg_light_checkpoint.as_ref().map(|path| replace_home(&self.directories().base, path)),
 			};
 			Cmd::Run(run_cmd)
 		};
@@ -522,6 +530,10 @@ impl Configuration {
 				listen_addr: self.stratum_interface(),
 				port: self.args.arg_ports_shift + self.args.arg_stratum_port,
 				secret: self.args.arg_stratum_secret.as_ref().map(|s| s.parse::<H256>().unwrap_or_else(|_| keccak(s))),
+				share_difficulty: match self.args.arg_stratum_share_difficulty {
+					Some(ref difficulty) => Some(to_u256(difficulty)?),
+					None => None,
+				},
 			}))
 		} else { Ok(None) }
 	}
@@ -546,9 +558,14 @@ impl Configuration {
 			work_queue_size: self.args.arg_work_queue_size,
 			enable_resubmission: !self.args.flag_remove_solved,
 			infinite_pending_block: self.args.flag_infinite_pending_block,
+			block_builder: to_block_builder(&self.args.arg_block_builder, self.args.arg_block_builder_time_budget)?,
 
 			tx_queue_penalization: to_queue_penalization(self.args.arg_tx_time_limit)?,
-			tx_queue_strategy: to_queue_strategy(&self.args.arg_tx_queue_strategy)?,
+			tx_queue_strategy: to_queue_strategy(&self.args.arg_tx_queue_strategy, to_addresses(&self.args.arg_tx_queue_priority)?)?,
+			tx_queue_replacement: pool::ReplacementPolicy {
+				min_gas_price_bump: self.args.arg_tx_queue_replacement_bump,
+				local_without_bump: self.args.flag_tx_queue_local_replacement_without_bump,
+			},
 			tx_queue_no_unfamiliar_locals: self.args.flag_tx_queue_no_unfamiliar_locals,
 			refuse_service_transactions: self.args.flag_refuse_service_transactions,
 
@@ -561,6 +578,13 @@ impl Configuration {
 
 	fn pool_limits(&self) -> Result<pool::Options, String> {
 		let max_count = self.args.arg_tx_queue_size;
+		let max_gas_per_sender = match self.args.arg_tx_queue_per_sender_gas {
+			Some(ref d) => {
+				let gas = to_u256(d)?;
+				if gas > U256::from(u64::max_value()) { u64::max_value() } else { gas.low_u64() }
+			},
+			None => u64::max_value(),
+		};
 
 		Ok(pool::Options {
 			max_count,
@@ -570,6 +594,10 @@ impl Configuration {
 			} else {
 				usize::max_value()
 			},
+			max_local: self.args.arg_tx_queue_locals.unwrap_or_else(usize::max_value),
+			max_retracted: self.args.arg_tx_queue_retracted.unwrap_or_else(usize::max_value),
+			max_external: self.args.arg_tx_queue_externals.unwrap_or_else(usize::max_value),
+			max_gas_per_sender,
 		})
 	}
 
@@ -697,6 +725,17 @@ impl Configuration {
 			U256::from_dec_str(&format!("{:.0}", wei_per_gas)).unwrap()
 		}
 
+		if let Some(options) = self.gas_price_oracle_options()? {
+			let minimal_gas_price = match self.args.arg_gasprice.as_ref() {
+				Some(dec) => to_u256(dec)?,
+				None => self.args.arg_min_gas_price.map(U256::from).unwrap_or_else(U256::zero),
+			};
+			return Ok(GasPricerConfig::Oracle {
+				minimal_gas_price: minimal_gas_price,
+				options: options,
+			});
+		}
+
 		if let Some(dec) = self.args.arg_gasprice.as_ref() {
 			return Ok(GasPricerConfig::Fixed(to_u256(dec)?));
 		} else if let Some(dec) = self.args.arg_min_gas_price {
@@ -725,6 +764,31 @@ impl Configuration {
 		Ok(GasPricerConfig::Fixed(wei_per_gas))
 	}
 
+	fn gas_price_oracle_options(&self) -> Result<Option<GasPriceOracleOptions>, String> {
+		let blocks = match self.args.arg_gas_price_oracle_blocks {
+			Some(blocks) => blocks,
+			None => return Ok(None),
+		};
+
+		let percentiles = self.args.arg_gas_price_oracle_percentiles.split(',')
+			.map(|s| match s.trim().parse::<usize>() {
+				Ok(pct) if pct <= 100 => Ok(pct),
+				_ => Err(format!("Invalid gas price oracle percentile: {}", s)),
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		if percentiles.len() != 3 {
+			return Err(format!("Expected slow, standard and fast gas price oracle percentiles, got: {}", self.args.arg_gas_price_oracle_percentiles));
+		}
+
+		Ok(Some(GasPriceOracleOptions {
+			blocks: blocks,
+			slow_percentile: percentiles[0],
+			standard_percentile: percentiles[1],
+			fast_percentile: percentiles[2],
+		}))
+	}
+
 	fn extra_data(&self) -> Result<Bytes, String> {
 		match self.args.arg_extradata.as_ref().or(self.args.arg_extra_data.as_ref()) {
 			Some(x) if x.len() <= 32 => Ok(x.as_bytes().to_owned()),
@@ -772,6 +836,23 @@ impl Configuration {
 		Ok((listen_address, public_address))
 	}
 
+	fn light_serve_config(&self) -> Result<sync::LightServeConfig, String> {
+		let priority_peers = match self.args.arg_light_serve_priority_keys {
+			Some(ref keys) if !keys.is_empty() => keys.split(',').map(|key| {
+				let key = key.trim();
+				Public::from_str(key.trim_left_matches("0x"))
+					.map_err(|_| format!("Invalid light client node ID: {}", key))
+			}).collect::<Result<Vec<_>, _>>()?,
+			_ => Vec::new(),
+		};
+
+		Ok(sync::LightServeConfig {
+			max_peers: self.args.arg_light_serve_max_peers.map(|n| n as usize),
+			recharge_rate: self.args.arg_light_serve_recharge,
+			priority_peers: priority_peers,
+		})
+	}
+
 	fn net_config(&self) -> Result<NetworkConfiguration, String> {
 		let mut ret = NetworkConfiguration::new();
 		ret.nat_enabled = self.args.arg_nat == "any" || self.args.arg_nat == "upnp";
@@ -1244,7 +1325,7 @@ mod tests {
 
 	use tempdir::TempDir;
 	use ethcore::client::{VMType, BlockId};
-	use ethcore::miner::MinerOptions;
+	use ethcore::miner::{MinerOptions, BlockBuilder};
 	use miner::pool::PrioritizationStrategy;
 	use parity_rpc::NetworkSettings;
 	use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
@@ -1496,6 +1577,7 @@ mod tests {
 			public_node: false,
 			warp_sync: true,
 			warp_barrier: None,
+			state_sync: false,
 			acc_conf: Default::default(),
 			gas_pricer_conf: Default::default(),
 			miner_extras: Default::default(),
@@ -1534,9 +1616,12 @@ mod tests {
 			download_old_blocks: true,
 			verifier_settings: Default::default(),
 			serve_light: true,
+			light_serve_conf: Default::default(),
 			light: false,
 			no_hardcoded_sync: false,
+			light_checkpoint: None,
 			no_persistent_txqueue: false,
+			tx_queue_journal: None,
 			whisper: Default::default(),
 		};
 		expected.secretstore_conf.enabled = cfg!(feature = "secretstore");
@@ -1552,11 +1637,37 @@ mod tests {
 		// when
 		let conf0 = parse(&["parity"]);
 		let conf2 = parse(&["parity", "--tx-queue-strategy", "gas_price"]);
+		let conf3 = parse(&["parity", "--tx-queue-strategy", "sender_fairness"]);
+		let conf4 = parse(&["parity", "--tx-queue-strategy", "priority", "--tx-queue-priority", "0x0000000000000000000000000000000000000042"]);
+		let conf5 = parse(&["parity", "--tx-queue-strategy", "priority"]);
+		let conf6 = parse(&["parity", "--tx-queue-strategy", "reward"]);
 
 		// then
 		assert_eq!(conf0.miner_options().unwrap(), mining_options);
 		mining_options.tx_queue_strategy = PrioritizationStrategy::GasPriceOnly;
 		assert_eq!(conf2.miner_options().unwrap(), mining_options);
+		mining_options.tx_queue_strategy = PrioritizationStrategy::SenderFairness;
+		assert_eq!(conf3.miner_options().unwrap(), mining_options);
+		mining_options.tx_queue_strategy = PrioritizationStrategy::PriorityAddresses(vec![Address::from(0x42)].into_iter().collect());
+		assert_eq!(conf4.miner_options().unwrap(), mining_options);
+		assert!(conf5.miner_options().is_err());
+		mining_options.tx_queue_strategy = PrioritizationStrategy::EffectiveReward;
+		assert_eq!(conf6.miner_options().unwrap(), mining_options);
+	}
+
+	#[test]
+	fn should_parse_block_builder() {
+		// given
+		let conf0 = parse(&["parity"]);
+		let conf1 = parse(&["parity", "--block-builder", "fees", "--block-builder-time-budget", "200"]);
+		let conf2 = parse(&["parity", "--block-builder", "xxx"]);
+
+		// then
+		assert_eq!(conf0.miner_options().unwrap().block_builder, BlockBuilder::Priority);
+		assert_eq!(conf1.miner_options().unwrap().block_builder, BlockBuilder::MaximizeFees {
+			time_budget: Duration::from_millis(200),
+		});
+		assert!(conf2.miner_options().is_err());
 	}
 
 	#[test]
@@ -1983,6 +2094,7 @@ mod tests {
 		assert_eq!(conf0.secretstore_config().unwrap().http_port, 8083);
 		assert_eq!(conf0.ipfs_config().port, 5002);
 		assert_eq!(conf0.stratum_options().unwrap().unwrap().port, 8009);
+		assert_eq!(conf0.stratum_options().unwrap().unwrap().share_difficulty, None);
 
 
 		assert_eq!(conf1.net_addresses().unwrap().0.port(), 30304);
@@ -2101,6 +2213,55 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn should_parse_gas_price_oracle() {
+		let args = vec!["parity", "--gas-price-oracle-blocks=20", "--gas-price-oracle-percentiles=10,60,95", "--min-gas-price=5"];
+		let conf = Configuration::parse(&args).unwrap();
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::Oracle {
+			minimal_gas_price: 5.into(),
+			options: GasPriceOracleOptions {
+				blocks: 20,
+				slow_percentile: 10,
+				standard_percentile: 60,
+				fast_percentile: 95,
+			},
+		}));
+
+		let args = vec!["parity", "--gas-price-oracle-blocks=20", "--gas-price-oracle-percentiles=10,101,95"];
+		let conf = Configuration::parse(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+
+		let args = vec!["parity", "--gas-price-oracle-blocks=20", "--gas-price-oracle-percentiles=10,60"];
+		let conf = Configuration::parse(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+	}
+
+	#[test]
+	fn should_parse_pool_limits() {
+		let args = vec!["parity", "--tx-queue-locals=100", "--tx-queue-externals=1000", "--tx-queue-per-sender-gas=0x1000000"];
+		let conf = Configuration::parse(&args).unwrap();
+		let limits = conf.pool_limits().unwrap();
+		assert_eq!(limits.max_local, 100);
+		assert_eq!(limits.max_retracted, usize::max_value());
+		assert_eq!(limits.max_external, 1000);
+		assert_eq!(limits.max_gas_per_sender, 0x1000000);
+
+		let args = vec!["parity"];
+		let conf = Configuration::parse(&args).unwrap();
+		assert_eq!(conf.pool_limits().unwrap().max_gas_per_sender, u64::max_value());
+	}
+
+	#[test]
+	fn should_parse_stratum_share_difficulty() {
+		let args = vec!["parity", "--stratum", "--stratum-share-difficulty=0x100000"];
+		let conf = Configuration::parse(&args).unwrap();
+		assert_eq!(conf.stratum_options().unwrap().unwrap().share_difficulty, Some(0x100000.into()));
+
+		let args = vec!["parity", "--stratum", "--stratum-share-difficulty=lots"];
+		let conf = Configuration::parse(&args).unwrap();
+		assert!(conf.stratum_options().is_err());
+	}
+
 	#[test]
 	fn should_respect_only_min_peers_and_default() {
 		let args = vec!["parity", "--min-peers=5"];
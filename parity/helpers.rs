@@ -21,7 +21,7 @@ use std::fs::File;
 use ethereum_types::{U256, clean_0x, Address};
 use journaldb::Algorithm;
 use ethcore::client::{Mode, BlockId, VMType, DatabaseCompactionProfile, ClientConfig, VerifierType};
-use ethcore::miner::{PendingSet, Penalization};
+use ethcore::miner::{PendingSet, Penalization, BlockBuilder};
 use miner::pool::PrioritizationStrategy;
 use cache::CacheConfig;
 use dir::DatabaseDirectories;
@@ -96,9 +96,13 @@ pub fn to_pending_set(s: &str) -> Result<PendingSet, String> {
 	}
 }
 
-pub fn to_queue_strategy(s: &str) -> Result<PrioritizationStrategy, String> {
+pub fn to_queue_strategy(s: &str, priority_addresses: Vec<Address>) -> Result<PrioritizationStrategy, String> {
 	match s {
 		"gas_price" => Ok(PrioritizationStrategy::GasPriceOnly),
+		"sender_fairness" => Ok(PrioritizationStrategy::SenderFairness),
+		"priority" if priority_addresses.is_empty() => Err("No priority addresses given for priority queue strategy.".into()),
+		"priority" => Ok(PrioritizationStrategy::PriorityAddresses(priority_addresses.into_iter().collect())),
+		"reward" => Ok(PrioritizationStrategy::EffectiveReward),
 		other => Err(format!("Invalid queue strategy: {}", other)),
 	}
 }
@@ -112,6 +116,16 @@ pub fn to_queue_penalization(time: Option<u64>) -> Result<Penalization, String>
 	})
 }
 
+pub fn to_block_builder(s: &str, time_budget_ms: u64) -> Result<BlockBuilder, String> {
+	match s {
+		"priority" => Ok(BlockBuilder::Priority),
+		"fees" => Ok(BlockBuilder::MaximizeFees {
+			time_budget: Duration::from_millis(time_budget_ms),
+		}),
+		other => Err(format!("Invalid block builder: {}", other)),
+	}
+}
+
 pub fn to_address(s: Option<String>) -> Result<Address, String> {
 	match s {
 		Some(ref a) => clean_0x(a).parse().map_err(|_| format!("Invalid address: {:?}", a)),
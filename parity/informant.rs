@@ -93,6 +93,7 @@ pub struct SyncInfo {
 	num_peers: usize,
 	max_peers: u32,
 	snapshot_sync: bool,
+	state_sync: Option<(usize, usize)>,
 }
 
 pub struct Report {
@@ -156,6 +157,11 @@ impl InformantData for FullNodeInformantData {
 					num_peers: status.num_peers,
 					max_peers: status.current_max_peers(net_config.min_peers, net_config.max_peers),
 					snapshot_sync: status.is_snapshot_syncing(),
+					state_sync: if status.is_state_syncing() {
+						Some((status.state_nodes_done, status.state_nodes_pending))
+					} else {
+						None
+					},
 				}))
 			}
 			_ => (is_major_importing(self.sync.as_ref().map(|s| s.status().state), queue_info.clone()), None),
@@ -201,6 +207,7 @@ impl InformantData for LightNodeInformantData {
 			num_peers: peer_numbers.connected,
 			max_peers: peer_numbers.max as u32,
 			snapshot_sync: false,
+			state_sync: None,
 		});
 
 		Report {
@@ -284,6 +291,7 @@ impl<T: InformantData> Informant<T> {
 				_ => false,
 			}
 		);
+		let state_sync = sync_info.as_ref().and_then(|s| s.state_sync);
 		if !importing && !snapshot_sync && elapsed < Duration::from_secs(30) {
 			return;
 		}
@@ -297,8 +305,9 @@ impl<T: InformantData> Informant<T> {
 
 		info!(target: "import", "{}  {}  {}  {}",
 			match importing {
-				true => match snapshot_sync {
-					false => format!("Syncing {} {}  {}  {}+{} Qed",
+				true => match (snapshot_sync, state_sync) {
+					(false, Some((done, pending))) => format!("Syncing state {}/{} nodes", done, done + pending),
+					(false, None) => format!("Syncing {} {}  {}  {}+{} Qed",
 						paint(White.bold(), format!("{:>8}", format!("#{}", chain_info.best_block_number))),
 						paint(White.bold(), format!("{}", chain_info.best_block_hash)),
 						if self.target.executes_transactions() {
@@ -315,7 +324,7 @@ impl<T: InformantData> Informant<T> {
 						paint(Green.bold(), format!("{:5}", queue_info.unverified_queue_size)),
 						paint(Green.bold(), format!("{:5}", queue_info.verified_queue_size))
 					),
-					true => {
+					(true, _) => {
 						self.snapshot.as_ref().map_or(String::new(), |s|
 							match s.status() {
 								RestorationStatus::Ongoing { state_chunks, block_chunks, state_chunks_done, block_chunks_done } => {
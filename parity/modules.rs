@@ -17,7 +17,7 @@
 use std::sync::Arc;
 
 use ethcore::client::BlockChainClient;
-use sync::{self, AttachedProtocol, SyncConfig, NetworkConfiguration, Params, ConnectionFilter};
+use sync::{self, AttachedProtocol, SyncConfig, NetworkConfiguration, LightServeConfig, Params, ConnectionFilter};
 use ethcore::snapshot::SnapshotService;
 use light::Provider;
 
@@ -34,6 +34,7 @@ pub fn sync(
 	snapshot_service: Arc<SnapshotService>,
 	private_tx_handler: Arc<PrivateTxHandler>,
 	provider: Arc<Provider>,
+	light_serve_cfg: LightServeConfig,
 	_log_settings: &LogConfig,
 	attached_protos: Vec<AttachedProtocol>,
 	connection_filter: Option<Arc<ConnectionFilter>>,
@@ -45,6 +46,7 @@ pub fn sync(
 		snapshot_service: snapshot_service,
 		private_tx_handler,
 		network_config: net_cfg,
+		light_serve_config: light_serve_cfg,
 		attached_protos: attached_protos,
 	},
 	connection_filter)?;
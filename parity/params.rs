@@ -24,7 +24,7 @@ use ethereum_types::{U256, Address};
 use futures_cpupool::CpuPool;
 use hash_fetch::fetch::Client as FetchClient;
 use journaldb::Algorithm;
-use miner::gas_pricer::{GasPricer, GasPriceCalibratorOptions};
+use miner::gas_pricer::{GasPricer, GasPriceCalibratorOptions, GasPriceOracleOptions};
 use parity_version::version_data;
 use user_defaults::UserDefaults;
 
@@ -230,6 +230,10 @@ pub enum GasPricerConfig {
 	Calibrated {
 		usd_per_tx: f32,
 		recalibration_period: Duration,
+	},
+	Oracle {
+		minimal_gas_price: U256,
+		options: GasPriceOracleOptions,
 	}
 }
 
@@ -255,9 +259,20 @@ impl GasPricerConfig {
 					fetch,
 					p,
 				)
+			},
+			GasPricerConfig::Oracle { minimal_gas_price, ref options } => {
+				GasPricer::new_oracle(options.clone(), minimal_gas_price)
 			}
 		}
 	}
+
+	/// Options of the gas price oracle, if one is configured.
+	pub fn oracle_options(&self) -> Option<GasPriceOracleOptions> {
+		match *self {
+			GasPricerConfig::Oracle { ref options, .. } => Some(options.clone()),
+			_ => None,
+		}
+	}
 }
 
 #[derive(Debug, PartialEq)]
@@ -26,6 +26,7 @@ use ethcore_service::PrivateTxService;
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::Client;
 use ethcore::miner::Miner;
+use ethcore::miner::stratum::StratumWorkers;
 use ethcore::snapshot::SnapshotService;
 use ethcore_logger::RotatingLogger;
 use sync::{ManageNetwork, SyncProvider, LightSync};
@@ -35,6 +36,7 @@ use jsonrpc_core::{self as core, MetaIoHandler};
 use light::client::LightChainClient;
 use light::{TransactionQueue as LightTransactionQueue, Cache as LightDataCache};
 use miner::external::ExternalMiner;
+use miner::gas_pricer::GasPriceOracleOptions;
 use node_health::NodeHealth;
 use parity_reactor;
 use parity_rpc::dispatch::{FullDispatcher, LightDispatcher};
@@ -68,6 +70,8 @@ pub enum Api {
 	ParitySet,
 	/// Traces (Safe)
 	Traces,
+	/// Geth-compatible transaction pool inspection (Safe)
+	TxPool,
 	/// Rpc (Safe)
 	Rpc,
 	/// SecretStore (UNSAFE: arbitrary hash signing)
@@ -100,6 +104,7 @@ impl FromStr for Api {
 			"parity_accounts" => Ok(ParityAccounts),
 			"parity_set" => Ok(ParitySet),
 			"traces" => Ok(Traces),
+			"txpool" => Ok(TxPool),
 			"rpc" => Ok(Rpc),
 			"secretstore" => Ok(SecretStore),
 			"private" => Ok(Private),
@@ -186,6 +191,7 @@ fn to_modules(apis: &HashSet<Api>) -> BTreeMap<String, String> {
 			Api::ParityPubSub => ("parity_pubsub", "1.0"),
 			Api::ParitySet => ("parity_set", "1.0"),
 			Api::Traces => ("traces", "1.0"),
+			Api::TxPool => ("txpool", "1.0"),
 			Api::Rpc => ("rpc", "1.0"),
 			Api::SecretStore => ("secretstore", "1.0"),
 			Api::Private => ("private", "1.0"),
@@ -223,6 +229,7 @@ pub struct FullDependencies {
 	pub private_tx_service: Option<Arc<PrivateTxService>>,
 	pub miner: Arc<Miner>,
 	pub external_miner: Arc<ExternalMiner>,
+	pub stratum: Option<Arc<StratumWorkers>>,
 	pub logger: Arc<RotatingLogger>,
 	pub settings: Arc<NetworkSettings>,
 	pub net_service: Arc<ManageNetwork>,
@@ -339,6 +346,8 @@ impl FullDependencies {
 						signer,
 						self.dapps_address.clone(),
 						self.ws_address.clone(),
+						self.stratum.clone(),
+						self.gas_price_percentile,
 					).to_delegate());
 
 					if !for_generic_pubsub {
@@ -362,6 +371,7 @@ impl FullDependencies {
 						&self.miner,
 						&self.updater,
 						&self.net_service,
+						&self.secret_store,
 						self.dapps_service.clone(),
 						self.fetch.clone(),
 						self.pool.clone(),
@@ -370,6 +380,9 @@ impl FullDependencies {
 				Api::Traces => {
 					handler.extend_with(TracesClient::new(&self.client).to_delegate())
 				},
+				Api::TxPool => {
+					handler.extend_with(TxPoolClient::new(&self.client, &self.miner).to_delegate())
+				},
 				Api::Rpc => {
 					let modules = to_modules(&apis);
 					handler.extend_with(RpcClient::new(modules).to_delegate());
@@ -449,6 +462,7 @@ pub struct LightDependencies<T> {
 	pub whisper_rpc: Option<::whisper::RpcFactory>,
 	pub private_tx_service: Option<Arc<PrivateTransactionManager>>,
 	pub gas_price_percentile: usize,
+	pub gas_price_oracle: Option<GasPriceOracleOptions>,
 }
 
 impl<C: LightChainClient + 'static> LightDependencies<C> {
@@ -556,6 +570,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 						self.dapps_address.clone(),
 						self.ws_address.clone(),
 						self.gas_price_percentile,
+						self.gas_price_oracle.clone(),
 					).to_delegate());
 
 					if !for_generic_pubsub {
@@ -583,7 +598,13 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 					).to_delegate())
 				},
 				Api::Traces => {
-					handler.extend_with(light::TracesClient.to_delegate())
+					handler.extend_with(light::TracesClient::new(
+						Arc::new(dispatcher.clone()),
+						self.gas_price_percentile,
+					).to_delegate())
+				},
+				Api::TxPool => {
+					handler.extend_with(light::TxPoolClient::new(Arc::new(dispatcher.clone())).to_delegate())
 				},
 				Api::Rpc => {
 					let modules = to_modules(&apis);
@@ -654,17 +675,20 @@ impl ApiSet {
 			ApiSet::PublicContext => public_list,
 			ApiSet::UnsafeContext => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::TxPool);
 				public_list.insert(Api::ParityPubSub);
 				public_list
 			},
 			ApiSet::IpcContext => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::TxPool);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list
 			},
 			ApiSet::SafeContext => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::TxPool);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list.insert(Api::ParitySet);
@@ -674,6 +698,7 @@ impl ApiSet {
 			},
 			ApiSet::All => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::TxPool);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list.insert(Api::ParitySet);
@@ -709,6 +734,7 @@ mod test {
 		assert_eq!(Api::ParityAccounts, "parity_accounts".parse().unwrap());
 		assert_eq!(Api::ParitySet, "parity_set".parse().unwrap());
 		assert_eq!(Api::Traces, "traces".parse().unwrap());
+		assert_eq!(Api::TxPool, "txpool".parse().unwrap());
 		assert_eq!(Api::Rpc, "rpc".parse().unwrap());
 		assert_eq!(Api::SecretStore, "secretstore".parse().unwrap());
 		assert_eq!(Api::Private, "private".parse().unwrap());
@@ -731,7 +757,7 @@ mod test {
 	fn test_api_set_unsafe_context() {
 		let expected = vec![
 			// make sure this list contains only SAFE methods
-			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::Whisper, Api::WhisperPubSub, Api::Private,
+			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::TxPool, Api::Rpc, Api::Whisper, Api::WhisperPubSub, Api::Private,
 		].into_iter().collect();
 		assert_eq!(ApiSet::UnsafeContext.list_apis(), expected);
 	}
@@ -740,7 +766,7 @@ mod test {
 	fn test_api_set_ipc_context() {
 		let expected = vec![
 			// safe
-			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::Whisper, Api::WhisperPubSub, Api::Private,
+			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::TxPool, Api::Rpc, Api::Whisper, Api::WhisperPubSub, Api::Private,
 			// semi-safe
 			Api::ParityAccounts
 		].into_iter().collect();
@@ -751,7 +777,7 @@ mod test {
 	fn test_api_set_safe_context() {
 		let expected = vec![
 			// safe
-			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub, Api::Private,
+			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::TxPool, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub, Api::Private,
 			// semi-safe
 			Api::ParityAccounts,
 			// Unsafe
@@ -763,7 +789,7 @@ mod test {
 	#[test]
 	fn test_all_apis() {
 		assert_eq!("all".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
-			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub,
+			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::TxPool, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub,
 			Api::ParityAccounts,
 			Api::ParitySet, Api::Signer,
 			Api::Personal,
@@ -774,7 +800,7 @@ mod test {
 	#[test]
 	fn test_all_without_personal_apis() {
 		assert_eq!("personal,all,-personal".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
-			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub,
+			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::TxPool, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub,
 			Api::ParityAccounts,
 			Api::ParitySet, Api::Signer,
 			Api::Private
@@ -784,7 +810,7 @@ mod test {
 	#[test]
 	fn test_safe_parsing() {
 		assert_eq!("safe".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
-			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::Whisper, Api::WhisperPubSub, Api::Private,
+			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::TxPool, Api::Rpc, Api::Whisper, Api::WhisperPubSub, Api::Private,
 		].into_iter().collect()));
 	}
 }
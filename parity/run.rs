@@ -28,7 +28,7 @@ use ethcore::client::{Client, Mode, DatabaseCompactionProfile, VMType, BlockChai
 use ethcore::ethstore::ethkey;
 use ethcore::miner::{stratum, Miner, MinerService, MinerOptions};
 use ethcore::snapshot;
-use ethcore::spec::{SpecParams, OptimizeFor};
+use ethcore::spec::{SpecParams, SpecHardcodedSync, OptimizeFor};
 use ethcore::verification::queue::VerifierSettings;
 use ethcore_logger::{Config as LogConfig, RotatingLogger};
 use ethcore_service::ClientService;
@@ -102,6 +102,7 @@ pub struct RunCmd {
 	pub network_id: Option<u64>,
 	pub warp_sync: bool,
 	pub warp_barrier: Option<u64>,
+	pub state_sync: bool,
 	pub public_node: bool,
 	pub acc_conf: AccountsConfig,
 	pub gas_pricer_conf: GasPricerConfig,
@@ -132,10 +133,13 @@ pub struct RunCmd {
 	pub download_old_blocks: bool,
 	pub verifier_settings: VerifierSettings,
 	pub serve_light: bool,
+	pub light_serve_conf: sync::LightServeConfig,
 	pub light: bool,
 	pub no_persistent_txqueue: bool,
+	pub tx_queue_journal: Option<Duration>,
 	pub whisper: ::whisper::Config,
 	pub no_hardcoded_sync: bool,
+	pub light_checkpoint: Option<String>,
 }
 
 pub fn open_ui(ws_conf: &rpc::WsConfiguration, ui_conf: &rpc::UiConfiguration, logger_config: &LogConfig) -> Result<(), String> {
@@ -163,6 +167,7 @@ pub fn open_dapp(dapps_conf: &dapps::Configuration, rpc_conf: &rpc::HttpConfigur
 // node info fetcher for the local store.
 struct FullNodeInfo {
 	miner: Option<Arc<Miner>>, // TODO: only TXQ needed, just use that after decoupling.
+	journal_pool: bool,
 }
 
 impl ::local_store::NodeInfo for FullNodeInfo {
@@ -180,6 +185,28 @@ impl ::local_store::NodeInfo for FullNodeInfo {
 			})
 			.collect()
 	}
+
+	fn pool_transactions(&self) -> Option<Vec<::local_store::PoolTransaction>> {
+		let miner = match self.miner.as_ref() {
+			Some(m) if self.journal_pool => m,
+			_ => return None,
+		};
+
+		let transactions = miner.queued_transactions()
+			.into_iter()
+			.map(|tx| ::local_store::PoolTransaction {
+				pending: tx.pending().clone(),
+				inserted_at: tx.insertion_time(),
+				priority: match tx.priority() {
+					::miner::pool::Priority::Local => ::local_store::Priority::Local,
+					::miner::pool::Priority::Retracted => ::local_store::Priority::Retracted,
+					::miner::pool::Priority::Regular => ::local_store::Priority::Regular,
+				},
+			})
+			.collect();
+
+		Some(transactions)
+	}
 }
 
 type LightClient = ::light::client::Client<::light_helpers::EpochFetch>;
@@ -191,7 +218,16 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	use parking_lot::{Mutex, RwLock};
 
 	// load spec
-	let spec = cmd.spec.spec(SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory))?;
+	let mut spec = cmd.spec.spec(SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory))?;
+
+	// replace the spec's hardcoded sync with the trusted checkpoint, if any.
+	if let Some(ref path) = cmd.light_checkpoint {
+		if cmd.no_hardcoded_sync {
+			return Err("--light-checkpoint cannot be used together with --no-hardcoded-sync".into());
+		}
+
+		spec.hardcoded_sync = Some(load_light_checkpoint(path)?);
+	}
 
 	// load genesis hash
 	let genesis_hash = spec.genesis_header().hash();
@@ -382,6 +418,7 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 		whisper_rpc: whisper_factory,
 		private_tx_service: None, //TODO: add this to client.
 		gas_price_percentile: cmd.gas_price_percentile,
+		gas_price_oracle: cmd.gas_pricer_conf.oracle_options(),
 	});
 
 	let dependencies = rpc::Dependencies {
@@ -509,7 +546,22 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	}
 
 	sync_config.fork_block = spec.fork_block();
-	let mut warp_sync = spec.engine.supports_warp() && cmd.warp_sync;
+	let mut state_sync = cmd.state_sync;
+	if state_sync {
+		if fat_db {
+			warn!("Warning: State Sync is disabled because Fat DB is turned on.");
+			state_sync = false;
+		} else if tracing {
+			warn!("Warning: State Sync is disabled because tracing is turned on.");
+			state_sync = false;
+		} else if algorithm != Algorithm::OverlayRecent {
+			warn!("Warning: State Sync is disabled because of non-default pruning mode.");
+			state_sync = false;
+		}
+	}
+	sync_config.state_sync = state_sync;
+
+	let mut warp_sync = spec.engine.supports_warp() && cmd.warp_sync && !state_sync;
 	if warp_sync {
 		// Logging is not initialized yet, so we print directly to stderr
 		if fat_db {
@@ -584,6 +636,11 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		warn!("The --no-hardcoded-sync flag has no effect if you don't use --light");
 	}
 
+	// display warning if using --light-checkpoint
+	if cmd.light_checkpoint.is_some() {
+		warn!("The --light-checkpoint option has no effect if you don't use --light");
+	}
+
 	// create client config
 	let mut client_config = to_client_config(
 		&cmd.cache_config,
@@ -652,7 +709,8 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 			miner: match cmd.no_persistent_txqueue {
 				true => None,
 				false => Some(miner.clone()),
-			}
+			},
+			journal_pool: cmd.tx_queue_journal.is_some(),
 		};
 
 		let store = ::local_store::create(db, ::ethcore::db::COL_NODE_INFO, node_info);
@@ -665,12 +723,38 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 			}
 		}
 
-		// re-queue pending transactions.
+		// re-queue the journaled pool first, so that transactions keep their original insertion time and priority.
+		if let (Some(max_age), false) = (cmd.tx_queue_journal, cmd.no_persistent_txqueue) {
+			match store.pool_transactions(max_age) {
+				Ok(journaled) => {
+					let journaled: Vec<_> = journaled.into_iter()
+						.map(|tx| {
+							let priority = match tx.priority {
+								::local_store::Priority::Local => ::miner::pool::Priority::Local,
+								::local_store::Priority::Retracted => ::miner::pool::Priority::Retracted,
+								::local_store::Priority::Regular => ::miner::pool::Priority::Regular,
+							};
+							(tx.pending, priority, tx.inserted_at)
+						})
+						.collect();
+					let count = journaled.len();
+					let imported = miner.import_journaled_transactions(&*client, journaled)
+						.into_iter()
+						.filter(Result::is_ok)
+						.count();
+					info!("Reloaded {} of {} journaled transactions.", imported, count);
+				}
+				Err(e) => warn!("Error loading journaled transaction queue from disk: {}", e),
+			}
+		}
+
+		// re-queue pending transactions, the ones restored from the journal are already in the queue.
 		match store.pending_transactions() {
 			Ok(pending) => {
 				for pending_tx in pending {
-					if let Err(e) = miner.import_own_transaction(&*client, pending_tx) {
-						warn!("Error importing saved transaction: {}", e)
+					match miner.import_own_transaction(&*client, pending_tx) {
+						Ok(()) | Err(::transaction::Error::AlreadyImported) => {},
+						Err(e) => warn!("Error importing saved transaction: {}", e),
 					}
 				}
 			}
@@ -687,10 +771,14 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	let external_miner = Arc::new(ExternalMiner::default());
 
 	// start stratum
-	if let Some(ref stratum_config) = cmd.stratum {
-		stratum::Stratum::register(stratum_config, miner.clone(), Arc::downgrade(&client))
-			.map_err(|e| format!("Stratum start error: {:?}", e))?;
-	}
+	let stratum_workers = match cmd.stratum {
+		Some(ref stratum_config) => {
+			let stratum = stratum::Stratum::register(stratum_config, miner.clone(), Arc::downgrade(&client))
+				.map_err(|e| format!("Stratum start error: {:?}", e))?;
+			Some(Arc::new(stratum) as Arc<stratum::StratumWorkers>)
+		},
+		None => None,
+	};
 
 	let mut attached_protos = Vec::new();
 
@@ -711,6 +799,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		snapshot_service.clone(),
 		private_tx_service.clone(),
 		client.clone(),
+		cmd.light_serve_conf.clone(),
 		&cmd.logger_config,
 		attached_protos,
 		connection_filter.clone().map(|f| f as Arc<::sync::ConnectionFilter + 'static>),
@@ -804,6 +893,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		secret_store: secret_store,
 		miner: miner.clone(),
 		external_miner: external_miner.clone(),
+		stratum: stratum_workers,
 		logger: logger.clone(),
 		settings: Arc::new(cmd.net_settings.clone()),
 		net_service: manage_network.clone(),
@@ -1033,6 +1123,22 @@ fn daemonize(_pid_file: String) -> Result<(), String> {
 	Err("daemon is no supported on windows".into())
 }
 
+fn load_light_checkpoint(path: &str) -> Result<SpecHardcodedSync, String> {
+	let file = ::std::fs::File::open(path)
+		.map_err(|e| format!("Could not open light checkpoint file {}: {}", path, e))?;
+	let checkpoint = SpecHardcodedSync::load(file)
+		.map_err(|e| format!("Invalid light checkpoint file {}: {}", path, e))?;
+	let header = checkpoint.header.decode()
+		.map_err(|e| format!("Invalid header in light checkpoint file {}: {}", path, e))?;
+
+	if header.number() == 0 {
+		return Err(format!("Light checkpoint in {} cannot start from the genesis block", path));
+	}
+
+	info!("Using trusted light checkpoint at block #{} ({})", header.number(), header.hash());
+	Ok(checkpoint)
+}
+
 fn print_running_environment(spec_name: &String, dirs: &Directories, db_dirs: &DatabaseDirectories, dapps_conf: &dapps::Configuration) {
 	info!("Starting {}", Colour::White.bold().paint(version()));
 	info!("Keys path {}", Colour::White.bold().paint(dirs.keys_path(spec_name).to_string_lossy().into_owned()));
@@ -36,6 +36,7 @@ use sync::LightSync;
 use ethcore::ids::BlockId;
 use ethcore::client::BlockChainClient;
 use ethcore::miner::{self, MinerService};
+use miner::gas_pricer::{GasPriceOracleOptions, GasPriceTiers};
 use ethcore::account_provider::AccountProvider;
 use crypto::DEFAULT_MAC;
 use transaction::{Action, SignedTransaction, PendingTransaction, Transaction};
@@ -190,6 +191,9 @@ impl<C: miner::BlockChainClient + BlockChainClient, M: MinerService> Dispatcher
 	}
 }
 
+/// Number of recent blocks sampled for the default gas price corpus.
+const GAS_PRICE_SAMPLE_SIZE: usize = 100;
+
 /// Get a recent gas price corpus.
 // TODO: this could be `impl Trait`.
 pub fn fetch_gas_price_corpus(
@@ -198,10 +202,23 @@ pub fn fetch_gas_price_corpus(
 	on_demand: Arc<OnDemand>,
 	cache: Arc<Mutex<LightDataCache>>,
 ) -> BoxFuture<Corpus<U256>> {
-	const GAS_PRICE_SAMPLE_SIZE: usize = 100;
+	fetch_gas_price_corpus_of(sync, client, on_demand, cache, GAS_PRICE_SAMPLE_SIZE)
+}
 
-	if let Some(cached) = { cache.lock().gas_price_corpus() } {
-		return Box::new(future::ok(cached))
+/// Get a gas price corpus sampled from the given number of recent blocks.
+/// Only the corpus of the default sample size is cached.
+pub fn fetch_gas_price_corpus_of(
+	sync: Arc<LightSync>,
+	client: Arc<LightChainClient>,
+	on_demand: Arc<OnDemand>,
+	cache: Arc<Mutex<LightDataCache>>,
+	sample_size: usize,
+) -> BoxFuture<Corpus<U256>> {
+	let cacheable = sample_size == GAS_PRICE_SAMPLE_SIZE;
+	if cacheable {
+		if let Some(cached) = { cache.lock().gas_price_corpus() } {
+			return Box::new(future::ok(cached))
+		}
 	}
 
 	let cache = cache.clone();
@@ -210,7 +227,7 @@ pub fn fetch_gas_price_corpus(
 		// and request each of the blocks from the network.
 		let block_requests = client.ancestry_iter(BlockId::Latest)
 			.filter(|hdr| hdr.gas_used() != U256::default())
-			.take(GAS_PRICE_SAMPLE_SIZE)
+			.take(sample_size)
 			.map(|hdr| request::Body(hdr.into()))
 			.collect::<Vec<_>>();
 
@@ -230,7 +247,9 @@ pub fn fetch_gas_price_corpus(
 				// produce a corpus from the vector and cache it.
 				// It's later used to get a percentile for default gas price.
 				let corpus: ::stats::Corpus<_> = prices.into();
-				cache.lock().set_gas_price_corpus(corpus.clone());
+				if cacheable {
+					cache.lock().set_gas_price_corpus(corpus.clone());
+				}
 				corpus
 			})
 	});
@@ -306,14 +325,22 @@ impl LightDispatcher {
 		)
 	}
 
+	/// Get a gas price corpus sampled from the given number of recent blocks.
+	pub fn gas_price_corpus_of(&self, blocks: usize) -> BoxFuture<Corpus<U256>> {
+		fetch_gas_price_corpus_of(
+			self.sync.clone(),
+			self.client.clone(),
+			self.on_demand.clone(),
+			self.cache.clone(),
+			blocks,
+		)
+	}
+
 	/// Get an account's next nonce.
+	///
+	/// This is the account's nonce in the latest state, advanced past any
+	/// local transactions queued on top of it.
 	pub fn next_nonce(&self, addr: Address) -> BoxFuture<U256> {
-		// fast path where we don't go to network; nonce provided or can be gotten from queue.
-		let maybe_nonce = self.transaction_queue.read().next_nonce(&addr);
-		if let Some(nonce) = maybe_nonce {
-			return Box::new(future::ok(nonce))
-		}
-
 		let best_header = self.client.best_block_header();
 		let account_start_nonce = self.client.engine().account_start_nonce(best_header.number());
 		let nonce_future = self.sync.with_context(|ctx| self.on_demand.request(ctx, request::Account {
@@ -321,12 +348,20 @@ impl LightDispatcher {
 			address: addr,
 		}).expect("no back-references; therefore all back-references valid; qed"));
 
+		let transaction_queue = self.transaction_queue.clone();
 		match nonce_future {
 			Some(x) => Box::new(
 				x.map(move |acc| acc.map_or(account_start_nonce, |acc| acc.nonce))
+					.map(move |state_nonce| transaction_queue.read().next_nonce_from(&addr, state_nonce))
 					.map_err(|_| errors::no_light_peers())
 			),
-			None =>  Box::new(future::err(errors::network_disabled()))
+			None => {
+				// without the network, fall back to what the queue alone knows.
+				match self.transaction_queue.read().next_nonce(&addr) {
+					Some(nonce) => Box::new(future::ok(nonce)),
+					None => Box::new(future::err(errors::network_disabled())),
+				}
+			}
 		}
 	}
 }
@@ -423,7 +458,8 @@ impl Dispatcher for LightDispatcher {
 	}
 }
 
-fn sign_transaction(
+/// Sign a filled transaction request with given nonce.
+pub fn sign_transaction(
 	accounts: &AccountProvider,
 	filled: FilledTransactionRequest,
 	chain_id: Option<u64>,
@@ -759,11 +795,21 @@ fn decrypt(accounts: &AccountProvider, address: Address, msg: Bytes, password: S
 }
 
 /// Extract the default gas price from a client and miner.
+/// Uses the standard tier of the gas price oracle when the miner is configured with one.
 pub fn default_gas_price<C, M>(client: &C, miner: &M, percentile: usize) -> U256 where
 	C: BlockChainClient,
 	M: MinerService,
 {
-	client.gas_price_corpus(100).percentile(percentile).cloned().unwrap_or_else(|| miner.sensible_gas_price())
+	let suggested = match miner.gas_price_oracle() {
+		Some(options) => gas_price_tiers(client, &options).map(|tiers| tiers.standard),
+		None => client.gas_price_corpus(100).percentile(percentile).cloned(),
+	};
+	suggested.unwrap_or_else(|| miner.sensible_gas_price())
+}
+
+/// Suggest gas prices from transactions included in recent blocks.
+pub fn gas_price_tiers<C: BlockChainClient>(client: &C, options: &GasPriceOracleOptions) -> Option<GasPriceTiers> {
+	options.tiers(&client.gas_price_corpus(options.blocks))
 }
 
 /// Convert RPC confirmation payload to signer confirmation payload.
@@ -203,6 +203,30 @@ pub fn not_enough_data() -> Error {
 	}
 }
 
+pub fn insufficient_proof() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "The state proofs fetched from the network were not enough to re-execute the transaction.".into(),
+		data: None,
+	}
+}
+
+pub fn insufficient_replay_proof() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "The state proofs fetched from the network were not enough to replay the transaction.".into(),
+		data: Some(Value::String("Light clients can't replay transactions depending on the effects of earlier transactions in the same block.".into())),
+	}
+}
+
+pub fn checkpoint_unavailable<T: Into<String>>(details: T) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "The node does not have enough block history to produce a light client checkpoint.".into(),
+		data: Some(Value::String(details.into())),
+	}
+}
+
 pub fn token(e: String) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNKNOWN_ERROR),
@@ -235,6 +259,14 @@ pub fn ws_disabled() -> Error {
 	}
 }
 
+pub fn stratum_disabled() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Stratum Server is disabled. This API is not available.".into(),
+		data: None,
+	}
+}
+
 pub fn network_disabled() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
@@ -341,6 +373,7 @@ pub fn transaction_message(error: &TransactionError) -> String {
 		TooBig => "Transaction is too big, see chain specification for the limit.".into(),
 		InvalidRlp(ref descr) => format!("Invalid RLP data: {}", descr),
 		InvalidMetadata(ref err) => format!("Invalid metadata: {}", err),
+		UnsupportedCondition => "Transaction condition is not supported by this node. Light clients can't check conditions depending on the state.".into(),
 	}
 }
 
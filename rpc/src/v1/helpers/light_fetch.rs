@@ -16,30 +16,36 @@
 
 //! Helpers for fetching blockchain data either from the light client or the network.
 
+use std::cmp;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use ethcore::basic_account::BasicAccount;
+use ethcore::client::CallAnalytics;
 use ethcore::encoded;
 use ethcore::executed::{Executed, ExecutionError};
 use ethcore::ids::BlockId;
 use ethcore::filter::Filter as EthcoreFilter;
 use ethcore::receipt::Receipt;
+use ethcore::state::{self, ProvedExecution};
 
-use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_core::{BoxFuture, Result, Error};
 use jsonrpc_core::futures::{future, Future};
 use jsonrpc_core::futures::future::Either;
 use jsonrpc_macros::Trailing;
 
+use light::bloom_bits;
 use light::cache::Cache;
 use light::client::LightChainClient;
 use light::cht;
 use light::on_demand::{request, OnDemand, HeaderRef, Request as OnDemandRequest, Response as OnDemandResponse};
 use light::request::Field;
+use light::TransactionQueue;
 
-use sync::LightSync;
-use ethereum_types::{U256, Address};
+use sync::{LightSync, LightSyncProvider};
+use ethereum_types::{U256, Address, Bloom};
 use hash::H256;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use transaction::{Action, Transaction as EthTransaction, SignedTransaction, LocalizedTransaction};
 
 use v1::helpers::{CallRequest as CallRequestHelper, errors, dispatch};
@@ -102,6 +108,21 @@ fn extract_header(res: &[OnDemandResponse], header: HeaderRef) -> Option<encoded
 	}
 }
 
+// Note: Here we treat `Pending` as `Latest`.
+//       Since light clients don't produce pending blocks
+//       (they don't have state) we can safely fallback to `Latest`.
+fn num_to_id(num: BlockNumber) -> BlockId {
+	match num {
+		BlockNumber::Num(n) => BlockId::Number(n),
+		BlockNumber::Earliest => BlockId::Earliest,
+		BlockNumber::Latest => BlockId::Latest,
+		BlockNumber::Pending => {
+			warn!("`Pending` is deprecated and may be removed in future versions. Falling back to `Latest`");
+			BlockId::Latest
+		}
+	}
+}
+
 impl LightFetch {
 	// push the necessary requests onto the request chain to get the header by the given ID.
 	// yield a header reference which other requests can use.
@@ -190,25 +211,72 @@ impl LightFetch {
 
 	/// Helper for getting proved execution.
 	pub fn proved_execution(&self, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<ExecutionResult> {
+		self.proved_execution_at(req.into(), num_to_id(num.unwrap_or_default()))
+	}
+
+	/// Helper for getting proved execution on top of the sender's queued local transactions.
+	///
+	/// At the latest block, the sender's transactions from `transaction_queue` which would
+	/// execute before this one are applied to the proved state first, so their nonces and
+	/// the balance they commit are accounted for. Queued transactions aren't executed
+	/// virtually, so this fails if the sender can't afford them. Otherwise this is `proved_execution`.
+	pub fn proved_execution_with_queued(
+		&self,
+		req: CallRequest,
+		num: Trailing<BlockNumber>,
+		transaction_queue: Arc<RwLock<TransactionQueue>>,
+	) -> BoxFuture<ExecutionResult> {
+		let req: CallRequestHelper = req.into();
+		let id = num_to_id(num.unwrap_or_default());
+		if id != BlockId::Latest {
+			return self.proved_execution_at(req, id);
+		}
+
+		let from = req.from.unwrap_or(Address::zero());
+		let account_start_nonce = self.client.engine().account_start_nonce(self.client.best_block_header().number());
+		let fetcher = self.clone();
+
+		Box::new(self.account(from, id).and_then(move |acc| -> BoxFuture<ExecutionResult> {
+			let state_nonce = acc.map_or(account_start_nonce, |acc| acc.nonce);
+			let queued: Vec<_> = transaction_queue.read().pending_from(&from, state_nonce)
+				.into_iter()
+				.map(|tx| tx.transaction)
+				.collect();
+
+			if queued.is_empty() {
+				return fetcher.proved_execution_at(req, id);
+			}
+
+			let env_info = match fetcher.client.env_info(id) {
+				Some(env_info) => env_info,
+				None => return Box::new(future::err(errors::unknown_block())),
+			};
+
+			// executed locally, so there's no need to search for enough gas.
+			let tx = EthTransaction {
+				nonce: req.nonce.unwrap_or_else(|| state_nonce + queued.len().into()),
+				action: req.to.map_or(Action::Create, Action::Call),
+				gas: req.gas.unwrap_or(env_info.gas_limit),
+				gas_price: req.gas_price.unwrap_or_else(U256::zero),
+				value: req.value.unwrap_or_else(U256::zero),
+				data: req.data.unwrap_or_default(),
+				metadata: req.metadata.unwrap_or_else(Vec::new),
+				metadataLimit: req.metadata_limit.unwrap_or_else(U256::zero),
+				isOld: false,
+				..Default::default()
+			}.fake_sign(from);
+
+			fetcher.traced_execution(id, env_info, queued, tx, Default::default())
+		}))
+	}
+
+	// proved execution of a call request at the given block.
+	fn proved_execution_at(&self, req: CallRequestHelper, id: BlockId) -> BoxFuture<ExecutionResult> {
 		const DEFAULT_GAS_PRICE: u64 = 21_000;
 		// starting gas when gas not provided.
 		const START_GAS: u64 = 50_000;
 
 		let (sync, on_demand, client) = (self.sync.clone(), self.on_demand.clone(), self.client.clone());
-		let req: CallRequestHelper = req.into();
-
-		// Note: Here we treat `Pending` as `Latest`.
-		//       Since light clients don't produce pending blocks
-		//       (they don't have state) we can safely fallback to `Latest`.
-		let id = match num.unwrap_or_default() {
-			BlockNumber::Num(n) => BlockId::Number(n),
-			BlockNumber::Earliest => BlockId::Earliest,
-			BlockNumber::Latest => BlockId::Latest,
-			BlockNumber::Pending => {
-				warn!("`Pending` is deprecated and may be removed in future versions. Falling back to `Latest`");
-				BlockId::Latest
-			}
-		};
 
 		let from = req.from.unwrap_or(Address::zero());
 		let nonce_fut = match req.nonce {
@@ -292,6 +360,63 @@ impl LightFetch {
 		}))
 	}
 
+	/// Helper for executing a transaction locally with the given analytics,
+	/// after a sequence of prior transactions, on top of the state at `state_id`.
+	///
+	/// Proofs of execution for each transaction are fetched from the network and
+	/// merged. The merged proof may not cover state only touched because of an
+	/// earlier transaction's effects, in which case this fails with
+	/// `errors::insufficient_replay_proof`.
+	pub fn traced_execution(
+		&self,
+		state_id: BlockId,
+		env_info: ::vm::EnvInfo,
+		prior: Vec<SignedTransaction>,
+		tx: SignedTransaction,
+		analytics: CallAnalytics,
+	) -> BoxFuture<ExecutionResult> {
+		let mut reqs = Vec::new();
+		let header_ref = match self.make_header_requests(state_id, &mut reqs) {
+			Ok(r) => r,
+			Err(e) => return Box::new(future::err(e)),
+		};
+
+		let engine = self.client.engine().clone();
+		let mut env_info = env_info;
+		env_info.gas_limit = U256::max_value();
+
+		let first_proof = reqs.len();
+		for t in prior.iter().chain(Some(&tx)) {
+			reqs.push(request::ExecutionProof {
+				tx: t.clone(),
+				header: header_ref.clone(),
+				env_info: env_info.clone(),
+				engine: engine.clone(),
+			}.into());
+		}
+
+		Box::new(self.send_requests(reqs, move |res| {
+			let root = extract_header(&res, header_ref)
+				.expect("these responses correspond to requests that header_ref belongs to \
+						therefore it will not fail; qed")
+				.state_root();
+
+			let items: Vec<_> = res.into_iter().skip(first_proof).flat_map(|r| match r {
+				OnDemandResponse::ExecutionProof(items) => items,
+				_ => panic!("responses correspond directly with requests in amount and type; qed"),
+			}).collect();
+
+			(root, items)
+		}).and_then(move |(root, items)| {
+			match state::check_proof_with_analytics(&items, root, &prior, &tx, engine.machine(), &env_info, analytics) {
+				ProvedExecution::Complete(executed) => Ok(Ok(executed)),
+				ProvedExecution::Failed(e) => Ok(Err(e)),
+				ProvedExecution::BadProof if !prior.is_empty() => Err(errors::insufficient_replay_proof()),
+				ProvedExecution::BadProof => Err(errors::insufficient_proof()),
+			}
+		}))
+	}
+
 	/// Get a block itself. Fails on unknown block ID.
 	pub fn block(&self, id: BlockId) -> BoxFuture<encoded::Block> {
 		let mut reqs = Vec::new();
@@ -325,6 +450,10 @@ impl LightFetch {
 	}
 
 	/// Get transaction logs
+	///
+	/// The blocks of complete bloom-bit sections within the range are located through the
+	/// sections' bit vectors if any peer can serve them, the others by the log blooms of
+	/// the local headers.
 	pub fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>> {
 		use std::collections::BTreeMap;
 		use jsonrpc_core::futures::stream::{self, Stream};
@@ -338,40 +467,75 @@ impl LightFetch {
 			BlockId::Number(x) => Some(x),
 		};
 
-		match (block_number(filter.to_block), block_number(filter.from_block)) {
-			(Some(to), Some(from)) if to < from => return Box::new(future::ok(Vec::new())),
-			(Some(_), Some(_)) => {},
+		let (from, to) = match (block_number(filter.from_block), block_number(filter.to_block)) {
+			(Some(from), Some(to)) if to < from => return Box::new(future::ok(Vec::new())),
+			(Some(from), Some(to)) => (from, to),
 			_ => return Box::new(future::err(errors::unknown_block())),
+		};
+
+		let bit_combos = filter.bloom_possibilities();
+
+		// filters without any bloom bits match every block, so they gain nothing from the index.
+		let use_bloom_bits = bit_combos.iter().all(|bloom| !bloom.is_zero()) &&
+			self.sync.peers().iter().any(|peer| peer.pip_info.as_ref().map_or(false, |pip| pip.version >= 2));
+
+		let sections: Vec<(u64, H256)> = match use_bloom_bits {
+			true => {
+				let first = (cmp::max(from, 1) - 1 + bloom_bits::SIZE - 1) / bloom_bits::SIZE;
+				let end = to / bloom_bits::SIZE;
+				(first..end)
+					.filter_map(|section| self.client.bloom_bits_root(section as usize).map(|root| (section, root)))
+					.collect()
+			}
+			false => Vec::new(),
+		};
+
+		// find the blocks of each indexed section which may match the filter, and fetch their receipts.
+		let mut receipts_futures: Vec<BoxFuture<Vec<(u64, Vec<Receipt>)>>> = Vec::with_capacity(sections.len());
+		for &(section, root) in &sections {
+			let fetcher = self.clone();
+			receipts_futures.push(Box::new(self.bloom_bits_matches(section, root, &bit_combos).and_then(move |numbers| {
+				future::join_all(numbers.into_iter()
+					.map(move |num| fetcher.receipts(BlockId::Number(num)).map(move |receipts| (num, receipts)))
+					.collect::<Vec<_>>())
+			})));
 		}
 
+		let is_indexed = move |num: u64| bloom_bits::block_to_section_number(num)
+			.map_or(false, |section| sections.iter().any(|&(indexed, _)| indexed == section));
+
 		let maybe_future = self.sync.with_context(move |ctx| {
-			// find all headers which match the filter, and fetch the receipts for each one.
+			// find all other headers which match the filter, and fetch the receipts for each one.
 			// match them with their numbers for easy sorting later.
-			let bit_combos = filter.bloom_possibilities();
-			let receipts_futures: Vec<_> = self.client.ancestry_iter(filter.to_block)
+			receipts_futures.extend(self.client.ancestry_iter(filter.to_block)
 				.take_while(|ref hdr| BlockId::Number(hdr.number()) != filter.from_block)
 				.take_while(|ref hdr| BlockId::Hash(hdr.hash()) != filter.from_block)
+				.filter(|ref hdr| !is_indexed(hdr.number()))
 				.filter(|ref hdr| {
 					let hdr_bloom = hdr.log_bloom();
 					bit_combos.iter().find(|&bloom| hdr_bloom & *bloom == *bloom).is_some()
 				})
 				.map(|hdr| (hdr.number(), request::BlockReceipts(hdr.into())))
-				.map(|(num, req)| self.on_demand.request(ctx, req).expect(NO_INVALID_BACK_REFS).map(move |x| (num, x)))
-				.collect();
+				.map(|(num, req)| -> BoxFuture<Vec<(u64, Vec<Receipt>)>> {
+					Box::new(self.on_demand.request(ctx, req).expect(NO_INVALID_BACK_REFS)
+						.map(move |x| vec![(num, x)])
+						.map_err(errors::on_demand_cancel))
+				}));
 
 			// as the receipts come in, find logs within them which match the filter.
 			// insert them into a BTreeMap to maintain order by number and block index.
 			stream::futures_unordered(receipts_futures)
-				.fold(BTreeMap::new(), move |mut matches, (num, receipts)| {
-					for (block_index, log) in receipts.into_iter().flat_map(|r| r.logs).enumerate() {
-						if filter.matches(&log) {
-							matches.insert((num, block_index), log.into());
+				.fold(BTreeMap::new(), move |mut matches, blocks| {
+					for (num, receipts) in blocks {
+						for (block_index, log) in receipts.into_iter().flat_map(|r| r.logs).enumerate() {
+							if filter.matches(&log) {
+								matches.insert((num, block_index), log.into());
+							}
 						}
 					}
-					future::ok(matches)
+					future::ok::<_, Error>(matches)
 				}) // and then collect them into a vector.
 				.map(|matches| matches.into_iter().map(|(_, v)| v).collect())
-				.map_err(errors::on_demand_cancel)
 		});
 
 		match maybe_future {
@@ -380,6 +544,34 @@ impl LightFetch {
 		}
 	}
 
+	// Get the numbers of the blocks of a bloom-bit section whose log blooms may match
+	// any of `blooms`, from the section's vectors of all bits set in them.
+	fn bloom_bits_matches(&self, section: u64, root: H256, blooms: &[Bloom]) -> BoxFuture<Vec<u64>> {
+		let bit_sets: Vec<Vec<u64>> = blooms.iter().map(bloom_bits::bloom_bits).collect();
+		let mut bits: Vec<u64> = bit_sets.iter().flat_map(|set| set.iter().cloned()).collect();
+		bits.sort();
+		bits.dedup();
+
+		let reqs = bits.iter()
+			.map(|&bit| request::BloomBits { section: section, bit: bit, root: root }.into())
+			.collect();
+
+		self.send_requests(reqs, move |res| {
+			let vectors: HashMap<u64, Vec<u8>> = bits.into_iter().zip(res.into_iter().map(|r| match r {
+				OnDemandResponse::BloomBits(vector) => vector,
+				_ => panic!("responses correspond directly with requests in amount and type; qed"),
+			})).collect();
+
+			let mut numbers: Vec<u64> = bit_sets.iter().flat_map(|set| {
+				let set_vectors: Vec<Vec<u8>> = set.iter().map(|bit| vectors[bit].clone()).collect();
+				bloom_bits::matching_blocks(section, &set_vectors)
+			}).collect();
+			numbers.sort();
+			numbers.dedup();
+			numbers
+		})
+	}
+
 	// Get a transaction by hash. also returns the index in the block.
 	// Only returns transactions in the canonical chain.
 	pub fn transaction_by_hash(&self, tx_hash: H256, eip86_transition: u64)
@@ -486,6 +486,11 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 			_ => (false, None, None),
 		};
 
+		let (pulled_states, known_states) = match status.is_state_syncing() {
+			true => (Some(status.state_nodes_done), Some(status.state_nodes_done + status.state_nodes_pending)),
+			false => (None, None),
+		};
+
 
 		if warping || is_major_importing(Some(status.state), client.queue_info()) {
 			let chain_info = client.chain_info();
@@ -498,6 +503,8 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				highest_block: highest_block.into(),
 				warp_chunks_amount: warp_chunks_amount.map(|x| U256::from(x as u64)).map(Into::into),
 				warp_chunks_processed: warp_chunks_processed.map(|x| U256::from(x as u64)).map(Into::into),
+				pulled_states: pulled_states.map(|x| U256::from(x as u64)).map(Into::into),
+				known_states: known_states.map(|x| U256::from(x as u64)).map(Into::into),
 			};
 			Ok(SyncStatus::Info(info))
 		} else {
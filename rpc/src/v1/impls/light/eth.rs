@@ -242,6 +242,8 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 				highest_block: highest_block.into(),
 				warp_chunks_amount: None,
 				warp_chunks_processed: None,
+				pulled_states: None,
+				known_states: None,
 			}))
 		} else {
 			Ok(SyncStatus::None)
@@ -405,7 +407,8 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 
 	fn estimate_gas(&self, _meta: Self::Metadata, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
 		// TODO: binary chop for more accurate estimates.
-		Box::new(self.fetcher().proved_execution(req, num).and_then(|res| {
+		let transaction_queue = self.transaction_queue.clone();
+		Box::new(self.fetcher().proved_execution_with_queued(req, num, transaction_queue).and_then(|res| {
 			match res {
 				Ok(exec) => Ok((exec.refunded + exec.gas_used).into()),
 				Err(e) => Err(errors::execution(e)),
@@ -23,6 +23,7 @@ pub mod eth;
 pub mod parity;
 pub mod parity_set;
 pub mod trace;
+pub mod tx_pool;
 pub mod net;
 
 pub use self::eth::EthClient;
@@ -30,3 +31,4 @@ pub use self::parity::ParityClient;
 pub use self::parity_set::ParitySetClient;
 pub use self::net::NetClient;
 pub use self::trace::TracesClient;
+pub use self::tx_pool::TxPoolClient;
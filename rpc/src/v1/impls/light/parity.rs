@@ -23,13 +23,14 @@ use version::version_data;
 use crypto::{ecies, DEFAULT_MAC};
 use ethkey::{Brain, Generator};
 use ethstore::random_phrase;
-use sync::LightSyncProvider;
+use sync::{LightSyncProvider, ManageNetwork};
 use ethcore::account_provider::AccountProvider;
 use ethcore_logger::RotatingLogger;
 use node_health::{NodeHealth, Health};
 use ethcore::ids::BlockId;
 
 use light::client::LightChainClient;
+use miner::gas_pricer::GasPriceOracleOptions;
 
 use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_core::futures::Future;
@@ -41,11 +42,12 @@ use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
 	Bytes, U256, U64, H160, H256, H512, CallRequest,
-	Peers, Transaction, RpcSettings, Histogram,
-	TransactionStats, LocalTransactionStatus,
+	Peers, Transaction, RpcSettings, Histogram, GasPriceTiers,
+	TransactionStats, LocalTransactionStatus, TxPoolEviction, StratumWorker, BlockExclusion,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, Header, RichHeader,
+	AccountInfo, HwAccountInfo, Header, RichHeader, NodeReputation,
+	LightCheckpoint,
 };
 use Host;
 
@@ -62,6 +64,7 @@ pub struct ParityClient {
 	ws_address: Option<Host>,
 	eip86_transition: u64,
 	gas_price_percentile: usize,
+	gas_price_oracle: Option<GasPriceOracleOptions>,
 }
 
 impl ParityClient {
@@ -77,6 +80,7 @@ impl ParityClient {
 		dapps_address: Option<Host>,
 		ws_address: Option<Host>,
 		gas_price_percentile: usize,
+		gas_price_oracle: Option<GasPriceOracleOptions>,
 	) -> Self {
 		ParityClient {
 			light_dispatch,
@@ -90,6 +94,7 @@ impl ParityClient {
 			eip86_transition: client.eip86_transition(),
 			client,
 			gas_price_percentile,
+			gas_price_oracle,
 		}
 	}
 
@@ -200,6 +205,10 @@ impl Parity for ParityClient {
 		})
 	}
 
+	fn node_reputations(&self) -> Result<Vec<NodeReputation>> {
+		Ok(self.light_dispatch.sync.node_reputations().into_iter().map(Into::into).collect())
+	}
+
 	fn net_port(&self) -> Result<u16> {
 		Ok(self.settings.network_port)
 	}
@@ -235,6 +244,17 @@ impl Parity for ParityClient {
 			.map(Into::into))
 	}
 
+	fn gas_price_oracle(&self) -> BoxFuture<GasPriceTiers> {
+		// without a configured oracle the standard tier matches the default gas price.
+		let options = self.gas_price_oracle.clone().unwrap_or_else(|| GasPriceOracleOptions {
+			standard_percentile: self.gas_price_percentile,
+			..Default::default()
+		});
+		Box::new(self.light_dispatch.gas_price_corpus_of(options.blocks)
+			.and_then(move |corpus| options.tiers(&corpus).ok_or_else(errors::not_enough_data))
+			.map(Into::into))
+	}
+
 	fn unsigned_transactions_count(&self) -> Result<usize> {
 		match self.signer {
 			None => Err(errors::signer_disabled()),
@@ -329,6 +349,18 @@ impl Parity for ParityClient {
 		Ok(map)
 	}
 
+	fn tx_pool_evictions(&self) -> Result<Vec<TxPoolEviction>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn block_exclusions(&self) -> Result<Vec<BlockExclusion>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn stratum_workers(&self) -> Result<Vec<StratumWorker>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn dapps_url(&self) -> Result<String> {
 		helpers::to_url(&self.dapps_address)
 			.ok_or_else(|| errors::dapps_disabled())
@@ -445,4 +477,8 @@ impl Parity for ParityClient {
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
 	}
+
+	fn light_checkpoint(&self) -> Result<LightCheckpoint> {
+		Err(errors::light_unimplemented(None))
+	}
 }
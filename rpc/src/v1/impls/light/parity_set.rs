@@ -27,6 +27,7 @@ use hash::keccak_buffer;
 
 use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_core::futures::Future;
+use jsonrpc_macros::Trailing;
 use v1::helpers::dapps::DappsService;
 use v1::helpers::errors;
 use v1::traits::ParitySet;
@@ -77,6 +78,14 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn propose_signer(&self, _address: H160, _add: bool) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn discard_signer(&self, _address: H160) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn set_transactions_limit(&self, _limit: usize) -> Result<bool> {
 		Err(errors::light_unimplemented(None))
 	}
@@ -159,4 +168,12 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 	fn remove_transaction(&self, _hash: H256) -> Result<Option<Transaction>> {
 		Err(errors::light_unimplemented(None))
 	}
+
+	fn cancel_transaction(&self, _hash: H256) -> Result<Option<H256>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn send_bundle(&self, _transactions: Vec<Bytes>, _target_block: Trailing<u64>) -> Result<H256> {
+		Err(errors::light_unimplemented(None))
+	}
 }
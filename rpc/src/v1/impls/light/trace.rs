@@ -16,16 +16,84 @@
 
 //! Traces api implementation.
 
-use jsonrpc_core::Result;
+use std::sync::Arc;
+
+use ethcore::error::CallError;
+use ethcore::ids::BlockId;
+use rlp::Rlp;
+use transaction::SignedTransaction;
+
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_core::futures::{future, Future};
 use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
-use v1::helpers::errors;
+use v1::helpers::{errors, fake_sign};
+use v1::helpers::dispatch::LightDispatcher;
+use v1::helpers::light_fetch::{LightFetch, ExecutionResult};
+use v1::impls::traces::to_call_analytics;
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256};
 
+/// Maximal index of a transaction in its block which can be replayed.
+///
+/// Replaying a transaction requires executing all the earlier transactions of the block.
+const MAX_REPLAYED_INDEX: usize = 32;
+
 /// Traces api implementation.
-// TODO: all calling APIs should be possible w. proved remote TX execution.
-pub struct TracesClient;
+///
+/// Calls and transaction replays are executed locally with the requested
+/// analytics, over state proved by full peers.
+///
+/// Proofs for the earlier transactions of a replayed one are fetched separately against
+/// the parent state, so transactions depending on the effects of earlier ones in the same
+/// block can't be replayed.
+pub struct TracesClient {
+	light_dispatch: Arc<LightDispatcher>,
+	eip86_transition: u64,
+	gas_price_percentile: usize,
+}
+
+impl TracesClient {
+	/// Creates new `TracesClient`.
+	pub fn new(light_dispatch: Arc<LightDispatcher>, gas_price_percentile: usize) -> Self {
+		TracesClient {
+			eip86_transition: light_dispatch.client.eip86_transition(),
+			light_dispatch,
+			gas_price_percentile,
+		}
+	}
+
+	/// Create a light blockchain data fetcher.
+	fn fetcher(&self) -> LightFetch {
+		LightFetch {
+			client: self.light_dispatch.client.clone(),
+			on_demand: self.light_dispatch.on_demand.clone(),
+			sync: self.light_dispatch.sync.clone(),
+			cache: self.light_dispatch.cache.clone(),
+			gas_price_percentile: self.gas_price_percentile,
+		}
+	}
+
+	// execute a transaction on top of the state at the given block.
+	fn execute_at(&self, signed: SignedTransaction, flags: TraceOptions, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults> {
+		// Note: Here we treat `Pending` as `Latest`.
+		//       Since light clients don't produce pending blocks
+		//       (they don't have state) we can safely fallback to `Latest`.
+		let id = match block.unwrap_or_default() {
+			BlockNumber::Num(n) => BlockId::Number(n),
+			BlockNumber::Earliest => BlockId::Earliest,
+			BlockNumber::Latest | BlockNumber::Pending => BlockId::Latest,
+		};
+
+		let env_info = match self.light_dispatch.client.env_info(id) {
+			Some(env_info) => env_info,
+			None => return Box::new(future::err(errors::unknown_block())),
+		};
+
+		Box::new(self.fetcher().traced_execution(id, env_info, Vec::new(), signed, to_call_analytics(flags))
+			.and_then(|res| res.map(TraceResults::from).map_err(errors::execution)))
+	}
+}
 
 impl Traces for TracesClient {
 	type Metadata = Metadata;
@@ -46,20 +114,63 @@ impl Traces for TracesClient {
 		Err(errors::light_unimplemented(None))
 	}
 
-	fn call(&self, _meta: Self::Metadata, _request: CallRequest, _flags: TraceOptions, _block: Trailing<BlockNumber>) -> Result<TraceResults> {
-		Err(errors::light_unimplemented(None))
+	fn call(&self, meta: Self::Metadata, request: CallRequest, flags: TraceOptions, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults> {
+		let request = CallRequest::into(request);
+		let signed = try_bf!(fake_sign::sign_call(request, meta.is_dapp()));
+
+		self.execute_at(signed, flags, block)
 	}
 
 	fn call_many(&self, _meta: Self::Metadata, _request: Vec<(CallRequest, TraceOptions)>, _block: Trailing<BlockNumber>) -> Result<Vec<TraceResults>> {
 		Err(errors::light_unimplemented(None))
 	}
 
-	fn raw_transaction(&self, _raw_transaction: Bytes, _flags: TraceOptions, _block: Trailing<BlockNumber>) -> Result<TraceResults> {
-		Err(errors::light_unimplemented(None))
+	fn raw_transaction(&self, raw_transaction: Bytes, flags: TraceOptions, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults> {
+		let tx = try_bf!(Rlp::new(&raw_transaction.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e)));
+		let signed = try_bf!(SignedTransaction::new(tx).map_err(errors::transaction));
+
+		self.execute_at(signed, flags, block)
 	}
 
-	fn replay_transaction(&self, _transaction_hash: H256, _flags: TraceOptions) -> Result<TraceResults> {
-		Err(errors::light_unimplemented(None))
+	fn replay_transaction(&self, transaction_hash: H256, flags: TraceOptions) -> BoxFuture<TraceResults> {
+		let fetcher = self.fetcher();
+		let client = self.light_dispatch.client.clone();
+		let analytics = to_call_analytics(flags);
+
+		Box::new(self.fetcher().transaction_by_hash(transaction_hash.into(), self.eip86_transition)
+			.and_then(|found| match found.and_then(|(tx, index)| tx.block_hash.map(|hash| (hash, index))) {
+				Some(located) => Ok(located),
+				None => Err(errors::call(CallError::TransactionNotFound)),
+			})
+			.and_then(|(block_hash, index)| if index > MAX_REPLAYED_INDEX {
+				Err(errors::light_unimplemented(Some(format!(
+					"Only transactions up to index {} in their block can be replayed.", MAX_REPLAYED_INDEX
+				))))
+			} else {
+				Ok((block_hash, index))
+			})
+			.and_then(move |(block_hash, index)| {
+				fetcher.block(BlockId::Hash(block_hash.into())).map(move |block| (fetcher, block, index))
+			})
+			.and_then(move |(fetcher, block, index)| -> BoxFuture<ExecutionResult> {
+				let mut transactions = try_bf!(block.transactions().into_iter()
+					.map(SignedTransaction::new)
+					.collect::<::std::result::Result<Vec<_>, _>>()
+					.map_err(errors::transaction));
+
+				// replay on top of the parent's state, in the environment of the block itself.
+				transactions.truncate(index + 1);
+				let tx = transactions.pop()
+					.expect("transaction_by_hash only yields indices of transactions in the block; qed");
+
+				let env_info = match client.env_info(BlockId::Hash(block.hash())) {
+					Some(env_info) => env_info,
+					None => return Box::new(future::err(errors::unknown_block())),
+				};
+
+				fetcher.traced_execution(BlockId::Hash(block.parent_hash()), env_info, transactions, tx, analytics)
+			})
+			.and_then(|res| res.map(TraceResults::from).map_err(errors::execution)))
 	}
 
 	fn replay_block_transactions(&self,  _block_number: BlockNumber, _flags: TraceOptions) ->  Result<Vec<TraceResults>> {
@@ -0,0 +1,76 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool rpc implementation for the light client.
+use std::sync::Arc;
+
+use jsonrpc_core::Result;
+use v1::helpers::dispatch::LightDispatcher;
+use v1::traits::TxPool;
+use v1::types::{Transaction, TxPoolStatus, TxPoolContent, TxPoolInspect};
+
+/// Transaction pool rpc implementation for the light client.
+pub struct TxPoolClient {
+	light_dispatch: Arc<LightDispatcher>,
+	eip86_transition: u64,
+}
+
+impl TxPoolClient {
+	/// Creates new `TxPoolClient`.
+	pub fn new(light_dispatch: Arc<LightDispatcher>) -> Self {
+		TxPoolClient {
+			eip86_transition: light_dispatch.client.eip86_transition(),
+			light_dispatch,
+		}
+	}
+
+	fn pool_content(&self) -> TxPoolContent {
+		let txq = self.light_dispatch.transaction_queue.read();
+		let chain_info = self.light_dispatch.client.chain_info();
+		let (best_num, best_tm) = (chain_info.best_block_number, chain_info.best_block_timestamp);
+		let eip86_transition = self.eip86_transition;
+
+		TxPoolContent::new(
+			txq.ready_transactions(best_num, best_tm)
+				.into_iter()
+				.map(|tx| Transaction::from_pending(tx, best_num, eip86_transition)),
+			txq.future_transactions(best_num, best_tm)
+				.into_iter()
+				.map(|tx| Transaction::from_pending(tx, best_num, eip86_transition)),
+		)
+	}
+}
+
+impl TxPool for TxPoolClient {
+	fn status(&self) -> Result<TxPoolStatus> {
+		let txq = self.light_dispatch.transaction_queue.read();
+		let chain_info = self.light_dispatch.client.chain_info();
+		let (best_num, best_tm) = (chain_info.best_block_number, chain_info.best_block_timestamp);
+
+		Ok(TxPoolStatus {
+			pending: (txq.ready_transactions(best_num, best_tm).len() as u64).into(),
+			queued: (txq.future_transactions(best_num, best_tm).len() as u64).into(),
+		})
+	}
+
+	fn content(&self) -> Result<TxPoolContent> {
+		Ok(self.pool_content())
+	}
+
+	fn inspect(&self) -> Result<TxPoolInspect> {
+		Ok(self.pool_content().into())
+	}
+}
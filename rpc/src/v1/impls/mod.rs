@@ -31,6 +31,7 @@ mod signing_unsafe;
 mod rpc;
 mod secretstore;
 mod traces;
+mod tx_pool;
 mod web3;
 mod private;
 
@@ -49,6 +50,7 @@ pub use self::signer::SignerClient;
 pub use self::signing::SigningQueueClient;
 pub use self::signing_unsafe::SigningUnsafeClient;
 pub use self::traces::TracesClient;
+pub use self::tx_pool::TxPoolClient;
 pub use self::web3::Web3Client;
 pub use self::rpc::RpcClient;
 pub use self::secretstore::SecretStoreClient;
@@ -19,7 +19,8 @@ use std::sync::Arc;
 use std::str::FromStr;
 use std::collections::{BTreeMap, HashSet};
 
-use ethereum_types::Address;
+use ethereum_types::{Address, H256 as EthH256};
+use parking_lot::Mutex;
 use version::version_data;
 
 use crypto::{DEFAULT_MAC, ecies};
@@ -30,8 +31,11 @@ use ethcore::account_provider::AccountProvider;
 use ethcore::client::{BlockChainClient, StateClient, Call};
 use ethcore::ids::BlockId;
 use ethcore::miner::{self, MinerService};
+use ethcore::miner::stratum::StratumWorkers;
 use ethcore::mode::Mode;
 use ethcore::state::StateInfo;
+use light::cht;
+use miner::gas_pricer::GasPriceOracleOptions;
 use ethcore_logger::RotatingLogger;
 use node_health::{NodeHealth, Health};
 use updater::{Service as UpdateService};
@@ -45,12 +49,12 @@ use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
 	Bytes, U256, U64, H160, H256, H512, CallRequest,
-	Peers, Transaction, RpcSettings, Histogram,
-	TransactionStats, LocalTransactionStatus,
+	Peers, Transaction, RpcSettings, Histogram, GasPriceTiers,
+	TransactionStats, LocalTransactionStatus, TxPoolEviction, StratumWorker, BlockExclusion,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
-	block_number_to_id
+	AccountInfo, HwAccountInfo, RichHeader, NodeReputation,
+	LightCheckpoint, block_number_to_id
 };
 use Host;
 
@@ -68,7 +72,11 @@ pub struct ParityClient<C, M, U>  {
 	signer: Option<Arc<SignerService>>,
 	dapps_address: Option<Host>,
 	ws_address: Option<Host>,
+	stratum: Option<Arc<StratumWorkers>>,
 	eip86_transition: u64,
+	gas_price_percentile: usize,
+	// hash of the last block and root of each CHT computed for a light checkpoint.
+	checkpoint_chts: Mutex<Vec<(EthH256, EthH256)>>,
 }
 
 impl<C, M, U> ParityClient<C, M, U> where
@@ -88,6 +96,8 @@ impl<C, M, U> ParityClient<C, M, U> where
 		signer: Option<Arc<SignerService>>,
 		dapps_address: Option<Host>,
 		ws_address: Option<Host>,
+		stratum: Option<Arc<StratumWorkers>>,
+		gas_price_percentile: usize,
 	) -> Self {
 		let eip86_transition = client.eip86_transition();
 		ParityClient {
@@ -103,7 +113,10 @@ impl<C, M, U> ParityClient<C, M, U> where
 			signer,
 			dapps_address,
 			ws_address,
+			stratum,
 			eip86_transition,
+			gas_price_percentile,
+			checkpoint_chts: Mutex::new(Vec::new()),
 		}
 	}
 
@@ -223,6 +236,10 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		})
 	}
 
+	fn node_reputations(&self) -> Result<Vec<NodeReputation>> {
+		Ok(self.net.node_reputations().into_iter().map(Into::into).collect())
+	}
+
 	fn net_port(&self) -> Result<u16> {
 		Ok(self.settings.network_port)
 	}
@@ -262,6 +279,18 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		))
 	}
 
+	fn gas_price_oracle(&self) -> BoxFuture<GasPriceTiers> {
+		// without a configured oracle the standard tier matches the default gas price.
+		let options = self.miner.gas_price_oracle().unwrap_or_else(|| GasPriceOracleOptions {
+			standard_percentile: self.gas_price_percentile,
+			..Default::default()
+		});
+		Box::new(future::done(helpers::dispatch::gas_price_tiers(&*self.client, &options)
+			.ok_or_else(errors::not_enough_data)
+			.map(Into::into)
+		))
+	}
+
 	fn unsigned_transactions_count(&self) -> Result<usize> {
 		match self.signer {
 			None => Err(errors::signer_disabled()),
@@ -366,6 +395,20 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		)
 	}
 
+	fn tx_pool_evictions(&self) -> Result<Vec<TxPoolEviction>> {
+		Ok(self.miner.queue_evictions().into_iter().map(Into::into).collect())
+	}
+
+	fn block_exclusions(&self) -> Result<Vec<BlockExclusion>> {
+		Ok(self.miner.block_exclusions().into_iter().map(Into::into).collect())
+	}
+
+	fn stratum_workers(&self) -> Result<Vec<StratumWorker>> {
+		self.stratum.as_ref()
+			.map(|stratum| stratum.workers().into_iter().map(Into::into).collect())
+			.ok_or_else(errors::stratum_disabled)
+	}
+
 	fn dapps_url(&self) -> Result<String> {
 		helpers::to_url(&self.dapps_address)
 			.ok_or_else(|| errors::dapps_disabled())
@@ -505,4 +548,54 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
 	}
+
+	fn light_checkpoint(&self) -> Result<LightCheckpoint> {
+		let best_block_number = self.client.chain_info().best_block_number;
+
+		// the checkpoint header is the first block after the last complete CHT.
+		let num_chts = match cht::block_to_cht_number(best_block_number) {
+			Some(num) if num > 0 => num,
+			_ => return Err(errors::checkpoint_unavailable(format!("at least {} blocks are required", cht::SIZE + 1))),
+		};
+
+		// CHTs computed for earlier checkpoints are reused, unless their blocks were reorganized.
+		let mut computed = self.checkpoint_chts.lock();
+		let still_canonical = computed.iter().enumerate()
+			.take_while(|&(cht_num, &(last_hash, _))| {
+				let last_num = cht::start_number(cht_num as u64 + 1) - 1;
+				self.client.block_hash(BlockId::Number(last_num)) == Some(last_hash)
+			})
+			.count();
+		computed.truncate(still_canonical);
+
+		for cht_num in (computed.len() as u64)..num_chts {
+			let start = cht::start_number(cht_num);
+			let mut entries = Vec::with_capacity(cht::SIZE as usize);
+
+			for num in start..(start + cht::SIZE) {
+				let id = BlockId::Number(num);
+				match (self.client.block_hash(id), self.client.block_total_difficulty(id)) {
+					(Some(hash), Some(td)) => entries.push((hash, td)),
+					_ => return Err(errors::checkpoint_unavailable(format!("block #{} is missing", num))),
+				}
+			}
+
+			let last_hash = entries.last().expect("exactly `cht::SIZE` entries were collected; qed").0;
+			let root = cht::compute_root(cht_num, entries)
+				.expect("exactly `cht::SIZE` entries were collected; qed");
+			computed.push((last_hash, root));
+		}
+
+		let chts = computed.iter().take(num_chts as usize).map(|&(_, root)| root.into()).collect();
+
+		let id = BlockId::Number(cht::start_number(num_chts));
+		match (self.client.block_header(id), self.client.block_total_difficulty(id)) {
+			(Some(header), Some(td)) => Ok(LightCheckpoint {
+				header: header.into_inner().into(),
+				total_difficulty: td.into(),
+				chts: chts,
+			}),
+			_ => Err(errors::checkpoint_unavailable(format!("block #{} is missing", cht::start_number(num_chts)))),
+		}
+	}
 }
@@ -18,19 +18,26 @@
 use std::io;
 use std::sync::Arc;
 
-use ethcore::client::BlockChainClient;
-use ethcore::miner::MinerService;
+use ethcore::account_provider::AccountProvider;
+use ethcore::client::{BlockChainClient, EngineInfo};
+use ethcore::miner::{self, MinerService};
 use ethcore::mode::Mode;
+use miner::pool::Priority;
+use rlp::Rlp;
 use sync::ManageNetwork;
 use fetch::{self, Fetch};
 use futures_cpupool::CpuPool;
 use hash::keccak_buffer;
+use transaction::SignedTransaction;
 use updater::{Service as UpdateService};
 
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::Future;
+use jsonrpc_macros::Trailing;
+use v1::helpers::accounts::unwrap_provider;
 use v1::helpers::dapps::DappsService;
-use v1::helpers::errors;
+use v1::helpers::dispatch::{self, FullDispatcher, SignWith};
+use v1::helpers::{errors, FilledTransactionRequest};
 use v1::traits::ParitySet;
 use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
 
@@ -40,6 +47,7 @@ pub struct ParitySetClient<C, M, U, F = fetch::Client> {
 	miner: Arc<M>,
 	updater: Arc<U>,
 	net: Arc<ManageNetwork>,
+	accounts: Option<Arc<AccountProvider>>,
 	dapps: Option<Arc<DappsService>>,
 	fetch: F,
 	pool: CpuPool,
@@ -55,6 +63,7 @@ impl<C, M, U, F> ParitySetClient<C, M, U, F>
 		miner: &Arc<M>,
 		updater: &Arc<U>,
 		net: &Arc<ManageNetwork>,
+		accounts: &Option<Arc<AccountProvider>>,
 		dapps: Option<Arc<DappsService>>,
 		fetch: F,
 		pool: CpuPool,
@@ -64,6 +73,7 @@ impl<C, M, U, F> ParitySetClient<C, M, U, F>
 			miner: miner.clone(),
 			updater: updater.clone(),
 			net: net.clone(),
+			accounts: accounts.clone(),
 			dapps: dapps,
 			fetch: fetch,
 			pool: pool,
@@ -73,7 +83,7 @@ impl<C, M, U, F> ParitySetClient<C, M, U, F>
 }
 
 impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
-	C: BlockChainClient + 'static,
+	C: miner::BlockChainClient + BlockChainClient + EngineInfo + 'static,
 	M: MinerService + 'static,
 	U: UpdateService + 'static,
 	F: Fetch + 'static,
@@ -123,6 +133,20 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		Ok(true)
 	}
 
+	fn propose_signer(&self, address: H160, add: bool) -> Result<bool> {
+		match self.client.engine().propose_signer(address.into(), add) {
+			true => Ok(true),
+			false => Err(errors::unsupported("Engine does not support signer voting.", None)),
+		}
+	}
+
+	fn discard_signer(&self, address: H160) -> Result<bool> {
+		match self.client.engine().discard_signer(&address.into()) {
+			true => Ok(true),
+			false => Err(errors::unsupported("Engine does not support signer voting.", None)),
+		}
+	}
+
 	fn add_reserved_peer(&self, peer: String) -> Result<bool> {
 		match self.net.add_reserved_peer(peer) {
 			Ok(()) => Ok(true),
@@ -210,4 +234,52 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		   .map(|t| Transaction::from_pending(t.pending().clone(), block_number + 1, self.eip86_transition))
 		)
 	}
+
+	fn cancel_transaction(&self, hash: H256) -> Result<Option<H256>> {
+		let accounts = unwrap_provider(&self.accounts)?;
+		let tx = match self.miner.transaction(&hash.into()) {
+			Some(tx) => tx,
+			None => return Ok(None),
+		};
+
+		let sender = tx.signed().sender();
+		let nonce = tx.signed().nonce;
+		let gas_price = self.miner.queue_status().replacement
+			.min_replacement_gas_price(tx.signed().gas_price, tx.priority() == Priority::Local);
+		let request = FilledTransactionRequest {
+			from: sender,
+			used_default_from: false,
+			to: Some(sender),
+			gas_price,
+			gas: 21_000.into(),
+			value: 0.into(),
+			data: Vec::new(),
+			nonce: Some(nonce),
+			condition: None,
+			metadata: Vec::new(),
+			metadata_limit: 0.into(),
+		};
+
+		let signed = dispatch::sign_transaction(&*accounts, request, self.client.signing_chain_id(), nonce, SignWith::Nothing)?
+			.into_value();
+		FullDispatcher::dispatch_transaction(&*self.client, &*self.miner, signed.into(), true)
+			.map(|hash| Some(hash.into()))
+	}
+
+	fn send_bundle(&self, raw: Vec<Bytes>, target_block: Trailing<u64>) -> Result<H256> {
+		if raw.is_empty() {
+			return Err(errors::invalid_params("transactions", "Bundle needs to contain at least one transaction."));
+		}
+
+		let transactions = raw.into_iter()
+			.map(|raw| Rlp::new(&raw.into_vec()).as_val()
+				.map_err(errors::rlp)
+				.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))
+			)
+			.collect::<Result<Vec<_>>>()?;
+
+		self.miner.import_bundle(&*self.client, transactions, target_block.into())
+			.map(Into::into)
+			.map_err(errors::transaction)
+	}
 }
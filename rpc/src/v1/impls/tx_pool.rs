@@ -0,0 +1,96 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool rpc implementation.
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use ethcore::client::BlockChainClient;
+use ethcore::miner::{self, MinerService};
+
+use jsonrpc_core::Result;
+use v1::traits::TxPool;
+use v1::types::{Transaction, TxPoolStatus, TxPoolContent, TxPoolInspect};
+
+/// Transaction pool rpc implementation.
+pub struct TxPoolClient<C, M> {
+	client: Arc<C>,
+	miner: Arc<M>,
+	eip86_transition: u64,
+}
+
+impl<C, M> TxPoolClient<C, M> where
+	C: BlockChainClient,
+{
+	/// Creates new `TxPoolClient`.
+	pub fn new(client: &Arc<C>, miner: &Arc<M>) -> Self {
+		TxPoolClient {
+			client: client.clone(),
+			miner: miner.clone(),
+			eip86_transition: client.eip86_transition(),
+		}
+	}
+}
+
+impl<C, M> TxPoolClient<C, M> where
+	C: miner::BlockChainClient + BlockChainClient,
+	M: MinerService,
+{
+	fn pool_content(&self) -> TxPoolContent {
+		let block_number = self.client.chain_info().best_block_number;
+		let pending = self.miner.queue_ready_transactions(
+			&*self.client,
+			usize::max_value(),
+			miner::PendingOrdering::Priority,
+		);
+		let ready = pending.iter().map(|tx| tx.signed().hash()).collect::<HashSet<_>>();
+		let queued = self.miner.queued_transactions()
+			.into_iter()
+			.filter(|tx| !ready.contains(&tx.signed().hash()));
+
+		TxPoolContent::new(
+			pending.iter().map(|tx| Transaction::from_pending(tx.pending().clone(), block_number, self.eip86_transition)),
+			queued.map(|tx| Transaction::from_pending(tx.pending().clone(), block_number, self.eip86_transition)),
+		)
+	}
+}
+
+impl<C, M> TxPool for TxPoolClient<C, M> where
+	C: miner::BlockChainClient + BlockChainClient + 'static,
+	M: MinerService + 'static,
+{
+	fn status(&self) -> Result<TxPoolStatus> {
+		let pending = self.miner.queue_ready_transactions(
+			&*self.client,
+			usize::max_value(),
+			miner::PendingOrdering::Unordered,
+		).len();
+		let queued = self.miner.queued_transactions().len().saturating_sub(pending);
+
+		Ok(TxPoolStatus {
+			pending: (pending as u64).into(),
+			queued: (queued as u64).into(),
+		})
+	}
+
+	fn content(&self) -> Result<TxPoolContent> {
+		Ok(self.pool_content())
+	}
+
+	fn inspect(&self) -> Result<TxPoolInspect> {
+		Ok(self.pool_content().into())
+	}
+}
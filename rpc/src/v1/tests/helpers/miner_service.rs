@@ -30,8 +30,10 @@ use ethcore::ids::BlockId;
 use ethcore::miner::{self, MinerService, AuthoringParams};
 use ethcore::receipt::{Receipt, RichReceipt};
 use ethereum_types::{H256, U256, Address};
+use miner::gas_pricer::GasPriceOracleOptions;
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
-use miner::pool::{verifier, VerifiedTransaction, QueueStatus};
+use miner::pool::evictions::Eviction;
+use miner::pool::{verifier, Bundle, VerifiedTransaction, QueueStatus};
 use parking_lot::{RwLock, Mutex};
 use transaction::{self, UnverifiedTransaction, SignedTransaction, PendingTransaction};
 use txpool;
@@ -40,16 +42,24 @@ use txpool;
 pub struct TestMinerService {
 	/// Imported transactions.
 	pub imported_transactions: Mutex<Vec<SignedTransaction>>,
+	/// Imported bundles.
+	pub imported_bundles: Mutex<Vec<Arc<Bundle>>>,
 	/// Pre-existed pending transactions
 	pub pending_transactions: Mutex<HashMap<H256, SignedTransaction>>,
 	/// Pre-existed local transactions
 	pub local_transactions: Mutex<BTreeMap<H256, LocalTransactionStatus>>,
+	/// Pre-existed queue evictions
+	pub evictions: Mutex<Vec<Eviction>>,
+	/// Pre-existed pending block exclusions
+	pub exclusions: Mutex<Vec<miner::Exclusion>>,
 	/// Pre-existed pending receipts
 	pub pending_receipts: Mutex<BTreeMap<H256, Receipt>>,
 	/// Next nonces.
 	pub next_nonces: RwLock<HashMap<Address, U256>>,
 	/// Password held by Engine.
 	pub password: RwLock<String>,
+	/// Gas price oracle options.
+	pub gas_price_oracle: RwLock<Option<GasPriceOracleOptions>>,
 
 	authoring_params: RwLock<AuthoringParams>,
 }
@@ -58,11 +68,15 @@ impl Default for TestMinerService {
 	fn default() -> TestMinerService {
 		TestMinerService {
 			imported_transactions: Mutex::new(Vec::new()),
+			imported_bundles: Mutex::new(Vec::new()),
 			pending_transactions: Mutex::new(HashMap::new()),
 			local_transactions: Mutex::new(BTreeMap::new()),
+			evictions: Mutex::new(Vec::new()),
+			exclusions: Mutex::new(Vec::new()),
 			pending_receipts: Mutex::new(BTreeMap::new()),
 			next_nonces: RwLock::new(HashMap::new()),
 			password: RwLock::new(String::new()),
+			gas_price_oracle: RwLock::new(None),
 			authoring_params: RwLock::new(AuthoringParams {
 				author: Address::zero(),
 				gas_range_target: (12345.into(), 54321.into()),
@@ -176,6 +190,21 @@ impl MinerService for TestMinerService {
 		Ok(())
 	}
 
+	fn import_bundle<C: Nonce + Sync>(&self, _chain: &C, transactions: Vec<SignedTransaction>, target_block: Option<BlockNumber>)
+		-> Result<H256, transaction::Error> {
+
+		// lets assume that all bundles are valid
+		let transactions = transactions.into_iter()
+			.map(VerifiedTransaction::from_pending_block_transaction)
+			.map(Arc::new)
+			.collect();
+		let bundle = Bundle::new(transactions, target_block);
+		let hash = *bundle.hash();
+		self.imported_bundles.lock().push(Arc::new(bundle));
+
+		Ok(hash)
+	}
+
 	/// Called when blocks are imported to chain, updates transactions queue.
 	fn chain_new_blocks<C>(&self, _chain: &C, _imported: &[H256], _invalid: &[H256], _enacted: &[H256], _retracted: &[H256], _is_internal: bool) {
 		unimplemented!();
@@ -215,10 +244,22 @@ impl MinerService for TestMinerService {
 		self.local_transactions.lock().iter().map(|(hash, stats)| (*hash, stats.clone())).collect()
 	}
 
+	fn queue_evictions(&self) -> Vec<Eviction> {
+		self.evictions.lock().clone()
+	}
+
+	fn block_exclusions(&self) -> Vec<miner::Exclusion> {
+		self.exclusions.lock().clone()
+	}
+
 	fn ready_transactions<C>(&self, _chain: &C, _max_len: usize, _ordering: miner::PendingOrdering) -> Vec<Arc<VerifiedTransaction>> {
 		self.queued_transactions()
 	}
 
+	fn queue_ready_transactions<C>(&self, _chain: &C, _max_len: usize, _ordering: miner::PendingOrdering) -> Vec<Arc<VerifiedTransaction>> {
+		self.queued_transactions()
+	}
+
 	fn pending_transaction_hashes<C>(&self, _chain: &C) -> BTreeSet<H256> {
 		self.queued_transactions().into_iter().map(|tx| tx.signed().hash()).collect()
 	}
@@ -275,7 +316,9 @@ impl MinerService for TestMinerService {
 				max_count: 1_024,
 				max_per_sender: 16,
 				max_mem_usage: 5_000,
+				..Default::default()
 			},
+			replacement: Default::default(),
 		}
 	}
 
@@ -289,6 +332,10 @@ impl MinerService for TestMinerService {
 		20_000_000_000u64.into()
 	}
 
+	fn gas_price_oracle(&self) -> Option<GasPriceOracleOptions> {
+		self.gas_price_oracle.read().clone()
+	}
+
 	fn sensible_gas_limit(&self) -> U256 {
 		0x5208.into()
 	}
@@ -54,6 +54,8 @@ impl TestSyncProvider {
 				num_snapshot_chunks: 0,
 				snapshot_chunks_done: 0,
 				last_imported_old_block_number: None,
+				state_nodes_done: 0,
+				state_nodes_pending: 0,
 			}),
 		}
 	}
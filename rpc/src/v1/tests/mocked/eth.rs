@@ -169,6 +169,16 @@ fn rpc_eth_syncing() {
 
 	tester.snapshot.set_status(RestorationStatus::Inactive);
 
+	{
+		let mut status = tester.sync.status.write();
+		status.state = SyncState::StateNodes;
+		status.state_nodes_done = 300;
+		status.state_nodes_pending = 100;
+	}
+
+	let state_res = r#"{"jsonrpc":"2.0","result":{"currentBlock":"0x3e8","highestBlock":"0x9c4","knownStates":"0x190","pulledStates":"0x12c","startingBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(state_res.to_owned()));
+
 	// finish "syncing"
 	tester.add_blocks(1500, EachBlockWith::Nothing);
 
@@ -393,6 +403,20 @@ fn rpc_eth_gas_price() {
 	assert_eq!(EthTester::default().io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_gas_price_with_oracle() {
+	use miner::gas_pricer::GasPriceOracleOptions;
+
+	let tester = EthTester::default();
+	*tester.miner.gas_price_oracle.write() = Some(GasPriceOracleOptions::default());
+	tester.client.add_blocks(2, EachBlockWith::Transaction);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_gasPrice", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x2e90edd000","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_accounts() {
 	let tester = EthTester::default();
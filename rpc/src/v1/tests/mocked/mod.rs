@@ -31,4 +31,5 @@ mod secretstore;
 mod signer;
 mod signing;
 mod traces;
+mod tx_pool;
 mod web3;
@@ -21,6 +21,8 @@ use ethcore_logger::RotatingLogger;
 use ethereum_types::{Address, U256, H256};
 use ethstore::ethkey::{Generator, Random};
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
+use miner::pool::evictions::Eviction;
+use ethcore::miner::stratum::{StratumWorkers, WorkerStats};
 use node_health::{self, NodeHealth};
 use parity_reactor;
 use sync::ManageNetwork;
@@ -47,6 +49,7 @@ pub struct Dependencies {
 	pub accounts: Arc<AccountProvider>,
 	pub dapps_address: Option<Host>,
 	pub ws_address: Option<Host>,
+	pub stratum: Option<Arc<StratumWorkers>>,
 }
 
 impl Dependencies {
@@ -77,6 +80,7 @@ impl Dependencies {
 			accounts: Arc::new(AccountProvider::transient_provider()),
 			dapps_address: Some("127.0.0.1:18080".into()),
 			ws_address: Some("127.0.0.1:18546".into()),
+			stratum: None,
 		}
 	}
 
@@ -96,6 +100,8 @@ impl Dependencies {
 			signer,
 			self.dapps_address.clone(),
 			self.ws_address.clone(),
+			self.stratum.clone(),
+			50,
 		)
 	}
 
@@ -246,6 +252,25 @@ fn rpc_parity_default_extra_data() {
 	assert_eq!(io.handle_request_sync(request), Some(response));
 }
 
+#[test]
+fn rpc_parity_gas_price_oracle() {
+	use ethcore::client::EachBlockWith;
+	use miner::gas_pricer::GasPriceOracleOptions;
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_gasPriceOracle", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"The node does not have enough data to compute the given statistic."},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	deps.client.add_blocks(3, EachBlockWith::Transaction);
+	*deps.miner.gas_price_oracle.write() = Some(GasPriceOracleOptions { blocks: 2, ..Default::default() });
+
+	let response = r#"{"jsonrpc":"2.0","result":{"slow":"0x2e90edd000","standard":"0x2e90edd000","fast":"0x2e90edd000"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_gas_floor_target() {
 	let deps = Dependencies::new();
@@ -337,6 +362,17 @@ fn rpc_parity_net_peers() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_node_reputations() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_nodeReputations", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"enode":"enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770","reputation":42}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_net_port() {
 	let deps = Dependencies::new();
@@ -506,6 +542,86 @@ fn rpc_parity_local_transactions() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_tx_pool_evictions() {
+	use std::time::{Duration, UNIX_EPOCH};
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+	deps.miner.evictions.lock().push(Eviction {
+		hash: 10.into(),
+		sender: 5.into(),
+		limit: ::txpool::Limit::GasPerSender,
+		time: UNIX_EPOCH + Duration::from_secs(1_500_000_000),
+	});
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_txPoolEvictions", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"from":"0x0000000000000000000000000000000000000005","hash":"0x000000000000000000000000000000000000000000000000000000000000000a","reason":"gasPerSender","timestamp":1500000000}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_block_exclusions() {
+	use ethcore::miner::{Exclusion, ExclusionReason};
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+	deps.miner.exclusions.lock().push(Exclusion {
+		hash: 10.into(),
+		sender: 5.into(),
+		reason: ExclusionReason::AboveGasLimit,
+	});
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_blockExclusions", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"details":"Transaction gas exceeds block gas limit","from":"0x0000000000000000000000000000000000000005","hash":"0x000000000000000000000000000000000000000000000000000000000000000a","reason":"aboveGasLimit"}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+struct TestStratum;
+
+impl StratumWorkers for TestStratum {
+	fn workers(&self) -> Vec<WorkerStats> {
+		use std::time::{Duration, UNIX_EPOCH};
+
+		vec![WorkerStats {
+			id: "miner1".into(),
+			addr: "127.0.0.1:30000".parse().unwrap(),
+			difficulty: Some(1000.into()),
+			accepted_shares: 10,
+			blocks: 1,
+			stale_shares: 2,
+			invalid_shares: 3,
+			hashrate: 500.into(),
+			last_share: Some(UNIX_EPOCH + Duration::from_secs(1_500_000_000)),
+		}]
+	}
+}
+
+#[test]
+fn rpc_parity_stratum_workers() {
+	let mut deps = Dependencies::new();
+	deps.stratum = Some(Arc::new(TestStratum));
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_stratumWorkers", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"acceptedShares":10,"address":"127.0.0.1:30000","blocks":1,"difficulty":"0x3e8","hashrate":"0x1f4","id":"miner1","invalidShares":3,"lastShare":1500000000,"staleShares":2}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_stratum_workers_disabled() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_stratumWorkers", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Stratum Server is disabled. This API is not available."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_chain_status() {
 	let deps = Dependencies::new();
@@ -520,6 +636,38 @@ fn rpc_parity_chain_status() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_light_checkpoint() {
+	use ethcore::client::{BlockChainClient, BlockInfo, EachBlockWith};
+	use ethcore::ids::BlockId;
+	use light::cht;
+	use rustc_hex::ToHex;
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_lightCheckpoint", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"The node does not have enough block history to produce a light client checkpoint.","data":"at least 2049 blocks are required"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	deps.client.add_blocks(cht::SIZE as usize + 1, EachBlockWith::Nothing);
+
+	let root = cht::compute_root(0, (1..cht::SIZE + 1).map(|num| {
+		(BlockChainClient::block_hash(&*deps.client, BlockId::Number(num)).unwrap(), U256::zero())
+	})).unwrap();
+	let header = deps.client.block_header(BlockId::Number(cht::SIZE + 1)).unwrap();
+
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","result":{{"header":"0x{}","totalDifficulty":"0x0","CHTs":["0x{:x}"]}},"id":1}}"#,
+		header.into_inner().to_hex(),
+		root,
+	);
+	assert_eq!(io.handle_request_sync(request), Some(response.clone()));
+
+	// computed CHTs are reused.
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
 #[test]
 fn rpc_parity_node_kind() {
 	let deps = Dependencies::new();
@@ -57,7 +57,7 @@ fn parity_set_client(
 ) -> TestParitySetClient {
 	let dapps_service = Arc::new(TestDappsService);
 	let pool = CpuPool::new(1);
-	ParitySetClient::new(client, miner, updater, &(net.clone() as Arc<ManageNetwork>), Some(dapps_service), FakeFetch::new(Some(1)), pool)
+	ParitySetClient::new(client, miner, updater, &(net.clone() as Arc<ManageNetwork>), &None, Some(dapps_service), FakeFetch::new(Some(1)), pool)
 }
 
 #[test]
@@ -181,6 +181,20 @@ fn rpc_parity_set_engine_signer() {
 	assert_eq!(*miner.password.read(), "password".to_string());
 }
 
+#[test]
+fn rpc_parity_propose_signer_unsupported() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_proposeSigner", "params":["0xcd1722f3947def4cf144679da39c4c32bdc35681", true], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Engine does not support signer voting."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
 
 #[test]
 fn rpc_parity_set_transactions_limit() {
@@ -241,6 +255,100 @@ fn rpc_parity_remove_transaction() {
 	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_cancel_transaction() {
+	use ethcore::account_provider::AccountProvider;
+	use transaction::{Transaction, Action, SignedTransaction};
+
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let accounts = Arc::new(AccountProvider::transient_provider());
+	let sender = accounts.new_account("").unwrap();
+	accounts.unlock_account_permanently(sender, "".into()).unwrap();
+	let mut io = IoHandler::new();
+	io.extend_with(ParitySetClient::new(
+		&client,
+		&miner,
+		&updater_service(),
+		&(network as Arc<ManageNetwork>),
+		&Some(accounts.clone()),
+		None,
+		FakeFetch::new(Some(1)),
+		CpuPool::new(1),
+	).to_delegate());
+
+	let tx = Transaction {
+		nonce: 5.into(),
+		gas_price: 100.into(),
+		gas: 50_000.into(),
+		action: Action::Call(5.into()),
+		value: 1.into(),
+		..Default::default()
+	};
+	let signature = accounts.sign(sender, None, tx.hash(None)).unwrap();
+	let signed = SignedTransaction::new(tx.with_signature(signature, None)).unwrap();
+	let hash = signed.hash();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_cancelTransaction", "params":[""#.to_owned() + &format!("0x{:x}", hash) + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+
+	miner.pending_transactions.lock().insert(hash, signed);
+	let response = io.handle_request_sync(&request);
+
+	let imported = miner.imported_transactions.lock();
+	assert_eq!(imported.len(), 1);
+	assert_eq!(imported[0].sender(), sender);
+	assert_eq!(imported[0].nonce, U256::from(5));
+	assert_eq!(imported[0].gas_price, U256::from(112));
+	assert_eq!(imported[0].action, Action::Call(sender));
+	assert_eq!(imported[0].value, U256::zero());
+	assert_eq!(response, Some(format!(r#"{{"jsonrpc":"2.0","result":"0x{:x}","id":1}}"#, imported[0].hash())));
+}
+
+#[test]
+fn rpc_parity_send_bundle() {
+	use ethkey::{Generator, Random};
+	use rlp;
+	use rustc_hex::ToHex;
+	use transaction::{Transaction, Action};
+
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_sendBundle", "params":[[]], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: transactions","data":"\"Bundle needs to contain at least one transaction.\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let keypair = Random.generate().unwrap();
+	let raw = |nonce: u64| {
+		let tx = Transaction {
+			nonce: nonce.into(),
+			gas_price: 1.into(),
+			gas: 21_000.into(),
+			action: Action::Call(5.into()),
+			value: 1.into(),
+			..Default::default()
+		}.sign(keypair.secret(), None);
+		format!(r#""0x{}""#, rlp::encode(&tx).into_vec().to_hex())
+	};
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_sendBundle", "params":[[{}, {}], 10], "id": 1}}"#, raw(0), raw(1));
+	let response = io.handle_request_sync(&request);
+
+	let bundles = miner.imported_bundles.lock();
+	assert_eq!(bundles.len(), 1);
+	assert_eq!(bundles[0].target_block(), Some(10));
+	assert_eq!(bundles[0].transactions().len(), 2);
+	assert_eq!(bundles[0].transactions()[1].signed().nonce, U256::from(1));
+	assert_eq!(response, Some(format!(r#"{{"jsonrpc":"2.0","result":"0x{:x}","id":1}}"#, bundles[0].hash())));
+}
+
 #[test]
 fn rpc_parity_set_dapps_list() {
 	let miner = miner_service();
@@ -0,0 +1,76 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::client::TestBlockChainClient;
+use transaction::{Transaction, Action};
+
+use jsonrpc_core::IoHandler;
+use v1::tests::helpers::TestMinerService;
+use v1::{TxPool, TxPoolClient};
+
+fn io() -> IoHandler {
+	let client = Arc::new(TestBlockChainClient::new());
+	let miner = Arc::new(TestMinerService::default());
+
+	for nonce in 0..2u64 {
+		let tx = Transaction {
+			nonce: nonce.into(),
+			gas_price: 2.into(),
+			gas: 21_000.into(),
+			action: if nonce == 0 { Action::Call(5.into()) } else { Action::Create },
+			value: 1.into(),
+			data: vec![],
+		}.fake_sign(2.into());
+		miner.pending_transactions.lock().insert(tx.hash(), tx);
+	}
+
+	let mut io = IoHandler::new();
+	io.extend_with(TxPoolClient::new(&client, &miner).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_txpool_status() {
+	let io = io();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_status", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"pending":"0x2","queued":"0x0"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_txpool_inspect() {
+	let io = io();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_inspect", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"pending":{"0x0000000000000000000000000000000000000002":{"0":"0x0000000000000000000000000000000000000005: 1 wei + 21000 gas × 2 wei","1":"contract creation: 1 wei + 21000 gas × 2 wei"}},"queued":{}},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_txpool_content() {
+	let io = io();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_content", "params": [], "id": 1}"#;
+	let response = io.handle_request_sync(request).unwrap();
+
+	assert!(response.contains(r#""queued":{}"#));
+	assert!(response.contains(r#""pending":{"0x0000000000000000000000000000000000000002":{"0":{"#));
+}
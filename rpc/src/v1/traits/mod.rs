@@ -29,6 +29,7 @@ pub mod personal;
 pub mod pubsub;
 pub mod signer;
 pub mod traces;
+pub mod tx_pool;
 pub mod rpc;
 pub mod secretstore;
 pub mod private;
@@ -46,6 +47,7 @@ pub use self::personal::Personal;
 pub use self::pubsub::PubSub;
 pub use self::signer::Signer;
 pub use self::traces::Traces;
+pub use self::tx_pool::TxPool;
 pub use self::rpc::Rpc;
 pub use self::secretstore::SecretStore;
 pub use self::private::Private;
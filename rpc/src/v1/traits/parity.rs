@@ -24,11 +24,12 @@ use jsonrpc_macros::Trailing;
 use node_health::Health;
 use v1::types::{
 	H160, H256, H512, U256, U64, Bytes, CallRequest,
-	Peers, Transaction, RpcSettings, Histogram,
-	TransactionStats, LocalTransactionStatus,
+	Peers, Transaction, RpcSettings, Histogram, GasPriceTiers,
+	TransactionStats, LocalTransactionStatus, TxPoolEviction, StratumWorker, BlockExclusion,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
+	AccountInfo, HwAccountInfo, RichHeader, NodeReputation,
+	LightCheckpoint,
 };
 
 build_rpc_trait! {
@@ -88,6 +89,10 @@ build_rpc_trait! {
 		#[rpc(name = "parity_netPeers")]
 		fn net_peers(&self) -> Result<Peers>;
 
+		/// Returns persistent reputations of known nodes, best first
+		#[rpc(name = "parity_nodeReputations")]
+		fn node_reputations(&self) -> Result<Vec<NodeReputation>>;
+
 		/// Returns network port
 		#[rpc(name = "parity_netPort")]
 		fn net_port(&self) -> Result<u16>;
@@ -108,6 +113,10 @@ build_rpc_trait! {
 		#[rpc(name = "parity_gasPriceHistogram")]
 		fn gas_price_histogram(&self) -> BoxFuture<Histogram>;
 
+		/// Returns slow, standard and fast gas prices suggested from transactions in latest blocks.
+		#[rpc(name = "parity_gasPriceOracle")]
+		fn gas_price_oracle(&self) -> BoxFuture<GasPriceTiers>;
+
 		/// Returns number of unsigned transactions waiting in the signer queue (if signer enabled)
 		/// Returns error when signer is disabled
 		#[rpc(name = "parity_unsignedTransactionsCount")]
@@ -161,6 +170,20 @@ build_rpc_trait! {
 		#[rpc(name = "parity_localTransactions")]
 		fn local_transactions(&self) -> Result<BTreeMap<H256, LocalTransactionStatus>>;
 
+		/// Returns transactions recently evicted from the pool together with the limit that caused eviction.
+		#[rpc(name = "parity_txPoolEvictions")]
+		fn tx_pool_evictions(&self) -> Result<Vec<TxPoolEviction>>;
+
+		/// Returns pending transactions that were not included in the most recently prepared
+		/// pending block together with the reasons why.
+		#[rpc(name = "parity_blockExclusions")]
+		fn block_exclusions(&self) -> Result<Vec<BlockExclusion>>;
+
+		/// Returns share statistics and estimated hashrates of workers connected to the stratum server.
+		/// Returns error when stratum is disabled.
+		#[rpc(name = "parity_stratumWorkers")]
+		fn stratum_workers(&self) -> Result<Vec<StratumWorker>>;
+
 		/// Returns current Dapps Server interface and port or an error if dapps server is disabled.
 		#[rpc(name = "parity_dappsUrl")]
 		fn dapps_url(&self) -> Result<String>;
@@ -227,5 +250,10 @@ build_rpc_trait! {
 		/// Returns node's health report.
 		#[rpc(name = "parity_nodeHealth")]
 		fn node_health(&self) -> BoxFuture<Health>;
+
+		/// Returns a trusted checkpoint (latest header after the last complete CHT, its total
+		/// difficulty and all CHT roots) for starting light clients with `--light-checkpoint`.
+		#[rpc(name = "parity_lightCheckpoint")]
+		fn light_checkpoint(&self) -> Result<LightCheckpoint>;
 	}
 }
@@ -17,6 +17,7 @@
 //! Parity-specific rpc interface for operations altering the settings.
 
 use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_macros::Trailing;
 
 use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
 
@@ -47,6 +48,14 @@ build_rpc_trait! {
 		#[rpc(name = "parity_setEngineSigner")]
 		fn set_engine_signer(&self, H160, String) -> Result<bool>;
 
+		/// Proposes adding (`true`) or removing (`false`) a signer in engines with signer voting.
+		#[rpc(name = "parity_proposeSigner")]
+		fn propose_signer(&self, H160, bool) -> Result<bool>;
+
+		/// Discards a pending signer proposal.
+		#[rpc(name = "parity_discardSigner")]
+		fn discard_signer(&self, H160) -> Result<bool>;
+
 		/// Sets the limits for transaction queue.
 		#[rpc(name = "parity_setTransactionsLimit")]
 		fn set_transactions_limit(&self, usize) -> Result<bool>;
@@ -119,5 +128,18 @@ build_rpc_trait! {
 		/// Returns `true` when transaction was removed, `false` if it was not found.
 		#[rpc(name = "parity_removeTransaction")]
 		fn remove_transaction(&self, H256) -> Result<Option<Transaction>>;
+
+		/// Cancels a queued transaction sent from one of the unlocked accounts
+		/// by replacing it with a zero-value transfer to the sender with the same nonce.
+		/// Returns hash of the replacement transaction, `None` if the transaction was not found.
+		#[rpc(name = "parity_cancelTransaction")]
+		fn cancel_transaction(&self, H256) -> Result<Option<H256>>;
+
+		/// Submits a bundle of signed raw transactions to the local miner.
+		/// Transactions of the bundle are included in a block all together and in the given order, or not at all.
+		/// Optionally the bundle can target a single block number.
+		/// Returns hash of the bundle.
+		#[rpc(name = "parity_sendBundle")]
+		fn send_bundle(&self, Vec<Bytes>, Trailing<u64>) -> Result<H256>;
 	}
 }
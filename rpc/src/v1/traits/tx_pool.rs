@@ -0,0 +1,37 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-compatible transaction pool rpc interface.
+use jsonrpc_core::Result;
+
+use v1::types::{TxPoolStatus, TxPoolContent, TxPoolInspect};
+
+build_rpc_trait! {
+	/// Transaction pool rpc interface.
+	pub trait TxPool {
+		/// Returns number of pending and queued transactions.
+		#[rpc(name = "txpool_status")]
+		fn status(&self) -> Result<TxPoolStatus>;
+
+		/// Returns pending and queued transactions grouped by sender and nonce.
+		#[rpc(name = "txpool_content")]
+		fn content(&self) -> Result<TxPoolContent>;
+
+		/// Returns summaries of pending and queued transactions grouped by sender and nonce.
+		#[rpc(name = "txpool_inspect")]
+		fn inspect(&self) -> Result<TxPoolInspect>;
+	}
+}
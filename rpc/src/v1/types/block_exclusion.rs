@@ -0,0 +1,98 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity. If not, see <http://www.gnu.org/licenses/>.
+
+//! Transactions excluded from the pending block.
+
+use ethcore::miner::{Exclusion, ExclusionReason};
+use v1::types::{H160, H256};
+
+/// Reason why a transaction was not included in the pending block.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum BlockExclusionReason {
+	/// Transaction gas exceeds gas left in the block.
+	#[serde(rename="gasLimit")]
+	GasLimit,
+	/// Transaction gas exceeds block gas limit.
+	#[serde(rename="aboveGasLimit")]
+	AboveGasLimit,
+	/// Transaction nonce does not follow nonce of sender's preceding transactions.
+	#[serde(rename="invalidNonce")]
+	InvalidNonce,
+	/// Sender is not allowed to send transactions.
+	#[serde(rename="notAllowed")]
+	NotAllowed,
+	/// The block got full before the transaction was considered.
+	#[serde(rename="blockFull")]
+	BlockFull,
+	/// Transaction failed to verify or execute.
+	#[serde(rename="invalid")]
+	Invalid,
+}
+
+impl<'a> From<&'a ExclusionReason> for BlockExclusionReason {
+	fn from(reason: &'a ExclusionReason) -> Self {
+		match *reason {
+			ExclusionReason::GasLimit { .. } => BlockExclusionReason::GasLimit,
+			ExclusionReason::AboveGasLimit => BlockExclusionReason::AboveGasLimit,
+			ExclusionReason::InvalidNonce { .. } => BlockExclusionReason::InvalidNonce,
+			ExclusionReason::NotAllowed => BlockExclusionReason::NotAllowed,
+			ExclusionReason::BlockFull => BlockExclusionReason::BlockFull,
+			ExclusionReason::Invalid(_) => BlockExclusionReason::Invalid,
+		}
+	}
+}
+
+/// Pending transaction that was not included in the pending block.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BlockExclusion {
+	/// Transaction hash.
+	pub hash: H256,
+	/// Transaction sender.
+	pub from: H160,
+	/// Why the transaction was not included.
+	pub reason: BlockExclusionReason,
+	/// Human-readable details.
+	pub details: String,
+}
+
+impl From<Exclusion> for BlockExclusion {
+	fn from(exclusion: Exclusion) -> Self {
+		BlockExclusion {
+			hash: exclusion.hash.into(),
+			from: exclusion.sender.into(),
+			reason: (&exclusion.reason).into(),
+			details: exclusion.reason.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::*;
+
+	#[test]
+	fn should_serialize_exclusion() {
+		let exclusion: BlockExclusion = Exclusion {
+			hash: 5.into(),
+			sender: 10.into(),
+			reason: ExclusionReason::InvalidNonce { expected: 1.into(), got: 3.into() },
+		}.into();
+
+		let serialized = serde_json::to_string(&exclusion).unwrap();
+		assert_eq!(serialized, r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000005","from":"0x000000000000000000000000000000000000000a","reason":"invalidNonce","details":"Invalid transaction nonce: expected 1, got 3"}"#);
+	}
+}
@@ -0,0 +1,41 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity. If not, see <http://www.gnu.org/licenses/>.
+
+//! Suggested gas prices.
+
+use v1::types::U256;
+
+/// Gas prices suggested by the oracle.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasPriceTiers {
+	/// Gas price for slow inclusion.
+	pub slow: U256,
+	/// Gas price for standard inclusion.
+	pub standard: U256,
+	/// Gas price for fast inclusion.
+	pub fast: U256,
+}
+
+impl From<::miner::gas_pricer::GasPriceTiers> for GasPriceTiers {
+	fn from(t: ::miner::gas_pricer::GasPriceTiers) -> Self {
+		GasPriceTiers {
+			slow: t.slow.into(),
+			standard: t.standard.into(),
+			fast: t.fast.into(),
+		}
+	}
+}
@@ -0,0 +1,52 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Light client checkpoint.
+
+use v1::types::{Bytes, H256, U256};
+
+/// Trusted checkpoint for starting a light client without syncing all headers.
+///
+/// Serializes to the format of the `hardcodedSync` chain specification entry,
+/// so that it can be passed to a light client with `--light-checkpoint`.
+#[derive(Debug, Serialize)]
+pub struct LightCheckpoint {
+	/// RLP-encoded header of the block to start from.
+	pub header: Bytes,
+	/// Total difficulty including the block of `header`.
+	#[serde(rename="totalDifficulty")]
+	pub total_difficulty: U256,
+	/// Ordered roots of the CHTs before `header`.
+	#[serde(rename="CHTs")]
+	pub chts: Vec<H256>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::LightCheckpoint;
+
+	#[test]
+	fn test_serialize_light_checkpoint() {
+		let t = LightCheckpoint {
+			header: vec![0xc0].into(),
+			total_difficulty: 5u64.into(),
+			chts: vec![Default::default()],
+		};
+		let serialized = serde_json::to_string(&t).unwrap();
+		assert_eq!(serialized, r#"{"header":"0xc0","totalDifficulty":"0x5","CHTs":["0x0000000000000000000000000000000000000000000000000000000000000000"]}"#);
+	}
+}
@@ -19,6 +19,7 @@
 
 mod account_info;
 mod block;
+mod block_exclusion;
 mod block_number;
 mod bytes;
 mod call_request;
@@ -27,21 +28,26 @@ mod consensus_status;
 mod dapps;
 mod derivation;
 mod filter;
+mod gas_price_tiers;
 mod hash;
 mod histogram;
 mod index;
+mod light_checkpoint;
 mod log;
 mod node_kind;
 mod provenance;
 mod receipt;
 mod rpc_settings;
 mod secretstore;
+mod stratum_worker;
 mod sync;
 mod trace;
 mod trace_filter;
 mod transaction;
 mod transaction_request;
 mod transaction_condition;
+mod tx_pool;
+mod tx_pool_eviction;
 mod uint;
 mod work;
 mod private_receipt;
@@ -51,6 +57,7 @@ pub mod pubsub;
 pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo};
 pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
+pub use self::block_exclusion::{BlockExclusion, BlockExclusionReason};
 pub use self::block_number::{BlockNumber, block_number_to_id};
 pub use self::call_request::CallRequest;
 pub use self::confirmations::{
@@ -61,24 +68,29 @@ pub use self::consensus_status::*;
 pub use self::dapps::LocalDapp;
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
 pub use self::filter::{Filter, FilterChanges};
+pub use self::gas_price_tiers::GasPriceTiers;
 pub use self::hash::{H64, H160, H256, H512, H520, H2048};
 pub use self::histogram::Histogram;
 pub use self::index::Index;
+pub use self::light_checkpoint::LightCheckpoint;
 pub use self::log::Log;
 pub use self::node_kind::{NodeKind, Availability, Capability};
 pub use self::provenance::{Origin, DappId};
 pub use self::receipt::Receipt;
 pub use self::rpc_settings::RpcSettings;
 pub use self::secretstore::EncryptedDocumentKey;
+pub use self::stratum_worker::StratumWorker;
 pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
-	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
+	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo, NodeReputation,
 };
 pub use self::trace::{LocalizedTrace, TraceResults};
 pub use self::trace_filter::TraceFilter;
 pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
 pub use self::transaction_request::TransactionRequest;
 pub use self::transaction_condition::TransactionCondition;
+pub use self::tx_pool::{TxPoolStatus, TxPoolContent, TxPoolInspect, TxPoolSenders};
+pub use self::tx_pool_eviction::{TxPoolEviction, EvictionReason};
 pub use self::uint::{U128, U256, U64};
 pub use self::work::Work;
 pub use self::private_receipt::{PrivateTransactionReceipt, PrivateTransactionReceiptAndTransaction};
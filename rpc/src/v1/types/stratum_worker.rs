@@ -0,0 +1,65 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity. If not, see <http://www.gnu.org/licenses/>.
+
+//! Stratum worker statistics.
+
+use std::time::UNIX_EPOCH;
+
+use ethcore::miner::stratum::WorkerStats;
+use v1::types::U256;
+
+/// Statistics of a worker connected to the stratum server.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct StratumWorker {
+	/// Worker id.
+	pub id: String,
+	/// Address of the worker connection.
+	pub address: String,
+	/// Share difficulty of the worker (network difficulty if null).
+	pub difficulty: Option<U256>,
+	/// Number of accepted shares.
+	#[serde(rename="acceptedShares")]
+	pub accepted_shares: u64,
+	/// Number of shares that sealed a block.
+	pub blocks: u64,
+	/// Number of shares submitted for outdated work.
+	#[serde(rename="staleShares")]
+	pub stale_shares: u64,
+	/// Number of invalid shares.
+	#[serde(rename="invalidShares")]
+	pub invalid_shares: u64,
+	/// Hashrate estimated from recently accepted shares.
+	pub hashrate: U256,
+	/// Unix timestamp of the last submitted share.
+	#[serde(rename="lastShare")]
+	pub last_share: Option<u64>,
+}
+
+impl From<WorkerStats> for StratumWorker {
+	fn from(stats: WorkerStats) -> Self {
+		StratumWorker {
+			id: stats.id,
+			address: stats.addr.to_string(),
+			difficulty: stats.difficulty.map(Into::into),
+			accepted_shares: stats.accepted_shares,
+			blocks: stats.blocks,
+			stale_shares: stats.stale_shares,
+			invalid_shares: stats.invalid_shares,
+			hashrate: stats.hashrate.into(),
+			last_share: stats.last_share.and_then(|time| time.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_secs()),
+		}
+	}
+}
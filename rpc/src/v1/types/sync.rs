@@ -37,6 +37,12 @@ pub struct SyncInfo {
 	/// Warp sync snpashot chunks processed.
 	#[serde(rename="warpChunksProcessed")]
 	pub warp_chunks_processed: Option<U256>,
+	/// State sync trie nodes downloaded.
+	#[serde(rename="pulledStates", skip_serializing_if = "Option::is_none")]
+	pub pulled_states: Option<U256>,
+	/// State sync trie nodes known so far, downloaded or not.
+	#[serde(rename="knownStates", skip_serializing_if = "Option::is_none")]
+	pub known_states: Option<U256>,
 }
 
 /// Peers info
@@ -129,6 +135,24 @@ impl From<sync::PipProtocolInfo> for PipProtocolInfo {
 	}
 }
 
+/// Persistent reputation of a known node
+#[derive(Default, Debug, Serialize)]
+pub struct NodeReputation {
+	/// Node enode URL
+	pub enode: String,
+	/// Accumulated reputation score
+	pub reputation: i32,
+}
+
+impl From<sync::NodeReputation> for NodeReputation {
+	fn from(n: sync::NodeReputation) -> Self {
+		NodeReputation {
+			enode: n.enode,
+			reputation: n.reputation,
+		}
+	}
+}
+
 /// Sync status
 #[derive(Debug, PartialEq)]
 pub enum SyncStatus {
@@ -15,8 +15,11 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use transaction;
+use v1::types::{Bytes, H160, H256, U256};
 
-/// Represents condition on minimum block number or block timestamp.
+/// Represents condition on minimum block number, block timestamp or chain state.
+///
+/// Conditions on chain state are not supported by light clients.
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub enum TransactionCondition {
@@ -26,6 +29,30 @@ pub enum TransactionCondition {
 	/// Valid at given unix time.
 	#[serde(rename="time")]
 	Timestamp(u64),
+	/// Valid once given transaction has at least this number of confirmations.
+	#[serde(rename="confirmations")]
+	Confirmations {
+		/// Hash of the transaction to wait for.
+		transaction: H256,
+		/// Required number of confirmations (the including block counts as the first one).
+		count: u64,
+	},
+	/// Valid once balance of given account is at least the value.
+	#[serde(rename="balance")]
+	MinBalance {
+		/// Account to check.
+		address: H160,
+		/// Minimal balance.
+		value: U256,
+	},
+	/// Valid once call to given contract succeeds and returns `true`.
+	#[serde(rename="call")]
+	Call {
+		/// Contract to call.
+		to: H160,
+		/// Call data.
+		data: Bytes,
+	},
 }
 
 impl Into<transaction::Condition> for TransactionCondition {
@@ -33,6 +60,18 @@ impl Into<transaction::Condition> for TransactionCondition {
 		match self {
 			TransactionCondition::Number(n) => transaction::Condition::Number(n),
 			TransactionCondition::Timestamp(n) => transaction::Condition::Timestamp(n),
+			TransactionCondition::Confirmations { transaction, count } => transaction::Condition::Confirmations {
+				transaction: transaction.into(),
+				count,
+			},
+			TransactionCondition::MinBalance { address, value } => transaction::Condition::MinBalance {
+				address: address.into(),
+				value: value.into(),
+			},
+			TransactionCondition::Call { to, data } => transaction::Condition::Call {
+				to: to.into(),
+				data: data.into_vec(),
+			},
 		}
 	}
 }
@@ -42,6 +81,18 @@ impl From<transaction::Condition> for TransactionCondition {
 		match condition {
 			transaction::Condition::Number(n) => TransactionCondition::Number(n),
 			transaction::Condition::Timestamp(n) => TransactionCondition::Timestamp(n),
+			transaction::Condition::Confirmations { transaction, count } => TransactionCondition::Confirmations {
+				transaction: transaction.into(),
+				count,
+			},
+			transaction::Condition::MinBalance { address, value } => TransactionCondition::MinBalance {
+				address: address.into(),
+				value: value.into(),
+			},
+			transaction::Condition::Call { to, data } => TransactionCondition::Call {
+				to: to.into(),
+				data: data.into(),
+			},
 		}
 	}
 }
@@ -58,10 +109,35 @@ mod tests {
 		assert_eq!(deserialized, vec![TransactionCondition::Number(51), TransactionCondition::Timestamp(10)])
 	}
 
+	#[test]
+	fn state_condition_deserialization() {
+		let s = r#"[{ "confirmations": { "transaction": "0x0000000000000000000000000000000000000000000000000000000000000005", "count": 12 } },
+			{ "balance": { "address": "0x000000000000000000000000000000000000000a", "value": "0x64" } },
+			{ "call": { "to": "0x000000000000000000000000000000000000000a", "data": "0x010203" } }]"#;
+		let deserialized: Vec<TransactionCondition> = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, vec![
+			TransactionCondition::Confirmations { transaction: H256::from(5), count: 12 },
+			TransactionCondition::MinBalance { address: H160::from(10), value: U256::from(100) },
+			TransactionCondition::Call { to: H160::from(10), data: vec![1, 2, 3].into() },
+		])
+	}
+
 	#[test]
 	fn condition_into() {
 		assert_eq!(transaction::Condition::Number(100), TransactionCondition::Number(100).into());
 		assert_eq!(transaction::Condition::Timestamp(100), TransactionCondition::Timestamp(100).into());
 	}
+
+	#[test]
+	fn state_condition_into() {
+		assert_eq!(
+			transaction::Condition::MinBalance { address: 10.into(), value: 100.into() },
+			TransactionCondition::MinBalance { address: 10.into(), value: 100.into() }.into()
+		);
+		assert_eq!(
+			transaction::Condition::Call { to: 10.into(), data: vec![1, 2, 3] },
+			TransactionCondition::Call { to: 10.into(), data: vec![1, 2, 3].into() }.into()
+		);
+	}
 }
 
@@ -0,0 +1,153 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity. If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool contents grouped by sender and nonce.
+
+use std::collections::BTreeMap;
+
+use v1::types::{H160, U64, Transaction};
+
+/// Transactions of each sender keyed by (decimal) nonce.
+pub type TxPoolSenders<T> = BTreeMap<H160, BTreeMap<String, T>>;
+
+/// Number of transactions in the pool.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct TxPoolStatus {
+	/// Number of transactions ready to be included in the next block.
+	pub pending: U64,
+	/// Number of transactions waiting for a nonce gap to be filled or a condition to be met.
+	pub queued: U64,
+}
+
+/// Transactions in the pool grouped by sender and nonce.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct TxPoolContent {
+	/// Transactions ready to be included in the next block.
+	pub pending: TxPoolSenders<Transaction>,
+	/// Transactions waiting for a nonce gap to be filled or a condition to be met.
+	pub queued: TxPoolSenders<Transaction>,
+}
+
+/// Short textual summaries of transactions in the pool grouped by sender and nonce.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct TxPoolInspect {
+	/// Summaries of transactions ready to be included in the next block.
+	pub pending: TxPoolSenders<String>,
+	/// Summaries of transactions waiting for a nonce gap to be filled or a condition to be met.
+	pub queued: TxPoolSenders<String>,
+}
+
+impl TxPoolContent {
+	/// Group given pending and queued transactions by sender and nonce.
+	pub fn new<P, Q>(pending: P, queued: Q) -> Self where
+		P: IntoIterator<Item = Transaction>,
+		Q: IntoIterator<Item = Transaction>,
+	{
+		TxPoolContent {
+			pending: group(pending),
+			queued: group(queued),
+		}
+	}
+
+	/// Returns number of pending and queued transactions.
+	pub fn status(&self) -> TxPoolStatus {
+		let count = |senders: &TxPoolSenders<Transaction>| senders.values().map(|txs| txs.len() as u64).sum::<u64>();
+
+		TxPoolStatus {
+			pending: count(&self.pending).into(),
+			queued: count(&self.queued).into(),
+		}
+	}
+}
+
+impl From<TxPoolContent> for TxPoolInspect {
+	fn from(content: TxPoolContent) -> Self {
+		TxPoolInspect {
+			pending: summarize(content.pending),
+			queued: summarize(content.queued),
+		}
+	}
+}
+
+fn group<I: IntoIterator<Item = Transaction>>(transactions: I) -> TxPoolSenders<Transaction> {
+	let mut senders = TxPoolSenders::new();
+	for tx in transactions {
+		senders.entry(tx.from.clone())
+			.or_insert_with(BTreeMap::new)
+			.insert(tx.nonce.to_string(), tx);
+	}
+	senders
+}
+
+fn summarize(senders: TxPoolSenders<Transaction>) -> TxPoolSenders<String> {
+	senders.into_iter()
+		.map(|(sender, txs)| (sender, txs.into_iter().map(|(nonce, tx)| (nonce, summary(&tx))).collect()))
+		.collect()
+}
+
+fn summary(tx: &Transaction) -> String {
+	let to = match tx.to {
+		Some(ref to) => format!("0x{:?}", to),
+		None => "contract creation".into(),
+	};
+
+	format!("{}: {} wei + {} gas × {} wei", to, tx.value, tx.gas, tx.gas_price)
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use v1::types::{H160, Transaction};
+	use super::{TxPoolContent, TxPoolInspect};
+
+	fn transaction(from: u64, nonce: u64, to: Option<u64>) -> Transaction {
+		Transaction {
+			from: from.into(),
+			nonce: nonce.into(),
+			to: to.map(Into::into),
+			value: 1.into(),
+			gas: 21_000.into(),
+			gas_price: 2.into(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn should_group_transactions_by_sender_and_nonce() {
+		let content = TxPoolContent::new(
+			vec![transaction(1, 0, Some(5)), transaction(1, 1, None), transaction(2, 10, Some(5))],
+			vec![transaction(1, 3, Some(5))],
+		);
+
+		let status = content.status();
+		assert_eq!(status.pending, 3u64.into());
+		assert_eq!(status.queued, 1u64.into());
+		assert_eq!(content.pending.len(), 2);
+		assert_eq!(content.pending[&H160::from(1)].keys().collect::<Vec<_>>(), vec!["0", "1"]);
+		assert_eq!(content.queued[&H160::from(1)].keys().collect::<Vec<_>>(), vec!["3"]);
+	}
+
+	#[test]
+	fn should_serialize_inspect() {
+		let inspect: TxPoolInspect = TxPoolContent::new(
+			vec![transaction(1, 0, Some(5)), transaction(1, 1, None)],
+			vec![],
+		).into();
+
+		let serialized = serde_json::to_string(&inspect).unwrap();
+		assert_eq!(serialized, r#"{"pending":{"0x0000000000000000000000000000000000000001":{"0":"0x0000000000000000000000000000000000000005: 1 wei + 21000 gas × 2 wei","1":"contract creation: 1 wei + 21000 gas × 2 wei"}},"queued":{}}"#);
+	}
+}
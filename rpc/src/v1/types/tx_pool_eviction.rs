@@ -0,0 +1,106 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity. If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool evictions.
+
+use std::time::UNIX_EPOCH;
+
+use miner::pool::evictions::Eviction;
+use txpool::{Limit, Origin};
+use v1::types::{H160, H256};
+
+/// Limit of the transaction pool that caused the eviction.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum EvictionReason {
+	/// Pool was full.
+	#[serde(rename="count")]
+	Count,
+	/// Pool exceeded memory limit.
+	#[serde(rename="memoryUsage")]
+	MemoryUsage,
+	/// Sender had too many transactions.
+	#[serde(rename="perSender")]
+	PerSender,
+	/// Pool had too many local transactions.
+	#[serde(rename="local")]
+	Local,
+	/// Pool had too many retracted transactions.
+	#[serde(rename="retracted")]
+	Retracted,
+	/// Pool had too many external transactions.
+	#[serde(rename="external")]
+	External,
+	/// Sender's transactions exceeded total gas limit.
+	#[serde(rename="gasPerSender")]
+	GasPerSender,
+}
+
+impl From<Limit> for EvictionReason {
+	fn from(limit: Limit) -> Self {
+		match limit {
+			Limit::Count => EvictionReason::Count,
+			Limit::MemUsage => EvictionReason::MemoryUsage,
+			Limit::PerSender => EvictionReason::PerSender,
+			Limit::Origin(Origin::Local) => EvictionReason::Local,
+			Limit::Origin(Origin::Retracted) => EvictionReason::Retracted,
+			Limit::Origin(Origin::External) => EvictionReason::External,
+			Limit::GasPerSender => EvictionReason::GasPerSender,
+		}
+	}
+}
+
+/// Transaction evicted from the pool.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TxPoolEviction {
+	/// Hash of the evicted transaction.
+	pub hash: H256,
+	/// Sender of the evicted transaction.
+	pub from: H160,
+	/// Limit that caused the eviction.
+	pub reason: EvictionReason,
+	/// Unix timestamp of the eviction.
+	pub timestamp: u64,
+}
+
+impl From<Eviction> for TxPoolEviction {
+	fn from(eviction: Eviction) -> Self {
+		TxPoolEviction {
+			hash: eviction.hash.into(),
+			from: eviction.sender.into(),
+			reason: eviction.limit.into(),
+			timestamp: eviction.time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::*;
+
+	#[test]
+	fn should_serialize_eviction() {
+		let eviction = TxPoolEviction {
+			hash: 5.into(),
+			from: 10.into(),
+			reason: Limit::Origin(Origin::External).into(),
+			timestamp: 1_500_000_000,
+		};
+
+		let serialized = serde_json::to_string(&eviction).unwrap();
+		assert_eq!(serialized, r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000005","from":"0x000000000000000000000000000000000000000a","reason":"external","timestamp":1500000000}"#);
+	}
+}
@@ -35,6 +35,11 @@ error_chain! {
 			description("transaction is too cheap to replace existing transaction in the pool"),
 			display("[{}] too cheap to replace: {}", hash, old_hash)
 		}
+		/// Transaction would exceed the total gas limit of its sender.
+		SenderGasLimitReached(hash: Hash, max_gas: u64) {
+			description("transaction would exceed the total gas limit of its sender"),
+			display("[{}] exceeds the total gas limit of the sender: {}", hash, max_gas)
+		}
 	}
 }
 
@@ -47,6 +52,7 @@ impl PartialEq for ErrorKind {
 			(&AlreadyImported(ref h1), &AlreadyImported(ref h2)) => h1 == h2,
 			(&TooCheapToEnter(ref h1, ref s1), &TooCheapToEnter(ref h2, ref s2)) => h1 == h2 && s1 == s2,
 			(&TooCheapToReplace(ref old1, ref new1), &TooCheapToReplace(ref old2, ref new2)) => old1 == old2 && new1 == new2,
+			(&SenderGasLimitReached(ref h1, g1), &SenderGasLimitReached(ref h2, g2)) => h1 == h2 && g1 == g2,
 			_ => false,
 		}
 	}
@@ -95,7 +95,7 @@ pub mod scoring;
 
 pub use self::error::{Error, ErrorKind};
 pub use self::listener::{Listener, NoopListener};
-pub use self::options::Options;
+pub use self::options::{Options, Origin, Limit};
 pub use self::pool::{Pool, PendingIterator, Transaction};
 pub use self::ready::{Ready, Readiness};
 pub use self::scoring::Scoring;
@@ -121,4 +121,14 @@ pub trait VerifiedTransaction: fmt::Debug {
 
 	/// Transaction sender
 	fn sender(&self) -> &Self::Sender;
+
+	/// Transaction origin, limited separately by the pool.
+	fn origin(&self) -> Origin {
+		Origin::External
+	}
+
+	/// Gas limit of the transaction, counted towards the total gas of the sender.
+	fn gas(&self) -> u64 {
+		0
+	}
 }
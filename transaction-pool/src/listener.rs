@@ -16,6 +16,7 @@
 
 use std::sync::Arc;
 use error::ErrorKind;
+use options::Limit;
 
 /// Transaction pool listener.
 ///
@@ -34,6 +35,11 @@ pub trait Listener<T> {
 	/// The transaction was pushed out from the pool because of the limit.
 	fn dropped(&mut self, _tx: &Arc<T>, _by: Option<&T>) {}
 
+	/// The transaction was evicted from the pool to make room for another one because of given limit.
+	/// NOTE: This notification comes in addition to `dropped` or `added` (in case the transaction
+	/// was pushed out by a transaction from the same sender).
+	fn evicted(&mut self, _tx: &Arc<T>, _limit: &Limit) {}
+
 	/// The transaction was marked as invalid by executor.
 	fn invalid(&mut self, _tx: &Arc<T>) {}
 
@@ -68,6 +74,11 @@ impl<T, A, B> Listener<T> for (A, B) where
 		self.1.dropped(tx, by);
 	}
 
+	fn evicted(&mut self, tx: &Arc<T>, limit: &Limit) {
+		self.0.evicted(tx, limit);
+		self.1.evicted(tx, limit);
+	}
+
 	fn invalid(&mut self, tx: &Arc<T>) {
 		self.0.invalid(tx);
 		self.1.invalid(tx);
@@ -14,6 +14,34 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+/// Origin of a transaction.
+///
+/// Transactions of each origin are limited separately (see `Options`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Origin {
+	/// Transaction submitted locally.
+	Local,
+	/// Transaction re-imported from a retracted block.
+	Retracted,
+	/// Transaction received from external sources.
+	External,
+}
+
+/// Pool limit that caused a transaction to be evicted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+	/// Maximal number of transactions in the pool.
+	Count,
+	/// Maximal memory usage.
+	MemUsage,
+	/// Maximal number of transactions from single sender.
+	PerSender,
+	/// Maximal number of transactions of given origin.
+	Origin(Origin),
+	/// Maximal total gas of transactions from single sender.
+	GasPerSender,
+}
+
 /// Transaction Pool options.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Options {
@@ -23,6 +51,14 @@ pub struct Options {
 	pub max_per_sender: usize,
 	/// Maximal memory usage.
 	pub max_mem_usage: usize,
+	/// Maximal number of local transactions in the pool.
+	pub max_local: usize,
+	/// Maximal number of retracted transactions in the pool.
+	pub max_retracted: usize,
+	/// Maximal number of external transactions in the pool.
+	pub max_external: usize,
+	/// Maximal total gas of transactions from single sender.
+	pub max_gas_per_sender: u64,
 }
 
 impl Default for Options {
@@ -31,6 +67,21 @@ impl Default for Options {
 			max_count: 1024,
 			max_per_sender: 16,
 			max_mem_usage: 8 * 1024 * 1024,
+			max_local: usize::max_value(),
+			max_retracted: usize::max_value(),
+			max_external: usize::max_value(),
+			max_gas_per_sender: u64::max_value(),
+		}
+	}
+}
+
+impl Options {
+	/// Maximal number of transactions of given origin in the pool.
+	pub fn max_of_origin(&self, origin: Origin) -> usize {
+		match origin {
+			Origin::Local => self.max_local,
+			Origin::Retracted => self.max_retracted,
+			Origin::External => self.max_external,
 		}
 	}
 }
@@ -14,13 +14,14 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
 use std::sync::Arc;
 use std::slice;
 use std::collections::{hash_map, HashMap, BTreeSet};
 
 use error;
 use listener::{Listener, NoopListener};
-use options::Options;
+use options::{Options, Origin, Limit};
 use ready::{Ready, Readiness};
 use scoring::{self, Scoring, ScoreWithRef};
 use status::{LightStatus, Status};
@@ -65,6 +66,7 @@ pub struct Pool<T: VerifiedTransaction, S: Scoring<T>, L = NoopListener> {
 	scoring: S,
 	options: Options,
 	mem_usage: usize,
+	origin_count: HashMap<Origin, usize>,
 
 	transactions: HashMap<T::Sender, Transactions<T, S>>,
 	by_hash: HashMap<T::Hash, Transaction<T>>,
@@ -114,6 +116,7 @@ impl<T, S, L> Pool<T, S, L> where
 			scoring,
 			options,
 			mem_usage: 0,
+			origin_count: Default::default(),
 			transactions,
 			by_hash,
 			best_transactions: Default::default(),
@@ -132,10 +135,12 @@ impl<T, S, L> Pool<T, S, L> where
 	/// either because of limits (see `Options`) or because `Scoring` decides that the transaction
 	/// replaces an existing transaction from that sender.
 	/// If any limit is reached the transaction with the lowest `Score` is evicted to make room.
+	/// If the total gas limit of the sender is reached its transactions ordered after the new one are evicted.
 	///
-	/// The `Listener` will be informed on any drops or rejections.
+	/// The `Listener` will be informed on any drops, evictions or rejections.
 	pub fn import(&mut self, transaction: T) -> error::Result<Arc<T>> {
 		let mem_usage = transaction.mem_usage();
+		let origin = transaction.origin();
 
 		ensure!(!self.by_hash.contains_key(transaction.hash()), error::ErrorKind::AlreadyImported(format!("{:?}", transaction.hash())));
 
@@ -145,11 +150,22 @@ impl<T, S, L> Pool<T, S, L> where
 			transaction: Arc::new(transaction),
 		};
 
+		// Transactions of the sender that would be ordered after the new one and have to be evicted
+		// to fit into the total gas limit of the sender. They are removed only once the new transaction is accepted.
+		let gas_evictions = match self.gas_evictions(&transaction) {
+			Some(evictions) => evictions,
+			None => {
+				let error = error::ErrorKind::SenderGasLimitReached(format!("{:?}", transaction.hash()), self.options.max_gas_per_sender);
+				self.listener.rejected(&transaction, &error);
+				bail!(error)
+			},
+		};
+
 		// TODO [ToDr] Most likely move this after the transaction is inserted.
 		// Avoid using should_replace, but rather use scoring for that.
 		{
-			let remove_worst = |s: &mut Self, transaction| {
-				match s.remove_worst(transaction) {
+			let remove_worst = |s: &mut Self, transaction, limit| {
+				match s.remove_worst(transaction, limit) {
 					Err(err) => {
 						s.listener.rejected(transaction, err.kind());
 						Err(err)
@@ -157,22 +173,31 @@ impl<T, S, L> Pool<T, S, L> where
 					Ok(None) => Ok(false),
 					Ok(Some(removed)) => {
 						s.listener.dropped(&removed, Some(transaction));
+						s.listener.evicted(&removed, &limit);
 						s.finalize_remove(removed.hash());
 						Ok(true)
 					},
 				}
 			};
 
-			while self.by_hash.len() + 1 > self.options.max_count {
+			// Room freed by the pending gas evictions is taken into account.
+			while self.by_hash.len() + 1 > self.options.max_count + self.pending_removals(&gas_evictions, origin).0 {
 				trace!("Count limit reached: {} > {}", self.by_hash.len() + 1, self.options.max_count);
-				if !remove_worst(self, &transaction)? {
+				if !remove_worst(self, &transaction, Limit::Count)? {
 					break;
 				}
 			}
 
-			while self.mem_usage + mem_usage > self.options.max_mem_usage {
+			while self.mem_usage + mem_usage > self.options.max_mem_usage + self.pending_removals(&gas_evictions, origin).1 {
 				trace!("Mem limit reached: {} > {}", self.mem_usage + mem_usage, self.options.max_mem_usage);
-				if !remove_worst(self, &transaction)? {
+				if !remove_worst(self, &transaction, Limit::MemUsage)? {
+					break;
+				}
+			}
+
+			while self.count_of_origin(origin) + 1 > self.options.max_of_origin(origin) + self.pending_removals(&gas_evictions, origin).2 {
+				trace!("{:?} limit reached: {} > {}", origin, self.count_of_origin(origin) + 1, self.options.max_of_origin(origin));
+				if !remove_worst(self, &transaction, Limit::Origin(origin))? {
 					break;
 				}
 			}
@@ -190,17 +215,22 @@ impl<T, S, L> Pool<T, S, L> where
 		// update best and worst transactions from this sender (if required)
 		self.update_senders_worst_and_best(prev_state, current_state);
 
-		match result {
+		let new = match result {
 			AddResult::Ok(tx) => {
 				self.listener.added(&tx, None);
 				self.finalize_insert(&tx, None);
-				Ok(tx.transaction)
+				tx
+			},
+			AddResult::PushedOut { new, old } => {
+				self.listener.added(&new, Some(&old));
+				self.listener.evicted(&old, &Limit::PerSender);
+				self.finalize_insert(&new, Some(&old));
+				new
 			},
-			AddResult::PushedOut { new, old } |
 			AddResult::Replaced { new, old } => {
 				self.listener.added(&new, Some(&old));
 				self.finalize_insert(&new, Some(&old));
-				Ok(new.transaction)
+				new
 			},
 			AddResult::TooCheap { new, old } => {
 				let error = error::ErrorKind::TooCheapToReplace(format!("{:x}", old.hash()), format!("{:x}", new.hash()));
@@ -212,12 +242,27 @@ impl<T, S, L> Pool<T, S, L> where
 				self.listener.rejected(&new, &error);
 				bail!(error)
 			}
+		};
+
+		for evicted in gas_evictions {
+			// might have been already removed by other limits.
+			if !self.by_hash.contains_key(evicted.hash()) {
+				continue;
+			}
+
+			self.remove_from_set(evicted.sender(), |set, scoring| set.remove(&evicted, scoring));
+			self.listener.dropped(&evicted, Some(&new));
+			self.listener.evicted(&evicted, &Limit::GasPerSender);
+			self.finalize_remove(evicted.hash());
 		}
+
+		Ok(new.transaction)
 	}
 
 	/// Updates state of the pool statistics if the transaction was added to a set.
 	fn finalize_insert(&mut self, new: &Transaction<T>, old: Option<&Transaction<T>>) {
 		self.mem_usage += new.mem_usage();
+		*self.origin_count.entry(new.origin()).or_insert(0) += 1;
 		self.by_hash.insert(new.hash().clone(), new.clone());
 
 		if let Some(old) = old {
@@ -229,10 +274,74 @@ impl<T, S, L> Pool<T, S, L> where
 	fn finalize_remove(&mut self, hash: &T::Hash) -> Option<Arc<T>> {
 		self.by_hash.remove(hash).map(|old| {
 			self.mem_usage -= old.transaction.mem_usage();
+			if let Some(count) = self.origin_count.get_mut(&old.origin()) {
+				*count -= 1;
+			}
 			old.transaction
 		})
 	}
 
+	/// Returns number of transactions of given origin in the pool.
+	fn count_of_origin(&self, origin: Origin) -> usize {
+		self.origin_count.get(&origin).cloned().unwrap_or(0)
+	}
+
+	/// Returns total gas of transactions from the sender of given transaction,
+	/// not counting the transaction it might replace.
+	fn sender_gas(&self, transaction: &T) -> u64 {
+		let scoring = &self.scoring;
+		self.transactions.get(transaction.sender())
+			.map(|txs| txs.iter()
+				.filter(|tx| scoring.compare(tx, transaction) != cmp::Ordering::Equal)
+				.fold(0u64, |gas, tx| gas.saturating_add(tx.gas()))
+			)
+			.unwrap_or(0)
+	}
+
+	/// Returns transactions of the sender ordered after the given one that have to be evicted
+	/// for it to fit into the total gas limit of the sender.
+	///
+	/// Returns `None` if the transaction doesn't fit even after evicting them.
+	fn gas_evictions(&self, transaction: &Transaction<T>) -> Option<Vec<Transaction<T>>> {
+		let max_gas = self.options.max_gas_per_sender;
+		let mut sender_gas = self.sender_gas(transaction).saturating_add(transaction.gas());
+		let mut evictions = Vec::new();
+
+		if sender_gas <= max_gas {
+			return Some(evictions);
+		}
+
+		trace!("Sender gas limit reached: {} > {}", sender_gas, max_gas);
+		if let Some(txs) = self.transactions.get(transaction.sender()) {
+			for tx in txs.iter().rev() {
+				if sender_gas <= max_gas || self.scoring.compare(tx, transaction) != cmp::Ordering::Greater {
+					break;
+				}
+
+				sender_gas = sender_gas.saturating_sub(tx.gas());
+				evictions.push(tx.clone());
+			}
+		}
+
+		if sender_gas <= max_gas {
+			Some(evictions)
+		} else {
+			None
+		}
+	}
+
+	/// Returns number, memory usage and number of given origin of the transactions
+	/// that are going to be removed and are still in the pool.
+	fn pending_removals(&self, transactions: &[Transaction<T>], origin: Origin) -> (usize, usize, usize) {
+		transactions.iter()
+			.filter(|tx| self.by_hash.contains_key(tx.hash()))
+			.fold((0, 0, 0), |(count, mem, of_origin), tx| (
+				count + 1,
+				mem + tx.mem_usage(),
+				of_origin + if tx.origin() == origin { 1 } else { 0 },
+			))
+	}
+
 	/// Updates best and worst transactions from a sender.
 	fn update_senders_worst_and_best(
 		&mut self,
@@ -279,11 +388,21 @@ impl<T, S, L> Pool<T, S, L> where
 	}
 
 	/// Attempts to remove the worst transaction from the pool if it's worse than the given one.
+	/// In case of `Limit::Origin` only transactions of that origin are considered.
 	///
 	/// Returns `None` in case we couldn't decide if the transaction should replace the worst transaction or not.
 	/// In such case we will accept the transaction even though it is going to exceed the limit.
-	fn remove_worst(&mut self, transaction: &Transaction<T>) -> error::Result<Option<Transaction<T>>> {
-		let to_remove = match self.worst_transactions.iter().next_back() {
+	fn remove_worst(&mut self, transaction: &Transaction<T>, limit: Limit) -> error::Result<Option<Transaction<T>>> {
+		let worst = match limit {
+			// `worst_transactions` holds only the worst transaction of every sender,
+			// so look through all transactions of every sender.
+			Limit::Origin(origin) => self.transactions.values()
+				.filter_map(|txs| txs.worst_matching(|tx| tx.origin() == origin))
+				.map(|(score, tx)| ScoreWithRef::new(score, tx))
+				.max(),
+			_ => self.worst_transactions.iter().rev().next().cloned(),
+		};
+		let to_remove = match worst {
 			// No elements to remove? and the pool is still full?
 			None => {
 				warn!("The pool is full but there are no transactions to remove.");
@@ -293,7 +412,7 @@ impl<T, S, L> Pool<T, S, L> where
 				// We can't decide which of them should be removed, so accept both.
 				scoring::Choice::InsertNew => None,
 				// New transaction is better than the worst one so we can replace it.
-				scoring::Choice::ReplaceOld => Some(old.clone()),
+				scoring::Choice::ReplaceOld => Some(old),
 				// otherwise fail
 				scoring::Choice::RejectNew => {
 					return Err(error::ErrorKind::TooCheapToEnter(format!("{:?}", transaction.hash()), format!("{:?}", old.score)).into())
@@ -333,6 +452,7 @@ impl<T, S, L> Pool<T, S, L> where
 	/// NOTE: the drop-notification order will be arbitrary.
 	pub fn clear(&mut self) {
 		self.mem_usage = 0;
+		self.origin_count.clear();
 		self.transactions.clear();
 		self.best_transactions.clear();
 		self.worst_transactions.clear();
@@ -33,6 +33,7 @@ pub struct Transaction {
 	pub gas: U256,
 	pub sender: Address,
 	pub mem_usage: usize,
+	pub origin: Origin,
 }
 
 impl VerifiedTransaction for Transaction {
@@ -42,6 +43,8 @@ impl VerifiedTransaction for Transaction {
 	fn hash(&self) -> &H256 { &self.hash }
 	fn mem_usage(&self) -> usize { self.mem_usage }
 	fn sender(&self) -> &Address { &self.sender }
+	fn origin(&self) -> Origin { self.origin }
+	fn gas(&self) -> u64 { self.gas.low_u64() }
 }
 
 pub type SharedTransaction = Arc<Transaction>;
@@ -202,6 +205,105 @@ fn should_reject_if_above_sender_count() {
 	assert_eq!(txq.light_status().transaction_count, 1);
 }
 
+#[test]
+fn should_evict_worst_transaction_of_the_same_origin() {
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::with_options(Options {
+		max_external: 1,
+		..Default::default()
+	});
+
+	// when
+	let local = txq.import(b.tx().sender(0).gas_price(1).origin(Origin::Local).new()).unwrap();
+	let external1 = txq.import(b.tx().sender(1).gas_price(2).new()).unwrap();
+	let external2 = b.tx().sender(2).gas_price(1).new();
+	let hash = format!("{:?}", external2.hash());
+	assert_eq!(txq.import(external2).unwrap_err().kind(), &error::ErrorKind::TooCheapToEnter(hash, "0x2".into()));
+	let external3 = txq.import(b.tx().sender(3).gas_price(3).new()).unwrap();
+
+	// then
+	assert_eq!(txq.light_status().transaction_count, 2);
+	assert!(txq.find(local.hash()).is_some());
+	assert!(txq.find(external1.hash()).is_none());
+	assert!(txq.find(external3.hash()).is_some());
+}
+
+#[test]
+fn should_evict_sender_transactions_above_gas_limit() {
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::with_options(Options {
+		max_gas_per_sender: 50_000,
+		..Default::default()
+	});
+
+	// when
+	let tx0 = txq.import(b.tx().nonce(0).new()).unwrap();
+	let tx2 = txq.import(b.tx().nonce(2).new()).unwrap();
+	let tx1 = txq.import(b.tx().nonce(1).new()).unwrap();
+
+	// then
+	assert_eq!(txq.light_status().transaction_count, 2);
+	assert!(txq.find(tx0.hash()).is_some());
+	assert!(txq.find(tx1.hash()).is_some());
+	assert!(txq.find(tx2.hash()).is_none());
+
+	// replacement doesn't count twice
+	txq.import(b.tx().nonce(1).gas_price(1).new()).unwrap();
+	assert_eq!(txq.light_status().transaction_count, 2);
+
+	// when
+	let tx3 = b.tx().nonce(3).new();
+	let hash = format!("{:?}", tx3.hash());
+
+	// then
+	assert_eq!(txq.import(tx3).unwrap_err().kind(), &error::ErrorKind::SenderGasLimitReached(hash, 50_000));
+	assert_eq!(txq.import(b.tx().sender(1).nonce(0).gas(50_001).new()).is_err(), true);
+	assert_eq!(txq.light_status().transaction_count, 2);
+}
+
+#[test]
+fn should_not_evict_sender_transactions_if_import_is_rejected() {
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::with_options(Options {
+		max_gas_per_sender: 50_000,
+		..Default::default()
+	});
+	let tx0 = txq.import(b.tx().nonce(0).gas_price(5).new()).unwrap();
+	let tx1 = txq.import(b.tx().nonce(1).gas_price(5).new()).unwrap();
+
+	// when
+	let tx = b.tx().nonce(0).gas_price(1).gas(30_000).new();
+	let hash = format!("{:x}", tx.hash());
+
+	// then
+	assert_eq!(txq.import(tx).unwrap_err().kind(), &error::ErrorKind::TooCheapToReplace(format!("{:x}", tx0.hash()), hash));
+	assert_eq!(txq.light_status().transaction_count, 2);
+	assert!(txq.find(tx1.hash()).is_some());
+}
+
+#[test]
+fn should_evict_worst_transaction_of_the_same_origin_from_any_position() {
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::with_options(Options {
+		max_external: 2,
+		..Default::default()
+	});
+	// the worst transaction of the sender is local.
+	let external0 = txq.import(b.tx().sender(0).nonce(0).gas_price(2).new()).unwrap();
+	let local = txq.import(b.tx().sender(0).nonce(1).gas_price(5).origin(Origin::Local).new()).unwrap();
+	let external1 = txq.import(b.tx().sender(1).gas_price(3).new()).unwrap();
+
+	// when
+	let external2 = txq.import(b.tx().sender(2).gas_price(4).new()).unwrap();
+
+	// then
+	assert_eq!(txq.light_status().transaction_count, 3);
+	assert!(txq.find(external0.hash()).is_none());
+	assert!(txq.find(local.hash()).is_some());
+	assert!(txq.find(external1.hash()).is_some());
+	assert!(txq.find(external2.hash()).is_some());
+}
+
 #[test]
 fn should_construct_pending() {
 	// given
@@ -613,6 +715,10 @@ mod listener {
 			self.0.borrow_mut().push("dropped".into());
 		}
 
+		fn evicted(&mut self, _tx: &SharedTransaction, _limit: &Limit) {
+			self.0.borrow_mut().push("evicted".into());
+		}
+
 		fn invalid(&mut self, _tx: &SharedTransaction) {
 			self.0.borrow_mut().push("invalid".into());
 		}
@@ -656,10 +762,10 @@ mod listener {
 		assert_eq!(*results.borrow(), &["added"]);
 		// Third sender push out low gas price
 		txq.import(b.tx().sender(2).nonce(1).gas_price(4).new()).unwrap();
-		assert_eq!(*results.borrow(), &["added", "dropped", "added"]);
+		assert_eq!(*results.borrow(), &["added", "dropped", "evicted", "added"]);
 		// Reject (too cheap)
 		txq.import(b.tx().sender(2).nonce(1).gas_price(2).new()).unwrap_err();
-		assert_eq!(*results.borrow(), &["added", "dropped", "added", "rejected"]);
+		assert_eq!(*results.borrow(), &["added", "dropped", "evicted", "added", "rejected"]);
 
 		assert_eq!(txq.light_status().transaction_count, 2);
 	}
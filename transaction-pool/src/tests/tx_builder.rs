@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use super::{Transaction, U256, Address};
+use super::{Transaction, U256, Address, Origin};
 
 #[derive(Debug, Default, Clone)]
 pub struct TransactionBuilder {
@@ -23,6 +23,7 @@ pub struct TransactionBuilder {
 	gas: U256,
 	sender: Address,
 	mem_usage: usize,
+	origin: Option<Origin>,
 }
 
 impl TransactionBuilder {
@@ -50,15 +51,26 @@ impl TransactionBuilder {
 		self
 	}
 
+	pub fn gas<T: Into<U256>>(mut self, gas: T) -> Self {
+		self.gas = gas.into();
+		self
+	}
+
+	pub fn origin(mut self, origin: Origin) -> Self {
+		self.origin = Some(origin);
+		self
+	}
+
 	pub fn new(self) -> Transaction {
 		let hash = self.nonce ^ (U256::from(100) * self.gas_price) ^ (U256::from(100_000) * U256::from(self.sender.low_u64()));
 		Transaction {
 			hash: hash.into(),
 			nonce: self.nonce,
 			gas_price: self.gas_price,
-			gas: 21_000.into(),
+			gas: if self.gas.is_zero() { 21_000.into() } else { self.gas },
 			sender: self.sender,
 			mem_usage: self.mem_usage,
+			origin: self.origin.unwrap_or(Origin::External),
 		}
 	}
 }
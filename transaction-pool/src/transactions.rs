@@ -71,6 +71,13 @@ impl<T: fmt::Debug, S: Scoring<T>> Transactions<T, S> {
 		self.transactions.iter()
 	}
 
+	/// Returns the worst transaction (and its score) matching given predicate.
+	pub fn worst_matching<F: Fn(&Transaction<T>) -> bool>(&self, f: F) -> Option<(S::Score, Transaction<T>)> {
+		self.transactions.iter().zip(self.scores.iter()).rev()
+			.find(|&(tx, _)| f(tx))
+			.map(|(tx, score)| (score.clone(), tx.clone()))
+	}
+
 	pub fn worst_and_best(&self) -> Option<((S::Score, Transaction<T>), (S::Score, Transaction<T>))> {
 		let len = self.scores.len();
 		self.scores.get(0).cloned().map(|best| {
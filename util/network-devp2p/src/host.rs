@@ -40,7 +40,7 @@ use node_table::*;
 use network::{NetworkConfiguration, NetworkIoMessage, ProtocolId, PeerId, PacketId};
 use network::{NonReservedPeerMode, NetworkContext as NetworkContextTrait};
 use network::HostInfo as HostInfoTrait;
-use network::{SessionInfo, Error, ErrorKind, DisconnectReason, NetworkProtocolHandler};
+use network::{SessionInfo, Error, ErrorKind, DisconnectReason, NetworkProtocolHandler, NodeReputation};
 use discovery::{Discovery, TableUpdates, NodeEntry};
 use ip_utils::{map_external_address, select_public_address};
 use path::restrict_permissions_owner;
@@ -103,6 +103,7 @@ pub struct NetworkContext<'s> {
 	session: Option<SharedSession>,
 	session_id: Option<StreamToken>,
 	_reserved_peers: &'s HashSet<NodeId>,
+	nodes: &'s RwLock<NodeTable>,
 }
 
 impl<'s> NetworkContext<'s> {
@@ -113,6 +114,7 @@ impl<'s> NetworkContext<'s> {
 		session: Option<SharedSession>,
 		sessions: Arc<RwLock<Slab<SharedSession>>>,
 		reserved_peers: &'s HashSet<NodeId>,
+		nodes: &'s RwLock<NodeTable>,
 	) -> NetworkContext<'s> {
 		let id = session.as_ref().map(|s| s.lock().token());
 		NetworkContext {
@@ -122,6 +124,7 @@ impl<'s> NetworkContext<'s> {
 			session: session,
 			sessions: sessions,
 			_reserved_peers: reserved_peers,
+			nodes: nodes,
 		}
 	}
 
@@ -194,6 +197,18 @@ impl<'s> NetworkContextTrait for NetworkContext<'s> {
 	}
 
 	fn subprotocol_name(&self) -> ProtocolId { self.protocol }
+
+	fn peer_reputation(&self, peer: PeerId) -> i32 {
+		self.resolve_session(peer)
+			.and_then(|s| s.lock().id().cloned())
+			.map_or(0, |id| self.nodes.read().reputation(&id))
+	}
+
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32) {
+		if let Some(id) = self.resolve_session(peer).and_then(|s| s.lock().id().cloned()) {
+			self.nodes.write().adjust_reputation(&id, delta);
+		}
+	}
 }
 
 /// Shared host information
@@ -414,6 +429,11 @@ impl Host {
 		Ok(())
 	}
 
+	/// Get reputations of all known nodes, best first.
+	pub fn node_reputations(&self) -> Vec<NodeReputation> {
+		self.nodes.read().reputations()
+	}
+
 	/// Get all connected peers.
 	pub fn connected_peers(&self) -> Vec<PeerId> {
 		let sessions = self.sessions.read();
@@ -808,7 +828,7 @@ impl Host {
 				for p in ready_data {
 					let reserved = self.reserved_nodes.read();
 					if let Some(h) = handlers.get(&p).clone() {
-						h.connected(&NetworkContext::new(io, p, Some(session.clone()), self.sessions.clone(), &reserved), &token);
+						h.connected(&NetworkContext::new(io, p, Some(session.clone()), self.sessions.clone(), &reserved, &self.nodes), &token);
 						// accumulate pending packets.
 						let mut session = session.lock();
 						packet_data.extend(session.mark_connected(p));
@@ -819,7 +839,7 @@ impl Host {
 			for (p, packet_id, data) in packet_data {
 				let reserved = self.reserved_nodes.read();
 				if let Some(h) = handlers.get(&p).clone() {
-					h.read(&NetworkContext::new(io, p, Some(session.clone()), self.sessions.clone(), &reserved), &token, packet_id, &data);
+					h.read(&NetworkContext::new(io, p, Some(session.clone()), self.sessions.clone(), &reserved, &self.nodes), &token, packet_id, &data);
 				}
 			}
 		}
@@ -862,7 +882,7 @@ impl Host {
 		for p in to_disconnect {
 			let reserved = self.reserved_nodes.read();
 			if let Some(h) = self.handlers.read().get(&p).clone() {
-				h.disconnected(&NetworkContext::new(io, p, expired_session.clone(), self.sessions.clone(), &reserved), &token);
+				h.disconnected(&NetworkContext::new(io, p, expired_session.clone(), self.sessions.clone(), &reserved, &self.nodes), &token);
 			}
 		}
 		if deregister {
@@ -892,14 +912,14 @@ impl Host {
 	pub fn with_context<F>(&self, protocol: ProtocolId, io: &IoContext<NetworkIoMessage>, action: F) where F: FnOnce(&NetworkContextTrait) {
 		let reserved = { self.reserved_nodes.read() };
 
-		let context = NetworkContext::new(io, protocol, None, self.sessions.clone(), &reserved);
+		let context = NetworkContext::new(io, protocol, None, self.sessions.clone(), &reserved, &self.nodes);
 		action(&context);
 	}
 
 	pub fn with_context_eval<F, T>(&self, protocol: ProtocolId, io: &IoContext<NetworkIoMessage>, action: F) -> T where F: FnOnce(&NetworkContextTrait) -> T {
 		let reserved = { self.reserved_nodes.read() };
 
-		let context = NetworkContext::new(io, protocol, None, self.sessions.clone(), &reserved);
+		let context = NetworkContext::new(io, protocol, None, self.sessions.clone(), &reserved, &self.nodes);
 		action(&context)
 	}
 }
@@ -978,7 +998,7 @@ impl IoHandler<NetworkIoMessage> for Host {
 					None => { warn!(target: "network", "No handler found for protocol: {:?}", timer.protocol) },
 					Some(h) => {
 						let reserved = self.reserved_nodes.read();
-						h.timeout(&NetworkContext::new(io, timer.protocol, None, self.sessions.clone(), &reserved), timer.token);
+						h.timeout(&NetworkContext::new(io, timer.protocol, None, self.sessions.clone(), &reserved, &self.nodes), timer.token);
 					}
 				},
 				None => { warn!("Unknown timer token: {}", token); } // timer is not registerd through us
@@ -999,7 +1019,7 @@ impl IoHandler<NetworkIoMessage> for Host {
 				let h = handler.clone();
 				let reserved = self.reserved_nodes.read();
 				h.initialize(
-					&NetworkContext::new(io, *protocol, None, self.sessions.clone(), &reserved),
+					&NetworkContext::new(io, *protocol, None, self.sessions.clone(), &reserved, &self.nodes),
 					&*self.info.read(),
 				);
 				self.handlers.write().insert(*protocol, h);
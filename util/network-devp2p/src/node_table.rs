@@ -17,7 +17,7 @@
 use discovery::{TableUpdates, NodeEntry};
 use ethereum_types::H512;
 use ip_utils::*;
-use network::{Error, ErrorKind, AllowIP, IpFilter};
+use network::{Error, ErrorKind, AllowIP, IpFilter, NodeReputation};
 use rlp::{Rlp, RlpStream, DecoderError};
 use serde_json;
 use std::collections::{HashMap, HashSet};
@@ -26,7 +26,7 @@ use std::hash::{Hash, Hasher};
 use std::net::{SocketAddr, ToSocketAddrs, SocketAddrV4, SocketAddrV6, Ipv4Addr, Ipv6Addr};
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::{fs, mem, slice};
+use std::{cmp, fs, mem, slice};
 use std::time::{self, Duration, SystemTime};
 use rand::{self, Rng};
 
@@ -179,6 +179,7 @@ pub struct Node {
 	pub endpoint: NodeEndpoint,
 	pub peer_type: PeerType,
 	pub last_contact: Option<NodeContact>,
+	pub reputation: i32,
 }
 
 impl Node {
@@ -188,6 +189,7 @@ impl Node {
 			endpoint: endpoint,
 			peer_type: PeerType::Optional,
 			last_contact: None,
+			reputation: 0,
 		}
 	}
 }
@@ -218,6 +220,7 @@ impl FromStr for Node {
 			endpoint: endpoint,
 			peer_type: PeerType::Optional,
 			last_contact: None,
+			reputation: 0,
 		})
 	}
 }
@@ -236,6 +239,8 @@ impl Hash for Node {
 }
 
 const MAX_NODES: usize = 1024;
+const MAX_REPUTATION: i32 = 1000;
+const MIN_REPUTATION: i32 = -1000;
 const NODES_FILE: &str = "nodes.json";
 
 /// Node table backed by disk file.
@@ -256,8 +261,11 @@ impl NodeTable {
 
 	/// Add a node to table
 	pub fn add_node(&mut self, mut node: Node) {
-		// preserve node last_contact
-		node.last_contact = self.nodes.get(&node.id).and_then(|n| n.last_contact);
+		// preserve node last_contact and reputation
+		if let Some(n) = self.nodes.get(&node.id) {
+			node.last_contact = n.last_contact;
+			node.reputation = n.reputation;
+		}
 		self.nodes.insert(node.id.clone(), node);
 	}
 
@@ -268,6 +276,7 @@ impl NodeTable {
 	/// - (1) Nodes with a successful contact are ordered (most recent success first)
 	/// - (2) Nodes with unknown contact (older than 1 week or new nodes) are randomly shuffled
 	/// - (3) Nodes with a failed contact are ordered (oldest failure first)
+	/// - Within each group nodes with a higher reputation come first
 	/// - The final result is the concatenation of (1), (2) and (3)
 	fn ordered_entries(&self) -> Vec<&Node> {
 		let mut success = Vec::new();
@@ -298,6 +307,7 @@ impl NodeTable {
 			// inverse ordering, most recent successes come first
 			b.time().cmp(&a.time())
 		});
+		success.sort_by(|a, b| b.reputation.cmp(&a.reputation));
 
 		failures.sort_by(|a, b| {
 			let a = a.last_contact.expect("vector only contains values with defined last_contact; qed");
@@ -305,8 +315,10 @@ impl NodeTable {
 			// normal ordering, most distant failures come first
 			a.time().cmp(&b.time())
 		});
+		failures.sort_by(|a, b| b.reputation.cmp(&a.reputation));
 
 		rand::thread_rng().shuffle(&mut unknown);
+		unknown.sort_by(|a, b| b.reputation.cmp(&a.reputation));
 
 		success.append(&mut unknown);
 		success.append(&mut failures);
@@ -368,6 +380,28 @@ impl NodeTable {
 		}
 	}
 
+	/// Get node reputation, zero for unknown nodes.
+	pub fn reputation(&self, id: &NodeId) -> i32 {
+		self.nodes.get(id).map_or(0, |n| n.reputation)
+	}
+
+	/// Adjust node reputation by the given amount.
+	pub fn adjust_reputation(&mut self, id: &NodeId, delta: i32) {
+		if let Some(node) = self.nodes.get_mut(id) {
+			node.reputation = cmp::max(MIN_REPUTATION, cmp::min(MAX_REPUTATION, node.reputation.saturating_add(delta)));
+		}
+	}
+
+	/// Returns reputations of all nodes, highest first.
+	pub fn reputations(&self) -> Vec<NodeReputation> {
+		let mut nodes: Vec<&Node> = self.nodes.values().collect();
+		nodes.sort_by(|a, b| b.reputation.cmp(&a.reputation));
+		nodes.into_iter().map(|n| NodeReputation {
+			enode: format!("{}", n),
+			reputation: n.reputation,
+		}).collect()
+	}
+
 	/// Mark as useless, no further attempts to connect until next call to `clear_useless`.
 	pub fn mark_as_useless(&mut self, id: &NodeId) {
 		self.useless_nodes.insert(id.clone());
@@ -486,6 +520,8 @@ mod json {
 	pub struct Node {
 		pub url: String,
 		pub last_contact: Option<NodeContact>,
+		#[serde(default)]
+		pub reputation: i32,
 	}
 
 	impl Node {
@@ -493,6 +529,7 @@ mod json {
 			match super::Node::from_str(&self.url) {
 				Ok(mut node) => {
 					node.last_contact = self.last_contact.map(|c| c.into_node_contact());
+					node.reputation = self.reputation;
 					Some(node)
 				},
 				_ => None,
@@ -513,7 +550,8 @@ mod json {
 
 			Node {
 				url: format!("{}", node),
-				last_contact
+				last_contact,
+				reputation: node.reputation,
 			}
 		}
 	}
@@ -669,6 +707,42 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn table_reputation_order_and_persistence() {
+		let tempdir = TempDir::new("").unwrap();
+		let node1 = Node::from_str("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
+		let node2 = Node::from_str("enode://b979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
+		let node3 = Node::from_str("enode://c979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
+		let id1 = H512::from_str("a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c").unwrap();
+		let id2 = H512::from_str("b979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c").unwrap();
+		let id3 = H512::from_str("c979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c").unwrap();
+
+		{
+			let mut table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			table.add_node(node1);
+			table.add_node(node2);
+			table.add_node(node3);
+
+			table.note_success(&id1);
+			table.note_success(&id2);
+			table.note_success(&id3);
+			table.adjust_reputation(&id1, -10);
+			table.adjust_reputation(&id3, 20);
+			table.adjust_reputation(&id2, MAX_REPUTATION * 2);
+			assert_eq!(table.reputation(&id2), MAX_REPUTATION);
+		}
+
+		{
+			let table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			let r = table.nodes(IpFilter::default());
+			assert_eq!(r[0][..], id2[..]);
+			assert_eq!(r[1][..], id3[..]);
+			assert_eq!(r[2][..], id1[..]);
+			assert_eq!(table.reputation(&id1), -10);
+			assert_eq!(table.reputations().iter().map(|n| n.reputation).collect::<Vec<_>>(), vec![MAX_REPUTATION, 20, -10]);
+		}
+	}
+
 	#[test]
 	fn custom_allow() {
 		let filter = IpFilter {
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use network::{Error, NetworkConfiguration, NetworkProtocolHandler, NonReservedPeerMode};
-use network::{NetworkContext, PeerId, ProtocolId, NetworkIoMessage};
+use network::{NetworkContext, PeerId, ProtocolId, NetworkIoMessage, NodeReputation};
 use host::Host;
 use io::*;
 use parking_lot::RwLock;
@@ -141,6 +141,11 @@ impl NetworkService {
 		self.host.read().as_ref().map(|h| h.connected_peers()).unwrap_or_else(Vec::new)
 	}
 
+	/// Get reputations of all known nodes, best first.
+	pub fn node_reputations(&self) -> Vec<NodeReputation> {
+		self.host.read().as_ref().map(|h| h.node_reputations()).unwrap_or_else(Vec::new)
+	}
+
 	/// Try to add a reserved peer.
 	pub fn add_reserved_peer(&self, peer: &str) -> Result<(), Error> {
 		let host = self.host.read();
@@ -113,6 +113,15 @@ pub struct SessionInfo {
 	pub local_address: String,
 }
 
+/// Persistent reputation of a known node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeReputation {
+	/// Node enode URL
+	pub enode: String,
+	/// Accumulated reputation score
+	pub reputation: i32,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerCapabilityInfo {
 	pub protocol: ProtocolId,
@@ -287,6 +296,12 @@ pub trait NetworkContext {
 
 	/// Returns this object's subprotocol name.
 	fn subprotocol_name(&self) -> ProtocolId;
+
+	/// Returns the persistent reputation of a peer.
+	fn peer_reputation(&self, peer: PeerId) -> i32;
+
+	/// Adjust the persistent reputation of a peer by the given amount.
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32);
 }
 
 impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
@@ -337,6 +352,14 @@ impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
 	fn subprotocol_name(&self) -> ProtocolId {
 		(**self).subprotocol_name()
 	}
+
+	fn peer_reputation(&self, peer: PeerId) -> i32 {
+		(**self).peer_reputation(peer)
+	}
+
+	fn adjust_peer_reputation(&self, peer: PeerId, delta: i32) {
+		(**self).adjust_peer_reputation(peer, delta)
+	}
 }
 
 pub trait HostInfo {